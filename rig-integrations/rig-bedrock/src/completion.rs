@@ -181,6 +181,10 @@ impl completion::CompletionModel for CompletionModel {
         Self::new(client.clone(), model)
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: completion::CompletionRequest,
@@ -20,6 +20,10 @@ pub struct BedrockUsage {
     pub input_tokens: i32,
     pub output_tokens: i32,
     pub total_tokens: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write_input_tokens: Option<i32>,
 }
 
 impl GetTokenUsage for BedrockStreamingResponse {
@@ -28,6 +32,9 @@ impl GetTokenUsage for BedrockStreamingResponse {
             input_tokens: u.input_tokens as u64,
             output_tokens: u.output_tokens as u64,
             total_tokens: u.total_tokens as u64,
+            cached_input_tokens: u.cache_read_input_tokens.unwrap_or_default() as u64,
+            cache_creation_input_tokens: u.cache_write_input_tokens.unwrap_or_default() as u64,
+            ..Default::default()
         })
     }
 }
@@ -186,6 +193,8 @@ impl CompletionModel {
                                     input_tokens: usage.input_tokens,
                                     output_tokens: usage.output_tokens,
                                     total_tokens: usage.total_tokens,
+                                    cache_read_input_tokens: usage.cache_read_input_tokens,
+                                    cache_write_input_tokens: usage.cache_write_input_tokens,
                                 }),
                             }));
                         }
@@ -209,6 +218,8 @@ mod tests {
             input_tokens: 100,
             output_tokens: 50,
             total_tokens: 150,
+            cache_read_input_tokens: None,
+            cache_write_input_tokens: None,
         };
 
         assert_eq!(usage.input_tokens, 100);
@@ -223,6 +234,8 @@ mod tests {
                 input_tokens: 200,
                 output_tokens: 75,
                 total_tokens: 275,
+                cache_read_input_tokens: None,
+                cache_write_input_tokens: None,
             }),
         };
 
@@ -250,6 +263,8 @@ mod tests {
                 input_tokens: 448,
                 output_tokens: 68,
                 total_tokens: 516,
+                cache_read_input_tokens: Some(400),
+                cache_write_input_tokens: Some(32),
             }),
         };
 
@@ -258,6 +273,8 @@ mod tests {
         assert_eq!(usage.input_tokens, 448);
         assert_eq!(usage.output_tokens, 68);
         assert_eq!(usage.total_tokens, 516);
+        assert_eq!(usage.cached_input_tokens, 400);
+        assert_eq!(usage.cache_creation_input_tokens, 32);
     }
 
     #[test]
@@ -266,6 +283,8 @@ mod tests {
             input_tokens: 100,
             output_tokens: 50,
             total_tokens: 150,
+            cache_read_input_tokens: None,
+            cache_write_input_tokens: None,
         };
 
         // Test serialization
@@ -288,6 +307,8 @@ mod tests {
                 input_tokens: 200,
                 output_tokens: 75,
                 total_tokens: 275,
+                cache_read_input_tokens: None,
+                cache_write_input_tokens: None,
             }),
         };
 
@@ -6,12 +6,24 @@ use aws_sdk_bedrockruntime::types::{
     ToolSpecification,
 };
 use rig::OneOrMany;
-use rig::completion::{CompletionError, Message};
+use rig::completion::{CacheBreakpoint, CompletionError, Message};
 use rig::message::{DocumentMediaType, UserContent};
 
 pub struct AwsCompletionRequest(pub rig::completion::CompletionRequest);
 
+/// A Bedrock `cachePoint` block, marking the end of a cached prompt prefix.
+fn cache_point() -> aws_bedrock::CachePointBlock {
+    aws_bedrock::CachePointBlock::builder()
+        .r#type(aws_bedrock::CachePointType::Default)
+        .build()
+        .expect("Failed to build CachePointBlock")
+}
+
 impl AwsCompletionRequest {
+    fn has_cache_breakpoint(&self, breakpoint: CacheBreakpoint) -> bool {
+        self.0.cache_breakpoints.contains(&breakpoint)
+    }
+
     pub fn additional_params(&self) -> Option<aws_smithy_types::Document> {
         self.0
             .additional_params
@@ -52,6 +64,10 @@ impl AwsCompletionRequest {
             tools.push(tool);
         }
 
+        if !tools.is_empty() && self.has_cache_breakpoint(CacheBreakpoint::Tools) {
+            tools.push(Tool::CachePoint(cache_point()));
+        }
+
         if !tools.is_empty() {
             // Convert rig's ToolChoice to AWS Bedrock ToolChoice
             use aws_sdk_bedrockruntime::types as aws_bedrock;
@@ -94,10 +110,13 @@ impl AwsCompletionRequest {
     }
 
     pub fn system_prompt(&self) -> Option<Vec<SystemContentBlock>> {
-        self.0
-            .preamble
-            .to_owned()
-            .map(|system_prompt| vec![SystemContentBlock::Text(system_prompt)])
+        self.0.preamble.to_owned().map(|system_prompt| {
+            let mut system = vec![SystemContentBlock::Text(system_prompt)];
+            if self.has_cache_breakpoint(CacheBreakpoint::Preamble) {
+                system.push(SystemContentBlock::CachePoint(cache_point()));
+            }
+            system
+        })
     }
 
     pub fn messages(&self) -> Result<Vec<aws_bedrock::Message>, CompletionError> {
@@ -120,14 +139,40 @@ impl AwsCompletionRequest {
             full_history.push(Message::User { content });
         }
 
+        let history_offset = full_history.len();
+
         self.0.chat_history.iter().for_each(|message| {
             full_history.push(message.clone());
         });
 
-        full_history
+        let mut messages = full_history
             .into_iter()
             .map(|message| RigMessage(message).try_into())
-            .collect::<Result<Vec<aws_bedrock::Message>, _>>()
+            .collect::<Result<Vec<aws_bedrock::Message>, _>>()?;
+
+        // Documents are sent as a single message, so any document breakpoint caches all of them
+        let mut cached_messages = self
+            .0
+            .cache_breakpoints
+            .iter()
+            .filter_map(|breakpoint| match breakpoint {
+                CacheBreakpoint::Document(_) if history_offset > 0 => Some(0),
+                CacheBreakpoint::Message(index) => Some(index + history_offset),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        cached_messages.sort_unstable();
+        cached_messages.dedup();
+
+        for index in cached_messages {
+            if let Some(message) = messages.get_mut(index) {
+                message
+                    .content
+                    .push(aws_bedrock::ContentBlock::CachePoint(cache_point()));
+            }
+        }
+
+        Ok(messages)
     }
 }
 
@@ -153,6 +198,8 @@ mod tests {
             max_tokens: None,
             tool_choice: None,
             additional_params: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
         }
     }
 
@@ -384,4 +431,44 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_cache_breakpoints_add_cache_points() {
+        let request = CompletionRequest {
+            preamble: Some("You are a helpful assistant".to_string()),
+            tools: vec![ToolDefinition {
+                name: "test_tool".to_string(),
+                description: "A test tool".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {}
+                }),
+            }],
+            cache_breakpoints: vec![
+                CacheBreakpoint::Preamble,
+                CacheBreakpoint::Tools,
+                CacheBreakpoint::Message(0),
+            ],
+            ..minimal_request()
+        };
+
+        let aws_request = AwsCompletionRequest(request);
+
+        let system = aws_request.system_prompt().expect("Should have system");
+        assert_eq!(system.len(), 2);
+        assert!(matches!(system[1], SystemContentBlock::CachePoint(_)));
+
+        let tool_config = aws_request
+            .tools_config()
+            .expect("Should build tool config")
+            .expect("Should have tools");
+        assert_eq!(tool_config.tools().len(), 2);
+        assert!(matches!(tool_config.tools()[1], Tool::CachePoint(_)));
+
+        let messages = aws_request.messages().expect("Should build messages");
+        assert!(matches!(
+            messages[0].content.last(),
+            Some(aws_bedrock::ContentBlock::CachePoint(_))
+        ));
+    }
 }
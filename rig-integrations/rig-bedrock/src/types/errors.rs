@@ -46,39 +46,74 @@ pub struct AwsSdkConverseError(pub SdkError<ConverseError, HttpResponse>);
 
 impl From<AwsSdkConverseError> for CompletionError {
     fn from(value: AwsSdkConverseError) -> Self {
-        let error: String = match value.0.into_service_error() {
-            ConverseError::ModelTimeoutException(e) => e.message.unwrap_or("The request took too long to process. Processing time exceeded the model timeout length.".into()),
-            ConverseError::AccessDeniedException(e) => e.message.unwrap_or("The request is denied because you do not have sufficient permissions to perform the requested action.".into()),
-            ConverseError::ResourceNotFoundException(e) => e.message.unwrap_or("The specified resource ARN was not found.".into()),
-            ConverseError::ThrottlingException(e) => e.message.unwrap_or("Your request was denied due to exceeding the account quotas for AWS Bedrock.".into()),
-            ConverseError::ServiceUnavailableException(e) => e.message.unwrap_or("The service isn't currently available.".into()),
-            ConverseError::InternalServerException(e) => e.message.unwrap_or("An internal server error occurred.".into()),
-            ConverseError::ValidationException(e) => e.message.unwrap_or("The input fails to satisfy the constraints specified by AWS Bedrock.".into()),
-            ConverseError::ModelNotReadyException(e) => e.message.unwrap_or("The model specified in the request is not ready to serve inference requests. The AWS SDK will automatically retry the operation up to 5 times.".into()),
-            ConverseError::ModelErrorException(e) => e.message.unwrap_or("The request failed due to an error while processing the model.".into()),
-            _ => String::from("An unexpected error occurred. Verify Internet connection or AWS keys")
-        };
-        CompletionError::ProviderError(error)
+        match value.0.into_service_error() {
+            ConverseError::ModelTimeoutException(e) => CompletionError::ProviderError(e.message.unwrap_or("The request took too long to process. Processing time exceeded the model timeout length.".into())),
+            ConverseError::AccessDeniedException(e) => CompletionError::Authentication(e.message.unwrap_or("The request is denied because you do not have sufficient permissions to perform the requested action.".into())),
+            ConverseError::ResourceNotFoundException(e) => CompletionError::InvalidRequest(e.message.unwrap_or("The specified resource ARN was not found.".into())),
+            ConverseError::ThrottlingException(e) => CompletionError::RateLimited {
+                retry_after: None,
+                body: e.message.unwrap_or("Your request was denied due to exceeding the account quotas for AWS Bedrock.".into()),
+            },
+            ConverseError::ServiceUnavailableException(e) => CompletionError::Overloaded(e.message.unwrap_or("The service isn't currently available.".into())),
+            ConverseError::InternalServerException(e) => CompletionError::ProviderError(e.message.unwrap_or("An internal server error occurred.".into())),
+            ConverseError::ValidationException(e) => validation_error(e.message.unwrap_or("The input fails to satisfy the constraints specified by AWS Bedrock.".into())),
+            ConverseError::ModelNotReadyException(e) => CompletionError::Overloaded(e.message.unwrap_or("The model specified in the request is not ready to serve inference requests. The AWS SDK will automatically retry the operation up to 5 times.".into())),
+            ConverseError::ModelErrorException(e) => CompletionError::ProviderError(e.message.unwrap_or("The request failed due to an error while processing the model.".into())),
+            _ => CompletionError::ProviderError(String::from("An unexpected error occurred. Verify Internet connection or AWS keys"))
+        }
     }
 }
 
 pub struct AwsSdkConverseStreamError(pub SdkError<ConverseStreamError, HttpResponse>);
 impl From<AwsSdkConverseStreamError> for CompletionError {
     fn from(value: AwsSdkConverseStreamError) -> Self {
-        let error: String = match value.0.into_service_error() {
-            ConverseStreamError::ModelTimeoutException(e) => e.message.unwrap(),
-            ConverseStreamError::AccessDeniedException(e) => e.message.unwrap(),
-            ConverseStreamError::ResourceNotFoundException(e) => e.message.unwrap(),
-            ConverseStreamError::ThrottlingException(e) => e.message.unwrap(),
-            ConverseStreamError::ServiceUnavailableException(e) => e.message.unwrap(),
-            ConverseStreamError::InternalServerException(e) => e.message.unwrap(),
-            ConverseStreamError::ModelStreamErrorException(e) => e.message.unwrap(),
-            ConverseStreamError::ValidationException(e) => e.message.unwrap(),
-            ConverseStreamError::ModelNotReadyException(e) => e.message.unwrap(),
-            ConverseStreamError::ModelErrorException(e) => e.message.unwrap(),
-            _ => "An unexpected error occurred. Verify Internet connection or AWS keys".into(),
-        };
-        CompletionError::ProviderError(error)
+        match value.0.into_service_error() {
+            ConverseStreamError::ModelTimeoutException(e) => {
+                CompletionError::ProviderError(e.message.unwrap())
+            }
+            ConverseStreamError::AccessDeniedException(e) => {
+                CompletionError::Authentication(e.message.unwrap())
+            }
+            ConverseStreamError::ResourceNotFoundException(e) => {
+                CompletionError::InvalidRequest(e.message.unwrap())
+            }
+            ConverseStreamError::ThrottlingException(e) => CompletionError::RateLimited {
+                retry_after: None,
+                body: e.message.unwrap(),
+            },
+            ConverseStreamError::ServiceUnavailableException(e) => {
+                CompletionError::Overloaded(e.message.unwrap())
+            }
+            ConverseStreamError::InternalServerException(e) => {
+                CompletionError::ProviderError(e.message.unwrap())
+            }
+            ConverseStreamError::ModelStreamErrorException(e) => {
+                CompletionError::ProviderError(e.message.unwrap())
+            }
+            ConverseStreamError::ValidationException(e) => validation_error(e.message.unwrap()),
+            ConverseStreamError::ModelNotReadyException(e) => {
+                CompletionError::Overloaded(e.message.unwrap())
+            }
+            ConverseStreamError::ModelErrorException(e) => {
+                CompletionError::ProviderError(e.message.unwrap())
+            }
+            _ => CompletionError::ProviderError(
+                "An unexpected error occurred. Verify Internet connection or AWS keys".into(),
+            ),
+        }
+    }
+}
+
+/// Bedrock reports prompts exceeding the model's context window as validation errors
+fn validation_error(message: String) -> CompletionError {
+    let lowercase = message.to_lowercase();
+    if ["too long", "context length", "too many tokens"]
+        .iter()
+        .any(|marker| lowercase.contains(marker))
+    {
+        CompletionError::ContextLengthExceeded(message)
+    } else {
+        CompletionError::InvalidRequest(message)
     }
 }
 
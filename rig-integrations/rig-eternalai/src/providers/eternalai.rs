@@ -508,6 +508,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                 input_tokens: usage.prompt_tokens as u64,
                 output_tokens: (usage.total_tokens - usage.prompt_tokens) as u64,
                 total_tokens: usage.total_tokens as u64,
+                ..Default::default()
             })
             .unwrap_or_default();
 
@@ -591,6 +592,10 @@ impl completion::CompletionModel for CompletionModel {
         Self::new(client.clone(), model, None)
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
@@ -10,6 +10,10 @@ use tokio_rusqlite::Connection;
 use tracing::{debug, info};
 use zerocopy::IntoBytes;
 
+pub mod memory;
+
+pub use memory::SqliteConversationMemory;
+
 #[derive(Debug)]
 pub enum SqliteError {
     DatabaseError(Box<dyn std::error::Error + Send + Sync>),
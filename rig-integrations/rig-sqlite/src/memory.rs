@@ -0,0 +1,136 @@
+//! SQLite implementation of a conversation memory.
+use rig::completion::Message;
+use rig::memory::{ConversationMemory, MemoryError};
+use tokio_rusqlite::Connection;
+
+/// [SqliteConversationMemory] stores the history of every session as rows of a single
+/// SQLite table, one JSON-serialized [Message] per row.
+///
+/// # Example
+/// ```rust
+/// use rig_sqlite::SqliteConversationMemory;
+/// use tokio_rusqlite::Connection;
+///
+/// let conn = Connection::open("conversations.db").await?;
+/// let memory = SqliteConversationMemory::new(conn).await?;
+///
+/// let agent = openai.agent("gpt-4o").memory(memory).build();
+/// ```
+#[derive(Clone)]
+pub struct SqliteConversationMemory {
+    conn: Connection,
+    table_name: String,
+}
+
+impl SqliteConversationMemory {
+    /// Create a new [SqliteConversationMemory] using the `conversation_messages` table.
+    pub async fn new(conn: Connection) -> Result<Self, MemoryError> {
+        Self::with_table_name(conn, "conversation_messages").await
+    }
+
+    /// Create a new [SqliteConversationMemory] using the given table name.
+    /// The table (and its session index) is created if it does not exist yet.
+    ///
+    /// As the table name is part of every query, it must match `^[A-Za-z_][A-Za-z0-9_]*$`.
+    pub async fn with_table_name(conn: Connection, table_name: &str) -> Result<Self, MemoryError> {
+        if !is_valid_table_name(table_name) {
+            return Err(MemoryError::DatastoreError(
+                format!("Invalid table name: {table_name}").into(),
+            ));
+        }
+
+        let table_name = table_name.to_string();
+        let create_table = format!(
+            "CREATE TABLE IF NOT EXISTS {table_name} (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                message TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_{table_name}_session_id ON {table_name}(session_id);"
+        );
+
+        conn.call(move |conn| {
+            conn.execute_batch(&create_table)?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| MemoryError::DatastoreError(Box::new(e)))?;
+
+        Ok(Self { conn, table_name })
+    }
+}
+
+fn is_valid_table_name(table_name: &str) -> bool {
+    let mut chars = table_name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl ConversationMemory for SqliteConversationMemory {
+    async fn load(&self, session_id: &str) -> Result<Vec<Message>, MemoryError> {
+        let session_id = session_id.to_string();
+        let query = format!(
+            "SELECT message FROM {} WHERE session_id = ?1 ORDER BY id ASC",
+            self.table_name
+        );
+
+        let rows = self
+            .conn
+            .call(move |conn| {
+                let mut stmt = conn.prepare(&query)?;
+                let rows = stmt
+                    .query_map([session_id], |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .await
+            .map_err(|e| MemoryError::DatastoreError(Box::new(e)))?;
+
+        rows.iter()
+            .map(|row| serde_json::from_str(row).map_err(MemoryError::from))
+            .collect()
+    }
+
+    async fn append(&self, session_id: &str, messages: Vec<Message>) -> Result<(), MemoryError> {
+        let session_id = session_id.to_string();
+        let query = format!(
+            "INSERT INTO {} (session_id, message) VALUES (?1, ?2)",
+            self.table_name
+        );
+        let messages = messages
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.conn
+            .call(move |conn| {
+                let txn = conn.transaction()?;
+                {
+                    let mut stmt = txn.prepare(&query)?;
+                    for message in messages {
+                        stmt.execute([&session_id, &message])?;
+                    }
+                }
+                txn.commit()?;
+                Ok(())
+            })
+            .await
+            .map_err(|e| MemoryError::DatastoreError(Box::new(e)))
+    }
+
+    async fn clear(&self, session_id: &str) -> Result<(), MemoryError> {
+        let session_id = session_id.to_string();
+        let query = format!("DELETE FROM {} WHERE session_id = ?1", self.table_name);
+
+        self.conn
+            .call(move |conn| {
+                conn.execute(&query, [session_id])?;
+                Ok(())
+            })
+            .await
+            .map_err(|e| MemoryError::DatastoreError(Box::new(e)))
+    }
+}
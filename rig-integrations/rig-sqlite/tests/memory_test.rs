@@ -0,0 +1,57 @@
+use rig::completion::Message;
+use rig::memory::ConversationMemory;
+use rig_sqlite::SqliteConversationMemory;
+use tokio_rusqlite::Connection;
+
+#[tokio::test]
+async fn conversation_memory_test() {
+    let conn = Connection::open_in_memory()
+        .await
+        .expect("Could not initialize SQLite connection");
+
+    let memory = SqliteConversationMemory::new(conn)
+        .await
+        .expect("Could not initialize SQLite conversation memory");
+
+    memory
+        .append(
+            "session-1",
+            vec![Message::user("hello"), Message::assistant("hi")],
+        )
+        .await
+        .unwrap();
+    memory
+        .append("session-2", vec![Message::user("bonjour")])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        memory.load("session-1").await.unwrap(),
+        vec![Message::user("hello"), Message::assistant("hi")]
+    );
+    assert_eq!(
+        memory.load("session-2").await.unwrap(),
+        vec![Message::user("bonjour")]
+    );
+
+    memory.clear("session-1").await.unwrap();
+    assert!(memory.load("session-1").await.unwrap().is_empty());
+    assert_eq!(memory.load("session-2").await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn conversation_memory_rejects_invalid_table_name() {
+    let conn = Connection::open_in_memory()
+        .await
+        .expect("Could not initialize SQLite connection");
+
+    let result =
+        SqliteConversationMemory::with_table_name(conn.clone(), "messages; DROP TABLE x").await;
+    assert!(result.is_err());
+
+    assert!(
+        SqliteConversationMemory::with_table_name(conn, "_agent_messages_2")
+            .await
+            .is_ok()
+    );
+}
@@ -1,24 +1,29 @@
 use rig::prelude::*;
-use rig::providers::openai;
-use rig::providers::openai::client::Client;
-use schemars::JsonSchema;
+use rig::{
+    agent::plan::{Plan, PlanAndExecute, PlanHook, PlanStep},
+    providers::openai::{self, client::Client},
+};
 
-#[derive(serde::Deserialize, JsonSchema, serde::Serialize, Debug)]
-struct Specification {
-    tasks: Vec<Task>,
-}
+const ORCHESTRATOR_PREAMBLE: &str = "
+Analyze the given task and break it down into 2-3 distinct approaches, one step per approach:
 
-#[derive(serde::Deserialize, JsonSchema, serde::Serialize, Debug)]
-struct Task {
-    original_task: String,
-    style: String,
-    guidelines: String,
-}
+Formal style: Write technically and precisely, focusing on detailed specifications
+Conversational style: Write in a friendly and engaging way that connects with the reader
+Hybrid style: Tell a story that includes technical details, combining emotional elements with specifications
+
+Each step should state the original task, the style to use and guidelines for that style.
+Finally, add a last step comparing the written materials, choosing the best one and giving your reasoning.
+
+Do not return text, only return a tool call.
+";
+
+#[derive(Clone)]
+struct ProgressLogger;
 
-#[derive(serde::Deserialize, JsonSchema, serde::Serialize, Debug)]
-struct TaskResults {
-    style: String,
-    response: String,
+impl PlanHook for ProgressLogger {
+    async fn on_step_start(&self, index: usize, step: &PlanStep) {
+        println!("Working on step {}: {}", index + 1, step.description);
+    }
 }
 
 #[tokio::main]
@@ -26,73 +31,29 @@ async fn main() -> Result<(), anyhow::Error> {
     // Create OpenAI client
     let openai_client = Client::from_env();
 
-    // Note that you can also create your own semantic router for this
-    // that uses a vector store under the hood
-    let classify_agent = openai_client.extractor::<Specification>(openai::GPT_4)
-        .preamble("
-            Analyze the given task and break it down into 2-3 distinct approaches.
-
-            Provide an Analysis:
-            Explain your understanding of the task and which variations would be valuable.
-            Focus on how each approach serves different aspects of the task.
-
-            Along with the analysis, provide 2-3 approaches to tackle the task, each with a brief description:
-
-            Formal style: Write technically and precisely, focusing on detailed specifications
-            Conversational style: Write in a friendly and engaging way that connects with the reader
-            Hybrid style: Tell a story that includes technical details, combining emotional elements with specifications
-
-            Return only JSON output.
-            ")
-        .build();
-
-    let specification = classify_agent.extract("
-        Write a product description for a new eco-friendly water bottle.
-        The target_audience is environmentally conscious millennials and key product features are: plastic-free, insulated, lifetime warranty
-        ").await.unwrap();
-
-    let content_agent = openai_client
-        .extractor::<TaskResults>(openai::GPT_4)
-        .preamble(
-            "
-                Generate content based on the original task, style, and guidelines.
-
-                Return only your response and the style you used as a JSON object.
-                ",
-        )
+    // The orchestrator breaks the task down into steps...
+    let orchestrator = openai_client
+        .extractor::<Plan>(openai::GPT_4)
+        .preamble(ORCHESTRATOR_PREAMBLE)
         .build();
 
-    let mut vec: Vec<TaskResults> = Vec::new();
-    for task in specification.tasks {
-        let results = content_agent
-            .extract(&format!(
-                "
-            Task: {},
-            Style: {},
-            Guidelines: {}
-            ",
-                task.original_task, task.style, task.guidelines
-            ))
-            .await
-            .unwrap();
-        vec.push(results);
-    }
-
-    let judge_agent = openai_client
-        .extractor::<Specification>(openai::GPT_4)
-        .preamble(
-            "
-            Analyze the given written materials and decide the best one, giving your reasoning.
-
-            Return the style as well as the corresponding material you have chosen as a JSON object.
-            ",
-        )
+    // ...which are executed one after the other by the worker, who sees the results of the previous steps
+    let worker = openai_client
+        .agent(openai::GPT_4)
+        .preamble("Generate content based on the original task, style, and guidelines of the current step.")
         .build();
 
-    let task_results_raw_json = serde_json::to_string_pretty(&vec).unwrap();
-    let results = judge_agent.extract(&task_results_raw_json).await.unwrap();
+    let response = PlanAndExecute::new(orchestrator, worker)
+        .with_hook(ProgressLogger)
+        .run("
+            Write a product description for a new eco-friendly water bottle.
+            The target_audience is environmentally conscious millennials and key product features are: plastic-free, insulated, lifetime warranty
+            ")
+        .await?;
 
-    println!("Results: {results:?}");
+    for step in &response.steps {
+        println!("\n{}\n{}", step.step.description, step.output);
+    }
 
     Ok(())
 }
@@ -0,0 +1,36 @@
+use rig::prelude::*;
+use rig::providers::openai;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A short summary of a movie
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+struct MovieSummary {
+    /// The title of the movie
+    title: String,
+    /// The year the movie was released
+    year: u16,
+    /// The genres of the movie
+    genres: Vec<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    // Create OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a film critic.")
+        .build();
+
+    // OpenAI natively supports structured outputs, so the schema of `MovieSummary` is sent as the
+    // output schema of the request. Other providers are asked to call a `submit` tool instead.
+    let summary = agent
+        .prompt_typed::<MovieSummary>("Summarize the movie Spirited Away.")
+        .await?;
+
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+
+    Ok(())
+}
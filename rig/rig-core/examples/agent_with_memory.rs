@@ -0,0 +1,35 @@
+use rig::prelude::*;
+
+use rig::memory::JsonFileConversationMemory;
+use rig::providers::openai;
+use rig::{completion::Prompt, providers};
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    // Create OpenAI client
+    let client = providers::openai::Client::from_env();
+
+    // Sessions are stored as JSON files, so the conversation survives process restarts
+    let memory = JsonFileConversationMemory::new("./sessions")?;
+
+    let agent = client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful assistant.")
+        .memory(memory)
+        .build();
+
+    agent
+        .prompt("Hi! My name is Ferris and I like crabs.")
+        .session("ferris")
+        .await?;
+
+    // The history of the "ferris" session is loaded before this prompt is sent
+    let response = agent
+        .prompt("What is my name and what do I like?")
+        .session("ferris")
+        .await?;
+
+    println!("{response}");
+
+    Ok(())
+}
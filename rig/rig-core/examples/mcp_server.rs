@@ -0,0 +1,101 @@
+//! An example of how you can serve Rig tools to any MCP client (eg: Claude Desktop or an IDE)
+//! over stdio.
+use rig::{
+    completion::ToolDefinition,
+    tool::{Tool, ToolSet, mcp_server::McpServer},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Deserialize)]
+struct OperationArgs {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Math error")]
+struct MathError;
+
+#[derive(Deserialize, Serialize)]
+struct Adder;
+
+impl Tool for Adder {
+    const NAME: &'static str = "add";
+
+    type Error = MathError;
+    type Args = OperationArgs;
+    type Output = i32;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "add".to_string(),
+            description: "Add x and y together".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "x": { "type": "number", "description": "The first number to add" },
+                    "y": { "type": "number", "description": "The second number to add" }
+                },
+                "required": ["x", "y"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        Ok(args.x + args.y)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct Subtract;
+
+impl Tool for Subtract {
+    const NAME: &'static str = "subtract";
+
+    type Error = MathError;
+    type Args = OperationArgs;
+    type Output = i32;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "subtract".to_string(),
+            description: "Subtract y from x (i.e.: x - y)".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "x": { "type": "number", "description": "The number to subtract from" },
+                    "y": { "type": "number", "description": "The number to subtract" }
+                },
+                "required": ["x", "y"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        Ok(args.x - args.y)
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Logs must go to stderr, since stdout is used by the MCP transport
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
+
+    let toolset = ToolSet::builder()
+        .static_tool(Adder)
+        .static_tool(Subtract)
+        .build()?;
+
+    // Agents can be served as well, since they implement `Tool`:
+    // `.static_tool(openai.agent("gpt-4o").name("researcher").build())`
+    McpServer::from_toolset(toolset)
+        .server_info("calculator", env!("CARGO_PKG_VERSION"))
+        .instructions("A calculator that can add and subtract numbers.")
+        .serve_stdio()
+        .await?;
+
+    Ok(())
+}
@@ -7,7 +7,7 @@ use rig::{
     agent::Agent,
     client::CompletionClient,
     completion::{self, CompletionError, CompletionModel, PromptError, ToolDefinition},
-    message::{AssistantContent, Message, Text, ToolResultContent, UserContent},
+    message::{AssistantContent, Message, Text, UserContent},
     streaming::{StreamedAssistantContent, StreamingCompletion},
     tool::{Tool, ToolSetError},
 };
@@ -142,14 +142,14 @@ where
                         content: OneOrMany::one(UserContent::tool_result_with_call_id(
                             id,
                             call_id,
-                            OneOrMany::one(ToolResultContent::text(tool_result)),
+                            tool_result.into_content(),
                         )),
                     });
                 } else {
                     chat_history.push(Message::User {
                         content: OneOrMany::one(UserContent::tool_result(
                             id,
-                            OneOrMany::one(ToolResultContent::text(tool_result)),
+                            tool_result.into_content(),
                         )),
                     });
 
@@ -135,7 +135,7 @@ async fn main() -> Result<(), anyhow::Error> {
     let toolset = ToolSet::builder()
         .dynamic_tool(Add)
         .dynamic_tool(Subtract)
-        .build();
+        .build()?;
     let embeddings = EmbeddingsBuilder::new(embedding_model.clone())
         .documents(toolset.schemas()?)?
         .build()
@@ -1,62 +1,30 @@
 use rig::prelude::*;
 use rig::{
-    agent::Agent,
-    completion::{CompletionError, CompletionModel, Prompt, PromptError, ToolDefinition},
-    extractor::Extractor,
-    message::Message,
+    agent::plan::{PLANNER_PREAMBLE, Plan, PlanAndExecute, PlanHook, PlanStep},
+    completion::ToolDefinition,
     providers::anthropic,
     tool::Tool,
 };
-use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-const CHAIN_OF_THOUGHT_PROMPT: &str = "
-You are an assistant that extracts reasoning steps from a given prompt.
-Do not return text, only return a tool call.
-";
+#[derive(Clone)]
+struct ProgressLogger;
 
-#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
-struct ChainOfThoughtSteps {
-    steps: Vec<String>,
-}
+impl PlanHook for ProgressLogger {
+    async fn on_plan(&self, plan: &Plan) {
+        println!("Plan:");
+        for (i, step) in plan.steps.iter().enumerate() {
+            println!("  {}. {}", i + 1, step.description);
+        }
+    }
 
-struct ReasoningAgent<M: CompletionModel> {
-    chain_of_thought_extractor: Extractor<M, ChainOfThoughtSteps>,
-    executor: Agent<M>,
-}
+    async fn on_step_finish(&self, index: usize, step: &PlanStep, output: &str) {
+        println!("Step {} ({}) done: {output}", index + 1, step.description);
+    }
 
-impl<M: CompletionModel> Prompt for ReasoningAgent<M> {
-    #[allow(refining_impl_trait)]
-    async fn prompt(&self, prompt: impl Into<Message> + Send) -> Result<String, PromptError> {
-        let prompt: Message = prompt.into();
-        let mut chat_history = vec![prompt.clone()];
-        let extracted = self
-            .chain_of_thought_extractor
-            .extract(prompt)
-            .await
-            .map_err(|e| {
-                tracing::error!("Extraction error: {:?}", e);
-                CompletionError::ProviderError("".into())
-            })?;
-        if extracted.steps.is_empty() {
-            return Ok("No reasoning steps provided.".into());
-        }
-        let mut reasoning_prompt = String::new();
-        for (i, step) in extracted.steps.iter().enumerate() {
-            reasoning_prompt.push_str(&format!("Step {}: {}\n", i + 1, step));
-        }
-        let response = self
-            .executor
-            .prompt(reasoning_prompt.as_str())
-            .with_history(&mut chat_history)
-            .multi_turn(20)
-            .await?;
-        tracing::info!(
-            "full chat history generated: {}",
-            serde_json::to_string_pretty(&chat_history).unwrap()
-        );
-        Ok(response)
+    async fn on_step_failed(&self, index: usize, step: &PlanStep, error: &str) {
+        println!("Step {} ({}) failed: {error}", index + 1, step.description);
     }
 }
 
@@ -69,36 +37,37 @@ async fn main() -> anyhow::Result<()> {
 
     // Create Anthropic client
     let anthropic_client = anthropic::Client::from_env();
-    let agent = ReasoningAgent {
-        chain_of_thought_extractor: anthropic_client
-            .extractor(anthropic::completion::CLAUDE_3_5_SONNET)
-            .preamble(CHAIN_OF_THOUGHT_PROMPT)
-            .build(),
-
-        executor: anthropic_client
-            .agent(anthropic::completion::CLAUDE_3_5_SONNET)
-            .preamble(
-                "You are an assistant here to help the user select which tool is most appropriate to perform arithmetic operations.
-                Follow these instructions closely.
-                1. Consider the user's request carefully and identify the core elements of the request.
-                2. Select which tool among those made available to you is appropriate given the context.
-                3. This is very important: never perform the operation yourself.
-                4. When you think you've finished calling tools for the operation, present the final result from the series of tool calls you made.
-                "
-            )
-            .tool(Add)
-            .tool(Subtract)
-            .tool(Multiply)
-            .tool(Divide)
-            .build(),
-    };
-
-    // Prompt the agent and print the response
-    let result = agent
-        .prompt("Calculate ((15 + 25) * (100 - 50)) / (200 / (10 + 10))")
+
+    let planner = anthropic_client
+        .extractor::<Plan>(anthropic::completion::CLAUDE_3_5_SONNET)
+        .preamble(PLANNER_PREAMBLE)
+        .build();
+
+    let executor = anthropic_client
+        .agent(anthropic::completion::CLAUDE_3_5_SONNET)
+        .preamble(
+            "You are an assistant here to help the user select which tool is most appropriate to perform arithmetic operations.
+            Follow these instructions closely.
+            1. Consider the user's request carefully and identify the core elements of the request.
+            2. Select which tool among those made available to you is appropriate given the context.
+            3. This is very important: never perform the operation yourself.
+            4. When you think you've finished calling tools for the operation, present the final result from the series of tool calls you made.
+            "
+        )
+        .tool(Add)
+        .tool(Subtract)
+        .tool(Multiply)
+        .tool(Divide)
+        .build();
+
+    // Plan the calculation, then execute each step with the tools
+    let response = PlanAndExecute::new(planner, executor)
+        .max_turns_per_step(5)
+        .with_hook(ProgressLogger)
+        .run("Calculate ((15 + 25) * (100 - 50)) / (200 / (10 + 10))")
         .await?;
 
-    println!("\n\nReasoning Agent Chat History: {result}");
+    println!("\n\nResult: {}", response.output);
 
     Ok(())
 }
@@ -246,7 +215,7 @@ impl Tool for Divide {
     }
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-        let result = args.x / args.y;
-        Ok(result)
+        // Returning an error makes the runner re-plan the remaining steps
+        args.x.checked_div(args.y).ok_or(MathError)
     }
 }
@@ -0,0 +1,258 @@
+//! This module contains the [Budget] type, which caps the tokens (or money) a multi-turn agent
+//! run can consume.
+//!
+//! A budget can be attached to a prompt request using
+//! [PromptRequest::with_budget](crate::agent::PromptRequest::with_budget) (or
+//! [StreamingPromptRequest::with_budget](crate::agent::StreamingPromptRequest::with_budget)).
+//! The aggregated [Usage] of the run is checked against the budget after every model response
+//! containing tool calls, before the tools are executed. If the budget is exceeded, the loop stops
+//! with [PromptError::BudgetExceeded](crate::completion::PromptError::BudgetExceeded), which
+//! carries the chat history so far (ending with the unanswered tool calls).
+//!
+//! The cost of a run is computed using the price of the model of the agent, looked up by its
+//! [model name](crate::completion::CompletionModel::model_name), so that a single budget (and price
+//! table) can be shared by agents using different models.
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     agent::{Budget, ModelPricing},
+//!     completion::{Prompt, PromptError},
+//!     providers::openai,
+//! };
+//!
+//! let openai = openai::Client::from_env();
+//! let agent = openai.agent("gpt-4o").build();
+//!
+//! // Stop the run once it has cost more than $0.50
+//! let budget = Budget::new()
+//!     .max_total_tokens(200_000)
+//!     .max_cost(0.5)
+//!     .model_pricing("gpt-4o", ModelPricing::new(2.5, 10.0))
+//!     .model_pricing("gpt-4o-mini", ModelPricing::new(0.15, 0.6));
+//!
+//! match agent.prompt("Plan my trip to Japan.").multi_turn(20).with_budget(budget).await {
+//!     Ok(response) => println!("{response}"),
+//!     Err(PromptError::BudgetExceeded { limit, chat_history, .. }) => {
+//!         println!("Stopped after {} messages: {limit}", chat_history.len());
+//!     }
+//!     Err(e) => return Err(e.into()),
+//! }
+//! ```
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::completion::Usage;
+
+/// The price of a model, in an arbitrary currency per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Price per million input tokens.
+    pub input_per_million: f64,
+    /// Price per million output tokens.
+    pub output_per_million: f64,
+}
+
+impl ModelPricing {
+    pub fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    /// Returns the cost of the given usage.
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        (usage.input_tokens as f64 * self.input_per_million
+            + usage.output_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Limits on the aggregated [Usage] of an agent run. Every limit is optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    pub max_input_tokens: Option<u64>,
+    pub max_output_tokens: Option<u64>,
+    pub max_total_tokens: Option<u64>,
+    /// The maximum cost of the run, computed using the price of the model in `pricing`.
+    pub max_cost: Option<f64>,
+    /// The price of each model, keyed by model name.
+    pub pricing: HashMap<String, ModelPricing>,
+}
+
+impl Budget {
+    /// Create a new budget without any limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of input tokens.
+    pub fn max_input_tokens(mut self, max_input_tokens: u64) -> Self {
+        self.max_input_tokens = Some(max_input_tokens);
+        self
+    }
+
+    /// Set the maximum number of output tokens.
+    pub fn max_output_tokens(mut self, max_output_tokens: u64) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    /// Set the maximum number of tokens (input and output).
+    pub fn max_total_tokens(mut self, max_total_tokens: u64) -> Self {
+        self.max_total_tokens = Some(max_total_tokens);
+        self
+    }
+
+    /// Set the maximum cost of the run. The price of the models used must be set with
+    /// [Budget::model_pricing], runs using a model without price don't have their cost checked.
+    pub fn max_cost(mut self, max_cost: f64) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    /// Set the price of the model with the given name.
+    pub fn model_pricing(mut self, model: impl Into<String>, pricing: ModelPricing) -> Self {
+        self.pricing.insert(model.into(), pricing);
+        self
+    }
+
+    /// Check the usage of the given model against the budget, returning the first limit that is
+    /// exceeded.
+    pub fn check(&self, model: Option<&str>, usage: &Usage) -> Result<(), BudgetLimit> {
+        if let Some(limit) = self.max_input_tokens
+            && usage.input_tokens > limit
+        {
+            return Err(BudgetLimit::InputTokens {
+                limit,
+                used: usage.input_tokens,
+            });
+        }
+
+        if let Some(limit) = self.max_output_tokens
+            && usage.output_tokens > limit
+        {
+            return Err(BudgetLimit::OutputTokens {
+                limit,
+                used: usage.output_tokens,
+            });
+        }
+
+        // Some providers only report input and output tokens
+        let total_tokens = usage
+            .total_tokens
+            .max(usage.input_tokens + usage.output_tokens);
+        if let Some(limit) = self.max_total_tokens
+            && total_tokens > limit
+        {
+            return Err(BudgetLimit::TotalTokens {
+                limit,
+                used: total_tokens,
+            });
+        }
+
+        if let Some(limit) = self.max_cost {
+            match model.and_then(|model| self.pricing.get(model)) {
+                Some(pricing) => {
+                    let spent = pricing.cost(usage);
+                    if spent > limit {
+                        return Err(BudgetLimit::Cost { limit, spent });
+                    }
+                }
+                None => tracing::warn!(
+                    "No pricing for model {}, the cost of the run is not checked",
+                    model.unwrap_or("<unknown>")
+                ),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The limit of a [Budget] that was exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, thiserror::Error)]
+pub enum BudgetLimit {
+    #[error("input token budget exceeded ({used}/{limit})")]
+    InputTokens { limit: u64, used: u64 },
+    #[error("output token budget exceeded ({used}/{limit})")]
+    OutputTokens { limit: u64, used: u64 },
+    #[error("total token budget exceeded ({used}/{limit})")]
+    TotalTokens { limit: u64, used: u64 },
+    #[error("cost budget exceeded ({spent:.4}/{limit:.4})")]
+    Cost { limit: f64, spent: f64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Budget, BudgetLimit, ModelPricing};
+    use crate::completion::Usage;
+
+    fn usage(input_tokens: u64, output_tokens: u64) -> Usage {
+        Usage {
+            input_tokens,
+            output_tokens,
+            total_tokens: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_budget_check() {
+        let budget = Budget::new()
+            .max_output_tokens(1_000)
+            .max_total_tokens(10_000);
+
+        assert_eq!(budget.check(None, &usage(8_000, 500)), Ok(()));
+        assert_eq!(
+            budget.check(None, &usage(8_000, 1_500)),
+            Err(BudgetLimit::OutputTokens {
+                limit: 1_000,
+                used: 1_500
+            })
+        );
+        // The total is computed from input and output tokens when the provider doesn't report it
+        assert_eq!(
+            budget.check(None, &usage(9_500, 600)),
+            Err(BudgetLimit::TotalTokens {
+                limit: 10_000,
+                used: 10_100
+            })
+        );
+    }
+
+    #[test]
+    fn test_budget_cost() {
+        let pricing = ModelPricing::new(2.0, 8.0);
+        assert_eq!(pricing.cost(&usage(1_000_000, 500_000)), 6.0);
+
+        let budget = Budget::new()
+            .max_cost(5.0)
+            .model_pricing("large", pricing)
+            .model_pricing("small", ModelPricing::new(0.5, 1.0));
+        assert_eq!(
+            budget.check(Some("large"), &usage(1_000_000, 300_000)),
+            Ok(())
+        );
+        assert_eq!(
+            budget.check(Some("large"), &usage(1_000_000, 500_000)),
+            Err(BudgetLimit::Cost {
+                limit: 5.0,
+                spent: 6.0
+            })
+        );
+        // The price of the model being used is looked up
+        assert_eq!(
+            budget.check(Some("small"), &usage(1_000_000, 500_000)),
+            Ok(())
+        );
+        // Models without price don't have their cost checked
+        assert_eq!(
+            budget.check(Some("other"), &usage(1_000_000, 500_000)),
+            Ok(())
+        );
+        assert_eq!(budget.check(None, &usage(1_000_000, 500_000)), Ok(()));
+    }
+}
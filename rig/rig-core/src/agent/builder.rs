@@ -3,10 +3,11 @@ use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 
 use crate::{
-    completion::{CompletionModel, Document},
+    completion::{CacheBreakpoint, CompletionModel, Document},
+    memory::{ConversationMemory, DynConversationMemory},
     message::ToolChoice,
     tool::{
-        Tool, ToolDyn, ToolSet,
+        Tool, ToolDyn, ToolSet, ToolSetError,
         server::{ToolServer, ToolServerHandle},
     },
     vector_store::VectorStoreIndexDyn,
@@ -14,9 +15,9 @@ use crate::{
 
 #[cfg(feature = "rmcp")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
-use crate::tool::rmcp::McpTool as RmcpTool;
+use crate::tool::{mcp_client::McpClient, rmcp::McpTool as RmcpTool};
 
-use super::Agent;
+use super::{Agent, context::ContextStrategy, context::ContextStrategyDyn};
 
 /// A builder for creating an agent
 ///
@@ -67,6 +68,12 @@ where
     tool_choice: Option<ToolChoice>,
     /// Default maximum depth for multi-turn agent calls
     default_max_depth: Option<usize>,
+    /// Conversation memory used to persist chat history across calls
+    memory: Option<DynConversationMemory>,
+    /// Strategy deciding which part of the chat history is sent to the model
+    context_strategy: Option<Arc<dyn ContextStrategyDyn>>,
+    /// Prompt cache breakpoints added to every completion request
+    cache_breakpoints: Vec<CacheBreakpoint>,
 }
 
 impl<M> AgentBuilder<M>
@@ -87,6 +94,9 @@ where
             tool_server_handle: None,
             tool_choice: None,
             default_max_depth: None,
+            memory: None,
+            context_strategy: None,
+            cache_breakpoints: vec![],
         }
     }
 
@@ -136,11 +146,19 @@ where
 
     /// Add a static tool to the agent
     pub fn tool(self, tool: impl Tool + 'static) -> AgentBuilderSimple<M> {
+        self.try_tool(tool).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add a static tool to the agent, failing instead of panicking if the tool can't be added.
+    pub fn try_tool(
+        self,
+        tool: impl Tool + 'static,
+    ) -> Result<AgentBuilderSimple<M>, ToolSetError> {
         let toolname = tool.name();
-        let tools = ToolSet::from_tools(vec![tool]);
+        let tools = ToolSet::from_tools(vec![tool])?;
         let static_tools = vec![toolname];
 
-        AgentBuilderSimple {
+        Ok(AgentBuilderSimple {
             name: self.name,
             description: self.description,
             model: self.model,
@@ -155,16 +173,32 @@ where
             tools,
             tool_choice: self.tool_choice,
             default_max_depth: self.default_max_depth,
-        }
+            memory: self.memory,
+            context_strategy: self.context_strategy,
+            cache_breakpoints: self.cache_breakpoints,
+            #[cfg(feature = "rmcp")]
+            mcp_clients: vec![],
+        })
     }
 
     /// Add a vector of boxed static tools to the agent
     /// This is useful when you need to dynamically add static tools to the agent
+    ///
+    /// # Panics
+    /// If two tools have the same name (see [AgentBuilder::try_tools]).
     pub fn tools(self, tools: Vec<Box<dyn ToolDyn>>) -> AgentBuilderSimple<M> {
+        self.try_tools(tools).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add a vector of boxed static tools to the agent. Fails if two tools have the same name.
+    pub fn try_tools(
+        self,
+        tools: Vec<Box<dyn ToolDyn>>,
+    ) -> Result<AgentBuilderSimple<M>, ToolSetError> {
         let static_tools = tools.iter().map(|tool| tool.name()).collect();
-        let tools = ToolSet::from_tools_boxed(tools);
+        let tools = ToolSet::from_tools_boxed(tools)?;
 
-        AgentBuilderSimple {
+        Ok(AgentBuilderSimple {
             name: self.name,
             description: self.description,
             model: self.model,
@@ -179,7 +213,39 @@ where
             tools,
             tool_choice: self.tool_choice,
             default_max_depth: self.default_max_depth,
-        }
+            memory: self.memory,
+            context_strategy: self.context_strategy,
+            cache_breakpoints: self.cache_breakpoints,
+            #[cfg(feature = "rmcp")]
+            mcp_clients: vec![],
+        })
+    }
+
+    /// Add a vector of boxed static tools to the agent, prefixing their names with the given
+    /// namespace (see [AgentBuilderSimple::namespaced_tools]).
+    ///
+    /// # Panics
+    /// If two tools have the same name, or if the namespace is invalid (see
+    /// [AgentBuilder::try_namespaced_tools]).
+    pub fn namespaced_tools(
+        self,
+        namespace: &str,
+        tools: Vec<Box<dyn ToolDyn>>,
+    ) -> AgentBuilderSimple<M> {
+        self.try_namespaced_tools(namespace, tools)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add a vector of boxed static tools to the agent, prefixing their names with the given
+    /// namespace. Fails if two tools have the same name, or if the namespace is invalid (see
+    /// [ToolSetError::InvalidNamespace]).
+    pub fn try_namespaced_tools(
+        self,
+        namespace: &str,
+        tools: Vec<Box<dyn ToolDyn>>,
+    ) -> Result<AgentBuilderSimple<M>, ToolSetError> {
+        self.try_tools(vec![])?
+            .try_namespaced_tools(namespace, tools)
     }
 
     pub fn tool_server_handle(mut self, handle: ToolServerHandle) -> Self {
@@ -195,11 +261,24 @@ where
         tool: rmcp::model::Tool,
         client: rmcp::service::ServerSink,
     ) -> AgentBuilderSimple<M> {
+        self.try_rmcp_tool(tool, client)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add an MCP tool (from `rmcp`) to the agent, failing instead of panicking if the tool can't
+    /// be added.
+    #[cfg(feature = "rmcp")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+    pub fn try_rmcp_tool(
+        self,
+        tool: rmcp::model::Tool,
+        client: rmcp::service::ServerSink,
+    ) -> Result<AgentBuilderSimple<M>, ToolSetError> {
         let toolname = tool.name.clone().to_string();
-        let tools = ToolSet::from_tools(vec![RmcpTool::from_mcp_server(tool, client)]);
+        let tools = ToolSet::from_tools(vec![RmcpTool::from_mcp_server(tool, client)])?;
         let static_tools = vec![toolname];
 
-        AgentBuilderSimple {
+        Ok(AgentBuilderSimple {
             name: self.name,
             description: self.description,
             model: self.model,
@@ -214,10 +293,18 @@ where
             tools,
             tool_choice: self.tool_choice,
             default_max_depth: self.default_max_depth,
-        }
+            memory: self.memory,
+            context_strategy: self.context_strategy,
+            cache_breakpoints: self.cache_breakpoints,
+            #[cfg(feature = "rmcp")]
+            mcp_clients: vec![],
+        })
     }
 
     /// Add an array of MCP tools (from `rmcp`) to the agent
+    ///
+    /// # Panics
+    /// If two tools have the same name (see [AgentBuilder::try_rmcp_tools]).
     #[cfg(feature = "rmcp")]
     #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
     pub fn rmcp_tools(
@@ -225,6 +312,19 @@ where
         tools: Vec<rmcp::model::Tool>,
         client: rmcp::service::ServerSink,
     ) -> AgentBuilderSimple<M> {
+        self.try_rmcp_tools(tools, client)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add an array of MCP tools (from `rmcp`) to the agent. Fails if two tools have the same
+    /// name.
+    #[cfg(feature = "rmcp")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+    pub fn try_rmcp_tools(
+        self,
+        tools: Vec<rmcp::model::Tool>,
+        client: rmcp::service::ServerSink,
+    ) -> Result<AgentBuilderSimple<M>, ToolSetError> {
         let (static_tools, tools) = tools.into_iter().fold(
             (Vec::new(), Vec::new()),
             |(mut toolnames, mut toolset), tool| {
@@ -236,9 +336,9 @@ where
             },
         );
 
-        let tools = ToolSet::from_tools(tools);
+        let tools = ToolSet::from_tools(tools)?;
 
-        AgentBuilderSimple {
+        Ok(AgentBuilderSimple {
             name: self.name,
             description: self.description,
             model: self.model,
@@ -253,6 +353,38 @@ where
             tools,
             tool_choice: self.tool_choice,
             default_max_depth: self.default_max_depth,
+            memory: self.memory,
+            context_strategy: self.context_strategy,
+            cache_breakpoints: self.cache_breakpoints,
+            #[cfg(feature = "rmcp")]
+            mcp_clients: vec![],
+        })
+    }
+
+    /// Add every tool of an MCP server to the agent. The tools are kept in sync with the tool
+    /// list of the server (see [McpClient]).
+    #[cfg(feature = "rmcp")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+    pub fn mcp_client(self, client: McpClient) -> AgentBuilderSimple<M> {
+        AgentBuilderSimple {
+            name: self.name,
+            description: self.description,
+            model: self.model,
+            preamble: self.preamble,
+            static_context: self.static_context,
+            static_tools: vec![],
+            additional_params: self.additional_params,
+            max_tokens: self.max_tokens,
+            dynamic_context: self.dynamic_context,
+            dynamic_tools: vec![],
+            temperature: self.temperature,
+            tools: ToolSet::default(),
+            tool_choice: self.tool_choice,
+            default_max_depth: self.default_max_depth,
+            memory: self.memory,
+            context_strategy: self.context_strategy,
+            cache_breakpoints: self.cache_breakpoints,
+            mcp_clients: vec![client],
         }
     }
 
@@ -279,6 +411,45 @@ where
         self
     }
 
+    /// Attach a conversation memory to the agent. Prompt requests that are given a session id
+    /// will load their history from, and persist new messages to, this memory.
+    pub fn memory(mut self, memory: impl ConversationMemory + 'static) -> Self {
+        self.memory = Some(Arc::new(memory));
+        self
+    }
+
+    /// Set the strategy used to fit the chat history in the model's context window.
+    /// It runs every time the agent builds a completion request, including on each turn of a
+    /// multi-turn prompt.
+    pub fn context_strategy(mut self, strategy: impl ContextStrategy + 'static) -> Self {
+        self.context_strategy = Some(Arc::new(strategy));
+        self
+    }
+
+    /// Mark the preamble as cacheable, so that providers supporting prompt caching can reuse it
+    /// across requests.
+    pub fn cache_preamble(mut self) -> Self {
+        if !self.cache_breakpoints.contains(&CacheBreakpoint::Preamble) {
+            self.cache_breakpoints.push(CacheBreakpoint::Preamble);
+        }
+        self
+    }
+
+    /// Mark the tool definitions as cacheable, so that providers supporting prompt caching can
+    /// reuse them across requests.
+    pub fn cache_tools(mut self) -> Self {
+        if !self.cache_breakpoints.contains(&CacheBreakpoint::Tools) {
+            self.cache_breakpoints.push(CacheBreakpoint::Tools);
+        }
+        self
+    }
+
+    /// Mark both the preamble and the tool definitions as cacheable.
+    /// See [CacheBreakpoint] for how each provider caches them.
+    pub fn prompt_caching(self) -> Self {
+        self.cache_preamble().cache_tools()
+    }
+
     /// Add some dynamic tools to the agent. On each prompt, `sample` tools from the
     /// dynamic toolset will be inserted in the request.
     pub fn dynamic_tools(
@@ -305,6 +476,11 @@ where
             tools: toolset,
             tool_choice: self.tool_choice,
             default_max_depth: self.default_max_depth,
+            memory: self.memory,
+            context_strategy: self.context_strategy,
+            cache_breakpoints: self.cache_breakpoints,
+            #[cfg(feature = "rmcp")]
+            mcp_clients: vec![],
         }
     }
 
@@ -347,6 +523,9 @@ where
             dynamic_context: Arc::new(RwLock::new(self.dynamic_context)),
             tool_server_handle,
             default_max_depth: self.default_max_depth,
+            memory: self.memory,
+            context_strategy: self.context_strategy,
+            cache_breakpoints: self.cache_breakpoints,
         }
     }
 }
@@ -404,6 +583,15 @@ where
     tool_choice: Option<ToolChoice>,
     /// Default maximum depth for multi-turn agent calls
     default_max_depth: Option<usize>,
+    /// Conversation memory used to persist chat history across calls
+    memory: Option<DynConversationMemory>,
+    /// Strategy deciding which part of the chat history is sent to the model
+    context_strategy: Option<Arc<dyn ContextStrategyDyn>>,
+    /// Prompt cache breakpoints added to every completion request
+    cache_breakpoints: Vec<CacheBreakpoint>,
+    /// MCP servers whose tools are available to the agent
+    #[cfg(feature = "rmcp")]
+    mcp_clients: Vec<McpClient>,
 }
 
 impl<M> AgentBuilderSimple<M>
@@ -426,6 +614,11 @@ where
             tools: ToolSet::default(),
             tool_choice: None,
             default_max_depth: None,
+            memory: None,
+            context_strategy: None,
+            cache_breakpoints: vec![],
+            #[cfg(feature = "rmcp")]
+            mcp_clients: vec![],
         }
     }
 
@@ -474,36 +667,106 @@ where
     }
 
     /// Add a static tool to the agent
-    pub fn tool(mut self, tool: impl Tool + 'static) -> Self {
+    ///
+    /// # Panics
+    /// If a tool with the same name was already added (see [AgentBuilderSimple::try_tool]).
+    pub fn tool(self, tool: impl Tool + 'static) -> Self {
+        self.try_tool(tool).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add a static tool to the agent. Fails if a tool with the same name was already added.
+    pub fn try_tool(mut self, tool: impl Tool + 'static) -> Result<Self, ToolSetError> {
         let toolname = tool.name();
-        self.tools.add_tool(tool);
+        self.tools.add_tool(tool)?;
         self.static_tools.push(toolname);
-        self
+        Ok(self)
+    }
+
+    /// Add a vector of boxed static tools to the agent
+    ///
+    /// # Panics
+    /// If a tool with the same name was already added (see [AgentBuilderSimple::try_tools]).
+    pub fn tools(self, tools: Vec<Box<dyn ToolDyn>>) -> Self {
+        self.try_tools(tools).unwrap_or_else(|e| panic!("{e}"))
     }
 
-    pub fn tools(mut self, tools: Vec<Box<dyn ToolDyn>>) -> Self {
+    /// Add a vector of boxed static tools to the agent. Fails if a tool with the same name was
+    /// already added, in which case none of the tools is added.
+    pub fn try_tools(mut self, tools: Vec<Box<dyn ToolDyn>>) -> Result<Self, ToolSetError> {
         let toolnames: Vec<String> = tools.iter().map(|tool| tool.name()).collect();
-        let tools = ToolSet::from_tools_boxed(tools);
-        self.tools.add_tools(tools);
+        self.tools.add_tools(ToolSet::from_tools_boxed(tools)?)?;
         self.static_tools.extend(toolnames);
-        self
+        Ok(self)
+    }
+
+    /// Add a vector of boxed static tools to the agent, prefixing their names with the given
+    /// namespace (eg: `github__search`, see [ToolSet::namespaced]). This is useful to add tools
+    /// whose names conflict with the names of other tools.
+    ///
+    /// # Panics
+    /// If a tool with the same (namespaced) name was already added, or if the namespace is
+    /// invalid (see [AgentBuilderSimple::try_namespaced_tools]).
+    pub fn namespaced_tools(self, namespace: &str, tools: Vec<Box<dyn ToolDyn>>) -> Self {
+        self.try_namespaced_tools(namespace, tools)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add a vector of boxed static tools to the agent, prefixing their names with the given
+    /// namespace. Fails if a tool with the same (namespaced) name was already added, or if the
+    /// namespace is invalid (see [ToolSetError::InvalidNamespace]).
+    pub fn try_namespaced_tools(
+        mut self,
+        namespace: &str,
+        tools: Vec<Box<dyn ToolDyn>>,
+    ) -> Result<Self, ToolSetError> {
+        let tools = ToolSet::from_tools_boxed(tools)?.namespaced(namespace)?;
+        let toolnames: Vec<String> = tools.tools.keys().cloned().collect();
+        self.tools.add_tools(tools)?;
+        self.static_tools.extend(toolnames);
+        Ok(self)
     }
 
     /// Add an array of MCP tools (from `rmcp`) to the agent
+    ///
+    /// # Panics
+    /// If a tool with the same name was already added (see
+    /// [AgentBuilderSimple::try_rmcp_tools]).
     #[cfg(feature = "rmcp")]
     #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
     pub fn rmcp_tools(
-        mut self,
+        self,
         tools: Vec<rmcp::model::Tool>,
         client: rmcp::service::ServerSink,
     ) -> Self {
-        for tool in tools {
-            let tool_name = tool.name.to_string();
-            let tool = RmcpTool::from_mcp_server(tool, client.clone());
-            self.static_tools.push(tool_name);
-            self.tools.add_tool(tool);
-        }
+        self.try_rmcp_tools(tools, client)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
 
+    /// Add an array of MCP tools (from `rmcp`) to the agent. Fails if a tool with the same name
+    /// was already added, in which case none of the tools is added.
+    #[cfg(feature = "rmcp")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+    pub fn try_rmcp_tools(
+        self,
+        tools: Vec<rmcp::model::Tool>,
+        client: rmcp::service::ServerSink,
+    ) -> Result<Self, ToolSetError> {
+        self.try_tools(
+            tools
+                .into_iter()
+                .map(|tool| -> Box<dyn ToolDyn> {
+                    Box::new(RmcpTool::from_mcp_server(tool, client.clone()))
+                })
+                .collect(),
+        )
+    }
+
+    /// Add every tool of an MCP server to the agent. The tools are kept in sync with the tool
+    /// list of the server (see [McpClient]).
+    #[cfg(feature = "rmcp")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+    pub fn mcp_client(mut self, client: McpClient) -> Self {
+        self.mcp_clients.push(client);
         self
     }
 
@@ -530,17 +793,72 @@ where
         self
     }
 
+    /// Attach a conversation memory to the agent. Prompt requests that are given a session id
+    /// will load their history from, and persist new messages to, this memory.
+    pub fn memory(mut self, memory: impl ConversationMemory + 'static) -> Self {
+        self.memory = Some(Arc::new(memory));
+        self
+    }
+
+    /// Set the strategy used to fit the chat history in the model's context window.
+    /// It runs every time the agent builds a completion request, including on each turn of a
+    /// multi-turn prompt.
+    pub fn context_strategy(mut self, strategy: impl ContextStrategy + 'static) -> Self {
+        self.context_strategy = Some(Arc::new(strategy));
+        self
+    }
+
+    /// Mark the preamble as cacheable, so that providers supporting prompt caching can reuse it
+    /// across requests.
+    pub fn cache_preamble(mut self) -> Self {
+        if !self.cache_breakpoints.contains(&CacheBreakpoint::Preamble) {
+            self.cache_breakpoints.push(CacheBreakpoint::Preamble);
+        }
+        self
+    }
+
+    /// Mark the tool definitions as cacheable, so that providers supporting prompt caching can
+    /// reuse them across requests.
+    pub fn cache_tools(mut self) -> Self {
+        if !self.cache_breakpoints.contains(&CacheBreakpoint::Tools) {
+            self.cache_breakpoints.push(CacheBreakpoint::Tools);
+        }
+        self
+    }
+
+    /// Mark both the preamble and the tool definitions as cacheable.
+    /// See [CacheBreakpoint] for how each provider caches them.
+    pub fn prompt_caching(self) -> Self {
+        self.cache_preamble().cache_tools()
+    }
+
     /// Add some dynamic tools to the agent. On each prompt, `sample` tools from the
     /// dynamic toolset will be inserted in the request.
+    ///
+    /// # Panics
+    /// If a tool of the toolset has the same name as a tool that was already added (see
+    /// [AgentBuilderSimple::try_dynamic_tools]).
     pub fn dynamic_tools(
-        mut self,
+        self,
         sample: usize,
         dynamic_tools: impl VectorStoreIndexDyn + Send + Sync + 'static,
         toolset: ToolSet,
     ) -> Self {
+        self.try_dynamic_tools(sample, dynamic_tools, toolset)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add some dynamic tools to the agent. Fails if a tool of the toolset has the same name as a
+    /// tool that was already added.
+    pub fn try_dynamic_tools(
+        mut self,
+        sample: usize,
+        dynamic_tools: impl VectorStoreIndexDyn + Send + Sync + 'static,
+        toolset: ToolSet,
+    ) -> Result<Self, ToolSetError> {
+        self.tools.add_tools(toolset)?;
         self.dynamic_tools.push((sample, Box::new(dynamic_tools)));
-        self.tools.add_tools(toolset);
-        self
+        Ok(self)
     }
 
     /// Set the temperature of the model
@@ -563,11 +881,13 @@ where
 
     /// Build the agent
     pub fn build(self) -> Agent<M> {
-        let tool_server_handle = ToolServer::new()
+        let tool_server = ToolServer::new()
             .static_tool_names(self.static_tools)
             .add_tools(self.tools)
-            .add_dynamic_tools(self.dynamic_tools)
-            .run();
+            .add_dynamic_tools(self.dynamic_tools);
+        #[cfg(feature = "rmcp")]
+        let tool_server = tool_server.mcp_clients(self.mcp_clients);
+        let tool_server_handle = tool_server.run();
 
         Agent {
             name: self.name,
@@ -582,6 +902,9 @@ where
             dynamic_context: Arc::new(RwLock::new(self.dynamic_context)),
             tool_server_handle,
             default_max_depth: self.default_max_depth,
+            memory: self.memory,
+            context_strategy: self.context_strategy,
+            cache_breakpoints: self.cache_breakpoints,
         }
     }
 }
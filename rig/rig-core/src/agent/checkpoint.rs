@@ -0,0 +1,240 @@
+//! This module contains the [Checkpoint] type and the [CheckpointSink] trait, which allow a
+//! multi-turn agent run to be persisted after every turn and resumed later on.
+//!
+//! A sink can be attached to a prompt request using
+//! [PromptRequest::with_checkpoint_sink](crate::agent::PromptRequest::with_checkpoint_sink) (or
+//! [StreamingPromptRequest::with_checkpoint_sink](crate::agent::StreamingPromptRequest::with_checkpoint_sink)).
+//! The agent loop then writes a [Checkpoint] to the sink:
+//! - after each model response that contains tool calls (before the tools are executed), and
+//! - after the results of those tool calls have been added to the chat history.
+//!
+//! A run can be resumed from its latest checkpoint using [PromptRequest::from_checkpoint](crate::agent::PromptRequest::from_checkpoint)
+//! (or [StreamingPromptRequest::from_checkpoint](crate::agent::StreamingPromptRequest::from_checkpoint)).
+//! Pending tool calls are executed first, then the loop carries on where it left off.
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     agent::{PromptRequest, checkpoint::JsonFileCheckpointSink},
+//!     completion::Prompt,
+//!     providers::openai,
+//! };
+//!
+//! let openai = openai::Client::from_env();
+//! let agent = openai.agent("gpt-4o").build();
+//!
+//! let sink = JsonFileCheckpointSink::new("run.checkpoint.json");
+//!
+//! let response = match sink.load()? {
+//!     // The previous run died halfway through, pick it back up
+//!     Some(checkpoint) => PromptRequest::from_checkpoint(&agent, checkpoint),
+//!     None => agent.prompt("Research and summarize the latest Rust release."),
+//! }
+//! .multi_turn(20)
+//! .with_checkpoint_sink(sink)
+//! .await?;
+//! ```
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    completion::{Message, Usage},
+    message::ToolCall,
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    /// Json error (e.g.: serialization, deserialization, etc.)
+    #[error("Json error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    /// Io error (e.g.: reading or writing a checkpoint file)
+    #[error("Io error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[cfg(not(target_family = "wasm"))]
+    #[error("Datastore error: {0}")]
+    DatastoreError(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[cfg(target_family = "wasm")]
+    #[error("Datastore error: {0}")]
+    DatastoreError(#[from] Box<dyn std::error::Error + 'static>),
+}
+
+/// A serializable snapshot of a multi-turn agent run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The full chat history of the run so far (including the initial prompt).
+    pub chat_history: Vec<Message>,
+    /// The conversation depth (ie, the number of completion calls) reached so far.
+    pub depth: usize,
+    /// Tool calls requested by the model whose results are not part of the chat history yet.
+    pub pending_tool_calls: Vec<ToolCall>,
+    /// The token usage aggregated over every completion call of the run so far.
+    pub usage: Usage,
+}
+
+/// Trait for checkpoint storage backends.
+pub trait CheckpointSink: WasmCompatSend + WasmCompatSync {
+    /// Store a checkpoint. Every call supersedes the checkpoints previously saved for the run.
+    fn save(
+        &self,
+        checkpoint: &Checkpoint,
+    ) -> impl std::future::Future<Output = Result<(), CheckpointError>> + WasmCompatSend;
+}
+
+/// Wrapper trait to allow for dynamic dispatch of checkpoint sinks
+pub trait CheckpointSinkDyn: WasmCompatSend + WasmCompatSync {
+    fn save<'a>(
+        &'a self,
+        checkpoint: &'a Checkpoint,
+    ) -> WasmBoxedFuture<'a, Result<(), CheckpointError>>;
+}
+
+impl<T: CheckpointSink> CheckpointSinkDyn for T {
+    fn save<'a>(
+        &'a self,
+        checkpoint: &'a Checkpoint,
+    ) -> WasmBoxedFuture<'a, Result<(), CheckpointError>> {
+        Box::pin(<Self as CheckpointSink>::save(self, checkpoint))
+    }
+}
+
+/// A shared, type-erased checkpoint sink as stored on a prompt request.
+pub type DynCheckpointSink = Arc<dyn CheckpointSinkDyn>;
+
+/// Writes `checkpoint` to the sink (if any).
+pub(crate) async fn save_checkpoint(
+    sink: &Option<DynCheckpointSink>,
+    checkpoint: impl FnOnce() -> Checkpoint,
+) -> Result<(), CheckpointError> {
+    if let Some(sink) = sink {
+        sink.save(&checkpoint()).await?;
+    }
+
+    Ok(())
+}
+
+/// [InMemoryCheckpointSink] keeps the latest checkpoint in memory.
+///
+/// Cloning the sink is cheap and all clones share the same checkpoint, so a clone can be kept
+/// around to inspect the checkpoint after (or while) the run is going.
+#[derive(Clone, Default)]
+pub struct InMemoryCheckpointSink {
+    latest: Arc<tokio::sync::RwLock<Option<Checkpoint>>>,
+}
+
+impl InMemoryCheckpointSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the latest checkpoint saved to this sink, if any.
+    pub async fn latest(&self) -> Option<Checkpoint> {
+        self.latest.read().await.clone()
+    }
+}
+
+impl CheckpointSink for InMemoryCheckpointSink {
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<(), CheckpointError> {
+        *self.latest.write().await = Some(checkpoint.clone());
+
+        Ok(())
+    }
+}
+
+/// [JsonFileCheckpointSink] writes the latest checkpoint to a JSON file.
+#[cfg(not(target_family = "wasm"))]
+pub struct JsonFileCheckpointSink {
+    path: std::path::PathBuf,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl JsonFileCheckpointSink {
+    pub fn new(path: impl AsRef<std::path::Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Read the checkpoint stored in the file, if it exists.
+    pub fn load(&self) -> Result<Option<Checkpoint>, CheckpointError> {
+        match std::fs::read(&self.path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Remove the checkpoint file (e.g.: once the run has completed).
+    pub fn clear(&self) -> Result<(), CheckpointError> {
+        match std::fs::remove_file(&self.path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl CheckpointSink for JsonFileCheckpointSink {
+    async fn save(&self, checkpoint: &Checkpoint) -> Result<(), CheckpointError> {
+        // Write to a temporary file first so that a crash never leaves a truncated checkpoint behind
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(checkpoint)?)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Checkpoint, CheckpointSink, JsonFileCheckpointSink};
+    use crate::{
+        OneOrMany,
+        completion::{Message, Usage},
+        message::{AssistantContent, ToolCall, ToolFunction},
+    };
+
+    #[tokio::test]
+    async fn test_json_file_checkpoint_roundtrip() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let sink = JsonFileCheckpointSink::new(dir.path().join("run.json"));
+        assert!(sink.load().unwrap().is_none());
+
+        let tool_call = ToolCall::new(
+            "call_1".to_string(),
+            ToolFunction {
+                name: "add".to_string(),
+                arguments: serde_json::json!({"x": 1, "y": 2}),
+            },
+        );
+        let checkpoint = Checkpoint {
+            chat_history: vec![
+                Message::user("What is 1 + 2?"),
+                Message::Assistant {
+                    id: None,
+                    content: OneOrMany::one(AssistantContent::ToolCall(tool_call.clone())),
+                },
+            ],
+            depth: 1,
+            pending_tool_calls: vec![tool_call],
+            usage: Usage {
+                input_tokens: 10,
+                output_tokens: 5,
+                total_tokens: 15,
+                ..Default::default()
+            },
+        };
+
+        sink.save(&checkpoint).await.unwrap();
+        assert_eq!(sink.load().unwrap(), Some(checkpoint));
+
+        sink.clear().unwrap();
+        assert!(sink.load().unwrap().is_none());
+    }
+}
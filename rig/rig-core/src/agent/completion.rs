@@ -1,17 +1,22 @@
+use super::context::ContextStrategyDyn;
 use super::prompt_request::{self, PromptRequest};
 use crate::{
     agent::prompt_request::streaming::StreamingPromptRequest,
     completion::{
-        Chat, Completion, CompletionError, CompletionModel, CompletionRequestBuilder, Document,
-        GetTokenUsage, Message, Prompt, PromptError,
+        CacheBreakpoint, Chat, Completion, CompletionError, CompletionModel,
+        CompletionRequestBuilder, Document, GetTokenUsage, Message, Prompt, PromptError,
     },
-    message::ToolChoice,
+    extractor::{self, ExtractionError},
+    memory::DynConversationMemory,
+    message::{AssistantContent, ToolChoice},
     streaming::{StreamingChat, StreamingCompletion, StreamingPrompt},
     tool::server::ToolServerHandle,
     vector_store::{VectorStoreError, request::VectorSearchRequest},
     wasm_compat::WasmCompatSend,
 };
 use futures::{StreamExt, TryStreamExt, stream};
+use schemars::{JsonSchema, schema_for};
+use serde::de::DeserializeOwned;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 
@@ -75,6 +80,12 @@ where
     pub tool_choice: Option<ToolChoice>,
     /// Default maximum depth for recursive agent calls
     pub default_max_depth: Option<usize>,
+    /// Conversation memory used to persist chat history across calls
+    pub memory: Option<DynConversationMemory>,
+    /// Strategy deciding which part of the chat history is sent to the model
+    pub context_strategy: Option<Arc<dyn ContextStrategyDyn>>,
+    /// Prompt cache breakpoints added to every completion request
+    pub cache_breakpoints: Vec<CacheBreakpoint>,
 }
 
 impl<M> Agent<M>
@@ -85,6 +96,53 @@ where
     pub(crate) fn name(&self) -> &str {
         self.name.as_deref().unwrap_or(UNKNOWN_AGENT_NAME)
     }
+
+    /// Prompt the agent and deserialize its response into `T`.
+    ///
+    /// If the model natively supports structured outputs (see
+    /// [CompletionModel::supports_output_schema]), the JSON schema of `T` is sent as the output
+    /// schema of the request. Otherwise, the model is required to call a `submit` tool taking `T`
+    /// as its arguments (the same approach used by [Extractor](crate::extractor::Extractor)).
+    ///
+    /// Note: This sends a single completion request. The preamble and context documents of the
+    /// agent are used, but its tools are not made available to the model.
+    pub async fn prompt_typed<T>(
+        &self,
+        prompt: impl Into<Message> + WasmCompatSend,
+    ) -> Result<T, ExtractionError>
+    where
+        T: JsonSchema + DeserializeOwned,
+    {
+        let mut request = self.completion(prompt, vec![]).await?.build();
+        request.tools.clear();
+        request.tool_choice = None;
+
+        if !self.model.supports_output_schema() {
+            request.tools.push(extractor::submit_tool_definition::<T>());
+            request.tool_choice = Some(ToolChoice::Required);
+
+            let response = self.model.completion(request).await?;
+            return extractor::parse_submit_call(response.choice);
+        }
+
+        request.output_schema = Some(schema_for!(T));
+        let response = self.model.completion(request).await?;
+
+        let text = response
+            .choice
+            .into_iter()
+            .filter_map(|content| match content {
+                AssistantContent::Text(text) => Some(text.text),
+                _ => None,
+            })
+            .collect::<String>();
+
+        if text.trim().is_empty() {
+            return Err(ExtractionError::NoData);
+        }
+
+        Ok(serde_json::from_str(&text)?)
+    }
 }
 
 impl<M> Completion<M> for Agent<M>
@@ -107,6 +165,11 @@ where
                 .find_map(|message| message.rag_text())
         });
 
+        let chat_history = match &self.context_strategy {
+            Some(strategy) => strategy.apply(chat_history, &prompt).await?,
+            None => chat_history,
+        };
+
         let completion_request = self
             .model
             .completion_request(prompt)
@@ -114,7 +177,8 @@ where
             .temperature_opt(self.temperature)
             .max_tokens_opt(self.max_tokens)
             .additional_params_opt(self.additional_params.clone())
-            .documents(self.static_context.clone());
+            .documents(self.static_context.clone())
+            .cache_breakpoints(self.cache_breakpoints.clone());
         let completion_request = if let Some(preamble) = &self.preamble {
             completion_request.preamble(preamble.to_owned())
         } else {
@@ -281,3 +345,82 @@ where
         StreamingPromptRequest::new(arc, prompt).with_history(chat_history)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use crate::{
+        agent::AgentBuilder,
+        completion::{CompletionError, CompletionModel},
+        message::{AssistantContent, ToolChoice},
+        test_utils::ScriptedModel,
+    };
+
+    #[derive(Debug, PartialEq, Deserialize, JsonSchema)]
+    struct Movie {
+        title: String,
+        year: u32,
+    }
+
+    #[tokio::test]
+    async fn test_prompt_typed_with_output_schema() {
+        let model = ScriptedModel::new([AssistantContent::text(
+            r#"{"title": "Spirited Away", "year": 2001}"#,
+        )])
+        .with_output_schema_support();
+        let agent = AgentBuilder::new(model.clone()).build();
+
+        let movie: Movie = agent.prompt_typed("Spirited Away").await.unwrap();
+        assert_eq!(movie.title, "Spirited Away");
+        assert_eq!(movie.year, 2001);
+
+        let request = &model.requests.lock().unwrap()[0];
+        assert!(request.output_schema.is_some());
+        assert!(request.tools.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prompt_typed_falls_back_to_submit_tool() {
+        let model = ScriptedModel::new([AssistantContent::tool_call(
+            "call_1",
+            "submit",
+            json!({"title": "Spirited Away", "year": 2001}),
+        )]);
+        let agent = AgentBuilder::new(model.clone()).build();
+
+        let movie: Movie = agent.prompt_typed("Spirited Away").await.unwrap();
+        assert_eq!(
+            movie,
+            Movie {
+                title: "Spirited Away".to_string(),
+                year: 2001
+            }
+        );
+
+        // The schema is sent as the submit tool the model is required to call
+        let request = &model.requests.lock().unwrap()[0];
+        assert!(request.output_schema.is_none());
+        assert_eq!(request.tools.len(), 1);
+        assert_eq!(request.tools[0].name, "submit");
+        assert_eq!(
+            request.tools[0].parameters["properties"]["year"]["type"],
+            "integer"
+        );
+        assert_eq!(request.tool_choice, Some(ToolChoice::Required));
+    }
+
+    #[tokio::test]
+    async fn test_output_schema_is_rejected_by_unsupported_models() {
+        let model = ScriptedModel::new([AssistantContent::text("{}")]);
+        let result = model
+            .completion_request("Spirited Away")
+            .output_schema(schemars::schema_for!(Movie))
+            .send()
+            .await;
+        assert!(matches!(result, Err(CompletionError::RequestError(_))));
+        assert!(model.requests.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,520 @@
+//! Context window management for the agent loop.
+//!
+//! A [ContextStrategy] can be attached to an agent using
+//! [AgentBuilder::context_strategy](crate::agent::AgentBuilder::context_strategy). It runs every
+//! time the agent builds a completion request (i.e.: before each `completion` or
+//! `stream_completion` call of the agent loop) and decides which part of the chat history is
+//! sent to the model. The caller's chat history itself is never modified.
+//!
+//! Strategies only ever cut the history at the start of a "turn", i.e.: at a user message that is
+//! not a tool result. This guarantees that a `ToolCall` is never separated from its matching
+//! `ToolResult`. When the prompt is a tool result (i.e.: the agent is in the middle of a tool
+//! turn), the ongoing turn is always kept.
+//!
+//! Token budgets are checked with a [TokenCounter], the [model's](CompletionModel::token_counter)
+//! one by default.
+//!
+//! Rig ships with the following strategies:
+//! - [DropOldestTurns]: drops the oldest turns until the history fits in a token budget.
+//! - [KeepLastTurns]: only keeps the last `n` turns (the preamble is always sent).
+//! - [SummarizeOlderTurns]: replaces older turns with a summary written by a given model.
+use crate::{
+    OneOrMany,
+    completion::{CompletionError, CompletionModel, Message},
+    message::{AssistantContent, ToolResultContent, UserContent},
+    tokens::{BpeEstimator, CharEstimator, TokenCounter},
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
+use std::sync::Arc;
+
+/// Returns a rough, provider-agnostic estimate of the number of tokens a message will use.
+///
+/// Text is counted at roughly 4 characters per token, tool call arguments are counted through
+/// their JSON representation and non-text content (images, audio, video, binary documents) is
+/// counted as a flat amount. See [CharEstimator].
+pub fn estimate_tokens(message: &Message) -> u64 {
+    CharEstimator::default().count_message(message)
+}
+
+/// Returns the estimated number of tokens of a list of messages (see [estimate_tokens]).
+pub fn estimate_history_tokens(messages: &[Message]) -> u64 {
+    messages.iter().map(estimate_tokens).sum()
+}
+
+/// Returns the indices at which the history can be cut without separating a tool call from its
+/// result, i.e.: the indices of user messages that do not contain any tool result.
+fn turn_starts(history: &[Message]) -> Vec<usize> {
+    history
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, message)| match message {
+            Message::User { content }
+                if !content
+                    .iter()
+                    .any(|c| matches!(c, UserContent::ToolResult(_))) =>
+            {
+                Some(idx)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the lowest index the history can be cut at while keeping the ongoing turn when
+/// `prompt` is a tool result: the tool calls it answers must be sent along with it.
+fn ongoing_turn_start(history: &[Message], prompt: &Message) -> usize {
+    let is_tool_result = match prompt {
+        Message::User { content } => content
+            .iter()
+            .any(|c| matches!(c, UserContent::ToolResult(_))),
+        Message::Assistant { .. } => false,
+    };
+
+    if is_tool_result {
+        turn_starts(history).last().copied().unwrap_or(0)
+    } else {
+        history.len()
+    }
+}
+
+/// Trait for strategies deciding which part of the chat history is sent to the model.
+pub trait ContextStrategy: WasmCompatSend + WasmCompatSync {
+    /// Returns the chat history to send to the model along with `prompt`.
+    /// `history` does not contain `prompt`.
+    fn apply(
+        &self,
+        history: Vec<Message>,
+        prompt: &Message,
+    ) -> impl std::future::Future<Output = Result<Vec<Message>, CompletionError>> + WasmCompatSend;
+}
+
+/// Wrapper trait to allow for dynamic dispatch of context strategies
+pub trait ContextStrategyDyn: WasmCompatSend + WasmCompatSync {
+    fn apply<'a>(
+        &'a self,
+        history: Vec<Message>,
+        prompt: &'a Message,
+    ) -> WasmBoxedFuture<'a, Result<Vec<Message>, CompletionError>>;
+}
+
+impl<T: ContextStrategy> ContextStrategyDyn for T {
+    fn apply<'a>(
+        &'a self,
+        history: Vec<Message>,
+        prompt: &'a Message,
+    ) -> WasmBoxedFuture<'a, Result<Vec<Message>, CompletionError>> {
+        Box::pin(<Self as ContextStrategy>::apply(self, history, prompt))
+    }
+}
+
+/// Drops the oldest turns of the chat history until the estimated number of tokens of the
+/// history and prompt fits in `max_tokens`.
+///
+/// If even the latest turn does not fit, only the latest turn is kept.
+#[derive(Clone)]
+pub struct DropOldestTurns {
+    max_tokens: u64,
+    counter: Arc<dyn TokenCounter>,
+}
+
+impl DropOldestTurns {
+    /// Counts tokens with [BpeEstimator], the default counter of every model. Use
+    /// [DropOldestTurns::for_model] to count them with a model's own counter instead.
+    pub fn new(max_tokens: u64) -> Self {
+        Self {
+            max_tokens,
+            counter: Arc::new(BpeEstimator),
+        }
+    }
+
+    /// Counts tokens with the [token counter](CompletionModel::token_counter) of `model`.
+    pub fn for_model<M>(model: &M, max_tokens: u64) -> Self
+    where
+        M: CompletionModel,
+    {
+        Self {
+            max_tokens,
+            counter: model.token_counter(),
+        }
+    }
+
+    /// Set the [TokenCounter] used to check the history against the budget.
+    pub fn token_counter(mut self, counter: impl TokenCounter + 'static) -> Self {
+        self.counter = Arc::new(counter);
+        self
+    }
+}
+
+impl std::fmt::Debug for DropOldestTurns {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DropOldestTurns")
+            .field("max_tokens", &self.max_tokens)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ContextStrategy for DropOldestTurns {
+    async fn apply(
+        &self,
+        mut history: Vec<Message>,
+        prompt: &Message,
+    ) -> Result<Vec<Message>, CompletionError> {
+        let prompt_tokens = self.counter.count_message(prompt);
+        if self.counter.count_messages(&history) + prompt_tokens <= self.max_tokens {
+            return Ok(history);
+        }
+
+        let starts = turn_starts(&history);
+        let Some(&latest) = starts.last() else {
+            return Ok(history);
+        };
+
+        let cut = starts
+            .into_iter()
+            .find(|&start| {
+                self.counter.count_messages(&history[start..]) + prompt_tokens <= self.max_tokens
+            })
+            .unwrap_or_else(|| {
+                tracing::warn!(
+                    "Latest turn does not fit in the context budget of {} tokens",
+                    self.max_tokens
+                );
+                latest
+            })
+            .min(ongoing_turn_start(&history, prompt));
+
+        Ok(history.split_off(cut))
+    }
+}
+
+/// Only keeps the last `n` turns of the chat history.
+///
+/// The preamble and static context documents are not part of the chat history, so they are always sent.
+/// The ongoing turn is always kept when the prompt is a tool result, even if `n` is 0.
+#[derive(Debug, Clone)]
+pub struct KeepLastTurns {
+    n: usize,
+}
+
+impl KeepLastTurns {
+    pub fn new(n: usize) -> Self {
+        Self { n }
+    }
+}
+
+impl ContextStrategy for KeepLastTurns {
+    async fn apply(
+        &self,
+        mut history: Vec<Message>,
+        prompt: &Message,
+    ) -> Result<Vec<Message>, CompletionError> {
+        let starts = turn_starts(&history);
+        if starts.len() <= self.n {
+            return Ok(history);
+        }
+
+        let cut = if self.n == 0 {
+            history.len()
+        } else {
+            starts[starts.len() - self.n]
+        };
+
+        Ok(history.split_off(cut.min(ongoing_turn_start(&history, prompt))))
+    }
+}
+
+const DEFAULT_SUMMARY_PREAMBLE: &str = "\
+    You summarize conversations between a user and an AI assistant. \
+    Write a concise summary of the conversation you are given, keeping every fact, decision, \
+    tool result and open question that could be needed to continue the conversation. \
+    Only output the summary.";
+
+/// Once the estimated number of tokens of the history and prompt exceeds `max_tokens`, replaces
+/// all but the last `keep_last_turns` turns with a summary written by `model`. Tokens are counted
+/// with the [token counter](CompletionModel::token_counter) of `model` unless another one is set.
+///
+/// The summary is prepended to the first kept user message. Note that the summary is recomputed
+/// every time the history exceeds the budget, so `max_tokens` should leave some headroom.
+#[derive(Clone)]
+pub struct SummarizeOlderTurns<M>
+where
+    M: CompletionModel,
+{
+    model: M,
+    max_tokens: u64,
+    keep_last_turns: usize,
+    preamble: String,
+    counter: Arc<dyn TokenCounter>,
+}
+
+impl<M> SummarizeOlderTurns<M>
+where
+    M: CompletionModel,
+{
+    pub fn new(model: M, max_tokens: u64) -> Self {
+        let counter = model.token_counter();
+        Self {
+            model,
+            max_tokens,
+            keep_last_turns: 1,
+            preamble: DEFAULT_SUMMARY_PREAMBLE.to_string(),
+            counter,
+        }
+    }
+
+    /// Set the number of most recent turns that are never summarized (1 by default).
+    pub fn keep_last_turns(mut self, keep_last_turns: usize) -> Self {
+        self.keep_last_turns = keep_last_turns.max(1);
+        self
+    }
+
+    /// Set the system prompt used when asking the model for a summary.
+    pub fn preamble(mut self, preamble: &str) -> Self {
+        self.preamble = preamble.to_string();
+        self
+    }
+
+    /// Set the [TokenCounter] used to check the history against the budget.
+    pub fn token_counter(mut self, counter: impl TokenCounter + 'static) -> Self {
+        self.counter = Arc::new(counter);
+        self
+    }
+
+    fn transcript(messages: &[Message]) -> String {
+        let mut transcript = String::new();
+
+        for message in messages {
+            match message {
+                Message::User { content } => {
+                    for content in content.iter() {
+                        match content {
+                            UserContent::Text(text) => {
+                                transcript.push_str(&format!("User: {}\n", text.text))
+                            }
+                            UserContent::ToolResult(result) => {
+                                for content in result.content.iter() {
+                                    if let ToolResultContent::Text(text) = content {
+                                        transcript.push_str(&format!(
+                                            "Tool result ({}): {}\n",
+                                            result.id, text.text
+                                        ));
+                                    }
+                                }
+                            }
+                            _ => transcript.push_str("User: <attachment>\n"),
+                        }
+                    }
+                }
+                Message::Assistant { content, .. } => {
+                    for content in content.iter() {
+                        match content {
+                            AssistantContent::Text(text) => {
+                                transcript.push_str(&format!("Assistant: {}\n", text.text))
+                            }
+                            AssistantContent::ToolCall(tool_call) => transcript.push_str(&format!(
+                                "Assistant called tool {} ({}) with arguments: {}\n",
+                                tool_call.function.name, tool_call.id, tool_call.function.arguments
+                            )),
+                            AssistantContent::Reasoning(_) | AssistantContent::Image(_) => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        transcript
+    }
+}
+
+impl<M> ContextStrategy for SummarizeOlderTurns<M>
+where
+    M: CompletionModel,
+{
+    async fn apply(
+        &self,
+        mut history: Vec<Message>,
+        prompt: &Message,
+    ) -> Result<Vec<Message>, CompletionError> {
+        if self.counter.count_messages(&history) + self.counter.count_message(prompt)
+            <= self.max_tokens
+        {
+            return Ok(history);
+        }
+
+        let starts = turn_starts(&history);
+        if starts.len() < self.keep_last_turns {
+            return Ok(history);
+        }
+
+        let cut =
+            starts[starts.len() - self.keep_last_turns].min(ongoing_turn_start(&history, prompt));
+        if cut == 0 {
+            return Ok(history);
+        }
+
+        let mut kept = history.split_off(cut);
+        let response = self
+            .model
+            .completion_request(Message::user(Self::transcript(&history)))
+            .preamble(self.preamble.clone())
+            .send()
+            .await?;
+
+        let summary = response
+            .choice
+            .iter()
+            .filter_map(|content| match content {
+                AssistantContent::Text(text) => Some(text.text.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let summary = UserContent::text(format!("Summary of the earlier conversation:\n{summary}"));
+
+        // `cut` is a turn start, so the first kept message is always a plain user message
+        match kept.first_mut() {
+            Some(Message::User { content }) => content.insert(0, summary),
+            _ => kept.insert(
+                0,
+                Message::User {
+                    content: OneOrMany::one(summary),
+                },
+            ),
+        }
+
+        Ok(kept)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ContextStrategy, DropOldestTurns, KeepLastTurns, estimate_history_tokens, estimate_tokens,
+        turn_starts,
+    };
+    use crate::{
+        OneOrMany,
+        completion::Message,
+        message::{AssistantContent, ToolCall, ToolFunction},
+        tokens::{CharEstimator, TokenCounter},
+    };
+
+    /// Counts every message as 100 tokens
+    struct FlatCounter;
+
+    impl TokenCounter for FlatCounter {
+        fn count_text(&self, _text: &str) -> u64 {
+            0
+        }
+
+        fn message_overhead(&self) -> u64 {
+            100
+        }
+    }
+
+    fn tool_turn(question: &str) -> Vec<Message> {
+        vec![
+            Message::user(question),
+            Message::Assistant {
+                id: None,
+                content: OneOrMany::one(AssistantContent::ToolCall(ToolCall::new(
+                    "call_1".to_string(),
+                    ToolFunction::new("lookup".to_string(), serde_json::json!({"q": question})),
+                ))),
+            },
+            Message::tool_result("call_1", "result"),
+            Message::assistant("answer"),
+        ]
+    }
+
+    #[test]
+    fn test_turn_starts_skip_tool_results() {
+        let history = [tool_turn("first"), tool_turn("second")].concat();
+        assert_eq!(turn_starts(&history), vec![0, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_keep_last_turns() {
+        let history = [tool_turn("first"), tool_turn("second"), tool_turn("third")].concat();
+        let kept = KeepLastTurns::new(2)
+            .apply(history.clone(), &Message::user("prompt"))
+            .await
+            .unwrap();
+
+        assert_eq!(kept, history[4..].to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_keep_last_turns_keeps_ongoing_tool_turn() {
+        let mut history = tool_turn("first");
+        history.extend(tool_turn("second").into_iter().take(2));
+        // The agent is sending the result of the tool called in the second turn
+        let prompt = Message::tool_result("call_1", "result");
+
+        for n in [0, 1] {
+            let kept = KeepLastTurns::new(n)
+                .apply(history.clone(), &prompt)
+                .await
+                .unwrap();
+            assert_eq!(kept, history[4..].to_vec());
+        }
+
+        // A new turn does not need any history
+        let kept = KeepLastTurns::new(0)
+            .apply(history.clone(), &Message::user("prompt"))
+            .await
+            .unwrap();
+        assert!(kept.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_turns_never_splits_tool_calls() {
+        let history = [tool_turn("first"), tool_turn("second")].concat();
+        let prompt = Message::user("prompt");
+        let latest_turn_tokens = estimate_history_tokens(&history[4..]) + estimate_tokens(&prompt);
+
+        // Budget fits the latest turn plus one more message, but never a partial turn
+        let kept = DropOldestTurns::new(latest_turn_tokens + 10)
+            .token_counter(CharEstimator::default())
+            .apply(history.clone(), &prompt)
+            .await
+            .unwrap();
+        assert_eq!(kept, history[4..].to_vec());
+
+        // Everything fits
+        let kept = DropOldestTurns::new(u64::MAX)
+            .apply(history.clone(), &prompt)
+            .await
+            .unwrap();
+        assert_eq!(kept, history);
+
+        // Nothing fits, the latest turn is kept anyway
+        let kept = DropOldestTurns::new(1)
+            .apply(history.clone(), &prompt)
+            .await
+            .unwrap();
+        assert_eq!(kept, history[4..].to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_turns_uses_token_counter() {
+        let history = [tool_turn("first"), tool_turn("second")].concat();
+        let prompt = Message::user("prompt");
+
+        // 9 messages of 100 tokens don't fit, the latest turn and prompt (500 tokens) do
+        let kept = DropOldestTurns::new(899)
+            .token_counter(FlatCounter)
+            .apply(history.clone(), &prompt)
+            .await
+            .unwrap();
+        assert_eq!(kept, history[4..].to_vec());
+
+        let kept = DropOldestTurns::new(900)
+            .token_counter(FlatCounter)
+            .apply(history.clone(), &prompt)
+            .await
+            .unwrap();
+        assert_eq!(kept, history);
+    }
+}
@@ -106,17 +106,27 @@
 //! let response = agent.prompt("What does \"glarb-glarb\" mean?").await
 //!     .expect("Failed to prompt the agent");
 //! ```
+pub mod budget;
 mod builder;
+pub mod checkpoint;
 mod completion;
+pub mod context;
+pub mod plan;
 pub(crate) mod prompt_request;
+pub mod team;
 mod tool;
 
 pub use crate::message::Text;
+pub use budget::{Budget, BudgetLimit, ModelPricing};
 pub use builder::{AgentBuilder, AgentBuilderSimple};
+pub use checkpoint::{Checkpoint, CheckpointSink};
 pub use completion::Agent;
+pub use context::{
+    ContextStrategy, DropOldestTurns, KeepLastTurns, SummarizeOlderTurns, estimate_tokens,
+};
 pub use prompt_request::streaming::{
     FinalResponse, MultiTurnStreamItem, StreamingError, StreamingPromptRequest, StreamingResult,
     stream_to_stdout,
 };
-pub use prompt_request::{CancelSignal, PromptRequest, PromptResponse};
+pub use prompt_request::{CancelSignal, PromptRequest, PromptResponse, ToolCallDecision};
 pub use prompt_request::{PromptHook, StreamingPromptHook};
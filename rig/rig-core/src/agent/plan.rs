@@ -0,0 +1,466 @@
+//! This module contains [PlanAndExecute], a runner which first asks a model for an explicit plan
+//! and then executes its steps one by one with an agent.
+//!
+//! The plan is extracted with an [Extractor] (see [PLANNER_PREAMBLE] for a suitable preamble).
+//! Each step is then sent to the executor agent, which goes through the usual multi-turn tool
+//! loop. If one of the tools called during a step returns an error, the step is stopped and the
+//! planner is asked for a new plan covering the remaining work (up to `max_replans` times).
+//! Progress can be observed through a [PlanHook].
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     agent::plan::{PLANNER_PREAMBLE, Plan, PlanAndExecute},
+//!     client::{CompletionClient, ProviderClient},
+//!     providers::openai,
+//! };
+//!
+//! let openai = openai::Client::from_env();
+//!
+//! let planner = openai
+//!     .extractor::<Plan>("gpt-4o")
+//!     .preamble(PLANNER_PREAMBLE)
+//!     .build();
+//!
+//! let executor = openai
+//!     .agent("gpt-4o")
+//!     .preamble("You are a research assistant.")
+//!     .tool(WebSearch)
+//!     .build();
+//!
+//! let response = PlanAndExecute::new(planner, executor)
+//!     .max_replans(2)
+//!     .run("Compare the release notes of the last two Rust versions.")
+//!     .await?;
+//!
+//! for step in &response.steps {
+//!     println!("{}: {}", step.step.description, step.output);
+//! }
+//! ```
+use std::sync::{Arc, Mutex};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    completion::{CompletionModel, Prompt, PromptError, Usage},
+    extractor::{ExtractionError, Extractor},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+use super::{Agent, CancelSignal, PromptHook};
+
+/// A preamble suitable for the planner of a [PlanAndExecute] runner.
+pub const PLANNER_PREAMBLE: &str = "
+You are a planner. Break the given task down into a short list of concrete steps that can be
+executed one after the other by an assistant with access to tools. Each step should be
+self-contained and describe what needs to be done, not how the previous steps went.
+If you are given the steps that have already been completed and a step that failed, only plan
+the remaining work. Do not return text, only return a tool call.
+";
+
+/// A plan, as extracted by the planner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Plan {
+    /// The steps to execute, in order.
+    pub steps: Vec<PlanStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PlanStep {
+    /// What needs to be done in this step.
+    pub description: String,
+}
+
+/// A step that has been executed along with the response of the executor.
+#[derive(Debug, Clone)]
+pub struct StepResult {
+    pub step: PlanStep,
+    pub output: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlanResponse {
+    /// The response of the executor to the last step of the plan.
+    pub output: String,
+    /// Every step that has been executed, in order (across re-plans).
+    pub steps: Vec<StepResult>,
+    /// The number of times the task has been re-planned.
+    pub replans: usize,
+    /// The token usage aggregated over every planner call and every step executed.
+    pub total_usage: Usage,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlanError {
+    /// Something went wrong while extracting the plan
+    #[error("ExtractionError: {0}")]
+    ExtractionError(#[from] ExtractionError),
+
+    /// Something went wrong while executing a step
+    #[error("PromptError: {0}")]
+    PromptError(#[from] PromptError),
+
+    /// The planner returned a plan without any step
+    #[error("The planner returned an empty plan")]
+    EmptyPlan,
+
+    /// A step failed and the task cannot be re-planned anymore
+    #[error("Step \"{step}\" failed: {error}")]
+    StepFailed { step: String, error: String },
+}
+
+/// Trait for hooks observing the progress of a [PlanAndExecute] run.
+pub trait PlanHook: Clone + WasmCompatSend + WasmCompatSync {
+    #[allow(unused_variables)]
+    /// Called when a plan (or a new plan, after a step failed) has been extracted.
+    fn on_plan(&self, plan: &Plan) -> impl Future<Output = ()> + WasmCompatSend {
+        async {}
+    }
+
+    #[allow(unused_variables)]
+    /// Called before a step of the current plan is executed.
+    fn on_step_start(
+        &self,
+        index: usize,
+        step: &PlanStep,
+    ) -> impl Future<Output = ()> + WasmCompatSend {
+        async {}
+    }
+
+    #[allow(unused_variables)]
+    /// Called after a step of the current plan has been executed successfully.
+    fn on_step_finish(
+        &self,
+        index: usize,
+        step: &PlanStep,
+        output: &str,
+    ) -> impl Future<Output = ()> + WasmCompatSend {
+        async {}
+    }
+
+    #[allow(unused_variables)]
+    /// Called when a tool returned an error while executing a step of the current plan.
+    fn on_step_failed(
+        &self,
+        index: usize,
+        step: &PlanStep,
+        error: &str,
+    ) -> impl Future<Output = ()> + WasmCompatSend {
+        async {}
+    }
+}
+
+impl PlanHook for () {}
+
+/// Runs a task by extracting a plan and executing its steps with an agent.
+pub struct PlanAndExecute<M, H = ()>
+where
+    M: CompletionModel,
+    H: PlanHook,
+{
+    planner: Extractor<M, Plan>,
+    executor: Agent<M>,
+    max_replans: usize,
+    max_turns_per_step: usize,
+    hook: Option<H>,
+}
+
+impl<M> PlanAndExecute<M, ()>
+where
+    M: CompletionModel,
+{
+    pub fn new(planner: Extractor<M, Plan>, executor: Agent<M>) -> Self {
+        Self {
+            planner,
+            executor,
+            max_replans: 2,
+            max_turns_per_step: 5,
+            hook: None,
+        }
+    }
+}
+
+impl<M, H> PlanAndExecute<M, H>
+where
+    M: CompletionModel,
+    H: PlanHook,
+{
+    /// Set how many times the task can be re-planned after a step failed (2 by default).
+    pub fn max_replans(mut self, max_replans: usize) -> Self {
+        self.max_replans = max_replans;
+        self
+    }
+
+    /// Set the maximum depth of the tool loop used to execute a single step (5 by default).
+    pub fn max_turns_per_step(mut self, max_turns_per_step: usize) -> Self {
+        self.max_turns_per_step = max_turns_per_step;
+        self
+    }
+
+    /// Attach a hook observing the progress of the run.
+    pub fn with_hook<H2>(self, hook: H2) -> PlanAndExecute<M, H2>
+    where
+        H2: PlanHook,
+    {
+        PlanAndExecute {
+            planner: self.planner,
+            executor: self.executor,
+            max_replans: self.max_replans,
+            max_turns_per_step: self.max_turns_per_step,
+            hook: Some(hook),
+        }
+    }
+
+    /// Plan the given task and execute it.
+    pub async fn run(&self, task: impl Into<String>) -> Result<PlanResponse, PlanError> {
+        let task = task.into();
+
+        let mut steps: Vec<StepResult> = Vec::new();
+        let mut replans = 0;
+        let mut usage = Usage::new();
+
+        let planned = self.planner.extract_with_usage(task.as_str()).await?;
+        usage += planned.usage;
+        let mut plan = planned.data;
+
+        loop {
+            if plan.steps.is_empty() {
+                return Err(PlanError::EmptyPlan);
+            }
+
+            if let Some(ref hook) = self.hook {
+                hook.on_plan(&plan).await;
+            }
+
+            let mut failure = None;
+
+            for (index, step) in plan.steps.iter().enumerate() {
+                if let Some(ref hook) = self.hook {
+                    hook.on_step_start(index, step).await;
+                }
+
+                let step_hook = StepHook::default();
+                let result = self
+                    .executor
+                    .prompt(step_prompt(&task, &steps, step))
+                    .multi_turn(self.max_turns_per_step)
+                    .with_hook(step_hook.clone())
+                    .extended_details()
+                    .await;
+
+                let tool_error = step_hook.error.lock().expect("lock poisoned").take();
+
+                match (result, tool_error) {
+                    (_, Some(error)) => {
+                        tracing::warn!("Step \"{}\" failed: {error}", step.description);
+                        if let Some(ref hook) = self.hook {
+                            hook.on_step_failed(index, step, &error).await;
+                        }
+                        failure = Some((step.clone(), error));
+                        break;
+                    }
+                    (Ok(response), None) => {
+                        usage += response.total_usage;
+                        if let Some(ref hook) = self.hook {
+                            hook.on_step_finish(index, step, &response.output).await;
+                        }
+                        steps.push(StepResult {
+                            step: step.clone(),
+                            output: response.output,
+                        });
+                    }
+                    (Err(e), None) => return Err(e.into()),
+                }
+            }
+
+            let Some((failed_step, error)) = failure else {
+                let output = steps
+                    .last()
+                    .map(|step| step.output.clone())
+                    .unwrap_or_default();
+
+                return Ok(PlanResponse {
+                    output,
+                    steps,
+                    replans,
+                    total_usage: usage,
+                });
+            };
+
+            if replans >= self.max_replans {
+                return Err(PlanError::StepFailed {
+                    step: failed_step.description,
+                    error,
+                });
+            }
+
+            replans += 1;
+            tracing::info!("Re-planning ({replans}/{})", self.max_replans);
+
+            let planned = self
+                .planner
+                .extract_with_usage(replan_prompt(&task, &steps, &failed_step, &error))
+                .await?;
+            usage += planned.usage;
+            plan = planned.data;
+        }
+    }
+}
+
+/// Prompt hook stopping a step as soon as one of its tools returns an error.
+#[derive(Clone, Default)]
+struct StepHook {
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl<M> PromptHook<M> for StepHook
+where
+    M: CompletionModel,
+{
+    async fn on_tool_error(
+        &self,
+        tool_name: &str,
+        _tool_call_id: Option<String>,
+        _args: &str,
+        error: &str,
+        cancel_sig: CancelSignal,
+    ) {
+        let error = format!("Tool {tool_name} returned an error: {error}");
+        cancel_sig.cancel_with_reason(&error);
+        cancel_sig.cancel();
+        self.error
+            .lock()
+            .expect("lock poisoned")
+            .get_or_insert(error);
+    }
+}
+
+fn completed_steps(steps: &[StepResult]) -> String {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            format!(
+                "{}. {}\n   Result: {}",
+                i + 1,
+                step.step.description,
+                step.output
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn step_prompt(task: &str, steps: &[StepResult], step: &PlanStep) -> String {
+    let mut prompt = format!("Overall task: {task}\n\n");
+    if !steps.is_empty() {
+        prompt.push_str(&format!("Completed steps:\n{}\n\n", completed_steps(steps)));
+    }
+    prompt.push_str(&format!(
+        "Current step: {}\n\nComplete the current step only.",
+        step.description
+    ));
+    prompt
+}
+
+fn replan_prompt(task: &str, steps: &[StepResult], failed_step: &PlanStep, error: &str) -> String {
+    let mut prompt = format!("Task: {task}\n\n");
+    if !steps.is_empty() {
+        prompt.push_str(&format!("Completed steps:\n{}\n\n", completed_steps(steps)));
+    }
+    prompt.push_str(&format!(
+        "The step \"{}\" failed with the following error: {error}\n\n\
+        Write a new plan for the remaining work.",
+        failed_step.description
+    ));
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::{Plan, PlanAndExecute};
+    use crate::{
+        agent::AgentBuilder,
+        completion::ToolDefinition,
+        extractor::ExtractorBuilder,
+        message::AssistantContent,
+        test_utils::{SCRIPTED_USAGE, ScriptedModel},
+        tool::Tool,
+    };
+
+    #[derive(Deserialize)]
+    struct FetchArgs {
+        url: String,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("Could not resolve {0}")]
+    struct FetchError(String);
+
+    struct Fetch;
+
+    impl Tool for Fetch {
+        const NAME: &'static str = "fetch";
+        type Error = FetchError;
+        type Args = FetchArgs;
+        type Output = String;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: Self::NAME.to_string(),
+                description: "Fetch a web page".to_string(),
+                parameters: json!({"type": "object", "properties": {"url": {"type": "string"}}}),
+            }
+        }
+
+        async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+            Err(FetchError(args.url))
+        }
+    }
+
+    fn submit(steps: &[&str]) -> AssistantContent {
+        let steps = steps
+            .iter()
+            .map(|description| json!({ "description": description }))
+            .collect::<Vec<_>>();
+        AssistantContent::tool_call("call_plan", "submit", json!({ "steps": steps }))
+    }
+
+    #[tokio::test]
+    async fn test_plan_and_execute_replans_on_tool_error() {
+        let planner = ExtractorBuilder::<ScriptedModel, Plan>::new(ScriptedModel::new([
+            submit(&["Fetch the docs", "Summarize them"]),
+            submit(&["Summarize from memory"]),
+        ]))
+        .build();
+
+        let executor = AgentBuilder::new(ScriptedModel::new([
+            AssistantContent::tool_call("call_1", "fetch", json!({"url": "docs.rs"})),
+            AssistantContent::text("Rig is a Rust library for LLM applications."),
+        ]))
+        .tool(Fetch)
+        .build();
+
+        let response = PlanAndExecute::new(planner, executor)
+            .run("Summarize the docs of rig")
+            .await
+            .unwrap();
+
+        assert_eq!(response.replans, 1);
+        assert_eq!(response.steps.len(), 1);
+        assert_eq!(response.steps[0].step.description, "Summarize from memory");
+        assert_eq!(
+            response.output,
+            "Rig is a Rust library for LLM applications."
+        );
+        // Both planner calls and the successful step are accounted for
+        assert_eq!(
+            response.total_usage.total_tokens,
+            3 * SCRIPTED_USAGE.total_tokens
+        );
+    }
+}
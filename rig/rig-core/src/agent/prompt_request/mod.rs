@@ -3,10 +3,11 @@ pub mod streaming;
 pub use streaming::StreamingPromptHook;
 
 use std::{
+    collections::HashMap,
     future::IntoFuture,
     marker::PhantomData,
     sync::{
-        Arc, OnceLock,
+        Arc, Mutex, OnceLock,
         atomic::{AtomicBool, AtomicU64, Ordering},
     },
 };
@@ -15,16 +16,26 @@ use tracing::{Instrument, span::Id};
 use futures::{StreamExt, stream};
 use tracing::info_span;
 
+use crate::telemetry::gen_ai_span;
 use crate::{
     OneOrMany,
     completion::{Completion, CompletionModel, Message, PromptError, Usage},
     json_utils,
-    message::{AssistantContent, UserContent},
-    tool::ToolSetError,
+    memory::{DynConversationMemory, MemoryError},
+    message::{AssistantContent, ToolCall, UserContent},
+    telemetry::SpanCombinator,
+    tool::{ToolContext, ToolOutput, ToolSetError, server::ToolServerError},
     wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
 };
 
-use super::Agent;
+use super::{
+    Agent,
+    budget::Budget,
+    checkpoint::{self, Checkpoint, CheckpointSink, DynCheckpointSink},
+};
+
+/// How many times in a row the model can send invalid arguments to the same tool by default.
+pub(crate) const DEFAULT_MAX_ARGUMENT_CORRECTIONS: usize = 3;
 
 pub trait PromptType {}
 pub struct Standard;
@@ -62,6 +73,18 @@ where
     hook: Option<P>,
     /// How many tools should be executed at the same time (1 by default).
     concurrency: usize,
+    /// Optional session id used to load and persist history through the agent's conversation memory
+    session_id: Option<String>,
+    /// Optional sink the agent loop writes a checkpoint to after every turn
+    checkpoint_sink: Option<DynCheckpointSink>,
+    /// Optional checkpoint to resume the agent loop from
+    resume_from: Option<Checkpoint>,
+    /// Optional budget the aggregated usage is checked against after every turn
+    budget: Option<Budget>,
+    /// How many times in a row the model can send invalid arguments to the same tool
+    max_argument_corrections: usize,
+    /// Context passed to every tool call (caller metadata and extensions)
+    tool_context: ToolContext,
 }
 
 impl<'a, M> PromptRequest<'a, Standard, M, ()>
@@ -78,6 +101,32 @@ where
             state: PhantomData,
             hook: None,
             concurrency: 1,
+            session_id: None,
+            checkpoint_sink: None,
+            resume_from: None,
+            budget: None,
+            max_argument_corrections: DEFAULT_MAX_ARGUMENT_CORRECTIONS,
+            tool_context: ToolContext::default(),
+        }
+    }
+
+    /// Create a PromptRequest resuming a multi-turn run from a [`Checkpoint`].
+    ///
+    /// Tool calls that were pending when the checkpoint was taken are executed first, then the
+    /// loop carries on with the conversation depth and aggregated usage of the checkpoint.
+    /// If the request is given a chat history through `.with_history()`, the history of the
+    /// checkpoint is appended to it. Conversation memory is not loaded again when resuming
+    /// (the checkpoint already holds the full history), but new messages are still persisted.
+    pub fn from_checkpoint(agent: &'a Agent<M>, checkpoint: Checkpoint) -> Self {
+        let prompt = checkpoint
+            .chat_history
+            .last()
+            .cloned()
+            .unwrap_or_else(|| Message::user(""));
+
+        Self {
+            resume_from: Some(checkpoint),
+            ..Self::new(agent, prompt)
         }
     }
 }
@@ -102,6 +151,12 @@ where
             state: PhantomData,
             hook: self.hook,
             concurrency: self.concurrency,
+            session_id: self.session_id,
+            checkpoint_sink: self.checkpoint_sink,
+            resume_from: self.resume_from,
+            budget: self.budget,
+            max_argument_corrections: self.max_argument_corrections,
+            tool_context: self.tool_context,
         }
     }
     /// Set the maximum depth for multi-turn conversations (ie, the maximum number of turns an LLM can have calling tools before writing a text response).
@@ -115,6 +170,12 @@ where
             state: PhantomData,
             hook: self.hook,
             concurrency: self.concurrency,
+            session_id: self.session_id,
+            checkpoint_sink: self.checkpoint_sink,
+            resume_from: self.resume_from,
+            budget: self.budget,
+            max_argument_corrections: self.max_argument_corrections,
+            tool_context: self.tool_context,
         }
     }
 
@@ -125,6 +186,57 @@ where
         self
     }
 
+    /// Attach a session id to the prompt request.
+    ///
+    /// If the agent has a conversation memory (see [`crate::agent::AgentBuilder::memory`]), the
+    /// history of the session is loaded before the first turn and every new message is appended
+    /// to the memory as the loop runs. The history is only loaded when no history was passed
+    /// through `.with_history()`, as it is assumed to already hold the conversation so far.
+    pub fn session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Attach a [`CheckpointSink`] to the prompt request.
+    ///
+    /// The agent loop writes a [`Checkpoint`] to the sink after every turn, which can later be
+    /// used to resume the run with [`PromptRequest::from_checkpoint`].
+    pub fn with_checkpoint_sink(mut self, sink: impl CheckpointSink + 'static) -> Self {
+        self.checkpoint_sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Set a token (or cost) [`Budget`] for the prompt request.
+    ///
+    /// The usage aggregated over every completion call is checked against the budget after each
+    /// response containing tool calls, before the tools are executed. If the budget is exceeded, the
+    /// loop stops with a [`crate::completion::request::PromptError::BudgetExceeded`].
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Set how many times in a row the model can call a tool with arguments that do not match
+    /// the schema of the tool (3 by default).
+    ///
+    /// Invalid arguments are sent back to the model as the result of the tool call, listing every
+    /// failing path, so that the model can correct them. Once the limit is exceeded, the loop
+    /// stops with a [`crate::tool::ToolSetError::ArgumentValidationError`].
+    pub fn with_max_argument_corrections(mut self, max_argument_corrections: usize) -> Self {
+        self.max_argument_corrections = max_argument_corrections;
+        self
+    }
+
+    /// Set the [ToolContext] passed to every tool call, carrying caller metadata (eg: the id or
+    /// the role of the user) and typed extensions.
+    ///
+    /// The id of the tool call, the name of the agent, the session and the cancellation signal of
+    /// the loop are added to the context of each call.
+    pub fn with_tool_context(mut self, tool_context: ToolContext) -> Self {
+        self.tool_context = tool_context;
+        self
+    }
+
     /// Add chat history to the prompt request
     pub fn with_history(self, history: &'a mut Vec<Message>) -> PromptRequest<'a, S, M, P> {
         PromptRequest {
@@ -135,6 +247,12 @@ where
             state: PhantomData,
             hook: self.hook,
             concurrency: self.concurrency,
+            session_id: self.session_id,
+            checkpoint_sink: self.checkpoint_sink,
+            resume_from: self.resume_from,
+            budget: self.budget,
+            max_argument_corrections: self.max_argument_corrections,
+            tool_context: self.tool_context,
         }
     }
 
@@ -151,6 +269,12 @@ where
             state: PhantomData,
             hook: Some(hook),
             concurrency: self.concurrency,
+            session_id: self.session_id,
+            checkpoint_sink: self.checkpoint_sink,
+            resume_from: self.resume_from,
+            budget: self.budget,
+            max_argument_corrections: self.max_argument_corrections,
+            tool_context: self.tool_context,
         }
     }
 }
@@ -164,7 +288,7 @@ pub struct CancelSignal {
 }
 
 impl CancelSignal {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             sig: Arc::new(AtomicBool::new(false)),
             reason: OnceLock::new(),
@@ -181,11 +305,11 @@ impl CancelSignal {
         let _ = self.reason.set(reason.to_string());
     }
 
-    fn is_cancelled(&self) -> bool {
+    pub fn is_cancelled(&self) -> bool {
         self.sig.load(Ordering::SeqCst)
     }
 
-    fn cancel_reason(&self) -> Option<&str> {
+    pub fn cancel_reason(&self) -> Option<&str> {
         self.reason.get().map(|x| x.as_str())
     }
 }
@@ -199,6 +323,36 @@ impl Clone for CancelSignal {
     }
 }
 
+/// The decision returned by [`PromptHook::on_tool_call`] and [`StreamingPromptHook::on_tool_call`].
+///
+/// This allows gating tools (e.g.: database writes) behind a user confirmation without
+/// cancelling the whole agent loop.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ToolCallDecision {
+    /// Execute the tool call as requested by the model.
+    #[default]
+    Approve,
+    /// Do not execute the tool call. The reason is sent back to the model as the tool result.
+    Reject { reason: String },
+    /// Execute the tool call with the given (JSON encoded) arguments instead.
+    /// The tool call recorded in the chat history keeps the arguments sent by the model.
+    RewriteArgs { args: String },
+}
+
+impl ToolCallDecision {
+    /// Reject the tool call, sending `reason` back to the model as the tool result.
+    pub fn reject(reason: impl Into<String>) -> Self {
+        Self::Reject {
+            reason: reason.into(),
+        }
+    }
+
+    /// Execute the tool call with the given (JSON encoded) arguments instead.
+    pub fn rewrite_args(args: impl Into<String>) -> Self {
+        Self::RewriteArgs { args: args.into() }
+    }
+}
+
 // dead code allowed because of functions being left empty to allow for users to not have to implement every single function
 /// Trait for per-request hooks to observe tool call events.
 pub trait PromptHook<M>: Clone + WasmCompatSend + WasmCompatSync
@@ -229,18 +383,21 @@ where
 
     #[allow(unused_variables)]
     /// Called before a tool is invoked.
+    /// The returned [`ToolCallDecision`] decides whether the tool call is executed as requested,
+    /// executed with rewritten arguments or rejected (approved by default).
     fn on_tool_call(
         &self,
         tool_name: &str,
         tool_call_id: Option<String>,
         args: &str,
         cancel_sig: CancelSignal,
-    ) -> impl Future<Output = ()> + WasmCompatSend {
-        async {}
+    ) -> impl Future<Output = ToolCallDecision> + WasmCompatSend {
+        async { ToolCallDecision::Approve }
     }
 
     #[allow(unused_variables)]
-    /// Called after a tool is invoked (and a result has been returned).
+    /// Called after a tool is invoked (and a result has been returned). Rejected calls are
+    /// reported as well, with the rejection reason as result.
     fn on_tool_result(
         &self,
         tool_name: &str,
@@ -251,6 +408,20 @@ where
     ) -> impl Future<Output = ()> + WasmCompatSend {
         async {}
     }
+
+    #[allow(unused_variables)]
+    /// Called when a tool returns an error, before [`PromptHook::on_tool_result`] is called with
+    /// the error message as the result.
+    fn on_tool_error(
+        &self,
+        tool_name: &str,
+        tool_call_id: Option<String>,
+        args: &str,
+        error: &str,
+        cancel_sig: CancelSignal,
+    ) -> impl Future<Output = ()> + WasmCompatSend {
+        async {}
+    }
 }
 
 impl<M> PromptHook<M> for () where M: CompletionModel {}
@@ -309,6 +480,174 @@ impl PromptResponse {
     }
 }
 
+/// Builds the [ToolContext] of a tool call made by the agent loop.
+pub(crate) fn tool_call_context<M: CompletionModel>(
+    tool_context: &ToolContext,
+    agent: &Agent<M>,
+    session_id: Option<&str>,
+    tool_call: &ToolCall,
+    cancel_sig: &CancelSignal,
+) -> ToolContext {
+    let mut context = tool_context
+        .clone()
+        .with_tool_call_id(&tool_call.id)
+        .with_cancel_signal(cancel_sig.clone());
+    if let Some(name) = &agent.name {
+        context = context.with_agent_name(name);
+    }
+    if let Some(session_id) = session_id {
+        context = context.with_session_id(session_id);
+    }
+    context
+}
+
+/// Executes a tool call of the agent loop: runs the hooks around it, passes `tool_context` to
+/// the tool and tracks invalid arguments. Returns the tool result (or the rejection reason if the
+/// hook rejected the call), or [ToolSetError::Interrupted] if the hook cancelled the loop.
+pub(crate) async fn dispatch_tool_call<M, P>(
+    agent: &Agent<M>,
+    hook: Option<&P>,
+    tool_call: &ToolCall,
+    cancel_sig: &CancelSignal,
+    argument_corrections: &ArgumentCorrections,
+    tool_context: ToolContext,
+) -> Result<ToolOutput, ToolSetError>
+where
+    M: CompletionModel,
+    P: PromptHook<M>,
+{
+    let tool_name = &tool_call.function.name;
+    let requested_args = json_utils::value_to_json_string(&tool_call.function.arguments);
+    let tool_span = tracing::Span::current();
+    tool_span.record("gen_ai.tool.name", tool_name);
+    tool_span.record("gen_ai.tool.call.id", &tool_call.id);
+    let decision = match hook {
+        Some(hook) => {
+            let decision = hook
+                .on_tool_call(
+                    tool_name,
+                    tool_call.call_id.clone(),
+                    &requested_args,
+                    cancel_sig.clone(),
+                )
+                .await;
+            if cancel_sig.is_cancelled() {
+                return Err(ToolSetError::Interrupted);
+            }
+            decision
+        }
+        None => ToolCallDecision::Approve,
+    };
+    let (args, rejection) = match decision {
+        ToolCallDecision::Approve => (requested_args, None),
+        ToolCallDecision::Reject { reason } => (requested_args, Some(reason)),
+        ToolCallDecision::RewriteArgs {
+            args: rewritten_args,
+        } => (rewritten_args, None),
+    };
+    tool_span.record("gen_ai.tool.call.arguments", &args);
+    let output = if let Some(reason) = rejection {
+        tracing::info!("tool call {tool_name} was rejected: {reason}");
+        ToolOutput::text(reason)
+    } else {
+        let result = agent
+            .tool_server_handle
+            .call_tool_with_context(tool_name, &args, tool_context)
+            .await;
+        argument_corrections.track(tool_name, &result)?;
+        match result {
+            Ok(res) => res,
+            Err(e) => {
+                tracing::warn!("Error while executing tool: {e}");
+                let error = e.to_string();
+                if let Some(hook) = hook {
+                    hook.on_tool_error(
+                        tool_name,
+                        tool_call.call_id.clone(),
+                        &args,
+                        &error,
+                        cancel_sig.clone(),
+                    )
+                    .await;
+                }
+                ToolOutput::text(error)
+            }
+        }
+    };
+    // Rejected calls are reported too, so that observers see every call
+    if let Some(hook) = hook {
+        hook.on_tool_result(
+            tool_name,
+            tool_call.call_id.clone(),
+            &args,
+            &output.to_string(),
+            cancel_sig.clone(),
+        )
+        .await;
+
+        if cancel_sig.is_cancelled() {
+            return Err(ToolSetError::Interrupted);
+        }
+    }
+    tool_span.record("gen_ai.tool.call.result", output.to_string());
+    tracing::info!("executed tool {tool_name} with args {args}. result: {output}");
+
+    Ok(output)
+}
+
+/// Keeps track of the consecutive calls to each tool whose arguments did not match the schema of
+/// the tool.
+pub(crate) struct ArgumentCorrections {
+    attempts: Mutex<HashMap<String, usize>>,
+    max_corrections: usize,
+}
+
+impl ArgumentCorrections {
+    pub(crate) fn new(max_corrections: usize) -> Self {
+        Self {
+            attempts: Mutex::new(HashMap::new()),
+            max_corrections,
+        }
+    }
+
+    /// Records the result of a call to `tool_name`, returning an error once its arguments were
+    /// invalid more than `max_corrections` times in a row.
+    pub(crate) fn track(
+        &self,
+        tool_name: &str,
+        result: &Result<ToolOutput, ToolServerError>,
+    ) -> Result<(), ToolSetError> {
+        let mut attempts = self.attempts.lock().expect("lock poisoned");
+
+        match result {
+            Err(ToolServerError::ToolsetError(ToolSetError::ArgumentValidationError(error))) => {
+                let attempts = attempts.entry(tool_name.to_string()).or_default();
+                *attempts += 1;
+                if *attempts > self.max_corrections {
+                    return Err(ToolSetError::ArgumentValidationError(error.clone()));
+                }
+            }
+            _ => {
+                attempts.remove(tool_name);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends `messages` to the conversation memory of the given session (if any).
+pub(crate) async fn persist_messages(
+    session: &Option<(DynConversationMemory, String)>,
+    messages: Vec<Message>,
+) -> Result<(), MemoryError> {
+    if let Some((memory, session_id)) = session {
+        memory.append(session_id, messages).await?;
+    }
+
+    Ok(())
+}
+
 impl<M, P> PromptRequest<'_, Extended, M, P>
 where
     M: CompletionModel,
@@ -316,40 +655,156 @@ where
 {
     async fn send(self) -> Result<PromptResponse, PromptError> {
         let agent_span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 "invoke_agent",
                 gen_ai.operation.name = "invoke_agent",
                 gen_ai.agent.name = self.agent.name(),
                 gen_ai.system_instructions = self.agent.preamble,
                 gen_ai.prompt = tracing::field::Empty,
                 gen_ai.completion = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
         };
 
         let agent = self.agent;
+        let session = agent.memory.clone().zip(self.session_id.clone());
+
+        let mut owned_history = Vec::new();
         let chat_history = if let Some(history) = self.chat_history {
-            history.push(self.prompt.to_owned());
             history
         } else {
-            &mut vec![self.prompt.to_owned()]
+            &mut owned_history
         };
 
+        let mut current_max_depth = 0;
+        let mut usage = Usage::new();
+        let mut pending_tool_calls = Vec::new();
+
+        if let Some(checkpoint) = self.resume_from {
+            chat_history.extend(checkpoint.chat_history);
+            current_max_depth = checkpoint.depth;
+            usage = checkpoint.usage;
+            pending_tool_calls = checkpoint.pending_tool_calls;
+        } else {
+            // History passed by the caller already holds the conversation so far
+            if let Some((memory, session_id)) = &session
+                && chat_history.is_empty()
+            {
+                chat_history.extend(memory.load(session_id).await?);
+            }
+
+            chat_history.push(self.prompt.to_owned());
+            persist_messages(&session, vec![self.prompt.to_owned()]).await?;
+        }
+
         if let Some(text) = self.prompt.rag_text() {
             agent_span.record("gen_ai.prompt", text);
         }
 
         let cancel_sig = CancelSignal::new();
 
-        let mut current_max_depth = 0;
-        let mut usage = Usage::new();
         let current_span_id: AtomicU64 = AtomicU64::new(0);
 
+        // Number of consecutive calls with invalid arguments, per tool
+        let argument_corrections = ArgumentCorrections::new(self.max_argument_corrections);
+
         // We need to do at least 2 loops for 1 roundtrip (user expects normal message)
         let last_prompt = loop {
+            if !pending_tool_calls.is_empty() {
+                let hook = self.hook.clone();
+
+                let tool_content = stream::iter(std::mem::take(&mut pending_tool_calls))
+                    .map(|tool_call| {
+                        let hook = hook.clone();
+                        let cancel_sig = cancel_sig.clone();
+
+                        let argument_corrections = &argument_corrections;
+                        let tool_context = tool_call_context(
+                            &self.tool_context,
+                            agent,
+                            self.session_id.as_deref(),
+                            &tool_call,
+                            &cancel_sig,
+                        );
+
+                        let tool_span = info_span!(
+                            "execute_tool",
+                            gen_ai.operation.name = "execute_tool",
+                            gen_ai.tool.type = "function",
+                            gen_ai.tool.name = tracing::field::Empty,
+                            gen_ai.tool.call.id = tracing::field::Empty,
+                            gen_ai.tool.call.arguments = tracing::field::Empty,
+                            gen_ai.tool.call.result = tracing::field::Empty
+                        );
+
+                        let tool_span = if current_span_id.load(Ordering::SeqCst) != 0 {
+                            let id = Id::from_u64(current_span_id.load(Ordering::SeqCst));
+                            tool_span.follows_from(id).to_owned()
+                        } else {
+                            tool_span
+                        };
+
+                        if let Some(id) = tool_span.id() {
+                            current_span_id.store(id.into_u64(), Ordering::SeqCst);
+                        };
+
+                        async move {
+                            let output = dispatch_tool_call(
+                                agent,
+                                hook.as_ref(),
+                                &tool_call,
+                                &cancel_sig,
+                                argument_corrections,
+                                tool_context,
+                            )
+                            .await?;
+                            if let Some(call_id) = tool_call.call_id.clone() {
+                                Ok(UserContent::tool_result_with_call_id(
+                                    tool_call.id.clone(),
+                                    call_id,
+                                    output.into(),
+                                ))
+                            } else {
+                                Ok(UserContent::tool_result(
+                                    tool_call.id.clone(),
+                                    output.into(),
+                                ))
+                            }
+                        }
+                        .instrument(tool_span)
+                    })
+                    .buffer_unordered(self.concurrency)
+                    .collect::<Vec<Result<UserContent, ToolSetError>>>()
+                    .await
+                    .into_iter()
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| {
+                        if matches!(e, ToolSetError::Interrupted) {
+                            PromptError::prompt_cancelled(
+                                chat_history.to_vec(),
+                                cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
+                            )
+                        } else {
+                            e.into()
+                        }
+                    })?;
+
+                let tool_result_message = Message::User {
+                    content: OneOrMany::many(tool_content).expect("There is atleast one tool call"),
+                };
+                chat_history.push(tool_result_message.clone());
+                persist_messages(&session, vec![tool_result_message]).await?;
+
+                checkpoint::save_checkpoint(&self.checkpoint_sink, || Checkpoint {
+                    chat_history: chat_history.to_vec(),
+                    depth: current_max_depth,
+                    pending_tool_calls: Vec::new(),
+                    usage,
+                })
+                .await?;
+            }
+
             let prompt = chat_history
                 .last()
                 .cloned()
@@ -384,7 +839,7 @@ where
                 }
             }
             let span = tracing::Span::current();
-            let chat_span = info_span!(
+            let chat_span = gen_ai_span!(
                 target: "rig::agent_chat",
                 parent: &span,
                 "chat",
@@ -394,8 +849,6 @@ where
                 gen_ai.request.model = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
                 gen_ai.input.messages = tracing::field::Empty,
                 gen_ai.output.messages = tracing::field::Empty,
             );
@@ -439,10 +892,12 @@ where
                 .iter()
                 .partition(|choice| matches!(choice, AssistantContent::ToolCall(_)));
 
-            chat_history.push(Message::Assistant {
+            let assistant_message = Message::Assistant {
                 id: None,
                 content: resp.choice.clone(),
-            });
+            };
+            chat_history.push(assistant_message.clone());
+            persist_messages(&session, vec![assistant_message]).await?;
 
             if tool_calls.is_empty() {
                 let merged_texts = texts
@@ -462,131 +917,40 @@ where
                 }
 
                 agent_span.record("gen_ai.completion", &merged_texts);
-                agent_span.record("gen_ai.usage.input_tokens", usage.input_tokens);
-                agent_span.record("gen_ai.usage.output_tokens", usage.output_tokens);
+                agent_span.record_token_usage(&usage);
 
                 // If there are no tool calls, depth is not relevant, we can just return the merged text response.
                 return Ok(PromptResponse::new(merged_texts, usage));
             }
 
-            let hook = self.hook.clone();
-
-            let tool_calls: Vec<AssistantContent> = tool_calls.into_iter().cloned().collect();
-            let tool_content = stream::iter(tool_calls)
-                .map(|choice| {
-                    let hook1 = hook.clone();
-                    let hook2 = hook.clone();
-
-                    let cancel_sig1 = cancel_sig.clone();
-                    let cancel_sig2 = cancel_sig.clone();
-
-                    let tool_span = info_span!(
-                        "execute_tool",
-                        gen_ai.operation.name = "execute_tool",
-                        gen_ai.tool.type = "function",
-                        gen_ai.tool.name = tracing::field::Empty,
-                        gen_ai.tool.call.id = tracing::field::Empty,
-                        gen_ai.tool.call.arguments = tracing::field::Empty,
-                        gen_ai.tool.call.result = tracing::field::Empty
-                    );
-
-                    let tool_span = if current_span_id.load(Ordering::SeqCst) != 0 {
-                        let id = Id::from_u64(current_span_id.load(Ordering::SeqCst));
-                        tool_span.follows_from(id).to_owned()
-                    } else {
-                        tool_span
-                    };
-
-                    if let Some(id) = tool_span.id() {
-                        current_span_id.store(id.into_u64(), Ordering::SeqCst);
-                    };
-
-                    async move {
-                        if let AssistantContent::ToolCall(tool_call) = choice {
-                            let tool_name = &tool_call.function.name;
-                            let args =
-                                json_utils::value_to_json_string(&tool_call.function.arguments);
-                            let tool_span = tracing::Span::current();
-                            tool_span.record("gen_ai.tool.name", tool_name);
-                            tool_span.record("gen_ai.tool.call.id", &tool_call.id);
-                            tool_span.record("gen_ai.tool.call.arguments", &args);
-                            if let Some(hook) = hook1 {
-                                hook.on_tool_call(
-                                    tool_name,
-                                    tool_call.call_id.clone(),
-                                    &args,
-                                    cancel_sig1.clone(),
-                                )
-                                .await;
-                                if cancel_sig1.is_cancelled() {
-                                    return Err(ToolSetError::Interrupted);
-                                }
-                            }
-                            let output =
-                                match agent.tool_server_handle.call_tool(tool_name, &args).await {
-                                    Ok(res) => res,
-                                    Err(e) => {
-                                        tracing::warn!("Error while executing tool: {e}");
-                                        e.to_string()
-                                    }
-                                };
-                            if let Some(hook) = hook2 {
-                                hook.on_tool_result(
-                                    tool_name,
-                                    tool_call.call_id.clone(),
-                                    &args,
-                                    &output.to_string(),
-                                    cancel_sig2.clone(),
-                                )
-                                .await;
-
-                                if cancel_sig2.is_cancelled() {
-                                    return Err(ToolSetError::Interrupted);
-                                }
-                            }
-                            tool_span.record("gen_ai.tool.call.result", &output);
-                            tracing::info!(
-                                "executed tool {tool_name} with args {args}. result: {output}"
-                            );
-                            if let Some(call_id) = tool_call.call_id.clone() {
-                                Ok(UserContent::tool_result_with_call_id(
-                                    tool_call.id.clone(),
-                                    call_id,
-                                    OneOrMany::one(output.into()),
-                                ))
-                            } else {
-                                Ok(UserContent::tool_result(
-                                    tool_call.id.clone(),
-                                    OneOrMany::one(output.into()),
-                                ))
-                            }
-                        } else {
-                            unreachable!(
-                                "This should never happen as we already filtered for `ToolCall`"
-                            )
-                        }
-                    }
-                    .instrument(tool_span)
-                })
-                .buffer_unordered(self.concurrency)
-                .collect::<Vec<Result<UserContent, ToolSetError>>>()
-                .await
+            pending_tool_calls = tool_calls
                 .into_iter()
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| {
-                    if matches!(e, ToolSetError::Interrupted) {
-                        PromptError::prompt_cancelled(
-                            chat_history.to_vec(),
-                            cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
-                        )
-                    } else {
-                        e.into()
-                    }
-                })?;
-
-            chat_history.push(Message::User {
-                content: OneOrMany::many(tool_content).expect("There is atleast one tool call"),
-            });
+                .filter_map(|choice| match choice {
+                    AssistantContent::ToolCall(tool_call) => Some(tool_call.clone()),
+                    _ => None,
+                })
+                .collect();
+
+            checkpoint::save_checkpoint(&self.checkpoint_sink, || Checkpoint {
+                chat_history: chat_history.to_vec(),
+                depth: current_max_depth,
+                pending_tool_calls: pending_tool_calls.clone(),
+                usage,
+            })
+            .await?;
+
+            // Tools are only executed if the budget allows for another round-trip to the model
+            if let Some(Err(limit)) = self
+                .budget
+                .as_ref()
+                .map(|budget| budget.check(agent.model.model_name(), &usage))
+            {
+                return Err(PromptError::BudgetExceeded {
+                    limit,
+                    usage,
+                    chat_history: Box::new(chat_history.to_vec()),
+                });
+            }
         };
 
         // If we reach here, we never resolved the final tool call. We need to do ... something.
@@ -597,3 +961,398 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex, atomic::Ordering};
+
+    use futures::StreamExt;
+
+    use super::{PromptHook, ToolCallDecision};
+    use crate::{
+        agent::{
+            AgentBuilder, Budget, BudgetLimit, CancelSignal, ModelPricing, StreamingPromptRequest,
+            checkpoint::InMemoryCheckpointSink,
+            prompt_request::streaming::{MultiTurnStreamItem, StreamingError, StreamingPromptHook},
+        },
+        completion::{CompletionModel, Message, Prompt, PromptError},
+        memory::{ConversationMemory, InMemoryConversationMemory},
+        message::{AssistantContent, ToolResultContent, UserContent},
+        streaming::{StreamedUserContent, StreamingPrompt},
+        test_utils::{EchoTool, ScriptedModel, echo_call},
+    };
+
+    /// A hook answering every tool call with `decision` and recording the reported results.
+    #[derive(Clone)]
+    struct DecidingHook {
+        decision: ToolCallDecision,
+        results: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl DecidingHook {
+        fn new(decision: ToolCallDecision) -> Self {
+            Self {
+                decision,
+                results: Default::default(),
+            }
+        }
+    }
+
+    impl<M: CompletionModel> PromptHook<M> for DecidingHook {
+        async fn on_tool_call(
+            &self,
+            _tool_name: &str,
+            _tool_call_id: Option<String>,
+            _args: &str,
+            _cancel_sig: CancelSignal,
+        ) -> ToolCallDecision {
+            self.decision.clone()
+        }
+
+        async fn on_tool_result(
+            &self,
+            _tool_name: &str,
+            _tool_call_id: Option<String>,
+            _args: &str,
+            result: &str,
+            _cancel_sig: CancelSignal,
+        ) {
+            self.results
+                .lock()
+                .expect("lock poisoned")
+                .push(result.to_string());
+        }
+    }
+
+    impl<M: CompletionModel> StreamingPromptHook<M> for DecidingHook {
+        async fn on_tool_call(
+            &self,
+            _tool_name: &str,
+            _tool_call_id: Option<String>,
+            _args: &str,
+            _cancel_sig: CancelSignal,
+        ) -> ToolCallDecision {
+            self.decision.clone()
+        }
+
+        async fn on_tool_result(
+            &self,
+            _tool_name: &str,
+            _tool_call_id: Option<String>,
+            _args: &str,
+            result: &str,
+            _cancel_sig: CancelSignal,
+        ) {
+            self.results
+                .lock()
+                .expect("lock poisoned")
+                .push(result.to_string());
+        }
+    }
+
+    /// A hook cancelling the run as soon as a tool is about to be called.
+    #[derive(Clone)]
+    struct CancellingHook;
+
+    impl<M: CompletionModel> StreamingPromptHook<M> for CancellingHook {
+        async fn on_tool_call(
+            &self,
+            _tool_name: &str,
+            _tool_call_id: Option<String>,
+            _args: &str,
+            cancel_sig: CancelSignal,
+        ) -> ToolCallDecision {
+            cancel_sig.cancel();
+            ToolCallDecision::Approve
+        }
+    }
+
+    /// Returns the text of the tool results sent in the last request to `model`.
+    fn sent_tool_results(model: &ScriptedModel) -> Vec<String> {
+        let requests = model.requests.lock().expect("lock poisoned");
+        let request = requests.last().expect("no request was sent");
+        request
+            .chat_history
+            .iter()
+            .filter_map(|message| match message {
+                Message::User { content } => Some(content.iter()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                UserContent::ToolResult(result) => match result.content.first() {
+                    ToolResultContent::Text(text) => Some(text.text),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_rejected_tool_call_is_reported() {
+        let decision = ToolCallDecision::reject("not allowed");
+
+        let tool = EchoTool::default();
+        let model = ScriptedModel::new([
+            echo_call("call_1", "pong", 0),
+            AssistantContent::text("Done."),
+        ]);
+        let hook = DecidingHook::new(decision.clone());
+        let agent = AgentBuilder::new(model.clone()).tool(tool.clone()).build();
+        agent.prompt("ping").with_hook(hook.clone()).await.unwrap();
+
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(sent_tool_results(&model), vec!["not allowed"]);
+        assert_eq!(*hook.results.lock().unwrap(), vec!["not allowed"]);
+
+        let tool = EchoTool::default();
+        let model = ScriptedModel::new([
+            echo_call("call_1", "pong", 0),
+            AssistantContent::text("Done."),
+        ]);
+        let hook = DecidingHook::new(decision);
+        let agent = AgentBuilder::new(model.clone()).tool(tool.clone()).build();
+        let mut stream = agent.stream_prompt("ping").with_hook(hook.clone()).await;
+        while let Some(item) = stream.next().await {
+            item.unwrap();
+        }
+
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(sent_tool_results(&model), vec!["not allowed"]);
+        assert_eq!(*hook.results.lock().unwrap(), vec!["not allowed"]);
+    }
+
+    #[tokio::test]
+    async fn test_rewritten_tool_call_args_are_used() {
+        let tool = EchoTool::default();
+        let model = ScriptedModel::new([
+            echo_call("call_1", "pong", 0),
+            AssistantContent::text("Done."),
+        ]);
+        let hook = DecidingHook::new(ToolCallDecision::rewrite_args(r#"{"text":"rewritten"}"#));
+        let agent = AgentBuilder::new(model.clone()).tool(tool.clone()).build();
+        agent.prompt("ping").with_hook(hook.clone()).await.unwrap();
+
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(sent_tool_results(&model), vec![r#""rewritten""#]);
+        assert_eq!(*hook.results.lock().unwrap(), vec![r#""rewritten""#]);
+
+        // The history keeps the arguments sent by the model
+        let requests = model.requests.lock().unwrap();
+        let tool_call = requests[1]
+            .chat_history
+            .iter()
+            .find_map(|message| match message {
+                Message::Assistant { content, .. } => content.iter().find_map(|c| match c {
+                    AssistantContent::ToolCall(call) => Some(call.clone()),
+                    _ => None,
+                }),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(tool_call.function.arguments["text"], "pong");
+    }
+
+    #[tokio::test]
+    async fn test_memory_is_not_loaded_over_caller_history() {
+        let memory = InMemoryConversationMemory::new();
+        memory
+            .append(
+                "session",
+                vec![Message::user("hello"), Message::assistant("hi")],
+            )
+            .await
+            .unwrap();
+
+        let model = ScriptedModel::new([AssistantContent::text("fine")]);
+        let agent = AgentBuilder::new(model.clone()).memory(memory).build();
+
+        let mut history = vec![Message::user("hello"), Message::assistant("hi")];
+        agent
+            .prompt("how are you?")
+            .with_history(&mut history)
+            .session("session")
+            .await
+            .unwrap();
+
+        // The persisted turn is already part of the history passed by the caller
+        let request = &model.requests.lock().unwrap()[0];
+        assert_eq!(request.chat_history.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_persists_every_turn() {
+        let memory = InMemoryConversationMemory::new();
+        let model = ScriptedModel::with_turns([
+            vec![
+                AssistantContent::text("Let me check."),
+                echo_call("call_1", "pong", 0),
+            ],
+            vec![AssistantContent::text("Done.")],
+        ]);
+        let agent = AgentBuilder::new(model)
+            .tool(EchoTool::default())
+            .memory(memory.clone())
+            .build();
+
+        let mut stream = agent.stream_prompt("ping").session("session").await;
+        while let Some(item) = stream.next().await {
+            item.unwrap();
+        }
+
+        let persisted = memory.load("session").await.unwrap();
+        assert_eq!(persisted.len(), 4);
+        assert_eq!(persisted[0], Message::user("ping"));
+        let Message::Assistant { content, .. } = &persisted[1] else {
+            panic!("expected the assistant turn with its tool call");
+        };
+        assert_eq!(content.first(), AssistantContent::text("Let me check."));
+        assert!(matches!(
+            content.iter().nth(1),
+            Some(AssistantContent::ToolCall(_))
+        ));
+        assert!(matches!(persisted[2], Message::User { .. }));
+        assert_eq!(persisted[3], Message::assistant("Done."));
+    }
+
+    #[tokio::test]
+    async fn test_streaming_resumes_pending_tool_calls() {
+        for concurrency in [1, 2] {
+            let tool = EchoTool::default();
+            let model = ScriptedModel::new([
+                echo_call("call_1", "pong", 0),
+                AssistantContent::text("Done."),
+            ]);
+            let agent = Arc::new(AgentBuilder::new(model.clone()).tool(tool.clone()).build());
+            let sink = InMemoryCheckpointSink::new();
+
+            // The run is interrupted right before the tool is executed
+            let mut stream = agent
+                .stream_prompt("ping")
+                .with_tool_concurrency(concurrency)
+                .with_checkpoint_sink(sink.clone())
+                .with_hook(CancellingHook)
+                .await;
+            while let Some(item) = stream.next().await {
+                if item.is_err() {
+                    break;
+                }
+            }
+            drop(stream);
+
+            let checkpoint = sink.latest().await.unwrap();
+            assert_eq!(checkpoint.pending_tool_calls.len(), 1);
+            assert_eq!(checkpoint.chat_history.len(), 2);
+            assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+
+            let mut stream = StreamingPromptRequest::from_checkpoint(agent.clone(), checkpoint)
+                .with_tool_concurrency(concurrency)
+                .await;
+            let mut tool_results = 0;
+            let mut final_response = None;
+            while let Some(item) = stream.next().await {
+                match item.unwrap() {
+                    MultiTurnStreamItem::StreamUserItem(_) => tool_results += 1,
+                    MultiTurnStreamItem::FinalResponse(response) => final_response = Some(response),
+                    _ => {}
+                }
+            }
+
+            assert_eq!(tool_results, 1);
+            assert_eq!(tool.calls.load(Ordering::SeqCst), 1);
+            assert_eq!(final_response.unwrap().response(), "Done.");
+            assert_eq!(sent_tool_results(&model), vec![r#""pong""#]);
+
+            // The resumed request holds the prompt, the tool call and its result
+            let requests = model.requests.lock().unwrap();
+            assert_eq!(requests[1].chat_history.len(), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_budget_is_checked_before_tools_are_executed() {
+        let budget = Budget::new()
+            .max_cost(0.00001)
+            .model_pricing("scripted", ModelPricing::new(1.0, 1.0));
+
+        let tool = EchoTool::default();
+        let model = ScriptedModel::new([
+            echo_call("call_1", "pong", 0),
+            AssistantContent::text("Done."),
+        ]);
+        let agent = AgentBuilder::new(model).tool(tool.clone()).build();
+        let result = agent
+            .prompt("ping")
+            .multi_turn(2)
+            .with_budget(budget.clone())
+            .await;
+
+        let Err(PromptError::BudgetExceeded {
+            limit,
+            chat_history,
+            ..
+        }) = result
+        else {
+            panic!("expected the budget to be exceeded");
+        };
+        assert!(matches!(limit, BudgetLimit::Cost { .. }));
+        // The history ends with the unanswered tool call
+        assert_eq!(chat_history.len(), 2);
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+
+        let tool = EchoTool::default();
+        let model = ScriptedModel::new([
+            echo_call("call_1", "pong", 0),
+            AssistantContent::text("Done."),
+        ]);
+        let agent = AgentBuilder::new(model).tool(tool.clone()).build();
+        let mut stream = agent
+            .stream_prompt("ping")
+            .multi_turn(2)
+            .with_budget(budget)
+            .await;
+
+        let mut budget_exceeded = false;
+        while let Some(item) = stream.next().await {
+            if let Err(StreamingError::Prompt(error)) = item {
+                budget_exceeded = matches!(*error, PromptError::BudgetExceeded { .. });
+            }
+        }
+        assert!(budget_exceeded);
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_tool_concurrency_keeps_result_order() {
+        let tool = EchoTool::default();
+        let model = ScriptedModel::with_turns([
+            vec![
+                echo_call("call_1", "slow", 100),
+                echo_call("call_2", "fast", 10),
+                echo_call("call_3", "faster", 0),
+            ],
+            vec![AssistantContent::text("Done.")],
+        ]);
+        let agent = AgentBuilder::new(model.clone()).tool(tool.clone()).build();
+
+        let mut stream = agent.stream_prompt("ping").with_tool_concurrency(3).await;
+        let mut results = vec![];
+        while let Some(item) = stream.next().await {
+            if let MultiTurnStreamItem::StreamUserItem(StreamedUserContent::ToolResult(result)) =
+                item.unwrap()
+            {
+                results.push(result.id);
+            }
+        }
+
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 3);
+        assert_eq!(tool.max_in_flight.load(Ordering::SeqCst), 3);
+        // Results are yielded and sent back in the order of the tool calls
+        assert_eq!(results, vec!["call_1", "call_2", "call_3"]);
+        assert_eq!(
+            sent_tool_results(&model),
+            vec![r#""slow""#, r#""fast""#, r#""faster""#]
+        );
+    }
+}
@@ -1,10 +1,12 @@
+use crate::telemetry::gen_ai_span;
 use crate::{
     OneOrMany,
-    agent::CancelSignal,
+    agent::{CancelSignal, ToolCallDecision},
     completion::GetTokenUsage,
     json_utils,
-    message::{AssistantContent, Reasoning, ToolResult, ToolResultContent, UserContent},
+    message::{AssistantContent, Reasoning, ToolCall, ToolResult, UserContent},
     streaming::{StreamedAssistantContent, StreamedUserContent, StreamingCompletion},
+    telemetry::SpanCombinator,
     wasm_compat::{WasmBoxedFuture, WasmCompatSend},
 };
 use futures::{Stream, StreamExt};
@@ -14,11 +16,18 @@ use tokio::sync::RwLock;
 use tracing::info_span;
 use tracing_futures::Instrument;
 
+use super::{
+    ArgumentCorrections, DEFAULT_MAX_ARGUMENT_CORRECTIONS, persist_messages, tool_call_context,
+};
 use crate::{
-    agent::Agent,
+    agent::{
+        Agent,
+        budget::Budget,
+        checkpoint::{Checkpoint, CheckpointSink, DynCheckpointSink},
+    },
     completion::{CompletionError, CompletionModel, PromptError},
     message::{Message, Text},
-    tool::ToolSetError,
+    tool::{ToolContext, ToolOutput, ToolSetError},
 };
 
 #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
@@ -112,6 +121,46 @@ where
     agent: Arc<Agent<M>>,
     /// Optional per-request hook for events
     hook: Option<P>,
+    /// How many tools should be executed at the same time (1 by default).
+    concurrency: usize,
+    /// Optional session id used to load and persist history through the agent's conversation memory
+    session_id: Option<String>,
+    /// Optional sink the agent loop writes a checkpoint to after every turn
+    checkpoint_sink: Option<DynCheckpointSink>,
+    /// Optional checkpoint to resume the agent loop from
+    resume_from: Option<Checkpoint>,
+    /// Optional budget the aggregated usage is checked against after every turn
+    budget: Option<Budget>,
+    /// How many times in a row the model can send invalid arguments to the same tool
+    max_argument_corrections: usize,
+    /// Context passed to every tool call (caller metadata and extensions)
+    tool_context: ToolContext,
+}
+
+impl<M> StreamingPromptRequest<M, ()>
+where
+    M: CompletionModel + 'static,
+    <M as CompletionModel>::StreamingResponse: WasmCompatSend + GetTokenUsage,
+{
+    /// Create a StreamingPromptRequest resuming a multi-turn run from a [`Checkpoint`].
+    ///
+    /// Tool calls that were pending when the checkpoint was taken are executed first (and their
+    /// results streamed), then the loop carries on with the conversation depth and aggregated
+    /// usage of the checkpoint. If the request is given a chat history through `.with_history()`,
+    /// the history of the checkpoint is appended to it. Conversation memory is not loaded again
+    /// when resuming, but new messages are still persisted.
+    pub fn from_checkpoint(agent: Arc<Agent<M>>, checkpoint: Checkpoint) -> Self {
+        let prompt = checkpoint
+            .chat_history
+            .last()
+            .cloned()
+            .unwrap_or_else(|| Message::user(""));
+
+        Self {
+            resume_from: Some(checkpoint),
+            ..Self::new(agent, prompt)
+        }
+    }
 }
 
 impl<M, P> StreamingPromptRequest<M, P>
@@ -128,6 +177,13 @@ where
             max_depth: agent.default_max_depth.unwrap_or_default(),
             agent,
             hook: None,
+            concurrency: 1,
+            session_id: None,
+            checkpoint_sink: None,
+            resume_from: None,
+            budget: None,
+            max_argument_corrections: DEFAULT_MAX_ARGUMENT_CORRECTIONS,
+            tool_context: ToolContext::default(),
         }
     }
 
@@ -138,6 +194,75 @@ where
         self
     }
 
+    /// Add concurrency to the prompt request.
+    /// This will cause the agent to execute tools concurrently.
+    ///
+    /// When set above 1, the tool calls of a response are executed once the response has been
+    /// fully streamed (instead of one by one as they arrive). Tool results are still yielded in
+    /// the order the tool calls were emitted by the model.
+    pub fn with_tool_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Attach a session id to the prompt request.
+    ///
+    /// If the agent has a conversation memory (see [`crate::agent::AgentBuilder::memory`]), the
+    /// history of the session is loaded before the first turn and every new message is appended
+    /// to the memory as the loop runs. The history is only loaded when no history was passed
+    /// through `.with_history()`, as it is assumed to already hold the conversation so far.
+    pub fn session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Attach a [`CheckpointSink`] to the prompt request.
+    ///
+    /// The agent loop writes a [`Checkpoint`] to the sink before executing tool calls, with the
+    /// calls about to run as pending tool calls, and after their results have been added to the
+    /// chat history. When tools are executed while the response is being streamed (ie, without
+    /// tool concurrency nor budget), a checkpoint is written before each call and does not include the usage
+    /// of the response being streamed yet.
+    /// A run can be resumed from a checkpoint with [`StreamingPromptRequest::from_checkpoint`]
+    /// (or [`crate::agent::PromptRequest::from_checkpoint`]).
+    pub fn with_checkpoint_sink(mut self, sink: impl CheckpointSink + 'static) -> Self {
+        self.checkpoint_sink = Some(Arc::new(sink));
+        self
+    }
+
+    /// Set a token (or cost) [`Budget`] for the prompt request.
+    ///
+    /// The usage aggregated over every streamed response is checked against the budget once a
+    /// response containing tool calls has been fully streamed, before the tools are executed
+    /// (tool calls are therefore not executed while the response is being streamed). If the
+    /// budget is exceeded, the stream ends with a
+    /// [`crate::completion::request::PromptError::BudgetExceeded`].
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Set how many times in a row the model can call a tool with arguments that do not match
+    /// the schema of the tool (3 by default).
+    ///
+    /// Invalid arguments are sent back to the model as the result of the tool call, listing every
+    /// failing path, so that the model can correct them. Once the limit is exceeded, the stream
+    /// ends with a [`crate::tool::ToolSetError::ArgumentValidationError`].
+    pub fn with_max_argument_corrections(mut self, max_argument_corrections: usize) -> Self {
+        self.max_argument_corrections = max_argument_corrections;
+        self
+    }
+
+    /// Set the [ToolContext] passed to every tool call, carrying caller metadata (eg: the id or
+    /// the role of the user) and typed extensions.
+    ///
+    /// The id of the tool call, the name of the agent, the session and the cancellation signal of
+    /// the stream are added to the context of each call.
+    pub fn with_tool_context(mut self, tool_context: ToolContext) -> Self {
+        self.tool_context = tool_context;
+        self
+    }
+
     /// Add chat history to the prompt request
     pub fn with_history(mut self, history: Vec<Message>) -> Self {
         self.chat_history = Some(history);
@@ -155,20 +280,25 @@ where
             max_depth: self.max_depth,
             agent: self.agent,
             hook: Some(hook),
+            concurrency: self.concurrency,
+            session_id: self.session_id,
+            checkpoint_sink: self.checkpoint_sink,
+            resume_from: self.resume_from,
+            budget: self.budget,
+            max_argument_corrections: self.max_argument_corrections,
+            tool_context: self.tool_context,
         }
     }
 
     async fn send(self) -> StreamingResult<M::StreamingResponse> {
         let agent_span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 "invoke_agent",
                 gen_ai.operation.name = "invoke_agent",
                 gen_ai.agent.name = self.agent.name(),
                 gen_ai.system_instructions = self.agent.preamble,
                 gen_ai.prompt = tracing::field::Empty,
                 gen_ai.completion = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -180,24 +310,40 @@ where
         }
 
         let agent = self.agent;
+        let session = agent.memory.clone().zip(self.session_id.clone());
 
-        let chat_history = if let Some(history) = self.chat_history {
-            Arc::new(RwLock::new(history))
-        } else {
-            Arc::new(RwLock::new(vec![]))
-        };
-
+        let mut history = self.chat_history.unwrap_or_default();
         let mut current_max_depth = 0;
+        let mut aggregated_usage = crate::completion::Usage::new();
+        let mut resumed_tool_calls = Vec::new();
+        let resuming = self.resume_from.is_some();
+
+        if let Some(checkpoint) = self.resume_from {
+            history.extend(checkpoint.chat_history);
+            current_max_depth = checkpoint.depth;
+            aggregated_usage = checkpoint.usage;
+            resumed_tool_calls = checkpoint.pending_tool_calls;
+
+            // The last message is the prompt of the next turn, unless pending tool calls are
+            // answered first
+            if resumed_tool_calls.is_empty() {
+                history.pop();
+            }
+        }
+
+        let chat_history = Arc::new(RwLock::new(history));
+
         let mut last_prompt_error = String::new();
 
         let mut last_text_response = String::new();
         let mut is_text_response = false;
         let mut max_depth_reached = false;
 
-        let mut aggregated_usage = crate::completion::Usage::new();
-
         let cancel_sig = CancelSignal::new();
 
+        // Number of consecutive calls with invalid arguments, per tool
+        let argument_corrections = ArgumentCorrections::new(self.max_argument_corrections);
+
         // NOTE: We use .instrument(agent_span) instead of span.enter() to avoid
         // span context leaking to other concurrent tasks. Using span.enter() inside
         // async_stream::stream! holds the guard across yield points, which causes
@@ -208,7 +354,82 @@ where
             let mut current_prompt = prompt.clone();
             let mut did_call_tool = false;
 
+            // History passed by the caller (or resumed from a checkpoint) already holds the
+            // conversation so far
+            if let Some((memory, session_id)) = &session
+                && !resuming
+                && chat_history.read().await.is_empty()
+            {
+                let persisted = memory
+                    .load(session_id)
+                    .await
+                    .map_err(|e| Box::new(PromptError::from(e)))?;
+                chat_history.write().await.extend(persisted);
+            }
+
             'outer: loop {
+                // Tool calls left pending by the checkpoint the run is resumed from are answered first
+                if !resumed_tool_calls.is_empty() {
+                    let results = futures::stream::iter(std::mem::take(&mut resumed_tool_calls))
+                        .map(|tool_call| {
+                            let agent = &agent;
+                            let hook = self.hook.as_ref();
+                            let cancel_sig = &cancel_sig;
+                            let chat_history = &chat_history;
+                            let argument_corrections = &argument_corrections;
+                            let tool_context = tool_call_context(&self.tool_context, agent, self.session_id.as_deref(), &tool_call, cancel_sig);
+                            async move {
+                                let result = execute_tool_call(agent, hook, &tool_call, cancel_sig, chat_history, argument_corrections, tool_context).await;
+                                (tool_call, result)
+                            }
+                        })
+                        .buffered(self.concurrency.max(1))
+                        .collect::<Vec<_>>()
+                        .await;
+
+                    let mut tool_results = vec![];
+                    for (tool_call, result) in results {
+                        match result {
+                            Ok(output) => {
+                                tool_results.push((tool_call.id.clone(), tool_call.call_id.clone(), output.clone()));
+
+                                let tr = ToolResult { id: tool_call.id, call_id: tool_call.call_id, content: output.into() };
+                                yield Ok(MultiTurnStreamItem::StreamUserItem(StreamedUserContent::ToolResult(tr)));
+                            }
+                            Err(e @ StreamingError::Tool(ToolSetError::ArgumentValidationError(_))) => {
+                                yield Err(e);
+                                break 'outer;
+                            }
+                            Err(e) => {
+                                yield Err(e);
+                            }
+                        }
+                    }
+
+                    let new_messages = turn_messages("", Vec::new(), &tool_results);
+                    persist_messages(&session, new_messages.clone())
+                        .await
+                        .map_err(|e| Box::new(PromptError::from(e)))?;
+                    chat_history.write().await.extend(new_messages);
+
+                    if let Some(ref sink) = self.checkpoint_sink {
+                        let checkpoint = Checkpoint {
+                            chat_history: chat_history.read().await.to_vec(),
+                            depth: current_max_depth,
+                            pending_tool_calls: Vec::new(),
+                            usage: aggregated_usage,
+                        };
+                        sink.save(&checkpoint)
+                            .await
+                            .map_err(|e| Box::new(PromptError::from(e)))?;
+                    }
+
+                    current_prompt = match chat_history.write().await.pop() {
+                        Some(prompt) => prompt,
+                        None => unreachable!("Chat history should never be empty at this point"),
+                    };
+                }
+
                 if current_max_depth > self.max_depth + 1 {
                     last_prompt_error = current_prompt.rag_text().unwrap_or_default();
                     max_depth_reached = true;
@@ -237,7 +458,7 @@ where
                     }
                 }
 
-                let chat_stream_span = info_span!(
+                let chat_stream_span = gen_ai_span!(
                     target: "rig::agent_chat",
                     parent: tracing::Span::current(),
                     "chat_streaming",
@@ -247,8 +468,6 @@ where
                     gen_ai.request.model = tracing::field::Empty,
                     gen_ai.response.id = tracing::field::Empty,
                     gen_ai.response.model = tracing::field::Empty,
-                    gen_ai.usage.output_tokens = tracing::field::Empty,
-                    gen_ai.usage.input_tokens = tracing::field::Empty,
                     gen_ai.input.messages = tracing::field::Empty,
                     gen_ai.output.messages = tracing::field::Empty,
                 );
@@ -264,8 +483,18 @@ where
 
                 chat_history.write().await.push(current_prompt.clone());
 
+                // Tool result prompts of later turns have already been persisted when they were produced
+                if current_max_depth == 1 {
+                    persist_messages(&session, vec![current_prompt.clone()])
+                        .await
+                        .map_err(|e| Box::new(PromptError::from(e)))?;
+                }
+
                 let mut tool_calls = vec![];
                 let mut tool_results = vec![];
+                let mut pending_tool_calls = vec![];
+                // Text streamed during this turn, kept alongside its tool calls in the history
+                let mut turn_text = String::new();
 
                 while let Some(content) = stream.next().await {
                     match content {
@@ -275,6 +504,7 @@ where
                                 is_text_response = true;
                             }
                             last_text_response.push_str(&text.text);
+                            turn_text.push_str(&text.text);
                             if let Some(ref hook) = self.hook {
                                 hook.on_text_delta(&text.text, &last_text_response, cancel_sig.clone()).await;
                                 if cancel_sig.is_cancelled() {
@@ -287,70 +517,43 @@ where
                             did_call_tool = false;
                         },
                         Ok(StreamedAssistantContent::ToolCall(tool_call)) => {
-                            let tool_span = info_span!(
-                                parent: tracing::Span::current(),
-                                "execute_tool",
-                                gen_ai.operation.name = "execute_tool",
-                                gen_ai.tool.type = "function",
-                                gen_ai.tool.name = tracing::field::Empty,
-                                gen_ai.tool.call.id = tracing::field::Empty,
-                                gen_ai.tool.call.arguments = tracing::field::Empty,
-                                gen_ai.tool.call.result = tracing::field::Empty
-                            );
-
                             yield Ok(MultiTurnStreamItem::stream_item(StreamedAssistantContent::ToolCall(tool_call.clone())));
 
-                            let tc_result = async {
-                                let tool_span = tracing::Span::current();
-                                let tool_args = json_utils::value_to_json_string(&tool_call.function.arguments);
-                                if let Some(ref hook) = self.hook {
-                                    hook.on_tool_call(&tool_call.function.name, tool_call.call_id.clone(), &tool_args, cancel_sig.clone()).await;
-                                    if cancel_sig.is_cancelled() {
-                                        return Err(StreamingError::Prompt(PromptError::prompt_cancelled(chat_history.read().await.to_vec(),
-                                            cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
-                                        ).into()));
-                                    }
-                                }
-
-                                tool_span.record("gen_ai.tool.name", &tool_call.function.name);
-                                tool_span.record("gen_ai.tool.call.arguments", &tool_args);
-
-                                let tool_result = match
-                                agent.tool_server_handle.call_tool(&tool_call.function.name, &tool_args).await {
-                                    Ok(thing) => thing,
-                                    Err(e) => {
-                                        tracing::warn!("Error while calling tool: {e}");
-                                        e.to_string()
-                                    }
-                                };
-
-                                tool_span.record("gen_ai.tool.call.result", &tool_result);
-
-                                if let Some(ref hook) = self.hook {
-                                    hook.on_tool_result(&tool_call.function.name, tool_call.call_id.clone(), &tool_args, &tool_result.to_string(), cancel_sig.clone())
-                                    .await;
-
-                                    if cancel_sig.is_cancelled() {
-                                        return Err(StreamingError::Prompt(PromptError::prompt_cancelled(chat_history.read().await.to_vec(),
-                                            cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
-                                        ).into()));
-                                    }
-                                }
-
-                                let tool_call_msg = AssistantContent::ToolCall(tool_call.clone());
+                            // Tool calls are executed once the response has been fully streamed when running
+                            // them concurrently, or when the usage of the response must be checked first
+                            if self.concurrency > 1 || self.budget.is_some() {
+                                pending_tool_calls.push(tool_call);
+                                continue;
+                            }
 
-                                tool_calls.push(tool_call_msg);
-                                tool_results.push((tool_call.id.clone(), tool_call.call_id.clone(), tool_result.clone()));
+                            if let Some(ref sink) = self.checkpoint_sink {
+                                let checkpoint = pending_checkpoint(
+                                    chat_history.read().await.to_vec(),
+                                    &turn_text,
+                                    &tool_calls,
+                                    &tool_results,
+                                    vec![tool_call.clone()],
+                                    current_max_depth,
+                                    aggregated_usage,
+                                );
+                                sink.save(&checkpoint)
+                                    .await
+                                    .map_err(|e| Box::new(PromptError::from(e)))?;
+                            }
 
-                                did_call_tool = true;
-                                Ok(tool_result)
-                            }.instrument(tool_span).await;
+                            match execute_tool_call(&agent, self.hook.as_ref(), &tool_call, &cancel_sig, &chat_history, &argument_corrections, tool_call_context(&self.tool_context, &agent, self.session_id.as_deref(), &tool_call, &cancel_sig)).await {
+                                Ok(output) => {
+                                    tool_calls.push(AssistantContent::ToolCall(tool_call.clone()));
+                                    tool_results.push((tool_call.id.clone(), tool_call.call_id.clone(), output.clone()));
+                                    did_call_tool = true;
 
-                            match tc_result {
-                                Ok(text) => {
-                                    let tr = ToolResult { id: tool_call.id, call_id: tool_call.call_id, content: OneOrMany::one(ToolResultContent::Text(Text { text })) };
+                                    let tr = ToolResult { id: tool_call.id, call_id: tool_call.call_id, content: output.into() };
                                     yield Ok(MultiTurnStreamItem::StreamUserItem(StreamedUserContent::ToolResult(tr)));
                                 }
+                                Err(e @ StreamingError::Tool(ToolSetError::ArgumentValidationError(_))) => {
+                                    yield Err(e);
+                                    break 'outer;
+                                }
                                 Err(e) => {
                                     yield Err(e);
                                 }
@@ -405,31 +608,89 @@ where
                     }
                 }
 
-                // Add (parallel) tool calls to chat history
-                if !tool_calls.is_empty() {
-                    chat_history.write().await.push(Message::Assistant {
-                        id: None,
-                        content: OneOrMany::many(tool_calls.clone()).expect("Impossible EmptyListError"),
-                    });
+                if !pending_tool_calls.is_empty() {
+                    let checkpoint = pending_checkpoint(
+                        chat_history.read().await.to_vec(),
+                        &turn_text,
+                        &tool_calls,
+                        &tool_results,
+                        pending_tool_calls.clone(),
+                        current_max_depth,
+                        aggregated_usage,
+                    );
+                    if let Some(ref sink) = self.checkpoint_sink {
+                        sink.save(&checkpoint)
+                            .await
+                            .map_err(|e| Box::new(PromptError::from(e)))?;
+                    }
+
+                    // Tools are only executed if the budget allows for another round-trip to the model
+                    if let Some(Err(limit)) = self.budget.as_ref().map(|budget| budget.check(agent.model.model_name(), &aggregated_usage)) {
+                        yield Err(Box::new(PromptError::BudgetExceeded {
+                            limit,
+                            usage: aggregated_usage,
+                            chat_history: Box::new(checkpoint.chat_history),
+                        }).into());
+                        break 'outer;
+                    }
+
+                    // Results are yielded in the order the tool calls were emitted by the model
+                    let results = futures::stream::iter(std::mem::take(&mut pending_tool_calls))
+                        .map(|tool_call| {
+                            let agent = &agent;
+                            let hook = self.hook.as_ref();
+                            let cancel_sig = &cancel_sig;
+                            let chat_history = &chat_history;
+                            let argument_corrections = &argument_corrections;
+                            let tool_context = tool_call_context(&self.tool_context, agent, self.session_id.as_deref(), &tool_call, cancel_sig);
+                            async move {
+                                let result = execute_tool_call(agent, hook, &tool_call, cancel_sig, chat_history, argument_corrections, tool_context).await;
+                                (tool_call, result)
+                            }
+                        })
+                        .buffered(self.concurrency.max(1))
+                        .collect::<Vec<_>>()
+                        .await;
+
+                    for (tool_call, result) in results {
+                        match result {
+                            Ok(output) => {
+                                tool_calls.push(AssistantContent::ToolCall(tool_call.clone()));
+                                tool_results.push((tool_call.id.clone(), tool_call.call_id.clone(), output.clone()));
+                                did_call_tool = true;
+
+                                let tr = ToolResult { id: tool_call.id, call_id: tool_call.call_id, content: output.into() };
+                                yield Ok(MultiTurnStreamItem::StreamUserItem(StreamedUserContent::ToolResult(tr)));
+                            }
+                            Err(e @ StreamingError::Tool(ToolSetError::ArgumentValidationError(_))) => {
+                                yield Err(e);
+                                break 'outer;
+                            }
+                            Err(e) => {
+                                yield Err(e);
+                            }
+                        }
+                    }
                 }
 
-                // Add tool results to chat history
-                for (id, call_id, tool_result) in tool_results {
-                    if let Some(call_id) = call_id {
-                        chat_history.write().await.push(Message::User {
-                            content: OneOrMany::one(UserContent::tool_result_with_call_id(
-                                &id,
-                                call_id.clone(),
-                                OneOrMany::one(ToolResultContent::text(&tool_result)),
-                            )),
-                        });
-                    } else {
-                        chat_history.write().await.push(Message::User {
-                            content: OneOrMany::one(UserContent::tool_result(
-                                &id,
-                                OneOrMany::one(ToolResultContent::text(&tool_result)),
-                            )),
-                        });
+                let new_messages = turn_messages(&turn_text, tool_calls, &tool_results);
+
+                if !new_messages.is_empty() {
+                    persist_messages(&session, new_messages.clone())
+                        .await
+                        .map_err(|e| Box::new(PromptError::from(e)))?;
+                    chat_history.write().await.extend(new_messages);
+
+                    if let Some(ref sink) = self.checkpoint_sink {
+                        let checkpoint = Checkpoint {
+                            chat_history: chat_history.read().await.to_vec(),
+                            depth: current_max_depth,
+                            pending_tool_calls: Vec::new(),
+                            usage: aggregated_usage,
+                        };
+                        sink.save(&checkpoint)
+                            .await
+                            .map_err(|e| Box::new(PromptError::from(e)))?;
                     }
                 }
 
@@ -440,9 +701,14 @@ where
                 };
 
                 if !did_call_tool {
+                    if !last_text_response.is_empty() {
+                        persist_messages(&session, vec![Message::assistant(&last_text_response)])
+                            .await
+                            .map_err(|e| Box::new(PromptError::from(e)))?;
+                    }
+
                     let current_span = tracing::Span::current();
-                    current_span.record("gen_ai.usage.input_tokens", aggregated_usage.input_tokens);
-                    current_span.record("gen_ai.usage.output_tokens", aggregated_usage.output_tokens);
+                    current_span.record_token_usage(&aggregated_usage);
                     tracing::info!("Agent multi-turn stream finished");
                     yield Ok(MultiTurnStreamItem::final_response(&last_text_response, aggregated_usage));
                     break;
@@ -462,6 +728,193 @@ where
     }
 }
 
+/// Returns the messages recording a turn in the chat history: the (parallel) tool calls of the
+/// response along with the text preceding them, followed by one message per tool result.
+fn turn_messages(
+    turn_text: &str,
+    mut tool_calls: Vec<AssistantContent>,
+    tool_results: &[(String, Option<String>, ToolOutput)],
+) -> Vec<Message> {
+    let mut messages = vec![];
+
+    if !tool_calls.is_empty() {
+        if !turn_text.is_empty() {
+            tool_calls.insert(0, AssistantContent::text(turn_text));
+        }
+        messages.push(Message::Assistant {
+            id: None,
+            content: OneOrMany::many(tool_calls).expect("Impossible EmptyListError"),
+        });
+    }
+
+    for (id, call_id, tool_result) in tool_results {
+        let content = match call_id {
+            Some(call_id) => UserContent::tool_result_with_call_id(
+                id,
+                call_id.clone(),
+                tool_result.clone().into(),
+            ),
+            None => UserContent::tool_result(id, tool_result.clone().into()),
+        };
+        messages.push(Message::User {
+            content: OneOrMany::one(content),
+        });
+    }
+
+    messages
+}
+
+/// Returns the checkpoint of a turn whose `pending` tool calls are about to be executed, the
+/// `executed` ones having already produced `tool_results`.
+fn pending_checkpoint(
+    mut chat_history: Vec<Message>,
+    turn_text: &str,
+    executed: &[AssistantContent],
+    tool_results: &[(String, Option<String>, ToolOutput)],
+    pending: Vec<ToolCall>,
+    depth: usize,
+    usage: crate::completion::Usage,
+) -> Checkpoint {
+    let tool_calls = executed
+        .iter()
+        .cloned()
+        .chain(pending.iter().cloned().map(AssistantContent::ToolCall))
+        .collect();
+    chat_history.extend(turn_messages(turn_text, tool_calls, tool_results));
+
+    Checkpoint {
+        chat_history,
+        depth,
+        pending_tool_calls: pending,
+        usage,
+    }
+}
+
+/// Executes a single tool call of a streamed response, running the hooks around it.
+/// Returns the tool result (or the rejection reason if the hook rejected the call).
+async fn execute_tool_call<M, P>(
+    agent: &Agent<M>,
+    hook: Option<&P>,
+    tool_call: &ToolCall,
+    cancel_sig: &CancelSignal,
+    chat_history: &RwLock<Vec<Message>>,
+    argument_corrections: &ArgumentCorrections,
+    tool_context: ToolContext,
+) -> Result<ToolOutput, StreamingError>
+where
+    M: CompletionModel,
+    P: StreamingPromptHook<M>,
+{
+    let tool_span = info_span!(
+        parent: tracing::Span::current(),
+        "execute_tool",
+        gen_ai.operation.name = "execute_tool",
+        gen_ai.tool.type = "function",
+        gen_ai.tool.name = tracing::field::Empty,
+        gen_ai.tool.call.id = tracing::field::Empty,
+        gen_ai.tool.call.arguments = tracing::field::Empty,
+        gen_ai.tool.call.result = tracing::field::Empty
+    );
+
+    async {
+        let tool_span = tracing::Span::current();
+        let requested_args = json_utils::value_to_json_string(&tool_call.function.arguments);
+        let decision = match hook {
+            Some(hook) => {
+                let decision = hook
+                    .on_tool_call(
+                        &tool_call.function.name,
+                        tool_call.call_id.clone(),
+                        &requested_args,
+                        cancel_sig.clone(),
+                    )
+                    .await;
+                if cancel_sig.is_cancelled() {
+                    return Err(StreamingError::Prompt(
+                        PromptError::prompt_cancelled(
+                            chat_history.read().await.to_vec(),
+                            cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
+                        )
+                        .into(),
+                    ));
+                }
+                decision
+            }
+            None => ToolCallDecision::Approve,
+        };
+        let (tool_args, rejection) = match decision {
+            ToolCallDecision::Approve => (requested_args, None),
+            ToolCallDecision::Reject { reason } => (requested_args, Some(reason)),
+            ToolCallDecision::RewriteArgs {
+                args: rewritten_args,
+            } => (rewritten_args, None),
+        };
+
+        tool_span.record("gen_ai.tool.name", &tool_call.function.name);
+        tool_span.record("gen_ai.tool.call.arguments", &tool_args);
+
+        let tool_result = if let Some(reason) = rejection {
+            tracing::info!(
+                "tool call {} was rejected: {reason}",
+                tool_call.function.name
+            );
+            ToolOutput::text(reason)
+        } else {
+            let result = agent
+                .tool_server_handle
+                .call_tool_with_context(&tool_call.function.name, &tool_args, tool_context)
+                .await;
+            argument_corrections.track(&tool_call.function.name, &result)?;
+            match result {
+                Ok(thing) => thing,
+                Err(e) => {
+                    tracing::warn!("Error while calling tool: {e}");
+                    let error = e.to_string();
+                    if let Some(hook) = hook {
+                        hook.on_tool_error(
+                            &tool_call.function.name,
+                            tool_call.call_id.clone(),
+                            &tool_args,
+                            &error,
+                            cancel_sig.clone(),
+                        )
+                        .await;
+                    }
+                    ToolOutput::text(error)
+                }
+            }
+        };
+
+        // Rejected calls are reported too, so that observers see every call
+        if let Some(hook) = hook {
+            hook.on_tool_result(
+                &tool_call.function.name,
+                tool_call.call_id.clone(),
+                &tool_args,
+                &tool_result.to_string(),
+                cancel_sig.clone(),
+            )
+            .await;
+
+            if cancel_sig.is_cancelled() {
+                return Err(StreamingError::Prompt(
+                    PromptError::prompt_cancelled(
+                        chat_history.read().await.to_vec(),
+                        cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
+                    )
+                    .into(),
+                ));
+            }
+        }
+
+        tool_span.record("gen_ai.tool.call.result", tool_result.to_string());
+
+        Ok(tool_result)
+    }
+    .instrument(tool_span)
+    .await
+}
+
 impl<M, P> IntoFuture for StreamingPromptRequest<M, P>
 where
     M: CompletionModel + 'static,
@@ -565,18 +1018,21 @@ where
 
     #[allow(unused_variables)]
     /// Called before a tool is invoked.
+    /// The returned [`ToolCallDecision`] decides whether the tool call is executed as requested,
+    /// executed with rewritten arguments or rejected (approved by default).
     fn on_tool_call(
         &self,
         tool_name: &str,
         tool_call_id: Option<String>,
         args: &str,
         cancel_sig: CancelSignal,
-    ) -> impl Future<Output = ()> + Send {
-        async {}
+    ) -> impl Future<Output = ToolCallDecision> + Send {
+        async { ToolCallDecision::Approve }
     }
 
     #[allow(unused_variables)]
-    /// Called after a tool is invoked (and a result has been returned).
+    /// Called after a tool is invoked (and a result has been returned). Rejected calls are
+    /// reported as well, with the rejection reason as result.
     fn on_tool_result(
         &self,
         tool_name: &str,
@@ -587,6 +1043,20 @@ where
     ) -> impl Future<Output = ()> + Send {
         async {}
     }
+
+    #[allow(unused_variables)]
+    /// Called when a tool returns an error, before [`StreamingPromptHook::on_tool_result`] is
+    /// called with the error message as the result.
+    fn on_tool_error(
+        &self,
+        tool_name: &str,
+        tool_call_id: Option<String>,
+        args: &str,
+        error: &str,
+        cancel_sig: CancelSignal,
+    ) -> impl Future<Output = ()> + Send {
+        async {}
+    }
 }
 
 impl<M> StreamingPromptHook<M> for () where M: CompletionModel {}
@@ -0,0 +1,671 @@
+//! This module contains the [Team] type, which lets several agents work on the same conversation
+//! by handing it off to each other.
+//!
+//! Unlike using an agent as a tool of another agent (where the sub-agent only gets a one-shot
+//! prompt), a handoff transfers the whole conversation: the receiving agent gets the shared chat
+//! history and stays in control until it hands the conversation off again (e.g.: back to the
+//! agent it got it from) or answers with a text response.
+//!
+//! Every member of the team is given a `handoff` tool listing the other members along with their
+//! description, which the model uses to route the conversation to the right agent (a team made of
+//! a single agent doesn't get the tool). Other tool calls are executed the same way as in the
+//! agent loop: through the hook (see [TeamRequest::with_hook]) and with the [ToolContext] of the
+//! request (see [TeamRequest::with_tool_context]).
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     agent::team::Team,
+//!     client::{CompletionClient, ProviderClient},
+//!     providers::openai,
+//! };
+//!
+//! let openai = openai::Client::from_env();
+//!
+//! let triage = openai
+//!     .agent("gpt-4o")
+//!     .name("triage")
+//!     .description("Greets the customer and routes their request")
+//!     .preamble("You are the front desk of ACME Inc. Hand off to the right agent.")
+//!     .build();
+//!
+//! let billing = openai
+//!     .agent("gpt-4o")
+//!     .name("billing")
+//!     .description("Answers questions about invoices and payments")
+//!     .preamble("You are the billing department of ACME Inc.")
+//!     .build();
+//!
+//! let team = Team::builder().agent(triage).agent(billing).build()?;
+//!
+//! let response = team.prompt("Why was I charged twice this month?").await?;
+//!
+//! for message in &response.messages {
+//!     println!("{}: {:?}", message.agent, message.message);
+//! }
+//! println!("{} answered: {}", response.agent, response.output);
+//! ```
+use std::future::IntoFuture;
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    OneOrMany,
+    completion::{Completion, CompletionModel, Message, PromptError, ToolDefinition, Usage},
+    message::{AssistantContent, UserContent},
+    tool::{ToolContext, ToolOutput, ToolSetError},
+    wasm_compat::WasmBoxedFuture,
+};
+
+use super::{
+    Agent, CancelSignal, PromptHook,
+    prompt_request::{
+        ArgumentCorrections, DEFAULT_MAX_ARGUMENT_CORRECTIONS, dispatch_tool_call,
+        tool_call_context,
+    },
+};
+
+/// Name of the tool the members of a team use to hand off the conversation.
+pub const HANDOFF_TOOL_NAME: &str = "handoff";
+
+#[derive(Debug, thiserror::Error)]
+pub enum TeamError {
+    /// A team needs at least one agent
+    #[error("A team needs at least one agent")]
+    EmptyTeam,
+
+    /// Agents are referenced by name in handoffs
+    #[error("Every agent of a team needs a name")]
+    UnnamedAgent,
+
+    #[error("Duplicate agent name: {0}")]
+    DuplicateAgent(String),
+}
+
+/// A group of agents sharing a conversation through handoffs.
+pub struct Team<M>
+where
+    M: CompletionModel,
+{
+    agents: Vec<Agent<M>>,
+    max_turns: usize,
+}
+
+impl<M> Team<M>
+where
+    M: CompletionModel,
+{
+    pub fn builder() -> TeamBuilder<M> {
+        TeamBuilder::new()
+    }
+
+    /// Returns the agents of the team. The first one is the entry point of the team.
+    pub fn agents(&self) -> &[Agent<M>] {
+        &self.agents
+    }
+
+    /// Prompt the team. The conversation starts with the first agent of the team unless
+    /// specified otherwise using [TeamRequest::starting_with].
+    pub fn prompt(&self, prompt: impl Into<Message>) -> TeamRequest<'_, M> {
+        TeamRequest {
+            team: self,
+            prompt: prompt.into(),
+            chat_history: None,
+            starting_agent: None,
+            hook: None,
+            tool_context: ToolContext::default(),
+        }
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.agents
+            .iter()
+            .position(|agent| agent.name.as_deref() == Some(name))
+    }
+
+    /// Definition of the handoff tool given to the agent at index `active`.
+    fn handoff_definition(&self, active: usize) -> ToolDefinition {
+        let others = self
+            .agents
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != active)
+            .map(|(_, agent)| agent)
+            .collect::<Vec<_>>();
+
+        let members = others
+            .iter()
+            .map(|agent| {
+                format!(
+                    "- {}: {}",
+                    agent.name(),
+                    agent.description.as_deref().unwrap_or("No description")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        ToolDefinition {
+            name: HANDOFF_TOOL_NAME.to_string(),
+            description: format!(
+                "Hand the conversation off to another agent of your team. The agent gets the \
+                whole conversation and takes over until it hands it off again or answers.\n\n\
+                Available agents:\n{members}"
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "agent": {
+                        "type": "string",
+                        "description": "The name of the agent to hand the conversation off to",
+                        "enum": others.iter().map(|agent| agent.name()).collect::<Vec<_>>(),
+                    },
+                    "reason": {
+                        "type": "string",
+                        "description": "Why the conversation is handed off",
+                    },
+                },
+                "required": ["agent"],
+            }),
+        }
+    }
+}
+
+/// A builder for creating a [Team].
+pub struct TeamBuilder<M>
+where
+    M: CompletionModel,
+{
+    agents: Vec<Agent<M>>,
+    max_turns: usize,
+}
+
+impl<M> Default for TeamBuilder<M>
+where
+    M: CompletionModel,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M> TeamBuilder<M>
+where
+    M: CompletionModel,
+{
+    pub fn new() -> Self {
+        Self {
+            agents: Vec::new(),
+            max_turns: 10,
+        }
+    }
+
+    /// Add an agent to the team. The first agent added is the entry point of the team.
+    pub fn agent(mut self, agent: Agent<M>) -> Self {
+        self.agents.push(agent);
+        self
+    }
+
+    /// Set the maximum number of completion calls (across all agents) for a single prompt
+    /// (10 by default).
+    pub fn max_turns(mut self, max_turns: usize) -> Self {
+        self.max_turns = max_turns;
+        self
+    }
+
+    pub fn build(self) -> Result<Team<M>, TeamError> {
+        if self.agents.is_empty() {
+            return Err(TeamError::EmptyTeam);
+        }
+
+        let mut names = std::collections::HashSet::new();
+        for agent in &self.agents {
+            let name = agent.name.as_deref().ok_or(TeamError::UnnamedAgent)?;
+            if !names.insert(name) {
+                return Err(TeamError::DuplicateAgent(name.to_string()));
+            }
+        }
+
+        Ok(Team {
+            agents: self.agents,
+            max_turns: self.max_turns,
+        })
+    }
+}
+
+/// A message of the conversation along with the name of the agent that produced it.
+#[derive(Debug, Clone)]
+pub struct TeamMessage {
+    pub agent: String,
+    pub message: Message,
+}
+
+#[derive(Debug, Clone)]
+pub struct TeamResponse {
+    /// The text response of the agent that finished the conversation.
+    pub output: String,
+    /// The name of the agent that finished the conversation.
+    pub agent: String,
+    /// The token usage aggregated over every completion call (across all agents).
+    pub total_usage: Usage,
+    /// Every message produced while answering the prompt, in order.
+    pub messages: Vec<TeamMessage>,
+}
+
+#[derive(Deserialize)]
+struct HandoffArgs {
+    agent: String,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// A request prompting a [Team], created with [Team::prompt].
+pub struct TeamRequest<'a, M, P = ()>
+where
+    M: CompletionModel,
+    P: PromptHook<M>,
+{
+    team: &'a Team<M>,
+    prompt: Message,
+    chat_history: Option<&'a mut Vec<Message>>,
+    starting_agent: Option<String>,
+    hook: Option<P>,
+    tool_context: ToolContext,
+}
+
+impl<'a, M, P> TeamRequest<'a, M, P>
+where
+    M: CompletionModel,
+    P: PromptHook<M>,
+{
+    /// Add chat history to the request. New messages are appended to it as the team works.
+    pub fn with_history(mut self, history: &'a mut Vec<Message>) -> Self {
+        self.chat_history = Some(history);
+        self
+    }
+
+    /// Start the conversation with the given agent (e.g.: the agent that finished the previous
+    /// prompt, see [TeamResponse::agent]) instead of the first agent of the team.
+    pub fn starting_with(mut self, agent: impl Into<String>) -> Self {
+        self.starting_agent = Some(agent.into());
+        self
+    }
+
+    /// Set the [ToolContext] passed to the tool calls of every agent of the team (see
+    /// [crate::agent::PromptRequest::with_tool_context]).
+    pub fn with_tool_context(mut self, tool_context: ToolContext) -> Self {
+        self.tool_context = tool_context;
+        self
+    }
+
+    /// Attach a per-request hook, called around the completion and tool calls of every agent of
+    /// the team. Handoffs are not reported to the hook as tool calls.
+    pub fn with_hook<P2>(self, hook: P2) -> TeamRequest<'a, M, P2>
+    where
+        P2: PromptHook<M>,
+    {
+        TeamRequest {
+            team: self.team,
+            prompt: self.prompt,
+            chat_history: self.chat_history,
+            starting_agent: self.starting_agent,
+            hook: Some(hook),
+            tool_context: self.tool_context,
+        }
+    }
+
+    async fn send(self) -> Result<TeamResponse, PromptError> {
+        let team = self.team;
+
+        let mut owned_history = Vec::new();
+        let chat_history = if let Some(history) = self.chat_history {
+            history
+        } else {
+            &mut owned_history
+        };
+
+        let mut active = match &self.starting_agent {
+            Some(name) => team.position(name).unwrap_or_else(|| {
+                tracing::warn!("Unknown agent {name}, starting with the first agent of the team");
+                0
+            }),
+            None => 0,
+        };
+
+        chat_history.push(self.prompt.clone());
+
+        let mut usage = Usage::new();
+        let mut messages = Vec::new();
+
+        let cancel_sig = CancelSignal::new();
+        let argument_corrections = ArgumentCorrections::new(DEFAULT_MAX_ARGUMENT_CORRECTIONS);
+
+        for turn in 1..=team.max_turns {
+            let agent = &team.agents[active];
+            tracing::info!(
+                "Team turn {turn}/{}: agent {} is in control",
+                team.max_turns,
+                agent.name()
+            );
+
+            let prompt = chat_history
+                .last()
+                .cloned()
+                .expect("there should always be at least one message in the chat history");
+
+            let history = &chat_history[..chat_history.len() - 1];
+            if let Some(ref hook) = self.hook {
+                hook.on_completion_call(&prompt, history, cancel_sig.clone())
+                    .await;
+                if cancel_sig.is_cancelled() {
+                    return Err(PromptError::prompt_cancelled(
+                        chat_history.to_vec(),
+                        cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
+                    ));
+                }
+            }
+
+            let request = agent.completion(prompt.clone(), history.to_vec()).await?;
+            // Agents can only hand off to other members of the team
+            let request = if team.agents.len() > 1 {
+                request.tool(team.handoff_definition(active))
+            } else {
+                request
+            };
+            let resp = request.send().await?;
+
+            usage += resp.usage;
+
+            if let Some(ref hook) = self.hook {
+                hook.on_completion_response(&prompt, &resp, cancel_sig.clone())
+                    .await;
+                if cancel_sig.is_cancelled() {
+                    return Err(PromptError::prompt_cancelled(
+                        chat_history.to_vec(),
+                        cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
+                    ));
+                }
+            }
+
+            let assistant_message = Message::Assistant {
+                id: None,
+                content: resp.choice.clone(),
+            };
+            chat_history.push(assistant_message.clone());
+            messages.push(TeamMessage {
+                agent: agent.name().to_string(),
+                message: assistant_message,
+            });
+
+            let tool_calls = resp
+                .choice
+                .iter()
+                .filter_map(|content| match content {
+                    AssistantContent::ToolCall(tool_call) => Some(tool_call),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            if tool_calls.is_empty() {
+                let output = resp
+                    .choice
+                    .iter()
+                    .filter_map(|content| match content {
+                        AssistantContent::Text(text) => Some(text.text.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                return Ok(TeamResponse {
+                    output,
+                    agent: agent.name().to_string(),
+                    total_usage: usage,
+                    messages,
+                });
+            }
+
+            let mut next = None;
+            let mut tool_content = Vec::with_capacity(tool_calls.len());
+
+            for tool_call in tool_calls {
+                let output = if tool_call.function.name == HANDOFF_TOOL_NAME {
+                    match serde_json::from_value::<HandoffArgs>(
+                        tool_call.function.arguments.clone(),
+                    ) {
+                        Ok(_) if next.is_some() => ToolOutput::text(
+                            "Ignored: the conversation has already been handed off",
+                        ),
+                        Ok(args) => match team.position(&args.agent) {
+                            Some(target) if target != active => {
+                                tracing::info!(
+                                    "agent {} handed off to {} (reason: {})",
+                                    agent.name(),
+                                    args.agent,
+                                    args.reason.as_deref().unwrap_or("<no reason given>")
+                                );
+                                next = Some(target);
+                                ToolOutput::text(format!("Handed off to {}", args.agent))
+                            }
+                            Some(_) => ToolOutput::text("You cannot hand off to yourself"),
+                            None => ToolOutput::text(format!("Unknown agent: {}", args.agent)),
+                        },
+                        Err(e) => ToolOutput::text(format!("Invalid handoff arguments: {e}")),
+                    }
+                } else {
+                    let tool_context =
+                        tool_call_context(&self.tool_context, agent, None, tool_call, &cancel_sig);
+                    dispatch_tool_call(
+                        agent,
+                        self.hook.as_ref(),
+                        tool_call,
+                        &cancel_sig,
+                        &argument_corrections,
+                        tool_context,
+                    )
+                    .await
+                    .map_err(|e| match e {
+                        ToolSetError::Interrupted => PromptError::prompt_cancelled(
+                            chat_history.to_vec(),
+                            cancel_sig.cancel_reason().unwrap_or("<no reason given>"),
+                        ),
+                        e => e.into(),
+                    })?
+                };
+
+                let content = output.into_content();
+                tool_content.push(match tool_call.call_id.clone() {
+                    Some(call_id) => UserContent::tool_result_with_call_id(
+                        tool_call.id.clone(),
+                        call_id,
+                        content,
+                    ),
+                    None => UserContent::tool_result(tool_call.id.clone(), content),
+                });
+            }
+
+            let tool_result_message = Message::User {
+                content: OneOrMany::many(tool_content).expect("There is atleast one tool call"),
+            };
+            chat_history.push(tool_result_message.clone());
+            messages.push(TeamMessage {
+                agent: agent.name().to_string(),
+                message: tool_result_message,
+            });
+
+            if let Some(next) = next {
+                active = next;
+            }
+        }
+
+        Err(PromptError::MaxDepthError {
+            max_depth: team.max_turns,
+            chat_history: Box::new(chat_history.clone()),
+            prompt: Box::new(self.prompt),
+        })
+    }
+}
+
+impl<'a, M, P> IntoFuture for TeamRequest<'a, M, P>
+where
+    M: CompletionModel,
+    P: PromptHook<M> + 'a,
+{
+    type Output = Result<TeamResponse, PromptError>;
+    type IntoFuture = WasmBoxedFuture<'a, Self::Output>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.send())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use serde_json::json;
+
+    use super::{Team, TeamError};
+    use crate::{
+        agent::{AgentBuilder, CancelSignal, PromptHook, ToolCallDecision},
+        completion::{CompletionModel, Message},
+        message::{AssistantContent, ToolResultContent, UserContent},
+        test_utils::{EchoTool, ScriptedModel, echo_call},
+    };
+
+    #[tokio::test]
+    async fn test_team_handoff() {
+        let triage_model = ScriptedModel::new([AssistantContent::tool_call(
+            "call_1",
+            "handoff",
+            json!({"agent": "billing", "reason": "billing question"}),
+        )]);
+        let billing_model = ScriptedModel::new([AssistantContent::text("You were charged once.")]);
+
+        let team = Team::builder()
+            .agent(
+                AgentBuilder::new(triage_model.clone())
+                    .name("triage")
+                    .build(),
+            )
+            .agent(
+                AgentBuilder::new(billing_model.clone())
+                    .name("billing")
+                    .description("Answers billing questions")
+                    .build(),
+            )
+            .build()
+            .unwrap();
+
+        let response = team.prompt("Was I charged twice?").await.unwrap();
+
+        assert_eq!(response.output, "You were charged once.");
+        assert_eq!(response.agent, "billing");
+        assert_eq!(response.total_usage.total_tokens, 30);
+        assert_eq!(
+            response
+                .messages
+                .iter()
+                .map(|message| message.agent.as_str())
+                .collect::<Vec<_>>(),
+            vec!["triage", "triage", "billing"]
+        );
+
+        // The triage agent sees billing in its handoff tool
+        let triage_request = &triage_model.requests.lock().unwrap()[0];
+        assert!(
+            triage_request.tools[0]
+                .description
+                .contains("billing: Answers billing questions")
+        );
+
+        // The billing agent gets the whole conversation, including the handoff
+        let billing_request = &billing_model.requests.lock().unwrap()[0];
+        assert_eq!(billing_request.chat_history.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_team_duplicate_agent() {
+        let model = ScriptedModel::new([]);
+        let result = Team::builder()
+            .agent(AgentBuilder::new(model.clone()).name("a").build())
+            .agent(AgentBuilder::new(model).name("a").build())
+            .build();
+
+        assert!(matches!(result, Err(TeamError::DuplicateAgent(name)) if name == "a"));
+    }
+
+    #[tokio::test]
+    async fn test_single_agent_team_has_no_handoff_tool() {
+        let model = ScriptedModel::new([AssistantContent::text("Hello!")]);
+        let team = Team::builder()
+            .agent(AgentBuilder::new(model.clone()).name("solo").build())
+            .build()
+            .unwrap();
+
+        let response = team.prompt("Hi").await.unwrap();
+
+        assert_eq!(response.output, "Hello!");
+        assert!(model.requests.lock().unwrap()[0].tools.is_empty());
+    }
+
+    /// A hook rejecting every tool call and counting the calls it was asked about.
+    #[derive(Clone, Default)]
+    struct RejectingHook {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl<M: CompletionModel> PromptHook<M> for RejectingHook {
+        async fn on_tool_call(
+            &self,
+            _tool_name: &str,
+            _tool_call_id: Option<String>,
+            _args: &str,
+            _cancel_sig: CancelSignal,
+        ) -> ToolCallDecision {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            ToolCallDecision::reject("not allowed")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_team_tool_calls_go_through_hook() {
+        let tool = EchoTool::default();
+        let model = ScriptedModel::new([
+            echo_call("call_1", "pong", 0),
+            AssistantContent::text("Done."),
+        ]);
+        let team = Team::builder()
+            .agent(
+                AgentBuilder::new(model.clone())
+                    .name("solo")
+                    .tool(tool.clone())
+                    .build(),
+            )
+            .build()
+            .unwrap();
+
+        let hook = RejectingHook::default();
+        let response = team.prompt("ping").with_hook(hook.clone()).await.unwrap();
+
+        assert_eq!(response.output, "Done.");
+        assert_eq!(hook.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+
+        let requests = model.requests.lock().unwrap();
+        let Message::User { content } = requests[1].chat_history.last() else {
+            panic!("expected the tool result to be sent back");
+        };
+        let UserContent::ToolResult(result) = content.first() else {
+            panic!("expected the tool result to be sent back");
+        };
+        assert_eq!(
+            result.content.first(),
+            ToolResultContent::text("not allowed")
+        );
+    }
+}
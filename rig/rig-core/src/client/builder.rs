@@ -534,6 +534,8 @@ impl DynClientBuilder {
             max_tokens: None,
             additional_params: None,
             tool_choice: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
             chat_history: crate::OneOrMany::one(prompt.into()),
         };
 
@@ -563,6 +565,8 @@ impl DynClientBuilder {
             max_tokens: None,
             additional_params: None,
             tool_choice: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
             chat_history: OneOrMany::many(history)
                 .unwrap_or_else(|_| OneOrMany::one(Message::user(""))),
         };
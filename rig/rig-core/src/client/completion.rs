@@ -108,6 +108,14 @@ impl CompletionModel for CompletionModelHandle<'_> {
     > + WasmCompatSend {
         self.0.stream(request)
     }
+
+    fn supports_output_schema(&self) -> bool {
+        self.0.supports_output_schema()
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        self.0.model_name()
+    }
 }
 
 #[allow(deprecated)]
@@ -15,7 +15,7 @@ pub use completion::CompletionClient;
 pub use embeddings::EmbeddingsClient;
 use http::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, marker::PhantomData, sync::Arc};
+use std::{fmt::Debug, marker::PhantomData, sync::Arc, time::Duration};
 use thiserror::Error;
 pub use verify::{VerifyClient, VerifyError};
 
@@ -34,6 +34,7 @@ use crate::{
     embeddings::EmbeddingModel,
     http_client::{
         self, Builder, HttpClientExt, LazyBody, MultipartForm, Request, Response, make_auth_header,
+        retry::{DEFAULT_MAX_RETRY_AFTER, RetryPolicy, send_with_retry},
     },
     prelude::TranscriptionClient,
     transcription::TranscriptionModel,
@@ -122,11 +123,16 @@ impl TryFrom<String> for Nothing {
     }
 }
 
+/// A [RetryPolicy] shared between a [Client] and its requests
+type SharedRetry = Arc<dyn RetryPolicy + Send + Sync + 'static>;
+
 #[derive(Clone)]
 pub struct Client<Ext = Nothing, H = reqwest::Client> {
     base_url: Arc<str>,
     headers: Arc<HeaderMap>,
-    http_client: H,
+    http_client: Arc<H>,
+    retry_policy: Option<SharedRetry>,
+    max_retry_after: Duration,
     ext: Ext,
 }
 
@@ -160,7 +166,8 @@ where
                     })
                     .collect::<Vec<(&HeaderName, &HeaderValue)>>(),
             )
-            .field("http_client", &self.http_client);
+            .field("http_client", &self.http_client)
+            .field("retries", &self.retry_policy.is_some());
 
         self.ext
             .fields()
@@ -272,6 +279,8 @@ impl<Ext, H> Client<Ext, H> {
             base_url: self.base_url,
             headers: self.headers,
             http_client: self.http_client,
+            retry_policy: self.retry_policy,
+            max_retry_after: self.max_retry_after,
             ext: new_ext,
         }
     }
@@ -296,7 +305,23 @@ where
             http::HeaderValue::from_static("application/json"),
         );
 
-        self.http_client.send(req)
+        let (parts, body) = req.into_parts();
+        let req = Request::from_parts(parts, body.into());
+        let http_client = self.http_client.clone();
+        let retry_policy = self.retry_policy.clone();
+        let max_retry_after = self.max_retry_after;
+
+        async move {
+            match retry_policy {
+                Some(policy) => {
+                    send_with_retry(policy.as_ref(), max_retry_after, || {
+                        http_client.send(req.clone())
+                    })
+                    .await
+                }
+                None => http_client.send::<Bytes, U>(req).await,
+            }
+        }
     }
 
     fn send_multipart<U>(
@@ -307,7 +332,21 @@ where
         U: From<Bytes>,
         U: WasmCompatSend + 'static,
     {
-        self.http_client.send_multipart(req)
+        let http_client = self.http_client.clone();
+        let retry_policy = self.retry_policy.clone();
+        let max_retry_after = self.max_retry_after;
+
+        async move {
+            match retry_policy {
+                Some(policy) => {
+                    send_with_retry(policy.as_ref(), max_retry_after, || {
+                        http_client.send_multipart(req.clone())
+                    })
+                    .await
+                }
+                None => http_client.send_multipart(req).await,
+            }
+        }
     }
 
     fn send_streaming<T>(
@@ -447,6 +486,8 @@ pub struct ClientBuilder<Ext, ApiKey = NeedsApiKey, H = reqwest::Client> {
     api_key: ApiKey,
     headers: HeaderMap,
     http_client: Option<H>,
+    retry_policy: Option<SharedRetry>,
+    max_retry_after: Duration,
     ext: Ext,
 }
 
@@ -461,6 +502,8 @@ where
             headers: Default::default(),
             base_url: ExtBuilder::BASE_URL.into(),
             http_client: None,
+            retry_policy: None,
+            max_retry_after: DEFAULT_MAX_RETRY_AFTER,
             ext: Default::default(),
         }
     }
@@ -475,6 +518,8 @@ impl<Ext, H> ClientBuilder<Ext, NeedsApiKey, H> {
             base_url: self.base_url,
             headers: self.headers,
             http_client: self.http_client,
+            retry_policy: self.retry_policy,
+            max_retry_after: self.max_retry_after,
             ext: self.ext,
         }
     }
@@ -494,6 +539,8 @@ where
             api_key,
             headers,
             http_client,
+            retry_policy,
+            max_retry_after,
             ext,
         } = self;
 
@@ -504,6 +551,8 @@ where
             api_key,
             headers,
             http_client,
+            retry_policy,
+            max_retry_after,
             ext: new_ext,
         }
     }
@@ -526,6 +575,8 @@ where
             base_url: self.base_url,
             api_key: self.api_key,
             headers: self.headers,
+            retry_policy: self.retry_policy,
+            max_retry_after: self.max_retry_after,
             ext: self.ext,
         }
     }
@@ -535,6 +586,49 @@ where
         Self { headers, ..self }
     }
 
+    /// Retry failed requests (completions, embeddings, transcriptions, image generations, etc.)
+    /// following `retry_policy`. Only failures for which the provider did not process the
+    /// request are retried: rate limits, server errors and connection failures. A delay requested
+    /// by the provider through a `Retry-After` header is honored, up to
+    /// [max_retry_after](Self::max_retry_after).
+    ///
+    /// Streaming requests are not retried.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// use rig::http_client::retry::ExponentialBackoff;
+    /// use std::time::Duration;
+    ///
+    /// let client = openai::Client::builder()
+    ///     .api_key("...")
+    ///     .retry_policy(ExponentialBackoff::new(
+    ///         Duration::from_millis(500),
+    ///         2.,
+    ///         Some(Duration::from_secs(30)),
+    ///         Some(3),
+    ///     ))
+    ///     .build()?;
+    /// ```
+    pub fn retry_policy<P>(self, retry_policy: P) -> Self
+    where
+        P: RetryPolicy + Send + Sync + 'static,
+    {
+        Self {
+            retry_policy: Some(Arc::new(retry_policy)),
+            ..self
+        }
+    }
+
+    /// Set the longest delay a provider may request through a `Retry-After` header when retrying
+    /// requests. Requests for which the provider asks to wait longer are not retried and fail
+    /// right away. Defaults to [DEFAULT_MAX_RETRY_AFTER].
+    pub fn max_retry_after(self, max_retry_after: Duration) -> Self {
+        Self {
+            max_retry_after,
+            ..self
+        }
+    }
+
     pub(crate) fn headers_mut(&mut self) -> &mut HeaderMap {
         &mut self.headers
     }
@@ -574,6 +668,8 @@ where
             base_url,
             mut headers,
             api_key,
+            retry_policy,
+            max_retry_after,
             ..
         } = self;
 
@@ -584,7 +680,9 @@ where
         let http_client = http_client.unwrap_or_default();
 
         Ok(Client {
-            http_client,
+            http_client: Arc::new(http_client),
+            retry_policy,
+            max_retry_after,
             base_url: Arc::from(base_url.as_str()),
             headers: Arc::new(headers),
             ext,
@@ -661,3 +759,53 @@ where
         M::make(self, model)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::http_client::{self, HttpClientExt, mock::MockHttpClient, retry::Constant};
+    use crate::providers::openai;
+    use bytes::Bytes;
+    use std::time::Duration;
+
+    fn retrying_client(http_client: MockHttpClient) -> openai::Client<MockHttpClient> {
+        openai::Client::<MockHttpClient>::builder()
+            .api_key("key")
+            .http_client(http_client)
+            .retry_policy(Constant::new(Duration::ZERO, Some(3)))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_client_retry_policy() {
+        // Rate limits are retried
+        let http_client = MockHttpClient::default()
+            .respond(429, "rate limited")
+            .respond(200, "{}");
+        let client = retrying_client(http_client.clone());
+        let req = client
+            .post("/chat/completions")
+            .unwrap()
+            .body(Bytes::new())
+            .unwrap();
+        assert!(client.send::<_, Bytes>(req).await.is_ok());
+        assert_eq!(http_client.requests().len(), 2);
+
+        // Invalid requests are not
+        let http_client = MockHttpClient::default()
+            .respond(400, "invalid request")
+            .respond(200, "{}");
+        let client = retrying_client(http_client.clone());
+        let req = client
+            .post("/chat/completions")
+            .unwrap()
+            .body(Bytes::new())
+            .unwrap();
+        assert!(matches!(
+            client.send::<_, Bytes>(req).await,
+            Err(http_client::Error::InvalidStatusCodeWithHeaders { status, .. })
+                if status.as_u16() == 400
+        ));
+        assert_eq!(http_client.requests().len(), 1);
+    }
+}
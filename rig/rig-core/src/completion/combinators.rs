@@ -0,0 +1,554 @@
+//! This module contains [CompletionModel] combinators that route requests across several
+//! (possibly heterogeneous) completion models:
+//! - [FallbackModel]: tries each model in order until one succeeds.
+//! - [RoundRobinModel]: spreads requests evenly across its models.
+//! - [WeightedModel]: spreads requests across its models proportionally to their weights.
+//!
+//! All combinators implement [CompletionModel] themselves (for both `completion` and `stream`),
+//! so they can be passed to [AgentBuilder::new](crate::agent::AgentBuilder::new) unchanged, or
+//! nested into one another.
+//!
+//! As the wrapped models may come from different providers, the raw responses are type-erased:
+//! the raw response of a completion is returned as a [serde_json::Value] and the final response of
+//! a stream is a [FinalCompletionResponse] (which only holds the token usage).
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     agent::AgentBuilder,
+//!     completion::{CompletionError, FallbackModel, Prompt},
+//!     prelude::*,
+//!     providers::{anthropic, ollama, openai},
+//! };
+//!
+//! let model = FallbackModel::new()
+//!     .with_model(openai::Client::from_env().completion_model(openai::GPT_4O))
+//!     .with_model(anthropic::Client::from_env().completion_model(anthropic::CLAUDE_3_5_SONNET))
+//!     .with_model(ollama::Client::new().completion_model("llama3.2"))
+//!     // Only fall back on transport errors and when the provider is rate limited or overloaded
+//!     .fallback_on(|err| {
+//!         matches!(
+//!             err,
+//!             CompletionError::HttpError(_)
+//!                 | CompletionError::RateLimited { .. }
+//!                 | CompletionError::Overloaded(_)
+//!         )
+//!     });
+//!
+//! let agent = AgentBuilder::new(model)
+//!     .preamble("You are a helpful assistant.")
+//!     .build();
+//!
+//! let response = agent.prompt("Hello!").await?;
+//! ```
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    client::FinalCompletionResponse,
+    completion::{CompletionError, CompletionModel, CompletionRequest, CompletionResponse},
+    streaming::{StreamingCompletionResponse, StreamingResultDyn},
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
+
+/// Type-erased completion model used by the combinators of this module.
+trait ErasedCompletionModel: WasmCompatSend + WasmCompatSync {
+    fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> WasmBoxedFuture<'_, Result<CompletionResponse<serde_json::Value>, CompletionError>>;
+
+    fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> WasmBoxedFuture<
+        '_,
+        Result<StreamingCompletionResponse<FinalCompletionResponse>, CompletionError>,
+    >;
+
+    fn supports_output_schema(&self) -> bool;
+}
+
+impl<M> ErasedCompletionModel for M
+where
+    M: CompletionModel + 'static,
+{
+    fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> WasmBoxedFuture<'_, Result<CompletionResponse<serde_json::Value>, CompletionError>> {
+        Box::pin(async move {
+            let response = CompletionModel::completion(self, request).await?;
+
+            Ok(CompletionResponse {
+                choice: response.choice,
+                usage: response.usage,
+                raw_response: serde_json::to_value(response.raw_response)?,
+            })
+        })
+    }
+
+    fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> WasmBoxedFuture<
+        '_,
+        Result<StreamingCompletionResponse<FinalCompletionResponse>, CompletionError>,
+    > {
+        Box::pin(async move {
+            let response = CompletionModel::stream(self, request).await?;
+
+            let stream = StreamingResultDyn {
+                inner: Box::pin(response.inner),
+            };
+
+            Ok(StreamingCompletionResponse::stream(Box::pin(stream)))
+        })
+    }
+
+    fn supports_output_schema(&self) -> bool {
+        CompletionModel::supports_output_schema(self)
+    }
+}
+
+type FallbackPredicate = Arc<dyn Fn(&CompletionError) -> bool + Send + Sync>;
+
+/// A [CompletionModel] that sends each request to its models in order, falling back to the next
+/// model whenever a model fails with an error matching the fallback predicate (by default, every
+/// error). The error of the last model tried is returned if no model succeeds.
+///
+/// Note: When streaming, only errors returned while establishing the stream trigger a fallback.
+/// Errors happening once the stream has started are yielded by the stream as usual.
+#[derive(Clone)]
+pub struct FallbackModel {
+    models: Vec<Arc<dyn ErasedCompletionModel>>,
+    should_fallback: FallbackPredicate,
+}
+
+impl Default for FallbackModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FallbackModel {
+    /// Create a new [FallbackModel] without any model.
+    pub fn new() -> Self {
+        Self {
+            models: Vec::new(),
+            should_fallback: Arc::new(|_| true),
+        }
+    }
+
+    /// Add a model to the end of the fallback chain.
+    pub fn with_model(mut self, model: impl CompletionModel + 'static) -> Self {
+        self.models.push(Arc::new(model));
+        self
+    }
+
+    /// Set which errors cause the next model to be tried. Errors for which `predicate` returns
+    /// `false` are returned immediately.
+    pub fn fallback_on(
+        mut self,
+        predicate: impl Fn(&CompletionError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.should_fallback = Arc::new(predicate);
+        self
+    }
+
+    fn no_models_error() -> CompletionError {
+        CompletionError::ProviderError("FallbackModel has no models to send the request to".into())
+    }
+}
+
+impl CompletionModel for FallbackModel {
+    type Response = serde_json::Value;
+    type StreamingResponse = FinalCompletionResponse;
+    type Client = Self;
+
+    /// Returns a clone of the given [FallbackModel] (the model name is ignored).
+    fn make(client: &Self::Client, _model: impl Into<String>) -> Self {
+        client.clone()
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        let mut last_error = None;
+
+        for (i, model) in self.models.iter().enumerate() {
+            match model.completion(request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(e) if (self.should_fallback)(&e) => {
+                    tracing::warn!("Completion model #{i} failed, falling back: {e}");
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(Self::no_models_error))
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        let mut last_error = None;
+
+        for (i, model) in self.models.iter().enumerate() {
+            match model.stream(request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(e) if (self.should_fallback)(&e) => {
+                    tracing::warn!("Completion model #{i} failed, falling back: {e}");
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(Self::no_models_error))
+    }
+
+    /// Only `true` if every model of the chain natively supports output schemas.
+    fn supports_output_schema(&self) -> bool {
+        !self.models.is_empty()
+            && self
+                .models
+                .iter()
+                .all(|model| model.supports_output_schema())
+    }
+}
+
+/// A [CompletionModel] that sends each request to the next of its models, in turn.
+#[derive(Clone)]
+pub struct RoundRobinModel {
+    models: Vec<Arc<dyn ErasedCompletionModel>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl Default for RoundRobinModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RoundRobinModel {
+    /// Create a new [RoundRobinModel] without any model.
+    pub fn new() -> Self {
+        Self {
+            models: Vec::new(),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Add a model to the rotation.
+    pub fn with_model(mut self, model: impl CompletionModel + 'static) -> Self {
+        self.models.push(Arc::new(model));
+        self
+    }
+
+    fn pick(&self) -> Result<&Arc<dyn ErasedCompletionModel>, CompletionError> {
+        if self.models.is_empty() {
+            return Err(CompletionError::ProviderError(
+                "RoundRobinModel has no models to send the request to".into(),
+            ));
+        }
+
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.models.len();
+        Ok(&self.models[i])
+    }
+}
+
+impl CompletionModel for RoundRobinModel {
+    type Response = serde_json::Value;
+    type StreamingResponse = FinalCompletionResponse;
+    type Client = Self;
+
+    /// Returns a clone of the given [RoundRobinModel] (the model name is ignored).
+    fn make(client: &Self::Client, _model: impl Into<String>) -> Self {
+        client.clone()
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        self.pick()?.completion(request).await
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        self.pick()?.stream(request).await
+    }
+
+    /// Only `true` if every model of the rotation natively supports output schemas.
+    fn supports_output_schema(&self) -> bool {
+        !self.models.is_empty()
+            && self
+                .models
+                .iter()
+                .all(|model| model.supports_output_schema())
+    }
+}
+
+/// A [CompletionModel] that sends each request to one of its models, picked at random with a
+/// probability proportional to its weight. Models with a weight of `0` never receive requests.
+#[derive(Clone, Default)]
+pub struct WeightedModel {
+    models: Vec<(Arc<dyn ErasedCompletionModel>, u32)>,
+}
+
+impl WeightedModel {
+    /// Create a new [WeightedModel] without any model.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a model with the given weight.
+    pub fn with_model(mut self, model: impl CompletionModel + 'static, weight: u32) -> Self {
+        self.models.push((Arc::new(model), weight));
+        self
+    }
+
+    fn pick(&self) -> Result<&Arc<dyn ErasedCompletionModel>, CompletionError> {
+        let total = self
+            .models
+            .iter()
+            .map(|(_, weight)| u64::from(*weight))
+            .sum::<u64>();
+
+        if total == 0 {
+            return Err(CompletionError::ProviderError(
+                "WeightedModel has no models with a non-zero weight to send the request to".into(),
+            ));
+        }
+
+        let mut target = fastrand::u64(0..total);
+        for (model, weight) in &self.models {
+            let weight = u64::from(*weight);
+            if target < weight {
+                return Ok(model);
+            }
+            target -= weight;
+        }
+
+        unreachable!("the target is always lower than the total weight")
+    }
+}
+
+impl CompletionModel for WeightedModel {
+    type Response = serde_json::Value;
+    type StreamingResponse = FinalCompletionResponse;
+    type Client = Self;
+
+    /// Returns a clone of the given [WeightedModel] (the model name is ignored).
+    fn make(client: &Self::Client, _model: impl Into<String>) -> Self {
+        client.clone()
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        self.pick()?.completion(request).await
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        self.pick()?.stream(request).await
+    }
+
+    /// Only `true` if every model that can receive requests natively supports output schemas.
+    fn supports_output_schema(&self) -> bool {
+        let mut models = self.models.iter().filter(|(_, weight)| *weight > 0);
+
+        models.clone().next().is_some() && models.all(|(model, _)| model.supports_output_schema())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use futures::StreamExt;
+
+    use super::{FallbackModel, RoundRobinModel, WeightedModel};
+    use crate::{
+        OneOrMany,
+        client::FinalCompletionResponse,
+        completion::{
+            CompletionError, CompletionModel, CompletionRequest, CompletionResponse, Message, Usage,
+        },
+        message::AssistantContent,
+        streaming::{RawStreamingChoice, StreamingCompletionResponse},
+    };
+
+    /// A completion model answering with its name, or failing with the given error.
+    #[derive(Clone)]
+    struct MockModel {
+        name: &'static str,
+        error: Option<fn() -> CompletionError>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl MockModel {
+        fn ok(name: &'static str) -> Self {
+            Self {
+                name,
+                error: None,
+                calls: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+
+        fn failing(name: &'static str, error: fn() -> CompletionError) -> Self {
+            Self {
+                error: Some(error),
+                ..Self::ok(name)
+            }
+        }
+    }
+
+    impl CompletionModel for MockModel {
+        type Response = String;
+        type StreamingResponse = FinalCompletionResponse;
+        type Client = ();
+
+        fn make(_: &Self::Client, _: impl Into<String>) -> Self {
+            Self::ok("mock")
+        }
+
+        async fn completion(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(error) = self.error {
+                return Err(error());
+            }
+
+            Ok(CompletionResponse {
+                choice: OneOrMany::one(AssistantContent::text(self.name)),
+                usage: Usage::new(),
+                raw_response: self.name.to_string(),
+            })
+        }
+
+        async fn stream(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if let Some(error) = self.error {
+                return Err(error());
+            }
+
+            let chunks = vec![
+                Ok(RawStreamingChoice::Message(self.name.to_string())),
+                Ok(RawStreamingChoice::FinalResponse(FinalCompletionResponse {
+                    usage: None,
+                })),
+            ];
+
+            Ok(StreamingCompletionResponse::stream(Box::pin(
+                futures::stream::iter(chunks),
+            )))
+        }
+    }
+
+    fn request() -> CompletionRequest {
+        CompletionRequest {
+            preamble: None,
+            chat_history: OneOrMany::one(Message::user("Hello!")),
+            documents: vec![],
+            tools: vec![],
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            additional_params: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
+        }
+    }
+
+    fn text(response: &CompletionResponse<serde_json::Value>) -> String {
+        match response.choice.first() {
+            AssistantContent::Text(text) => text.text,
+            _ => panic!("expected a text response"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model() {
+        let failing = MockModel::failing("first", || CompletionError::ProviderError("down".into()));
+        let model = FallbackModel::new()
+            .with_model(failing.clone())
+            .with_model(MockModel::ok("second"));
+
+        let response = model.completion(request()).await.unwrap();
+        assert_eq!(text(&response), "second");
+        assert_eq!(response.raw_response, serde_json::json!("second"));
+        assert_eq!(failing.calls.load(Ordering::SeqCst), 1);
+
+        let mut stream = model.stream(request()).await.unwrap();
+        while stream.next().await.is_some() {}
+        assert_eq!(
+            stream.choice,
+            OneOrMany::one(AssistantContent::text("second"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fallback_model_predicate() {
+        let second = MockModel::ok("second");
+        let model = FallbackModel::new()
+            .with_model(MockModel::failing("first", || {
+                CompletionError::ResponseError("bad response".into())
+            }))
+            .with_model(second.clone())
+            .fallback_on(|err| matches!(err, CompletionError::ProviderError(_)));
+
+        let err = model.completion(request()).await.unwrap_err();
+        assert!(matches!(err, CompletionError::ResponseError(_)));
+        assert_eq!(second.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_model() {
+        let model = RoundRobinModel::new()
+            .with_model(MockModel::ok("first"))
+            .with_model(MockModel::ok("second"));
+
+        let mut names = vec![];
+        for _ in 0..4 {
+            names.push(text(&model.completion(request()).await.unwrap()));
+        }
+
+        assert_eq!(names, vec!["first", "second", "first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_model_skips_zero_weights() {
+        let unused = MockModel::ok("unused");
+        let model = WeightedModel::new()
+            .with_model(unused.clone(), 0)
+            .with_model(MockModel::ok("used"), 3);
+
+        for _ in 0..10 {
+            assert_eq!(text(&model.completion(request()).await.unwrap()), "used");
+        }
+        assert_eq!(unused.calls.load(Ordering::SeqCst), 0);
+
+        assert!(WeightedModel::new().completion(request()).await.is_err());
+    }
+}
@@ -128,12 +128,13 @@ pub struct ToolResult {
     pub content: OneOrMany<ToolResultContent>,
 }
 
-/// Describes the content of a tool result, which can be text or an image.
+/// Describes the content of a tool result, which can be text, an image or a document.
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum ToolResultContent {
     Text(Text),
     Image(Image),
+    Document(Document),
 }
 
 /// Describes a tool call with an id and function to call, generally produced by a provider.
@@ -736,6 +737,33 @@ impl ToolResultContent {
             additional_params: None,
         })
     }
+
+    /// Helper constructor to make tool result documents from a base64-encoded string.
+    pub fn document_base64(data: impl Into<String>, media_type: Option<DocumentMediaType>) -> Self {
+        ToolResultContent::Document(Document {
+            data: DocumentSourceKind::Base64(data.into()),
+            media_type,
+            additional_params: None,
+        })
+    }
+
+    /// Helper constructor to make tool result documents from raw unencoded bytes.
+    pub fn document_raw(data: impl Into<Vec<u8>>, media_type: Option<DocumentMediaType>) -> Self {
+        ToolResultContent::Document(Document {
+            data: DocumentSourceKind::Raw(data.into()),
+            media_type,
+            ..Default::default()
+        })
+    }
+
+    /// Helper constructor to make tool result documents from a URL.
+    pub fn document_url(url: impl Into<String>, media_type: Option<DocumentMediaType>) -> Self {
+        ToolResultContent::Document(Document {
+            data: DocumentSourceKind::Url(url.into()),
+            media_type,
+            ..Default::default()
+        })
+    }
 }
 
 /// Trait for converting between MIME types and media types.
@@ -1,5 +1,7 @@
+pub mod combinators;
 pub mod message;
 pub mod request;
 
+pub use combinators::{FallbackModel, RoundRobinModel, WeightedModel};
 pub use message::{AssistantContent, Message, MessageError};
 pub use request::*;
@@ -64,11 +64,16 @@
 //! the individual traits, structs, and enums defined in this module.
 
 use super::message::{AssistantContent, DocumentMediaType};
+use crate::agent::budget::BudgetLimit;
+use crate::agent::checkpoint::CheckpointError;
 use crate::client::FinalCompletionResponse;
 #[allow(deprecated)]
 use crate::client::completion::CompletionModelHandle;
+use crate::http_client::{HeaderMap, retry};
+use crate::memory::MemoryError;
 use crate::message::ToolChoice;
 use crate::streaming::StreamingCompletionResponse;
+use crate::tokens::{BpeEstimator, TokenCounter};
 use crate::tool::server::ToolServerError;
 use crate::wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync};
 use crate::{OneOrMany, http_client, streaming};
@@ -77,11 +82,13 @@ use crate::{
     message::{Message, UserContent},
     tool::ToolSetError,
 };
+use http::StatusCode;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::{Add, AddAssign};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 // Errors
@@ -89,7 +96,7 @@ use thiserror::Error;
 pub enum CompletionError {
     /// Http error (e.g.: connection error, timeout, etc.)
     #[error("HttpError: {0}")]
-    HttpError(#[from] http_client::Error),
+    HttpError(#[source] http_client::Error),
 
     /// Json error (e.g.: serialization, deserialization)
     #[error("JsonError: {0}")]
@@ -116,6 +123,182 @@ pub enum CompletionError {
     /// Error returned by the completion model provider
     #[error("ProviderError: {0}")]
     ProviderError(String),
+
+    /// The provider rate limited the request. `retry_after` is the delay requested by the
+    /// provider, if any, and `body` the raw response body.
+    #[error("RateLimited: {body}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        body: String,
+    },
+
+    /// The request does not fit in the model's context window (raw response body)
+    #[error("ContextLengthExceeded: {0}")]
+    ContextLengthExceeded(String),
+
+    /// The API key is missing, invalid or lacks the required permissions (raw response body)
+    #[error("Authentication: {0}")]
+    Authentication(String),
+
+    /// The request or response was blocked by the provider's content filters (raw response body)
+    #[error("ContentFiltered: {0}")]
+    ContentFiltered(String),
+
+    /// The provider is temporarily overloaded or unavailable (raw response body)
+    #[error("Overloaded: {0}")]
+    Overloaded(String),
+
+    /// The provider rejected the request as invalid (raw response body)
+    #[error("InvalidRequest: {0}")]
+    InvalidRequest(String),
+}
+
+/// Error codes found in the error bodies of providers (OpenAI's `code` and `type`, Anthropic's
+/// `type`, Gemini's `status`) and the messages they use, for each class of error.
+const RATE_LIMIT_CODES: [&str; 2] = ["rate_limit", "resource_exhausted"];
+const CONTEXT_LENGTH_CODES: [&str; 2] = ["context_length_exceeded", "string_above_max_length"];
+const CONTEXT_LENGTH_MESSAGES: [&str; 6] = [
+    "context length",
+    "context window",
+    "prompt is too long",
+    "input token count",
+    "too many tokens",
+    "reduce the length",
+];
+/// Parameters limiting the output length. Messages naming them are about an invalid parameter
+/// (e.g.: `max_tokens` above the model's limit), not about the prompt overflowing the context.
+const MAX_TOKENS_PARAMETERS: [&str; 3] =
+    ["max_tokens", "max_completion_tokens", "max_output_tokens"];
+const CONTENT_FILTER_CODES: [&str; 2] = ["content_filter", "content_policy"];
+const CONTENT_FILTER_MESSAGES: [&str; 2] = ["content management policy", "content filter"];
+const AUTHENTICATION_CODES: [&str; 4] = [
+    "authentication",
+    "permission",
+    "invalid_api_key",
+    "unauthenticated",
+];
+const OVERLOADED_CODES: [&str; 2] = ["overloaded", "unavailable"];
+
+impl CompletionError {
+    /// Builds the error of a provider response with an unsuccessful `status`, classifying it from
+    /// the status code, the error code or message in `body` and the `Retry-After` headers.
+    /// Responses that cannot be classified become a [CompletionError::ProviderError].
+    pub fn from_response(status: StatusCode, headers: Option<&HeaderMap>, body: String) -> Self {
+        Self::classify(status, headers, body).unwrap_or_else(Self::ProviderError)
+    }
+
+    /// Builds the error of an error `body` returned along with a successful status (e.g.: the
+    /// `{"error": ...}` bodies of OpenAI compatible APIs), classifying it from the error code or
+    /// message. Errors that cannot be classified become a [CompletionError::ProviderError] holding
+    /// the provider's error `message`.
+    pub fn from_error_body(body: impl AsRef<[u8]>, message: impl Into<String>) -> Self {
+        let body = String::from_utf8_lossy(body.as_ref()).into_owned();
+        Self::classify(StatusCode::OK, None, body)
+            .unwrap_or_else(|_| Self::ProviderError(message.into()))
+    }
+
+    /// Classifies an unsuccessful response, giving back `body` if it cannot be classified.
+    fn classify(
+        status: StatusCode,
+        headers: Option<&HeaderMap>,
+        body: String,
+    ) -> Result<Self, String> {
+        let json = serde_json::from_str::<serde_json::Value>(&body).ok();
+        // Gemini sometimes wraps its error in an array
+        let json = match json {
+            Some(serde_json::Value::Array(mut errors)) if !errors.is_empty() => {
+                Some(errors.swap_remove(0))
+            }
+            json => json,
+        };
+        let error = json.as_ref().map(|json| json.get("error").unwrap_or(json));
+
+        let field = |name: &str| {
+            error
+                .and_then(|error| error.get(name))
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_lowercase)
+                .unwrap_or_default()
+        };
+        let codes = [field("code"), field("type"), field("status")].join(" ");
+        let message = match field("message") {
+            message if message.is_empty() => body.to_lowercase(),
+            message => message,
+        };
+        let matches = |markers: &[&str], text: &str| markers.iter().any(|m| text.contains(m));
+
+        if status == StatusCode::TOO_MANY_REQUESTS || matches(&RATE_LIMIT_CODES, &codes) {
+            let retry_after = headers
+                .and_then(retry::retry_after)
+                .or_else(|| error.and_then(retry_delay));
+            Ok(Self::RateLimited { retry_after, body })
+        } else if matches(&CONTEXT_LENGTH_CODES, &codes)
+            || (matches(&CONTEXT_LENGTH_MESSAGES, &message)
+                && !matches(&MAX_TOKENS_PARAMETERS, &message))
+        {
+            Ok(Self::ContextLengthExceeded(body))
+        } else if matches(&CONTENT_FILTER_CODES, &codes)
+            || matches(&CONTENT_FILTER_MESSAGES, &message)
+        {
+            Ok(Self::ContentFiltered(body))
+        } else if matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN)
+            || matches(&AUTHENTICATION_CODES, &codes)
+        {
+            Ok(Self::Authentication(body))
+        } else if status == StatusCode::SERVICE_UNAVAILABLE
+            // Anthropic's overloaded status
+            || status.as_u16() == 529
+            || matches(&OVERLOADED_CODES, &codes)
+        {
+            Ok(Self::Overloaded(body))
+        } else if status.is_client_error() {
+            Ok(Self::InvalidRequest(body))
+        } else {
+            Err(body)
+        }
+    }
+}
+
+/// Returns the delay of a Gemini `RetryInfo` error detail (e.g.: `"retryDelay": "17s"`).
+fn retry_delay(error: &serde_json::Value) -> Option<Duration> {
+    error
+        .get("details")?
+        .as_array()?
+        .iter()
+        .find_map(|detail| detail.get("retryDelay")?.as_str())
+        .and_then(|delay| delay.strip_suffix('s')?.parse::<f64>().ok())
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+}
+
+impl From<http_client::Error> for CompletionError {
+    fn from(error: http_client::Error) -> Self {
+        match error {
+            http_client::Error::InvalidStatusCodeWithHeaders {
+                status,
+                headers,
+                message,
+            } => Self::classify(status, Some(&headers), message).unwrap_or_else(|message| {
+                Self::HttpError(http_client::Error::InvalidStatusCodeWithHeaders {
+                    status,
+                    headers,
+                    message,
+                })
+            }),
+            http_client::Error::InvalidStatusCodeWithMessage(status, message) => {
+                Self::classify(status, None, message).unwrap_or_else(|message| {
+                    Self::HttpError(http_client::Error::InvalidStatusCodeWithMessage(
+                        status, message,
+                    ))
+                })
+            }
+            http_client::Error::InvalidStatusCode(status) => {
+                Self::classify(status, None, String::new()).unwrap_or(Self::HttpError(
+                    http_client::Error::InvalidStatusCode(status),
+                ))
+            }
+            error => Self::HttpError(error),
+        }
+    }
 }
 
 /// Prompt errors
@@ -133,6 +316,14 @@ pub enum PromptError {
     #[error("ToolServerError: {0}")]
     ToolServerError(#[from] ToolServerError),
 
+    /// There was an error while loading or persisting the conversation memory
+    #[error("MemoryError: {0}")]
+    MemoryError(#[from] MemoryError),
+
+    /// Something went wrong while saving a checkpoint
+    #[error("CheckpointError: {0}")]
+    CheckpointError(#[from] CheckpointError),
+
     /// The LLM tried to call too many tools during a multi-turn conversation.
     /// To fix this, you may either need to lower the amount of tools your model has access to (and then create other agents to share the tool load)
     /// or increase the amount of turns given in `.multi_turn()`.
@@ -149,6 +340,15 @@ pub enum PromptError {
         chat_history: Box<Vec<Message>>,
         reason: String,
     },
+
+    /// The aggregated token usage (or cost) of a multi-turn conversation exceeded the budget set
+    /// using `.with_budget()`.
+    #[error("BudgetExceeded: {limit}")]
+    BudgetExceeded {
+        limit: BudgetLimit,
+        usage: Usage,
+        chat_history: Box<Vec<Message>>,
+    },
 }
 
 impl PromptError {
@@ -197,6 +397,26 @@ pub struct ToolDefinition {
     pub parameters: serde_json::Value,
 }
 
+/// A prompt cache breakpoint of a [CompletionRequest].
+///
+/// A breakpoint asks the provider to cache the prompt up to (and including) the marked part, so
+/// that later requests sharing the same prefix are cheaper and faster. Each provider maps them to
+/// its own caching mechanism (e.g.: Anthropic's `cache_control`, Bedrock's `cachePoint`, Gemini's
+/// cached content) and providers without prompt caching ignore them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(tag = "type", content = "index", rename_all = "snake_case")]
+pub enum CacheBreakpoint {
+    /// Cache the prompt up to the preamble.
+    Preamble,
+    /// Cache the prompt up to the tool definitions.
+    Tools,
+    /// Cache the prompt up to the document at the given index of [CompletionRequest::documents].
+    /// Providers that don't send the documents (e.g.: Gemini) only cache what precedes them.
+    Document(usize),
+    /// Cache the prompt up to the message at the given index of [CompletionRequest::chat_history].
+    Message(usize),
+}
+
 // ================================================================
 // Implementations
 // ================================================================
@@ -280,6 +500,12 @@ impl GetTokenUsage for () {
     }
 }
 
+impl GetTokenUsage for Usage {
+    fn token_usage(&self) -> Option<crate::completion::Usage> {
+        Some(*self)
+    }
+}
+
 impl<T> GetTokenUsage for Option<T>
 where
     T: GetTokenUsage,
@@ -295,6 +521,9 @@ where
 
 /// Struct representing the token usage for a completion request.
 /// If tokens used are `0`, then the provider failed to supply token usage metrics.
+///
+/// The cache, reasoning and per-modality counts break down `input_tokens` and `output_tokens`
+/// (they are included in them), and are only set by the providers reporting them.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub struct Usage {
     /// The number of input ("prompt") tokens used in a given request.
@@ -303,6 +532,27 @@ pub struct Usage {
     pub output_tokens: u64,
     /// We store this separately as some providers may only report one number
     pub total_tokens: u64,
+    /// The number of input tokens read from the provider's prompt cache.
+    #[serde(default)]
+    pub cached_input_tokens: u64,
+    /// The number of input tokens written to the provider's prompt cache.
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
+    /// The number of output tokens spent on reasoning ("thinking").
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+    /// The number of input tokens used by audio.
+    #[serde(default)]
+    pub audio_input_tokens: u64,
+    /// The number of output tokens used by audio.
+    #[serde(default)]
+    pub audio_output_tokens: u64,
+    /// The number of input tokens used by images.
+    #[serde(default)]
+    pub image_input_tokens: u64,
+    /// The number of output tokens used by images.
+    #[serde(default)]
+    pub image_output_tokens: u64,
 }
 
 impl Usage {
@@ -312,6 +562,49 @@ impl Usage {
             input_tokens: 0,
             output_tokens: 0,
             total_tokens: 0,
+            cached_input_tokens: 0,
+            cache_creation_input_tokens: 0,
+            reasoning_tokens: 0,
+            audio_input_tokens: 0,
+            audio_output_tokens: 0,
+            image_input_tokens: 0,
+            image_output_tokens: 0,
+        }
+    }
+
+    /// Records this usage on the `gen_ai.usage.*` fields of a span.
+    /// The span must declare them, which spans created with [`crate::telemetry::gen_ai_span`] do.
+    /// Breakdowns (cache, reasoning, audio and image tokens) are only recorded when non-zero.
+    pub fn record_in_span(&self, span: &tracing::Span) {
+        if span.is_disabled() {
+            return;
+        }
+
+        span.record("gen_ai.usage.input_tokens", self.input_tokens);
+        span.record("gen_ai.usage.output_tokens", self.output_tokens);
+
+        let breakdowns = [
+            (
+                "gen_ai.usage.cache_read.input_tokens",
+                self.cached_input_tokens,
+            ),
+            (
+                "gen_ai.usage.cache_creation.input_tokens",
+                self.cache_creation_input_tokens,
+            ),
+            (
+                "gen_ai.usage.reasoning.output_tokens",
+                self.reasoning_tokens,
+            ),
+            ("gen_ai.usage.audio.input_tokens", self.audio_input_tokens),
+            ("gen_ai.usage.audio.output_tokens", self.audio_output_tokens),
+            ("gen_ai.usage.image.input_tokens", self.image_input_tokens),
+            ("gen_ai.usage.image.output_tokens", self.image_output_tokens),
+        ];
+        for (field, tokens) in breakdowns {
+            if tokens > 0 {
+                span.record(field, tokens);
+            }
         }
     }
 }
@@ -325,12 +618,9 @@ impl Default for Usage {
 impl Add for Usage {
     type Output = Self;
 
-    fn add(self, other: Self) -> Self::Output {
-        Self {
-            input_tokens: self.input_tokens + other.input_tokens,
-            output_tokens: self.output_tokens + other.output_tokens,
-            total_tokens: self.total_tokens + other.total_tokens,
-        }
+    fn add(mut self, other: Self) -> Self::Output {
+        self += other;
+        self
     }
 }
 
@@ -339,6 +629,13 @@ impl AddAssign for Usage {
         self.input_tokens += other.input_tokens;
         self.output_tokens += other.output_tokens;
         self.total_tokens += other.total_tokens;
+        self.cached_input_tokens += other.cached_input_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.audio_input_tokens += other.audio_input_tokens;
+        self.audio_output_tokens += other.audio_output_tokens;
+        self.image_input_tokens += other.image_input_tokens;
+        self.image_output_tokens += other.image_output_tokens;
     }
 }
 
@@ -380,6 +677,25 @@ pub trait CompletionModel: Clone + WasmCompatSend + WasmCompatSync {
     fn completion_request(&self, prompt: impl Into<Message>) -> CompletionRequestBuilder<Self> {
         CompletionRequestBuilder::new(self.clone(), prompt)
     }
+
+    /// Whether the model natively enforces [CompletionRequest::output_schema].
+    /// Models that don't are sent the schema as a tool instead when using
+    /// [Agent::prompt_typed](crate::agent::Agent::prompt_typed).
+    fn supports_output_schema(&self) -> bool {
+        false
+    }
+
+    /// Returns the name of the model requests are sent to (e.g.: `gpt-4o`), if known.
+    /// It is used to look up the price of the model in a [Budget](crate::agent::Budget).
+    fn model_name(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the offline [TokenCounter] used to estimate the number of tokens of requests sent
+    /// to this model. Defaults to [BpeEstimator].
+    fn token_counter(&self) -> Arc<dyn TokenCounter> {
+        Arc::new(BpeEstimator)
+    }
 }
 
 #[allow(deprecated)]
@@ -405,6 +721,10 @@ pub trait CompletionModelDyn: WasmCompatSend + WasmCompatSync {
         &self,
         prompt: Message,
     ) -> CompletionRequestBuilder<CompletionModelHandle<'_>>;
+
+    fn supports_output_schema(&self) -> bool;
+
+    fn model_name(&self) -> Option<&str>;
 }
 
 #[allow(deprecated)]
@@ -454,6 +774,14 @@ where
     ) -> CompletionRequestBuilder<CompletionModelHandle<'_>> {
         CompletionRequestBuilder::new(CompletionModelHandle::new(Arc::new(self.clone())), prompt)
     }
+
+    fn supports_output_schema(&self) -> bool {
+        CompletionModel::supports_output_schema(self)
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        CompletionModel::model_name(self)
+    }
 }
 
 /// Struct representing a general completion request that can be sent to a completion model provider.
@@ -476,6 +804,13 @@ pub struct CompletionRequest {
     pub tool_choice: Option<ToolChoice>,
     /// Additional provider-specific parameters to be sent to the completion model provider
     pub additional_params: Option<serde_json::Value>,
+    /// The JSON schema the response of the model should conform to.
+    /// Only providers that natively support structured outputs (see
+    /// [CompletionModel::supports_output_schema]) use it. Requests sent with a schema through a
+    /// [CompletionRequestBuilder] to other providers fail with a [CompletionError::RequestError].
+    pub output_schema: Option<schemars::Schema>,
+    /// The prompt cache breakpoints of the request (see [CacheBreakpoint]).
+    pub cache_breakpoints: Vec<CacheBreakpoint>,
 }
 
 impl CompletionRequest {
@@ -563,6 +898,8 @@ pub struct CompletionRequestBuilder<M: CompletionModel> {
     max_tokens: Option<u64>,
     tool_choice: Option<ToolChoice>,
     additional_params: Option<serde_json::Value>,
+    output_schema: Option<schemars::Schema>,
+    cache_breakpoints: Vec<CacheBreakpoint>,
 }
 
 impl<M: CompletionModel> CompletionRequestBuilder<M> {
@@ -578,6 +915,8 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
             max_tokens: None,
             tool_choice: None,
             additional_params: None,
+            output_schema: None,
+            cache_breakpoints: Vec::new(),
         }
     }
 
@@ -690,6 +1029,40 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
         self
     }
 
+    /// Sets the JSON schema the response of the model should conform to.
+    /// Note: Only providers that natively support structured outputs accept it (see
+    /// [CompletionModel::supports_output_schema]), sending the request to other providers fails.
+    /// Use [Agent::prompt_typed](crate::agent::Agent::prompt_typed) or an
+    /// [Extractor](crate::extractor::Extractor) to get structured outputs from any provider.
+    pub fn output_schema(mut self, schema: schemars::Schema) -> Self {
+        self.output_schema = Some(schema);
+        self
+    }
+
+    /// Sets the JSON schema the response of the model should conform to.
+    /// Note: Only providers that natively support structured outputs accept it
+    /// (see [CompletionRequestBuilder::output_schema])
+    pub fn output_schema_opt(mut self, schema: Option<schemars::Schema>) -> Self {
+        self.output_schema = schema;
+        self
+    }
+
+    /// Adds a prompt cache breakpoint to the completion request.
+    /// Note: Message indices refer to the chat history, in which the prompt is the last message.
+    pub fn cache_breakpoint(mut self, breakpoint: CacheBreakpoint) -> Self {
+        if !self.cache_breakpoints.contains(&breakpoint) {
+            self.cache_breakpoints.push(breakpoint);
+        }
+        self
+    }
+
+    /// Adds a list of prompt cache breakpoints to the completion request.
+    pub fn cache_breakpoints(self, breakpoints: Vec<CacheBreakpoint>) -> Self {
+        breakpoints.into_iter().fold(self, |builder, breakpoint| {
+            builder.cache_breakpoint(breakpoint)
+        })
+    }
+
     /// Builds the completion request.
     pub fn build(self) -> CompletionRequest {
         let chat_history = OneOrMany::many([self.chat_history, vec![self.prompt]].concat())
@@ -704,11 +1077,27 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
             max_tokens: self.max_tokens,
             tool_choice: self.tool_choice,
             additional_params: self.additional_params,
+            output_schema: self.output_schema,
+            cache_breakpoints: self.cache_breakpoints,
         }
     }
 
+    /// Returns an error if the request has an output schema the model would silently ignore.
+    fn check_output_schema(&self) -> Result<(), CompletionError> {
+        if self.output_schema.is_some() && !self.model.supports_output_schema() {
+            return Err(CompletionError::RequestError(
+                "The model does not support output schemas, use `Agent::prompt_typed` or an \
+                 `Extractor` instead"
+                    .into(),
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Sends the completion request to the completion model provider and returns the completion response.
     pub async fn send(self) -> Result<CompletionResponse<M::Response>, CompletionError> {
+        self.check_output_schema()?;
         let model = self.model.clone();
         model.completion(self.build()).await
     }
@@ -721,6 +1110,7 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
         <M as CompletionModel>::StreamingResponse: 'a,
         Self: 'a,
     {
+        self.check_output_schema()?;
         let model = self.model.clone();
         model.stream(self.build()).await
     }
@@ -787,6 +1177,8 @@ mod tests {
             max_tokens: None,
             tool_choice: None,
             additional_params: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
         };
 
         let expected = Message::User {
@@ -817,8 +1209,170 @@ mod tests {
             max_tokens: None,
             tool_choice: None,
             additional_params: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
         };
 
         assert_eq!(request.normalized_documents(), None);
     }
+
+    #[test]
+    fn test_usage_add_includes_breakdowns() {
+        let first = Usage {
+            input_tokens: 100,
+            output_tokens: 20,
+            total_tokens: 120,
+            cached_input_tokens: 80,
+            reasoning_tokens: 5,
+            ..Default::default()
+        };
+        let second = Usage {
+            input_tokens: 50,
+            output_tokens: 10,
+            total_tokens: 60,
+            cache_creation_input_tokens: 40,
+            audio_output_tokens: 10,
+            ..Default::default()
+        };
+
+        let mut total = first + second;
+        assert_eq!(total.input_tokens, 150);
+        assert_eq!(total.cached_input_tokens, 80);
+        assert_eq!(total.cache_creation_input_tokens, 40);
+        assert_eq!(total.reasoning_tokens, 5);
+        assert_eq!(total.audio_output_tokens, 10);
+
+        total += second;
+        assert_eq!(total.total_tokens, 240);
+        assert_eq!(total.cache_creation_input_tokens, 80);
+    }
+
+    #[test]
+    fn test_usage_deserializes_without_breakdowns() {
+        let usage: Usage =
+            serde_json::from_str(r#"{"input_tokens": 10, "output_tokens": 5, "total_tokens": 15}"#)
+                .unwrap();
+
+        assert_eq!(
+            usage,
+            Usage {
+                input_tokens: 10,
+                output_tokens: 5,
+                total_tokens: 15,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_completion_error_classification() {
+        let openai_context = r#"{"error": {"message": "This model's maximum context length is 128000 tokens.", "type": "invalid_request_error", "param": "messages", "code": "context_length_exceeded"}}"#;
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::BAD_REQUEST, None, openai_context.into()),
+            CompletionError::ContextLengthExceeded(body) if body == openai_context
+        ));
+
+        let anthropic_context = r#"{"type": "error", "error": {"type": "invalid_request_error", "message": "prompt is too long: 210000 tokens > 200000 maximum"}}"#;
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::BAD_REQUEST, None, anthropic_context.into()),
+            CompletionError::ContextLengthExceeded(_)
+        ));
+
+        let anthropic_overloaded =
+            r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#;
+        assert!(matches!(
+            CompletionError::from_response(
+                StatusCode::from_u16(529).unwrap(),
+                None,
+                anthropic_overloaded.into()
+            ),
+            CompletionError::Overloaded(_)
+        ));
+
+        let gemini_rate_limit = r#"[{"error": {"code": 429, "message": "Quota exceeded.", "status": "RESOURCE_EXHAUSTED", "details": [{"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "17s"}]}}]"#;
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::TOO_MANY_REQUESTS, None, gemini_rate_limit.into()),
+            CompletionError::RateLimited { retry_after: Some(delay), .. } if delay == Duration::from_secs(17)
+        ));
+
+        let azure_filter = r#"{"error": {"message": "The response was filtered due to the prompt triggering Azure OpenAI's content management policy.", "code": "content_filter", "status": 400}}"#;
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::BAD_REQUEST, None, azure_filter.into()),
+            CompletionError::ContentFiltered(_)
+        ));
+
+        let gemini_context = r#"{"error": {"code": 400, "message": "The input token count (1200000) exceeds the maximum number of tokens allowed (1048576).", "status": "INVALID_ARGUMENT"}}"#;
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::BAD_REQUEST, None, gemini_context.into()),
+            CompletionError::ContextLengthExceeded(_)
+        ));
+
+        // An invalid `max_tokens` is not a context overflow
+        let openai_max_tokens = r#"{"error": {"message": "max_tokens is too large: 100000. This model supports at most 16384 completion tokens, whereas you provided 100000.", "type": "invalid_request_error", "param": "max_tokens", "code": null}}"#;
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::BAD_REQUEST, None, openai_max_tokens.into()),
+            CompletionError::InvalidRequest(_)
+        ));
+        let groq_max_tokens = r#"{"error": {"message": "`max_tokens` must be less than or equal to `8192`, the maximum value for `max_tokens` is less than the `context_window` for this model", "type": "invalid_request_error"}}"#;
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::BAD_REQUEST, None, groq_max_tokens.into()),
+            CompletionError::InvalidRequest(_)
+        ));
+
+        // Errors returned along with a successful status
+        assert!(matches!(
+            CompletionError::from_error_body(openai_context, "context length"),
+            CompletionError::ContextLengthExceeded(_)
+        ));
+        assert!(matches!(
+            CompletionError::from_error_body(
+                r#"{"error": {"message": "Something went wrong"}}"#,
+                "Something went wrong"
+            ),
+            CompletionError::ProviderError(message) if message == "Something went wrong"
+        ));
+
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::UNAUTHORIZED, None, "Unauthorized".into()),
+            CompletionError::Authentication(_)
+        ));
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::UNPROCESSABLE_ENTITY, None, "{}".into()),
+            CompletionError::InvalidRequest(_)
+        ));
+        assert!(matches!(
+            CompletionError::from_response(StatusCode::INTERNAL_SERVER_ERROR, None, "oops".into()),
+            CompletionError::ProviderError(body) if body == "oops"
+        ));
+    }
+
+    #[test]
+    fn test_completion_error_from_http_error() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::RETRY_AFTER,
+            http::HeaderValue::from_static("3"),
+        );
+
+        let error = CompletionError::from(http_client::Error::InvalidStatusCodeWithHeaders {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            headers: Box::new(headers),
+            message: "Too many requests".into(),
+        });
+        assert!(matches!(
+            error,
+            CompletionError::RateLimited { retry_after: Some(delay), ref body }
+                if delay == Duration::from_secs(3) && body == "Too many requests"
+        ));
+
+        // Errors that can't be classified are kept as HTTP errors
+        let error = CompletionError::from(http_client::Error::InvalidStatusCodeWithMessage(
+            StatusCode::BAD_GATEWAY,
+            "Bad gateway".into(),
+        ));
+        assert!(matches!(
+            error,
+            CompletionError::HttpError(http_client::Error::InvalidStatusCodeWithMessage(..))
+        ));
+    }
 }
@@ -55,6 +55,7 @@ where
 {
     model: M,
     documents: Vec<(T, Vec<String>)>,
+    max_batch_tokens: Option<u64>,
 }
 
 impl<M, T> EmbeddingsBuilder<M, T>
@@ -67,9 +68,18 @@ where
         Self {
             model,
             documents: vec![],
+            max_batch_tokens: None,
         }
     }
 
+    /// Limit the estimated number of tokens of each request to the model provider, as counted by
+    /// the model's [token counter](EmbeddingModel::token_counter). A text exceeding the limit on
+    /// its own is sent in a request of its own.
+    pub fn max_batch_tokens(mut self, max_batch_tokens: u64) -> Self {
+        self.max_batch_tokens = Some(max_batch_tokens);
+        self
+    }
+
     /// Add a document to be embedded to the builder. `document` must implement the [Embed] trait.
     pub fn document(mut self, document: T) -> Result<Self, EmbedError> {
         let mut embedder = TextEmbedder::default();
@@ -111,12 +121,39 @@ where
             texts.push((i, doc_texts));
         }
 
+        // Chunk the texts of all documents into batches. Each batch size is at most the embedding
+        // API limit per request, and the token budget if one is set.
+        let counter = self.model.token_counter();
+        let mut batches: Vec<Vec<(usize, String)>> = Vec::new();
+        let mut batch_tokens = 0;
+        for (i, text) in texts
+            .into_iter()
+            .flat_map(|(i, texts)| texts.into_iter().map(move |text| (i, text)))
+        {
+            let tokens = self
+                .max_batch_tokens
+                .map(|_| counter.count_text(&text))
+                .unwrap_or_default();
+
+            match batches.last_mut() {
+                Some(batch)
+                    if batch.len() < M::MAX_DOCUMENTS
+                        && self
+                            .max_batch_tokens
+                            .is_none_or(|max| batch_tokens + tokens <= max) =>
+                {
+                    batch.push((i, text));
+                    batch_tokens += tokens;
+                }
+                _ => {
+                    batches.push(vec![(i, text)]);
+                    batch_tokens = tokens;
+                }
+            }
+        }
+
         // Compute the embeddings.
-        let mut embeddings = stream::iter(texts.into_iter())
-            // Merge the texts of each document into a single list of texts.
-            .flat_map(|(i, texts)| stream::iter(texts.into_iter().map(move |text| (i, text))))
-            // Chunk them into batches. Each batch size is at most the embedding API limit per request.
-            .chunks(M::MAX_DOCUMENTS)
+        let mut embeddings = stream::iter(batches)
             // Generate the embeddings for each batch.
             .map(|text| async {
                 let (ids, docs): (Vec<_>, Vec<_>) = text.into_iter().unzip();
@@ -166,6 +203,8 @@ mod tests {
     };
 
     use super::EmbeddingsBuilder;
+    use crate::tokens::{CharEstimator, TokenCounter};
+    use std::sync::{Arc, Mutex};
 
     #[derive(Clone)]
     struct Model;
@@ -407,4 +446,70 @@ mod tests {
             second_definition.1.rest()[0].document, "A fictional creature found in the distant, swampy marshlands of the planet Glibbo in the Andromeda galaxy.".to_string()
         )
     }
+
+    /// Counts one token per character and records the size of every batch it embeds
+    #[derive(Clone, Default)]
+    struct BatchModel {
+        batches: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl EmbeddingModel for BatchModel {
+        const MAX_DOCUMENTS: usize = 5;
+
+        type Client = Nothing;
+
+        fn make(_: &Self::Client, _: impl Into<String>, _: Option<usize>) -> Self {
+            Self::default()
+        }
+
+        fn ndims(&self) -> usize {
+            1
+        }
+
+        fn token_counter(&self) -> Arc<dyn TokenCounter> {
+            Arc::new(CharEstimator::new(1))
+        }
+
+        async fn embed_texts(
+            &self,
+            documents: impl IntoIterator<Item = String> + Send,
+        ) -> Result<Vec<crate::embeddings::Embedding>, crate::embeddings::EmbeddingError> {
+            let embeddings = documents
+                .into_iter()
+                .map(|document| Embedding {
+                    document,
+                    vec: vec![0.0],
+                })
+                .collect::<Vec<_>>();
+            self.batches.lock().unwrap().push(embeddings.len());
+            Ok(embeddings)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_max_batch_tokens() {
+        let model = BatchModel::default();
+        let result = EmbeddingsBuilder::new(model.clone())
+            .documents(
+                [
+                    "aaaa",
+                    "bbbb",
+                    "cccc",
+                    "a text longer than the budget",
+                    "dddd",
+                ]
+                .map(String::from),
+            )
+            .unwrap()
+            .max_batch_tokens(8)
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 5);
+
+        let mut batches = model.batches.lock().unwrap().clone();
+        batches.sort();
+        assert_eq!(batches, vec![1, 1, 1, 2]);
+    }
 }
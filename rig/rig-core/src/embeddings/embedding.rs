@@ -6,9 +6,11 @@
 //! Finally, the module defines the [EmbeddingError] enum, which represents various errors that
 //! can occur during embedding generation or processing.
 
+use crate::tokens::{BpeEstimator, TokenCounter};
 use crate::wasm_compat::WasmBoxedFuture;
 use crate::{http_client, wasm_compat::*};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, thiserror::Error)]
 pub enum EmbeddingError {
@@ -54,6 +56,12 @@ pub trait EmbeddingModel: WasmCompatSend + WasmCompatSync {
     /// The number of dimensions in the embedding vector.
     fn ndims(&self) -> usize;
 
+    /// Returns the offline [TokenCounter] used to estimate the number of tokens of texts
+    /// embedded by this model. Defaults to [BpeEstimator].
+    fn token_counter(&self) -> Arc<dyn TokenCounter> {
+        Arc::new(BpeEstimator)
+    }
+
     /// Embed multiple text documents in a single request
     fn embed_texts(
         &self,
@@ -31,12 +31,13 @@
 use std::marker::PhantomData;
 
 use schemars::{JsonSchema, schema_for};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::json;
 
 use crate::{
+    OneOrMany,
     agent::{Agent, AgentBuilder, AgentBuilderSimple},
-    completion::{Completion, CompletionError, CompletionModel, ToolDefinition},
+    completion::{Completion, CompletionError, CompletionModel, ToolDefinition, Usage},
     message::{AssistantContent, Message, ToolCall, ToolChoice, ToolFunction},
     tool::Tool,
     wasm_compat::{WasmCompatSend, WasmCompatSync},
@@ -56,6 +57,14 @@ pub enum ExtractionError {
     CompletionError(#[from] CompletionError),
 }
 
+/// The data extracted by [Extractor::extract_with_usage], along with the token usage aggregated
+/// over every attempt.
+#[derive(Debug, Clone)]
+pub struct ExtractionResponse<T> {
+    pub data: T,
+    pub usage: Usage,
+}
+
 /// Extractor for structured data from text
 pub struct Extractor<M, T>
 where
@@ -82,7 +91,19 @@ where
         &self,
         text: impl Into<Message> + WasmCompatSend,
     ) -> Result<T, ExtractionError> {
+        self.extract_with_usage(text)
+            .await
+            .map(|response| response.data)
+    }
+
+    /// Same as [Extractor::extract], but also returns the token usage aggregated over every
+    /// attempt (including the failed ones).
+    pub async fn extract_with_usage(
+        &self,
+        text: impl Into<Message> + WasmCompatSend,
+    ) -> Result<ExtractionResponse<T>, ExtractionError> {
         let mut last_error = None;
+        let mut usage = Usage::new();
         let text_message = text.into();
 
         for i in 0..=self.retries {
@@ -91,8 +112,8 @@ where
                 retries = self.retries - i
             );
             let attempt_text = text_message.clone();
-            match self.extract_json(attempt_text, vec![]).await {
-                Ok(data) => return Ok(data),
+            match self.extract_json(attempt_text, vec![], &mut usage).await {
+                Ok(data) => return Ok(ExtractionResponse { data, usage }),
                 Err(e) => {
                     tracing::warn!("Attempt {i} to extract JSON failed: {e:?}. Retrying...");
                     last_error = Some(e);
@@ -124,7 +145,10 @@ where
                 retries = self.retries - i
             );
             let attempt_text = text_message.clone();
-            match self.extract_json(attempt_text, chat_history.clone()).await {
+            match self
+                .extract_json(attempt_text, chat_history.clone(), &mut Usage::new())
+                .await
+            {
                 Ok(data) => return Ok(data),
                 Err(e) => {
                     tracing::warn!("Attempt {i} to extract JSON failed: {e:?}. Retrying...");
@@ -141,59 +165,12 @@ where
         &self,
         text: impl Into<Message> + WasmCompatSend,
         messages: Vec<Message>,
+        usage: &mut Usage,
     ) -> Result<T, ExtractionError> {
         let response = self.agent.completion(text, messages).await?.send().await?;
+        *usage += response.usage;
 
-        if !response.choice.iter().any(|x| {
-            let AssistantContent::ToolCall(ToolCall {
-                function: ToolFunction { name, .. },
-                ..
-            }) = x
-            else {
-                return false;
-            };
-
-            name == SUBMIT_TOOL_NAME
-        }) {
-            tracing::warn!(
-                "The submit tool was not called. If this happens more than once, please ensure the model you are using is powerful enough to reliably call tools."
-            );
-        }
-
-        let arguments = response
-            .choice
-            .into_iter()
-            // We filter tool calls to look for submit tool calls
-            .filter_map(|content| {
-                if let AssistantContent::ToolCall(ToolCall {
-                    function: ToolFunction { arguments, name },
-                    ..
-                }) = content
-                {
-                    if name == SUBMIT_TOOL_NAME {
-                        Some(arguments)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<_>>();
-
-        if arguments.len() > 1 {
-            tracing::warn!(
-                "Multiple submit calls detected, using the last one. Providers / agents should only ensure one submit call."
-            );
-        }
-
-        let raw_data = if let Some(arg) = arguments.into_iter().next() {
-            arg
-        } else {
-            return Err(ExtractionError::NoData);
-        };
-
-        Ok(serde_json::from_value(raw_data)?)
+        parse_submit_call(response.choice)
     }
 
     pub async fn get_inner(&self) -> &Agent<M> {
@@ -284,6 +261,70 @@ where
     }
 }
 
+/// Returns the definition of the `submit` tool used to force structured output through a tool call.
+pub(crate) fn submit_tool_definition<T: JsonSchema>() -> ToolDefinition {
+    ToolDefinition {
+        name: SUBMIT_TOOL_NAME.to_string(),
+        description: "Submit the structured data you extracted from the provided text.".to_string(),
+        parameters: json!(schema_for!(T)),
+    }
+}
+
+/// Deserializes the arguments of the `submit` tool call found in a model response.
+pub(crate) fn parse_submit_call<T: DeserializeOwned>(
+    choice: OneOrMany<AssistantContent>,
+) -> Result<T, ExtractionError> {
+    if !choice.iter().any(|x| {
+        let AssistantContent::ToolCall(ToolCall {
+            function: ToolFunction { name, .. },
+            ..
+        }) = x
+        else {
+            return false;
+        };
+
+        name == SUBMIT_TOOL_NAME
+    }) {
+        tracing::warn!(
+            "The submit tool was not called. If this happens more than once, please ensure the model you are using is powerful enough to reliably call tools."
+        );
+    }
+
+    let arguments = choice
+        .into_iter()
+        // We filter tool calls to look for submit tool calls
+        .filter_map(|content| {
+            if let AssistantContent::ToolCall(ToolCall {
+                function: ToolFunction { arguments, name },
+                ..
+            }) = content
+            {
+                if name == SUBMIT_TOOL_NAME {
+                    Some(arguments)
+                } else {
+                    None
+                }
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if arguments.len() > 1 {
+        tracing::warn!(
+            "Multiple submit calls detected, using the last one. Providers / agents should only ensure one submit call."
+        );
+    }
+
+    let raw_data = if let Some(arg) = arguments.into_iter().next() {
+        arg
+    } else {
+        return Err(ExtractionError::NoData);
+    };
+
+    Ok(serde_json::from_value(raw_data)?)
+}
+
 #[derive(Deserialize, Serialize)]
 struct SubmitTool<T>
 where
@@ -306,12 +347,7 @@ where
     type Output = T;
 
     async fn definition(&self, _prompt: String) -> ToolDefinition {
-        ToolDefinition {
-            name: Self::NAME.to_string(),
-            description: "Submit the structured data you extracted from the provided text."
-                .to_string(),
-            parameters: json!(schema_for!(T)),
-        }
+        submit_tool_definition::<T>()
     }
 
     async fn call(&self, data: Self::Args) -> Result<Self::Output, Self::Error> {
@@ -0,0 +1,96 @@
+//! A mock [HttpClientExt] answering requests with scripted responses, for tests.
+
+use super::{
+    Error, HttpClientExt, LazyBody, MultipartForm, Request, Response, Result, StreamingResponse,
+};
+use crate::wasm_compat::WasmCompatSend;
+use bytes::Bytes;
+use http::{HeaderMap, StatusCode};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Records the requests it is sent, and answers them with the scripted responses in order.
+/// Non-success statuses are returned as errors, like the `reqwest` client does.
+#[derive(Clone, Default)]
+pub(crate) struct MockHttpClient {
+    responses: Arc<Mutex<VecDeque<(StatusCode, Bytes)>>>,
+    requests: Arc<Mutex<Vec<(String, Bytes)>>>,
+}
+
+impl MockHttpClient {
+    /// Queues a response. Requests sent once the queue is empty fail with `501 Not Implemented`.
+    pub(crate) fn respond(self, status: u16, body: impl Into<Bytes>) -> Self {
+        self.responses.lock().unwrap().push_back((
+            StatusCode::from_u16(status).expect("valid status code"),
+            body.into(),
+        ));
+        self
+    }
+
+    /// The URI and body of the requests sent so far.
+    pub(crate) fn requests(&self) -> Vec<(String, Bytes)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpClientExt for MockHttpClient {
+    fn send<T, U>(
+        &self,
+        req: Request<T>,
+    ) -> impl Future<Output = Result<Response<LazyBody<U>>>> + WasmCompatSend + 'static
+    where
+        T: Into<Bytes>,
+        T: WasmCompatSend,
+        U: From<Bytes>,
+        U: WasmCompatSend + 'static,
+    {
+        let (parts, body) = req.into_parts();
+        self.requests
+            .lock()
+            .unwrap()
+            .push((parts.uri.to_string(), body.into()));
+
+        let (status, body) = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or((StatusCode::NOT_IMPLEMENTED, Bytes::new()));
+
+        let response = if status.is_success() {
+            let body: LazyBody<U> = Box::pin(async move { Ok(U::from(body)) });
+            Response::builder()
+                .status(status)
+                .body(body)
+                .map_err(Into::into)
+        } else {
+            Err(Error::InvalidStatusCodeWithHeaders {
+                status,
+                headers: Box::new(HeaderMap::new()),
+                message: String::from_utf8_lossy(&body).into(),
+            })
+        };
+        std::future::ready(response)
+    }
+
+    fn send_multipart<U>(
+        &self,
+        _req: Request<MultipartForm>,
+    ) -> impl Future<Output = Result<Response<LazyBody<U>>>> + WasmCompatSend + 'static
+    where
+        U: From<Bytes>,
+        U: WasmCompatSend + 'static,
+    {
+        std::future::ready(Err(Error::InvalidStatusCode(StatusCode::NOT_IMPLEMENTED)))
+    }
+
+    fn send_streaming<T>(
+        &self,
+        _req: Request<T>,
+    ) -> impl Future<Output = Result<StreamingResponse>> + WasmCompatSend
+    where
+        T: Into<Bytes>,
+    {
+        std::future::ready(Err(Error::InvalidStatusCode(StatusCode::NOT_IMPLEMENTED)))
+    }
+}
@@ -4,6 +4,8 @@ pub use http::{HeaderMap, HeaderValue, Method, Request, Response, Uri, request::
 use http::{HeaderName, StatusCode};
 use reqwest::Body;
 
+#[cfg(test)]
+pub(crate) mod mock;
 pub mod multipart;
 pub mod retry;
 pub mod sse;
@@ -22,6 +24,12 @@ pub enum Error {
     InvalidStatusCode(StatusCode),
     #[error("Invalid status code {0} with message: {1}")]
     InvalidStatusCodeWithMessage(StatusCode, String),
+    #[error("Invalid status code {status} with message: {message}")]
+    InvalidStatusCodeWithHeaders {
+        status: StatusCode,
+        headers: Box<HeaderMap>,
+        message: String,
+    },
     #[error("Header value outside of legal range: {0}")]
     InvalidHeaderValue(#[from] http::header::InvalidHeaderValue),
     #[error("Request in error state, cannot access headers")]
@@ -51,6 +59,19 @@ fn instance_error<E: std::error::Error + 'static>(error: E) -> Error {
     Error::Instance(error.into())
 }
 
+/// Builds the error returned for a response with an unsuccessful status code.
+async fn status_error(response: reqwest::Response) -> Error {
+    let status = response.status();
+    let headers = response.headers().clone();
+    let message = response.text().await.unwrap_or_default();
+
+    Error::InvalidStatusCodeWithHeaders {
+        status,
+        headers: Box::new(headers),
+        message,
+    }
+}
+
 pub type LazyBytes = WasmBoxedFuture<'static, Result<Bytes>>;
 pub type LazyBody<T> = WasmBoxedFuture<'static, Result<T>>;
 
@@ -76,6 +97,20 @@ pub async fn text(response: Response<LazyBody<Vec<u8>>>) -> Result<String> {
     Ok(String::from(String::from_utf8_lossy(&text)))
 }
 
+/// Turns a response with an unsuccessful status code into an [Error::InvalidStatusCodeWithHeaders].
+pub async fn into_status_error<T: AsRef<[u8]>>(response: Response<LazyBody<T>>) -> Error {
+    let (parts, body) = response.into_parts();
+
+    match body.await {
+        Ok(body) => Error::InvalidStatusCodeWithHeaders {
+            status: parts.status,
+            headers: Box::new(parts.headers),
+            message: String::from_utf8_lossy(body.as_ref()).into(),
+        },
+        Err(error) => error,
+    }
+}
+
 pub fn make_auth_header(key: impl AsRef<str>) -> Result<(HeaderName, HeaderValue)> {
     Ok((
         http::header::AUTHORIZATION,
@@ -146,10 +181,7 @@ impl HttpClientExt for reqwest::Client {
         async move {
             let response = req.send().await.map_err(instance_error)?;
             if !response.status().is_success() {
-                return Err(Error::InvalidStatusCodeWithMessage(
-                    response.status(),
-                    response.text().await.unwrap(),
-                ));
+                return Err(status_error(response).await);
             }
 
             let mut res = Response::builder().status(response.status());
@@ -191,10 +223,7 @@ impl HttpClientExt for reqwest::Client {
         async move {
             let response = req.send().await.map_err(instance_error)?;
             if !response.status().is_success() {
-                return Err(Error::InvalidStatusCodeWithMessage(
-                    response.status(),
-                    response.text().await.unwrap(),
-                ));
+                return Err(status_error(response).await);
             }
 
             let mut res = Response::builder().status(response.status());
@@ -239,10 +268,7 @@ impl HttpClientExt for reqwest::Client {
         async move {
             let response: reqwest::Response = client.execute(req).await.map_err(instance_error)?;
             if !response.status().is_success() {
-                return Err(Error::InvalidStatusCodeWithMessage(
-                    response.status(),
-                    response.text().await.unwrap(),
-                ));
+                return Err(status_error(response).await);
             }
 
             #[cfg(not(target_family = "wasm"))]
@@ -291,10 +317,7 @@ impl HttpClientExt for reqwest_middleware::ClientWithMiddleware {
         async move {
             let response = req.send().await.map_err(instance_error)?;
             if !response.status().is_success() {
-                return Err(Error::InvalidStatusCodeWithMessage(
-                    response.status(),
-                    response.text().await.unwrap(),
-                ));
+                return Err(status_error(response).await);
             }
 
             let mut res = Response::builder().status(response.status());
@@ -336,10 +359,7 @@ impl HttpClientExt for reqwest_middleware::ClientWithMiddleware {
         async move {
             let response = req.send().await.map_err(instance_error)?;
             if !response.status().is_success() {
-                return Err(Error::InvalidStatusCodeWithMessage(
-                    response.status(),
-                    response.text().await.unwrap(),
-                ));
+                return Err(status_error(response).await);
             }
 
             let mut res = Response::builder().status(response.status());
@@ -384,10 +404,7 @@ impl HttpClientExt for reqwest_middleware::ClientWithMiddleware {
         async move {
             let response: reqwest::Response = client.execute(req).await.map_err(instance_error)?;
             if !response.status().is_success() {
-                return Err(Error::InvalidStatusCodeWithMessage(
-                    response.status(),
-                    response.text().await.unwrap(),
-                ));
+                return Err(status_error(response).await);
             }
 
             #[cfg(not(target_family = "wasm"))]
@@ -1,7 +1,29 @@
 //! Helpers to handle connection delays when receiving errors
 
-use super::Error;
+use super::{Error, Result};
+use futures_timer::Delay;
+use http::HeaderMap;
 use std::time::Duration;
+use tracing::Instrument;
+
+/// Returns the delay requested by a provider through the `retry-after-ms` or `Retry-After`
+/// headers. `Retry-After` may either be a number of seconds or an HTTP date.
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(millis) = header("retry-after-ms").and_then(|value| value.parse::<f64>().ok()) {
+        return Duration::try_from_secs_f64(millis / 1000.).ok();
+    }
+
+    let value = header(http::header::RETRY_AFTER.as_str())?;
+    match value.parse::<f64>() {
+        Ok(seconds) => Duration::try_from_secs_f64(seconds).ok(),
+        Err(_) => {
+            let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+            date.signed_duration_since(chrono::Utc::now()).to_std().ok()
+        }
+    }
+}
 
 pub trait RetryPolicy {
     /// Submit a new retry delay based on the [`enum@Error`], last retry number and duration, if
@@ -118,3 +140,243 @@ pub const DEFAULT_RETRY: ExponentialBackoff = ExponentialBackoff::new(
     Some(Duration::from_secs(5)),
     None,
 );
+
+/// The longest delay a provider may request through a `Retry-After` header before
+/// [send_with_retry] gives up instead of waiting
+pub const DEFAULT_MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Status codes for which the provider did not process the request and it may be sent again:
+/// timeouts, rate limits and server errors (529 being Anthropic's "overloaded")
+const RETRYABLE_STATUS_CODES: [u16; 7] = [408, 429, 500, 502, 503, 504, 529];
+
+/// Returns whether a request which failed with `error` can safely be sent again, i.e. the
+/// provider answered with a rate limit or server error, or the connection could not be
+/// established. Other errors (timeouts while waiting for a response, invalid requests, etc.)
+/// are not retried, as the request may already have been processed.
+pub fn is_retryable(error: &Error) -> bool {
+    match error {
+        Error::InvalidStatusCode(status)
+        | Error::InvalidStatusCodeWithMessage(status, _)
+        | Error::InvalidStatusCodeWithHeaders { status, .. } => {
+            RETRYABLE_STATUS_CODES.contains(&status.as_u16())
+        }
+        #[cfg(not(target_family = "wasm"))]
+        Error::Instance(error) => {
+            if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+                return error.is_connect();
+            }
+
+            #[cfg(feature = "reqwest-middleware")]
+            if let Some(reqwest_middleware::Error::Reqwest(error)) =
+                error.downcast_ref::<reqwest_middleware::Error>()
+            {
+                return error.is_connect();
+            }
+
+            false
+        }
+        _ => false,
+    }
+}
+
+/// Sends a request through `send` until it succeeds, fails with an error that is not
+/// [retryable](is_retryable) or `policy` gives up.
+///
+/// Waits for the delay returned by `policy` between attempts, or longer if the provider asked
+/// for it through a `Retry-After` header. Gives up if the provider asks to wait longer than
+/// `max_retry_after`. Every attempt runs in its own `http_request` span,
+/// recording the number of previous attempts as `http.request.resend_count`.
+pub(crate) async fn send_with_retry<P, F, Fut, R>(
+    policy: &P,
+    max_retry_after: Duration,
+    mut send: F,
+) -> Result<R>
+where
+    P: RetryPolicy + ?Sized,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    let mut last_retry: Option<(usize, Duration)> = None;
+    let mut resend_count: usize = 0;
+
+    loop {
+        let span = tracing::info_span!(
+            target: "rig::http",
+            "http_request",
+            http.request.resend_count = resend_count,
+        );
+
+        let error = match send().instrument(span).await {
+            Ok(response) => return Ok(response),
+            Err(error) if is_retryable(&error) => error,
+            Err(error) => return Err(error),
+        };
+
+        let Some(delay) = policy.retry(&error, last_retry) else {
+            return Err(error);
+        };
+
+        let requested_delay = match &error {
+            Error::InvalidStatusCodeWithHeaders { headers, .. } => retry_after(headers),
+            _ => None,
+        };
+
+        if requested_delay.is_some_and(|requested| requested > max_retry_after) {
+            return Err(error);
+        }
+
+        // The policy's own delay is kept to compute the next backoff
+        last_retry = Some((
+            last_retry.map_or(0, |(num, _)| num.saturating_add(1)),
+            delay,
+        ));
+        resend_count += 1;
+
+        let delay = requested_delay.map_or(delay, |requested| requested.max(delay));
+        tracing::warn!(
+            target: "rig::http",
+            resend_count,
+            delay_ms = delay.as_millis() as u64,
+            "Request failed with {error}, retrying"
+        );
+
+        Delay::new(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+
+        headers.insert("retry-after-ms", HeaderValue::from_static("1500"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(1500)));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "retry-after",
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        // Dates in the past don't request any delay
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    fn status_error(status: u16) -> Error {
+        Error::InvalidStatusCodeWithHeaders {
+            status: http::StatusCode::from_u16(status).unwrap(),
+            headers: Default::default(),
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&status_error(429)));
+        assert!(is_retryable(&status_error(503)));
+        assert!(is_retryable(&status_error(529)));
+        assert!(is_retryable(&Error::InvalidStatusCode(
+            http::StatusCode::BAD_GATEWAY
+        )));
+
+        assert!(!is_retryable(&status_error(400)));
+        assert!(!is_retryable(&status_error(401)));
+        assert!(!is_retryable(&status_error(501)));
+        assert!(!is_retryable(&Error::StreamEnded));
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry() {
+        let policy = Constant::new(Duration::ZERO, Some(5));
+
+        // Retryable errors are retried until the request succeeds
+        let mut attempts = 0;
+        let result = send_with_retry(&policy, DEFAULT_MAX_RETRY_AFTER, || {
+            attempts += 1;
+            let result = if attempts < 3 {
+                Err(status_error(503))
+            } else {
+                Ok(attempts)
+            };
+            async move { result }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        // Other errors are returned right away
+        let mut attempts = 0;
+        let result: Result<()> = send_with_retry(&policy, DEFAULT_MAX_RETRY_AFTER, || {
+            attempts += 1;
+            async { Err(status_error(400)) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        // The error is returned once the policy gives up
+        let mut attempts = 0;
+        let result: Result<()> = send_with_retry(&Never, DEFAULT_MAX_RETRY_AFTER, || {
+            attempts += 1;
+            async { Err(status_error(429)) }
+        })
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::InvalidStatusCodeWithHeaders { status, .. }) if status.as_u16() == 429
+        ));
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_honors_retry_after() {
+        let policy = Constant::new(Duration::ZERO, Some(1));
+        let mut attempts = 0;
+        let start = std::time::Instant::now();
+
+        let result = send_with_retry(&policy, DEFAULT_MAX_RETRY_AFTER, || {
+            attempts += 1;
+            let result = if attempts == 1 {
+                let mut headers = HeaderMap::new();
+                headers.insert("retry-after-ms", HeaderValue::from_static("50"));
+                Err(Error::InvalidStatusCodeWithHeaders {
+                    status: http::StatusCode::TOO_MANY_REQUESTS,
+                    headers: Box::new(headers),
+                    message: String::new(),
+                })
+            } else {
+                Ok(())
+            };
+            async move { result }
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts, 2);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        // Requests asking to wait longer than the maximum are not retried
+        let mut attempts = 0;
+        let result: Result<()> = send_with_retry(&policy, Duration::from_millis(10), || {
+            attempts += 1;
+            let mut headers = HeaderMap::new();
+            headers.insert("retry-after", HeaderValue::from_static("3600"));
+            let error = Error::InvalidStatusCodeWithHeaders {
+                status: http::StatusCode::TOO_MANY_REQUESTS,
+                headers: Box::new(headers),
+                message: String::new(),
+            };
+            async move { Err(error) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
@@ -125,18 +125,23 @@ pub mod image_generation;
 pub mod integrations;
 pub(crate) mod json_utils;
 pub mod loaders;
+pub mod memory;
 pub mod one_or_many;
 pub mod pipeline;
 pub mod prelude;
 pub mod providers;
 
 pub mod streaming;
+pub mod tokens;
 pub mod tool;
 pub mod tools;
 pub mod transcription;
 pub mod vector_store;
 pub mod wasm_compat;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 // Re-export commonly used types and traits
 pub use completion::message;
 pub use embeddings::Embed;
@@ -146,4 +151,8 @@ pub use one_or_many::{EmptyListError, OneOrMany};
 #[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
 pub use rig_derive::{Embed, rig_tool as tool_macro};
 
+/// Re-exported so that tool parameters can derive `JsonSchema` (eg: with `#[rig_tool]`) without
+/// depending on `schemars` directly.
+pub use schemars;
+
 pub mod telemetry;
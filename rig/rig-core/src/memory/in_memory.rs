@@ -0,0 +1,82 @@
+//! In-memory implementation of a conversation memory.
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::RwLock;
+
+use super::{ConversationMemory, MemoryError};
+use crate::completion::Message;
+
+/// [InMemoryConversationMemory] keeps the history of every session in a process-local map.
+/// Cloning it yields a handle to the same underlying sessions.
+///
+/// Useful for tests and short-lived processes, as all sessions are lost once the process exits.
+#[derive(Clone, Default)]
+pub struct InMemoryConversationMemory {
+    sessions: Arc<RwLock<HashMap<String, Vec<Message>>>>,
+}
+
+impl InMemoryConversationMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ConversationMemory for InMemoryConversationMemory {
+    async fn load(&self, session_id: &str) -> Result<Vec<Message>, MemoryError> {
+        Ok(self
+            .sessions
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn append(&self, session_id: &str, messages: Vec<Message>) -> Result<(), MemoryError> {
+        self.sessions
+            .write()
+            .await
+            .entry(session_id.to_string())
+            .or_default()
+            .extend(messages);
+        Ok(())
+    }
+
+    async fn clear(&self, session_id: &str) -> Result<(), MemoryError> {
+        self.sessions.write().await.remove(session_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sessions_are_isolated() {
+        let memory = InMemoryConversationMemory::new();
+
+        memory
+            .append("a", vec![Message::user("hello"), Message::assistant("hi")])
+            .await
+            .unwrap();
+        memory
+            .append("b", vec![Message::user("bonjour")])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            memory.load("a").await.unwrap(),
+            vec![Message::user("hello"), Message::assistant("hi")]
+        );
+        assert_eq!(
+            memory.load("b").await.unwrap(),
+            vec![Message::user("bonjour")]
+        );
+        assert!(memory.load("c").await.unwrap().is_empty());
+
+        memory.clear("a").await.unwrap();
+        assert!(memory.load("a").await.unwrap().is_empty());
+        assert_eq!(memory.load("b").await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,131 @@
+//! JSON file implementation of a conversation memory.
+use std::path::{Path, PathBuf};
+
+use tokio::sync::Mutex;
+
+use super::{ConversationMemory, MemoryError};
+use crate::completion::Message;
+
+/// [JsonFileConversationMemory] stores the history of each session as a JSON array of
+/// [Message]s in `<directory>/<session_id>.json`.
+///
+/// Session ids may only contain ASCII alphanumeric characters, `-`, `_` and `.`, so that they
+/// can be safely used as file names.
+pub struct JsonFileConversationMemory {
+    directory: PathBuf,
+    /// Serializes read-modify-write cycles on session files within this process.
+    lock: Mutex<()>,
+}
+
+impl JsonFileConversationMemory {
+    /// Create a new [JsonFileConversationMemory] storing its sessions in `directory`.
+    /// The directory is created if it does not exist yet.
+    pub fn new(directory: impl AsRef<Path>) -> Result<Self, MemoryError> {
+        let directory = directory.as_ref().to_path_buf();
+        std::fs::create_dir_all(&directory)?;
+
+        Ok(Self {
+            directory,
+            lock: Mutex::new(()),
+        })
+    }
+
+    fn session_path(&self, session_id: &str) -> Result<PathBuf, MemoryError> {
+        let is_valid = !session_id.is_empty()
+            && session_id != "."
+            && session_id != ".."
+            && session_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+        if !is_valid {
+            return Err(MemoryError::InvalidSessionId(session_id.to_string()));
+        }
+
+        Ok(self.directory.join(format!("{session_id}.json")))
+    }
+
+    async fn read_session(path: &Path) -> Result<Vec<Message>, MemoryError> {
+        match tokio::fs::read(path).await {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl ConversationMemory for JsonFileConversationMemory {
+    async fn load(&self, session_id: &str) -> Result<Vec<Message>, MemoryError> {
+        let path = self.session_path(session_id)?;
+        let _guard = self.lock.lock().await;
+
+        Self::read_session(&path).await
+    }
+
+    async fn append(&self, session_id: &str, messages: Vec<Message>) -> Result<(), MemoryError> {
+        let path = self.session_path(session_id)?;
+        let _guard = self.lock.lock().await;
+
+        let mut history = Self::read_session(&path).await?;
+        history.extend(messages);
+
+        // Write to a temporary file first so that a crash never leaves a truncated session behind
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, serde_json::to_vec(&history)?).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+
+        Ok(())
+    }
+
+    async fn clear(&self, session_id: &str) -> Result<(), MemoryError> {
+        let path = self.session_path(session_id)?;
+        let _guard = self.lock.lock().await;
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_json_file_roundtrip() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let memory = JsonFileConversationMemory::new(dir.path()).unwrap();
+
+        memory
+            .append("session-1", vec![Message::user("hello")])
+            .await
+            .unwrap();
+        memory
+            .append("session-1", vec![Message::assistant("hi")])
+            .await
+            .unwrap();
+
+        // A fresh instance pointing at the same directory sees the persisted history
+        let reopened = JsonFileConversationMemory::new(dir.path()).unwrap();
+        assert_eq!(
+            reopened.load("session-1").await.unwrap(),
+            vec![Message::user("hello"), Message::assistant("hi")]
+        );
+
+        reopened.clear("session-1").await.unwrap();
+        assert!(memory.load("session-1").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_json_file_rejects_path_traversal() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let memory = JsonFileConversationMemory::new(dir.path()).unwrap();
+
+        assert!(matches!(
+            memory.load("../escape").await,
+            Err(MemoryError::InvalidSessionId(_))
+        ));
+    }
+}
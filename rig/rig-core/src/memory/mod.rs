@@ -0,0 +1,139 @@
+//! This module contains the [ConversationMemory] trait, which allows conversation state
+//! to be persisted across agent calls, keyed by a session id.
+//!
+//! A [ConversationMemory] can be attached to an [Agent](crate::agent::Agent) using
+//! [AgentBuilder::memory](crate::agent::AgentBuilder::memory). When a prompt request is then
+//! given a session id (see [PromptRequest::session](crate::agent::PromptRequest::session)), the
+//! agent loop loads the session's history before the first turn and appends every user,
+//! assistant, tool call and tool result message to the memory as the loop runs.
+//!
+//! Rig ships with the following implementations:
+//! - [InMemoryConversationMemory]: keeps sessions in a process-local map.
+//! - [JsonFileConversationMemory]: stores each session as a JSON file in a directory.
+//!
+//! A SQLite-backed implementation is available in the `rig-sqlite` companion crate.
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     completion::Prompt,
+//!     memory::InMemoryConversationMemory,
+//!     providers::openai,
+//! };
+//!
+//! let openai = openai::Client::from_env();
+//!
+//! let agent = openai.agent("gpt-4o")
+//!     .preamble("You are a helpful assistant.")
+//!     .memory(InMemoryConversationMemory::new())
+//!     .build();
+//!
+//! agent.prompt("My name is Ferris.").session("user-1234").await?;
+//!
+//! // The history of session "user-1234" is loaded before this prompt is sent
+//! let response = agent.prompt("What is my name?").session("user-1234").await?;
+//! ```
+use std::sync::Arc;
+
+use crate::{
+    completion::Message,
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
+
+pub mod in_memory;
+#[cfg(not(target_family = "wasm"))]
+pub mod json_file;
+
+pub use in_memory::InMemoryConversationMemory;
+#[cfg(not(target_family = "wasm"))]
+pub use json_file::JsonFileConversationMemory;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MemoryError {
+    /// Json error (e.g.: serialization, deserialization, etc.)
+    #[error("Json error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    /// Io error (e.g.: reading or writing a session file)
+    #[error("Io error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// The session id cannot be used by this memory backend
+    #[error("Invalid session id: {0}")]
+    InvalidSessionId(String),
+
+    #[cfg(not(target_family = "wasm"))]
+    #[error("Datastore error: {0}")]
+    DatastoreError(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[cfg(target_family = "wasm")]
+    #[error("Datastore error: {0}")]
+    DatastoreError(#[from] Box<dyn std::error::Error + 'static>),
+}
+
+/// Trait for conversation memory backends.
+///
+/// Implementations store an ordered list of [Message]s per session id.
+pub trait ConversationMemory: WasmCompatSend + WasmCompatSync {
+    /// Load the full history of the given session, oldest message first.
+    /// Sessions that do not exist yet should return an empty history.
+    fn load(
+        &self,
+        session_id: &str,
+    ) -> impl std::future::Future<Output = Result<Vec<Message>, MemoryError>> + WasmCompatSend;
+
+    /// Append messages to the end of the given session's history.
+    fn append(
+        &self,
+        session_id: &str,
+        messages: Vec<Message>,
+    ) -> impl std::future::Future<Output = Result<(), MemoryError>> + WasmCompatSend;
+
+    /// Remove all messages from the given session.
+    fn clear(
+        &self,
+        session_id: &str,
+    ) -> impl std::future::Future<Output = Result<(), MemoryError>> + WasmCompatSend;
+}
+
+/// Wrapper trait to allow for dynamic dispatch of conversation memory backends
+pub trait ConversationMemoryDyn: WasmCompatSend + WasmCompatSync {
+    fn load<'a>(
+        &'a self,
+        session_id: &'a str,
+    ) -> WasmBoxedFuture<'a, Result<Vec<Message>, MemoryError>>;
+
+    fn append<'a>(
+        &'a self,
+        session_id: &'a str,
+        messages: Vec<Message>,
+    ) -> WasmBoxedFuture<'a, Result<(), MemoryError>>;
+
+    fn clear<'a>(&'a self, session_id: &'a str) -> WasmBoxedFuture<'a, Result<(), MemoryError>>;
+}
+
+impl<T: ConversationMemory> ConversationMemoryDyn for T {
+    fn load<'a>(
+        &'a self,
+        session_id: &'a str,
+    ) -> WasmBoxedFuture<'a, Result<Vec<Message>, MemoryError>> {
+        Box::pin(<Self as ConversationMemory>::load(self, session_id))
+    }
+
+    fn append<'a>(
+        &'a self,
+        session_id: &'a str,
+        messages: Vec<Message>,
+    ) -> WasmBoxedFuture<'a, Result<(), MemoryError>> {
+        Box::pin(<Self as ConversationMemory>::append(
+            self, session_id, messages,
+        ))
+    }
+
+    fn clear<'a>(&'a self, session_id: &'a str) -> WasmBoxedFuture<'a, Result<(), MemoryError>> {
+        Box::pin(<Self as ConversationMemory>::clear(self, session_id))
+    }
+}
+
+/// A shared, type-erased conversation memory as stored on an [Agent](crate::agent::Agent).
+pub type DynConversationMemory = Arc<dyn ConversationMemoryDyn>;
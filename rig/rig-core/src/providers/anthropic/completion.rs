@@ -1,5 +1,6 @@
 //! Anthropic completion api implementation
 
+use crate::telemetry::gen_ai_span;
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, GetTokenUsage},
@@ -7,16 +8,17 @@ use crate::{
     message::{self, DocumentMediaType, DocumentSourceKind, MessageError, Reasoning},
     one_or_many::string_or_one_or_many,
     telemetry::{ProviderResponseExt, SpanCombinator},
+    tokens::RemoteTokenCounter,
     wasm_compat::*,
 };
 use std::{convert::Infallible, str::FromStr};
 
 use super::client::Client;
-use crate::completion::CompletionRequest;
+use crate::completion::{CacheBreakpoint, CompletionRequest};
 use crate::providers::anthropic::streaming::StreamingCompletionResponse;
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 
 // ================================================================
 // Anthropic Completion API
@@ -122,6 +124,8 @@ impl GetTokenUsage for Usage {
             + self.cache_read_input_tokens.unwrap_or_default();
         usage.output_tokens = self.output_tokens;
         usage.total_tokens = usage.input_tokens + usage.output_tokens;
+        usage.cached_input_tokens = self.cache_read_input_tokens.unwrap_or_default();
+        usage.cache_creation_input_tokens = self.cache_creation_input_tokens.unwrap_or_default();
 
         Some(usage)
     }
@@ -132,6 +136,19 @@ pub struct ToolDefinition {
     pub name: String,
     pub description: Option<String>,
     pub input_schema: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_control: Option<CacheControl>,
+}
+
+impl From<completion::ToolDefinition> for ToolDefinition {
+    fn from(tool: completion::ToolDefinition) -> Self {
+        Self {
+            name: tool.name,
+            description: Some(tool.description),
+            input_schema: tool.parameters,
+            cache_control: None,
+        }
+    }
 }
 
 /// Cache control directive for Anthropic prompt caching
@@ -168,11 +185,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
             )
         })?;
 
-        let usage = completion::Usage {
-            input_tokens: response.usage.input_tokens,
-            output_tokens: response.usage.output_tokens,
-            total_tokens: response.usage.input_tokens + response.usage.output_tokens,
-        };
+        let usage = response.usage.token_usage().unwrap_or_default();
 
         Ok(completion::CompletionResponse {
             choice,
@@ -251,6 +264,7 @@ impl FromStr for Content {
 pub enum ToolResultContent {
     Text { text: String },
     Image(ImageSource),
+    Document { source: DocumentSource },
 }
 
 impl FromStr for ToolResultContent {
@@ -408,6 +422,14 @@ impl From<ImageFormat> for message::ImageMediaType {
     }
 }
 
+impl From<DocumentFormat> for message::DocumentMediaType {
+    fn from(format: DocumentFormat) -> Self {
+        match format {
+            DocumentFormat::PDF => message::DocumentMediaType::PDF,
+        }
+    }
+}
+
 impl TryFrom<DocumentMediaType> for DocumentFormat {
     type Error = MessageError;
     fn try_from(value: DocumentMediaType) -> Result<Self, Self::Error> {
@@ -488,6 +510,26 @@ impl TryFrom<message::Message> for Message {
                                     r#type: SourceType::BASE64,
                                 }))
                             }
+                            message::ToolResultContent::Document(document) => {
+                                let (DocumentSourceKind::Base64(data)
+                                | DocumentSourceKind::String(data)) = document.data
+                                else {
+                                    return Err(MessageError::ConversionError(
+                                        "Only base64 encoded documents currently supported".into(),
+                                    ));
+                                };
+                                let media_type =
+                                    document.media_type.ok_or(MessageError::ConversionError(
+                                        "Document media type is required".to_owned(),
+                                    ))?;
+                                Ok(ToolResultContent::Document {
+                                    source: DocumentSource {
+                                        data,
+                                        media_type: media_type.try_into()?,
+                                        r#type: SourceType::BASE64,
+                                    },
+                                })
+                            }
                         })?,
                         is_error: None,
                         cache_control: None,
@@ -605,6 +647,10 @@ impl From<ToolResultContent> for message::ToolResultContent {
                 media_type: format,
                 ..
             }) => message::ToolResultContent::image_base64(data, Some(format.into()), None),
+            ToolResultContent::Document { source } => message::ToolResultContent::document_base64(
+                source.data,
+                Some(source.media_type.into()),
+            ),
         }
     }
 }
@@ -636,7 +682,7 @@ impl TryFrom<Message> for message::Message {
                         }
                         Content::Document { source, .. } => message::UserContent::document(
                             source.data,
-                            Some(message::DocumentMediaType::PDF),
+                            Some(source.media_type.into()),
                         ),
                         _ => {
                             return Err(MessageError::ConversionError(
@@ -807,14 +853,24 @@ struct AnthropicCompletionRequest {
     additional_params: Option<serde_json::Value>,
 }
 
+/// Maximum number of `cache_control` breakpoints Anthropic accepts in a single request.
+pub const MAX_CACHE_BREAKPOINTS: usize = 4;
+
+/// Helper to access the cache_control of a Content block, if it has one
+fn content_cache_control(content: &mut Content) -> Option<&mut Option<CacheControl>> {
+    match content {
+        Content::Text { cache_control, .. }
+        | Content::Image { cache_control, .. }
+        | Content::ToolResult { cache_control, .. }
+        | Content::Document { cache_control, .. } => Some(cache_control),
+        _ => None,
+    }
+}
+
 /// Helper to set cache_control on a Content block
 fn set_content_cache_control(content: &mut Content, value: Option<CacheControl>) {
-    match content {
-        Content::Text { cache_control, .. } => *cache_control = value,
-        Content::Image { cache_control, .. } => *cache_control = value,
-        Content::ToolResult { cache_control, .. } => *cache_control = value,
-        Content::Document { cache_control, .. } => *cache_control = value,
-        _ => {}
+    if let Some(cache_control) = content_cache_control(content) {
+        *cache_control = value;
     }
 }
 
@@ -841,6 +897,76 @@ pub fn apply_cache_control(system: &mut [SystemContent], messages: &mut [Message
     }
 }
 
+/// Apply the cache breakpoints of a [CompletionRequest] to the system prompt, tools and messages.
+/// `has_documents` tells whether the first message holds the documents of the request.
+///
+/// Anthropic rejects requests with more than [MAX_CACHE_BREAKPOINTS] breakpoints, so once they are
+/// applied (together with any set by [apply_cache_control]) the oldest ones, in prompt order
+/// (tools, system, messages), are dropped.
+pub fn apply_cache_breakpoints(
+    breakpoints: &[CacheBreakpoint],
+    has_documents: bool,
+    system: &mut [SystemContent],
+    tools: &mut [ToolDefinition],
+    messages: &mut [Message],
+) {
+    let history_offset = usize::from(has_documents);
+
+    for breakpoint in breakpoints {
+        match *breakpoint {
+            CacheBreakpoint::Preamble => {
+                if let Some(SystemContent::Text { cache_control, .. }) = system.last_mut() {
+                    *cache_control = Some(CacheControl::Ephemeral);
+                }
+            }
+            CacheBreakpoint::Tools => {
+                if let Some(tool) = tools.last_mut() {
+                    tool.cache_control = Some(CacheControl::Ephemeral);
+                }
+            }
+            CacheBreakpoint::Document(index) => {
+                if let Some(content) = messages
+                    .first_mut()
+                    .filter(|_| has_documents)
+                    .and_then(|docs| docs.content.iter_mut().nth(index))
+                {
+                    set_content_cache_control(content, Some(CacheControl::Ephemeral));
+                }
+            }
+            CacheBreakpoint::Message(index) => {
+                if let Some(message) = messages.get_mut(index + history_offset) {
+                    set_content_cache_control(
+                        message.content.last_mut(),
+                        Some(CacheControl::Ephemeral),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut cache_controls = tools
+        .iter_mut()
+        .map(|tool| &mut tool.cache_control)
+        .chain(
+            system
+                .iter_mut()
+                .map(|SystemContent::Text { cache_control, .. }| cache_control),
+        )
+        .chain(
+            messages
+                .iter_mut()
+                .flat_map(|message| message.content.iter_mut())
+                .filter_map(content_cache_control),
+        )
+        .filter(|cache_control| cache_control.is_some())
+        .collect::<Vec<_>>();
+
+    let excess = cache_controls.len().saturating_sub(MAX_CACHE_BREAKPOINTS);
+    for cache_control in cache_controls.drain(..excess) {
+        *cache_control = None;
+    }
+}
+
 /// Parameters for building an AnthropicCompletionRequest
 pub struct AnthropicRequestParams<'a> {
     pub model: &'a str,
@@ -866,9 +992,9 @@ impl TryFrom<AnthropicRequestParams<'_>> for AnthropicCompletionRequest {
         };
 
         let mut full_history = vec![];
-        if let Some(docs) = req.normalized_documents() {
-            full_history.push(docs);
-        }
+        let documents = req.normalized_documents();
+        let has_documents = documents.is_some();
+        full_history.extend(documents);
         full_history.extend(req.chat_history);
 
         let mut messages = full_history
@@ -876,14 +1002,10 @@ impl TryFrom<AnthropicRequestParams<'_>> for AnthropicCompletionRequest {
             .map(Message::try_from)
             .collect::<Result<Vec<Message>, _>>()?;
 
-        let tools = req
+        let mut tools = req
             .tools
             .into_iter()
-            .map(|tool| ToolDefinition {
-                name: tool.name,
-                description: Some(tool.description),
-                input_schema: tool.parameters,
-            })
+            .map(ToolDefinition::from)
             .collect::<Vec<_>>();
 
         // Convert system prompt to array format for cache_control support
@@ -904,6 +1026,13 @@ impl TryFrom<AnthropicRequestParams<'_>> for AnthropicCompletionRequest {
         if prompt_caching {
             apply_cache_control(&mut system, &mut messages);
         }
+        apply_cache_breakpoints(
+            &req.cache_breakpoints,
+            has_documents,
+            &mut system,
+            &mut tools,
+            &mut messages,
+        );
 
         Ok(Self {
             model: model.to_string(),
@@ -930,12 +1059,16 @@ where
         Self::new(client.clone(), model.into())
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         mut completion_request: completion::CompletionRequest,
     ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -944,8 +1077,6 @@ where
                 gen_ai.system_instructions = &completion_request.preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -989,17 +1120,15 @@ where
                 .client
                 .send::<_, Bytes>(req)
                 .await
-                .map_err(CompletionError::HttpError)?;
+                .map_err(CompletionError::from)?;
 
             if response.status().is_success() {
-                match serde_json::from_slice::<ApiResponse<CompletionResponse>>(
-                    response
-                        .into_body()
-                        .await
-                        .map_err(CompletionError::HttpError)?
-                        .to_vec()
-                        .as_slice(),
-                )? {
+                let response_body = response
+                    .into_body()
+                    .await
+                    .map_err(CompletionError::from)?
+                    .to_vec();
+                match serde_json::from_slice::<ApiResponse<CompletionResponse>>(&response_body)? {
                     ApiResponse::Message(completion) => {
                         let span = tracing::Span::current();
                         span.record_response_metadata(&completion);
@@ -1014,18 +1143,11 @@ where
                         completion.try_into()
                     }
                     ApiResponse::Error(ApiErrorResponse { message }) => {
-                        Err(CompletionError::ResponseError(message))
+                        Err(CompletionError::from_error_body(&response_body, message))
                     }
                 }
             } else {
-                let text: String = String::from_utf8_lossy(
-                    &response
-                        .into_body()
-                        .await
-                        .map_err(CompletionError::HttpError)?,
-                )
-                .into();
-                Err(CompletionError::ProviderError(text))
+                Err(crate::http_client::into_status_error(response).await.into())
             }
         }
         .instrument(span)
@@ -1043,6 +1165,69 @@ where
     }
 }
 
+/// Fields of a message request accepted by the token counting endpoint
+const COUNT_TOKENS_FIELDS: [&str; 6] = [
+    "model",
+    "messages",
+    "system",
+    "tools",
+    "tool_choice",
+    "thinking",
+];
+
+#[derive(Debug, Deserialize)]
+struct CountTokensResponse {
+    input_tokens: u64,
+}
+
+impl<T> RemoteTokenCounter for CompletionModel<T>
+where
+    T: HttpClientExt + Clone + Default + WasmCompatSend + WasmCompatSync + 'static,
+{
+    /// Counts the input tokens of `request` using the
+    /// [token counting API](https://docs.anthropic.com/en/docs/build-with-claude/token-counting).
+    async fn count_request_tokens(
+        &self,
+        mut request: completion::CompletionRequest,
+    ) -> Result<u64, CompletionError> {
+        // `max_tokens` is required to build the request, but isn't sent to the endpoint
+        request
+            .max_tokens
+            .get_or_insert(self.default_max_tokens.unwrap_or(1));
+
+        let request = AnthropicCompletionRequest::try_from(AnthropicRequestParams {
+            model: &self.model,
+            request,
+            prompt_caching: self.prompt_caching,
+        })?;
+
+        let mut body = serde_json::to_value(&request)?;
+        if let Some(body) = body.as_object_mut() {
+            body.retain(|key, _| COUNT_TOKENS_FIELDS.contains(&key.as_str()));
+        }
+
+        let req = self
+            .client
+            .post("/v1/messages/count_tokens")?
+            .body(serde_json::to_vec(&body)?)
+            .map_err(|e| CompletionError::HttpError(e.into()))?;
+
+        let response = self
+            .client
+            .send::<_, Bytes>(req)
+            .await
+            .map_err(CompletionError::from)?;
+
+        if response.status().is_success() {
+            let response_body = response.into_body().await.map_err(CompletionError::from)?;
+            let response: CountTokensResponse = serde_json::from_slice(&response_body)?;
+            Ok(response.input_tokens)
+        } else {
+            Err(crate::http_client::into_status_error(response).await.into())
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiErrorResponse {
     message: String,
@@ -1391,6 +1576,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_usage_reports_cache_tokens() {
+        let usage: Usage = serde_json::from_value(json!({
+            "input_tokens": 20,
+            "cache_read_input_tokens": 1000,
+            "cache_creation_input_tokens": 300,
+            "output_tokens": 50
+        }))
+        .unwrap();
+
+        let usage = usage.token_usage().unwrap();
+        assert_eq!(usage.input_tokens, 1320);
+        assert_eq!(usage.cached_input_tokens, 1000);
+        assert_eq!(usage.cache_creation_input_tokens, 300);
+        assert_eq!(usage.total_tokens, 1370);
+    }
+
     #[test]
     fn test_cache_control_serialization() {
         // Test SystemContent with cache_control
@@ -1464,4 +1666,172 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_cache_breakpoints() {
+        let request = CompletionRequest {
+            preamble: Some("You are a helpful assistant.".to_string()),
+            chat_history: OneOrMany::many(vec![
+                crate::completion::Message::user("First message"),
+                crate::completion::Message::assistant("Response"),
+                crate::completion::Message::user("Second message"),
+            ])
+            .unwrap(),
+            documents: vec![],
+            tools: vec![crate::completion::ToolDefinition {
+                name: "add".to_string(),
+                description: "Add two numbers".to_string(),
+                parameters: json!({"type": "object", "properties": {}}),
+            }],
+            temperature: None,
+            max_tokens: Some(1024),
+            tool_choice: None,
+            additional_params: None,
+            output_schema: None,
+            cache_breakpoints: vec![
+                CacheBreakpoint::Preamble,
+                CacheBreakpoint::Tools,
+                CacheBreakpoint::Message(0),
+            ],
+        };
+
+        let request = AnthropicCompletionRequest::try_from(AnthropicRequestParams {
+            model: "claude-sonnet-4-0",
+            request,
+            prompt_caching: false,
+        })
+        .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        let ephemeral = json!({"type": "ephemeral"});
+
+        assert_eq!(json["system"][0]["cache_control"], ephemeral);
+        assert_eq!(json["tools"][0]["cache_control"], ephemeral);
+        assert_eq!(
+            json["messages"][0]["content"][0]["cache_control"],
+            ephemeral
+        );
+        assert!(
+            json["messages"][1]["content"][0]
+                .get("cache_control")
+                .is_none()
+        );
+        assert!(
+            json["messages"][2]["content"][0]
+                .get("cache_control")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_cache_breakpoints_limit() {
+        let text = |text: &str| Content::Text {
+            text: text.to_string(),
+            cache_control: None,
+        };
+        let mut system = vec![SystemContent::Text {
+            text: "System prompt".to_string(),
+            cache_control: None,
+        }];
+        let mut tools = vec![ToolDefinition {
+            name: "add".to_string(),
+            description: None,
+            input_schema: json!({"type": "object"}),
+            cache_control: None,
+        }];
+        let mut messages = vec![
+            Message {
+                role: Role::User,
+                content: OneOrMany::many(vec![text("First document"), text("Second document")])
+                    .unwrap(),
+            },
+            Message {
+                role: Role::User,
+                content: OneOrMany::one(text("First message")),
+            },
+            Message {
+                role: Role::Assistant,
+                content: OneOrMany::one(text("Response")),
+            },
+            Message {
+                role: Role::User,
+                content: OneOrMany::one(text("Second message")),
+            },
+        ];
+
+        apply_cache_breakpoints(
+            &[
+                CacheBreakpoint::Preamble,
+                CacheBreakpoint::Tools,
+                CacheBreakpoint::Document(1),
+                CacheBreakpoint::Message(0),
+                CacheBreakpoint::Message(2),
+            ],
+            true,
+            &mut system,
+            &mut tools,
+            &mut messages,
+        );
+
+        let has_cache_control = |content: &Content| matches!(content, Content::Text { cache_control, .. } if cache_control.is_some());
+
+        // Tools come first in the prompt, so their breakpoint is the one dropped
+        assert!(tools[0].cache_control.is_none());
+        assert!(matches!(
+            &system[0],
+            SystemContent::Text {
+                cache_control: Some(_),
+                ..
+            }
+        ));
+        assert!(!has_cache_control(messages[0].content.first_ref()));
+        assert!(has_cache_control(messages[0].content.last_ref()));
+        assert!(has_cache_control(messages[1].content.first_ref()));
+        assert!(!has_cache_control(messages[2].content.first_ref()));
+        assert!(has_cache_control(messages[3].content.first_ref()));
+    }
+
+    #[tokio::test]
+    async fn test_count_request_tokens() {
+        use crate::http_client::mock::MockHttpClient;
+        use crate::tokens::RemoteTokenCounter;
+
+        let http_client = MockHttpClient::default().respond(200, r#"{"input_tokens": 42}"#);
+        let client = Client::<MockHttpClient>::builder()
+            .api_key("key")
+            .http_client(http_client.clone())
+            .build()
+            .unwrap();
+        let model = CompletionModel::new(client, "claude-sonnet-4-0");
+
+        let request = CompletionRequest {
+            preamble: Some("Be brief.".to_string()),
+            chat_history: OneOrMany::one(crate::completion::Message::user("Hello!")),
+            documents: vec![],
+            tools: vec![],
+            temperature: Some(0.5),
+            max_tokens: Some(1024),
+            tool_choice: None,
+            additional_params: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
+        };
+
+        assert_eq!(model.count_request_tokens(request).await.unwrap(), 42);
+
+        let requests = http_client.requests();
+        assert_eq!(requests.len(), 1);
+        let (uri, body) = &requests[0];
+        assert_eq!(uri, "https://api.anthropic.com/v1/messages/count_tokens");
+
+        // Only the fields accepted by the endpoint are sent
+        let body: serde_json::Value = serde_json::from_slice(body).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "model": "claude-sonnet-4-0",
+                "messages": [{ "role": "user", "content": [{ "type": "text", "text": "Hello!" }] }],
+                "system": [{ "type": "text", "text": "Be brief." }]
+            })
+        );
+    }
 }
@@ -2,12 +2,12 @@ use async_stream::stream;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::{Level, enabled, info_span};
+use tracing::{Level, enabled};
 use tracing_futures::Instrument;
 
 use super::completion::{
     CompletionModel, Content, Message, SystemContent, ToolChoice, ToolDefinition, Usage,
-    apply_cache_control,
+    apply_cache_breakpoints, apply_cache_control,
 };
 use crate::completion::{CompletionError, CompletionRequest, GetTokenUsage};
 use crate::http_client::sse::{Event, GenericEventSource};
@@ -16,7 +16,7 @@ use crate::json_utils::merge_inplace;
 use crate::streaming::{
     self, RawStreamingChoice, RawStreamingToolCall, StreamingResult, ToolCallDeltaContent,
 };
-use crate::telemetry::SpanCombinator;
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -76,13 +76,22 @@ pub struct PartialUsage {
     pub output_tokens: usize,
     #[serde(default)]
     pub input_tokens: Option<usize>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<usize>,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<usize>,
 }
 
 impl GetTokenUsage for PartialUsage {
     fn token_usage(&self) -> Option<crate::completion::Usage> {
         let mut usage = crate::completion::Usage::new();
 
-        usage.input_tokens = self.input_tokens.unwrap_or_default() as u64;
+        usage.cached_input_tokens = self.cache_read_input_tokens.unwrap_or_default() as u64;
+        usage.cache_creation_input_tokens =
+            self.cache_creation_input_tokens.unwrap_or_default() as u64;
+        usage.input_tokens = self.input_tokens.unwrap_or_default() as u64
+            + usage.cached_input_tokens
+            + usage.cache_creation_input_tokens;
         usage.output_tokens = self.output_tokens as u64;
         usage.total_tokens = usage.input_tokens + usage.output_tokens;
         Some(usage)
@@ -109,13 +118,7 @@ pub struct StreamingCompletionResponse {
 
 impl GetTokenUsage for StreamingCompletionResponse {
     fn token_usage(&self) -> Option<crate::completion::Usage> {
-        let mut usage = crate::completion::Usage::new();
-        usage.input_tokens = self.usage.input_tokens.unwrap_or(0) as u64;
-        usage.output_tokens = self.usage.output_tokens as u64;
-        usage.total_tokens =
-            self.usage.input_tokens.unwrap_or(0) as u64 + self.usage.output_tokens as u64;
-
-        Some(usage)
+        self.usage.token_usage()
     }
 }
 
@@ -129,7 +132,7 @@ where
     ) -> Result<streaming::StreamingCompletionResponse<StreamingCompletionResponse>, CompletionError>
     {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -138,8 +141,6 @@ where
                 gen_ai.system_instructions = &completion_request.preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = self.model,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
                 gen_ai.input.messages = tracing::field::Empty,
                 gen_ai.output.messages = tracing::field::Empty,
             )
@@ -157,9 +158,9 @@ where
         };
 
         let mut full_history = vec![];
-        if let Some(docs) = completion_request.normalized_documents() {
-            full_history.push(docs);
-        }
+        let documents = completion_request.normalized_documents();
+        let has_documents = documents.is_some();
+        full_history.extend(documents);
         full_history.extend(completion_request.chat_history);
 
         let mut messages = full_history
@@ -182,10 +183,23 @@ where
                 vec![]
             };
 
+        let mut tools = completion_request
+            .tools
+            .into_iter()
+            .map(ToolDefinition::from)
+            .collect::<Vec<_>>();
+
         // Apply cache control breakpoints only if prompt_caching is enabled
         if self.prompt_caching {
             apply_cache_control(&mut system, &mut messages);
         }
+        apply_cache_breakpoints(
+            &completion_request.cache_breakpoints,
+            has_documents,
+            &mut system,
+            &mut tools,
+            &mut messages,
+        );
 
         let mut body = json!({
             "model": self.model,
@@ -203,19 +217,11 @@ where
             merge_inplace(&mut body, json!({ "temperature": temperature }));
         }
 
-        if !completion_request.tools.is_empty() {
+        if !tools.is_empty() {
             merge_inplace(
                 &mut body,
                 json!({
-                    "tools": completion_request
-                        .tools
-                        .into_iter()
-                        .map(|tool| ToolDefinition {
-                            name: tool.name,
-                            description: Some(tool.description),
-                            input_schema: tool.parameters,
-                        })
-                        .collect::<Vec<_>>(),
+                    "tools": tools,
                     "tool_choice": ToolChoice::Auto,
                 }),
             );
@@ -248,7 +254,7 @@ where
             let mut current_tool_call: Option<ToolCallState> = None;
             let mut current_thinking: Option<ThinkingState> = None;
             let mut sse_stream = Box::pin(stream);
-            let mut input_tokens = 0;
+            let mut input_usage = None;
             let mut final_usage = None;
 
             let mut text_content = String::new();
@@ -262,7 +268,7 @@ where
                             Ok(event) => {
                                 match &event {
                                     StreamingEvent::MessageStart { message } => {
-                                        input_tokens = message.usage.input_tokens;
+                                        input_usage = Some(message.usage.clone());
 
                                         let span = tracing::Span::current();
                                         span.record("gen_ai.response.id", &message.id);
@@ -270,9 +276,21 @@ where
                                     },
                                     StreamingEvent::MessageDelta { delta, usage } => {
                                         if delta.stop_reason.is_some() {
+                                            let input_usage = input_usage.as_ref();
+                                            let to_usize = |tokens: u64| -> usize {
+                                                tokens.try_into().expect("Failed to convert input_tokens to usize")
+                                            };
                                             let usage = PartialUsage {
                                                  output_tokens: usage.output_tokens,
-                                                 input_tokens: Some(input_tokens.try_into().expect("Failed to convert input_tokens to usize")),
+                                                 input_tokens: Some(to_usize(input_usage.map(|u| u.input_tokens).unwrap_or_default())),
+                                                 cache_read_input_tokens: input_usage
+                                                     .and_then(|u| u.cache_read_input_tokens)
+                                                     .map(to_usize)
+                                                     .or(usage.cache_read_input_tokens),
+                                                 cache_creation_input_tokens: input_usage
+                                                     .and_then(|u| u.cache_creation_input_tokens)
+                                                     .map(to_usize)
+                                                     .or(usage.cache_creation_input_tokens),
                                             };
 
                                             let span = tracing::Span::current();
@@ -301,7 +319,7 @@ where
                         }
                     },
                     Err(e) => {
-                        yield Err(CompletionError::ProviderError(format!("SSE Error: {e}")));
+                        yield Err(e.into());
                         break;
                     }
                 }
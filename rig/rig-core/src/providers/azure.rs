@@ -21,6 +21,7 @@ use crate::completion::GetTokenUsage;
 use crate::http_client::multipart::Part;
 use crate::http_client::{self, HttpClientExt, MultipartForm, bearer_auth_header};
 use crate::streaming::StreamingCompletionResponse;
+use crate::telemetry::gen_ai_span;
 use crate::transcription::TranscriptionError;
 use crate::{
     completion::{self, CompletionError, CompletionRequest},
@@ -638,12 +639,16 @@ where
         Self::new(client.clone(), model.into())
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<openai::CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -652,8 +657,6 @@ where
                 gen_ai.system_instructions = &completion_request.preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -699,10 +702,15 @@ where
                         }
                         response.try_into()
                     }
-                    ApiResponse::Err(err) => Err(CompletionError::ProviderError(err.message)),
+                    ApiResponse::Err(err) => Err(CompletionError::from_error_body(
+                        &response_body,
+                        err.message,
+                    )),
                 }
             } else {
-                Err(CompletionError::ProviderError(
+                Err(CompletionError::from_response(
+                    status,
+                    None,
                     String::from_utf8_lossy(&response_body).to_string(),
                 ))
             }
@@ -742,7 +750,7 @@ where
             .map_err(http_client::Error::from)?;
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -751,8 +759,6 @@ where
                 gen_ai.system_instructions = &preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -856,7 +862,7 @@ where
 // ================================================================
 #[cfg(feature = "image")]
 pub use image_generation::*;
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 #[cfg(feature = "image")]
 #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
 mod image_generation {
@@ -1051,6 +1057,8 @@ mod azure_tests {
                 tools: vec![],
                 tool_choice: None,
                 additional_params: None,
+                output_schema: None,
+                cache_breakpoints: vec![],
             })
             .await
             .unwrap();
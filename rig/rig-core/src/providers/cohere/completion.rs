@@ -1,3 +1,4 @@
+use crate::telemetry::gen_ai_span;
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, GetTokenUsage},
@@ -12,7 +13,7 @@ use super::client::Client;
 use crate::completion::CompletionRequest;
 use crate::providers::cohere::streaming::StreamingCompletionResponse;
 use serde::{Deserialize, Serialize};
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CompletionResponse {
@@ -182,6 +183,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                     input_tokens: input_tokens as u64,
                     output_tokens: output_tokens as u64,
                     total_tokens: (input_tokens + output_tokens) as u64,
+                    ..Default::default()
                 }
             })
             .unwrap_or_default();
@@ -614,6 +616,10 @@ where
         Self::new(client.clone(), model.into())
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: completion::CompletionRequest,
@@ -621,7 +627,7 @@ where
         let request = CohereCompletionRequest::try_from((self.model.as_ref(), completion_request))?;
 
         let llm_span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
             target: "rig::completions",
             "chat",
             gen_ai.operation.name = "chat",
@@ -629,8 +635,6 @@ where
             gen_ai.request.model = self.model,
             gen_ai.response.id = tracing::field::Empty,
             gen_ai.response.model = self.model,
-            gen_ai.usage.output_tokens = tracing::field::Empty,
-            gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -675,7 +679,9 @@ where
                     json_response.try_into()?;
                 Ok(completion)
             } else {
-                Err(CompletionError::ProviderError(
+                Err(CompletionError::from_response(
+                    status,
+                    None,
                     String::from_utf8_lossy(&body).to_string(),
                 ))
             }
@@ -6,12 +6,12 @@ use crate::providers::cohere::completion::{
     AssistantContent, CohereCompletionRequest, Message, ToolCall, ToolCallFunction, ToolType, Usage,
 };
 use crate::streaming::{RawStreamingChoice, RawStreamingToolCall, ToolCallDeltaContent};
-use crate::telemetry::SpanCombinator;
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 use crate::{json_utils, streaming};
 use async_stream::stream;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tracing::{Level, enabled, info_span};
+use tracing::{Level, enabled};
 use tracing_futures::Instrument;
 
 #[derive(Debug, Deserialize)]
@@ -101,7 +101,7 @@ where
     {
         let mut request = CohereCompletionRequest::try_from((self.model.as_ref(), request))?;
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -109,8 +109,6 @@ where
                 gen_ai.request.model = self.model,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = self.model,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -251,7 +249,7 @@ where
                     }
                     Err(err) => {
                         tracing::error!(?err, "SSE error");
-                        yield Err(CompletionError::ProviderError(err.to_string()));
+                        yield Err(err.into());
                         break;
                     }
                 }
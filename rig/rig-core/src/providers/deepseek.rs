@@ -15,7 +15,7 @@ use bytes::Bytes;
 use futures::StreamExt;
 use http::Request;
 use std::collections::HashMap;
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 
 use crate::client::{
     self, BearerAuth, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder,
@@ -25,6 +25,7 @@ use crate::completion::GetTokenUsage;
 use crate::http_client::sse::{Event, GenericEventSource};
 use crate::http_client::{self, HttpClientExt};
 use crate::message::{Document, DocumentSourceKind};
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
@@ -158,6 +159,27 @@ impl Usage {
     }
 }
 
+impl GetTokenUsage for Usage {
+    fn token_usage(&self) -> Option<crate::completion::Usage> {
+        let mut usage = crate::completion::Usage::new();
+        usage.input_tokens = self.prompt_tokens as u64;
+        usage.output_tokens = self.completion_tokens as u64;
+        usage.total_tokens = self.total_tokens as u64;
+        usage.cached_input_tokens = self
+            .prompt_tokens_details
+            .as_ref()
+            .and_then(|details| details.cached_tokens)
+            .unwrap_or(self.prompt_cache_hit_tokens) as u64;
+        usage.reasoning_tokens = self
+            .completion_tokens_details
+            .as_ref()
+            .and_then(|details| details.reasoning_tokens)
+            .unwrap_or_default() as u64;
+
+        Some(usage)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct CompletionTokensDetails {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -220,10 +242,21 @@ impl Message {
 
 impl From<message::ToolResult> for Message {
     fn from(tool_result: message::ToolResult) -> Self {
-        let content = match tool_result.content.first() {
-            message::ToolResultContent::Text(text) => text.text,
-            message::ToolResultContent::Image(_) => String::from("[Image]"),
-        };
+        // DeepSeek only accepts text: images and binary documents are replaced by a placeholder
+        let content = tool_result
+            .content
+            .into_iter()
+            .map(|content| match content {
+                message::ToolResultContent::Text(text) => text.text,
+                message::ToolResultContent::Image(_) => String::from("[Image]"),
+                message::ToolResultContent::Document(Document {
+                    data: DocumentSourceKind::String(text),
+                    ..
+                }) => text,
+                message::ToolResultContent::Document(_) => String::from("[Document]"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
 
         Message::ToolResult {
             tool_call_id: tool_result.id,
@@ -421,11 +454,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
             )
         })?;
 
-        let usage = completion::Usage {
-            input_tokens: response.usage.prompt_tokens as u64,
-            output_tokens: response.usage.completion_tokens as u64,
-            total_tokens: response.usage.total_tokens as u64,
-        };
+        let usage = response.usage.token_usage().unwrap_or_default();
 
         Ok(completion::CompletionResponse {
             choice,
@@ -520,6 +549,10 @@ where
         }
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
@@ -528,7 +561,7 @@ where
         crate::completion::CompletionError,
     > {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -537,8 +570,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -572,11 +603,7 @@ where
                 match serde_json::from_slice::<ApiResponse<CompletionResponse>>(&response_body)? {
                     ApiResponse::Ok(response) => {
                         let span = tracing::Span::current();
-                        span.record("gen_ai.usage.input_tokens", response.usage.prompt_tokens);
-                        span.record(
-                            "gen_ai.usage.output_tokens",
-                            response.usage.completion_tokens,
-                        );
+                        span.record_token_usage(&response.usage);
                         if enabled!(Level::TRACE) {
                             tracing::trace!(target: "rig::completions",
                                 "DeepSeek completion response: {}",
@@ -585,10 +612,15 @@ where
                         }
                         response.try_into()
                     }
-                    ApiResponse::Err(err) => Err(CompletionError::ProviderError(err.message)),
+                    ApiResponse::Err(err) => Err(CompletionError::from_error_body(
+                        &response_body,
+                        err.message,
+                    )),
                 }
             } else {
-                Err(CompletionError::ProviderError(
+                Err(CompletionError::from_response(
+                    status,
+                    None,
                     String::from_utf8_lossy(&response_body).to_string(),
                 ))
             }
@@ -631,7 +663,7 @@ where
             .map_err(|e| CompletionError::HttpError(e.into()))?;
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -640,8 +672,6 @@ where
                 gen_ai.system_instructions = preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -682,12 +712,7 @@ pub struct StreamingCompletionResponse {
 
 impl GetTokenUsage for StreamingCompletionResponse {
     fn token_usage(&self) -> Option<crate::completion::Usage> {
-        let mut usage = crate::completion::Usage::new();
-        usage.input_tokens = self.usage.prompt_tokens as u64;
-        usage.output_tokens = self.usage.completion_tokens as u64;
-        usage.total_tokens = self.usage.total_tokens as u64;
-
-        Some(usage)
+        self.usage.token_usage()
     }
 }
 
@@ -951,6 +976,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_serialize_multi_part_tool_result() {
+        let tool_result = message::ToolResult {
+            id: "call_1".to_string(),
+            call_id: None,
+            content: OneOrMany::many(vec![
+                message::ToolResultContent::text("first"),
+                message::ToolResultContent::image_base64("aGVsbG8=", None, None),
+                message::ToolResultContent::text("second"),
+            ])
+            .unwrap(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(Message::from(tool_result)).unwrap(),
+            serde_json::json!({
+                "role": "tool",
+                "tool_call_id": "call_1",
+                "content": "first\n[Image]\nsecond"
+            })
+        );
+    }
+
     #[test]
     fn test_serialize_deserialize_tool_call_message() {
         let tool_call_choice_json = r#"
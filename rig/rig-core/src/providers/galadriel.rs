@@ -19,13 +19,14 @@ use crate::http_client::{self, HttpClientExt};
 use crate::message::MessageError;
 use crate::providers::openai::send_compatible_streaming_request;
 use crate::streaming::StreamingCompletionResponse;
+use crate::telemetry::gen_ai_span;
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
     json_utils, message,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{Instrument, enabled, info_span};
+use tracing::{Instrument, enabled};
 
 // ================================================================
 // Main Galadriel Client
@@ -262,6 +263,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                 input_tokens: usage.prompt_tokens as u64,
                 output_tokens: (usage.total_tokens - usage.prompt_tokens) as u64,
                 total_tokens: usage.total_tokens as u64,
+                ..Default::default()
             })
             .unwrap_or_default();
 
@@ -522,12 +524,16 @@ where
         Self::new(client.clone(), model.into())
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -536,8 +542,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -590,12 +594,10 @@ where
                         }
                         response.try_into()
                     }
-                    ApiResponse::Err(err) => Err(CompletionError::ProviderError(err.message)),
+                    ApiResponse::Err(err) => Err(CompletionError::from_error_body(&t, err.message)),
                 }
             } else {
-                let text = http_client::text(response).await?;
-
-                Err(CompletionError::ProviderError(text))
+                Err(http_client::into_status_error(response).await.into())
             }
         }
         .instrument(span)
@@ -626,7 +628,7 @@ where
             .map_err(http_client::Error::from)?;
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -635,8 +637,6 @@ where
                 gen_ai.system_instructions = preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
                 gen_ai.input.messages = serde_json::to_string(&request.messages)?,
                 gen_ai.output.messages = tracing::field::Empty,
             )
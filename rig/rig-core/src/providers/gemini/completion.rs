@@ -20,25 +20,31 @@ pub const GEMINI_2_0_FLASH_LITE: &str = "gemini-2.0-flash-lite";
 pub const GEMINI_2_0_FLASH: &str = "gemini-2.0-flash";
 
 use self::gemini_api_types::Schema;
-use crate::http_client::HttpClientExt;
+use crate::http_client::{self, HttpClientExt};
 use crate::message::{self, MimeType, Reasoning};
 
 use crate::providers::gemini::completion::gemini_api_types::{
     AdditionalParameters, FunctionCallingMode, ToolConfig,
 };
 use crate::providers::gemini::streaming::StreamingCompletionResponse;
-use crate::telemetry::SpanCombinator;
+use crate::telemetry::{SpanCombinator, gen_ai_span};
+use crate::tokens::RemoteTokenCounter;
 use crate::{
     OneOrMany,
-    completion::{self, CompletionError, CompletionRequest},
+    completion::{self, CacheBreakpoint, CompletionError, CompletionRequest},
 };
 use gemini_api_types::{
-    Content, FunctionDeclaration, GenerateContentRequest, GenerateContentResponse, Part, PartKind,
-    Role, Tool,
+    Content, FunctionDeclaration, GenerateContentRequest, GenerateContentResponse,
+    GenerationConfig, Part, PartKind, Role, Tool,
 };
+use serde::Deserialize;
 use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::convert::TryFrom;
-use tracing::{Level, enabled, info_span};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{Level, enabled};
 use tracing_futures::Instrument;
 
 use super::Client;
@@ -47,10 +53,33 @@ use super::Client;
 // Rig Implementation Types
 // =================================================================
 
+/// Default time-to-live of cached content created from [CacheBreakpoint]s.
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Cached content is recreated slightly before it expires so that requests never reference an expired cache.
+const CACHE_EXPIRY_MARGIN: chrono::TimeDelta = chrono::TimeDelta::seconds(60);
+
+/// How long a prefix whose cached content could not be created is sent uncached before retrying.
+const FAILED_CACHE_RETRY: chrono::TimeDelta = chrono::TimeDelta::minutes(10);
+
 #[derive(Clone, Debug)]
 pub struct CompletionModel<T = reqwest::Client> {
     pub(crate) client: Client<T>,
     pub model: String,
+    cache_ttl: Duration,
+    cached_contents: Arc<Mutex<HashMap<u64, CachedContentEntry>>>,
+}
+
+#[derive(Clone, Debug)]
+struct CachedContentEntry {
+    /// The name of the cached content, or `None` if creating it failed.
+    name: Option<String>,
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachedContent {
+    name: String,
 }
 
 impl<T> CompletionModel<T> {
@@ -58,13 +87,147 @@ impl<T> CompletionModel<T> {
         Self {
             client,
             model: model.into(),
+            cache_ttl: DEFAULT_CACHE_TTL,
+            cached_contents: Default::default(),
         }
     }
 
     pub fn with_model(client: Client<T>, model: &str) -> Self {
-        Self {
-            client,
-            model: model.into(),
+        Self::new(client, model)
+    }
+
+    /// Sets how long cached content created from [CacheBreakpoint]s is kept alive (one hour by default).
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+}
+
+impl<T> CompletionModel<T>
+where
+    T: HttpClientExt + Clone + 'static,
+{
+    /// Moves the part of `request` marked by `breakpoints` into Gemini [cached content](https://ai.google.dev/gemini-api/docs/caching).
+    ///
+    /// Gemini requires the system instruction, tools and tool config to live in the cached content, so any
+    /// breakpoint caches all of them together with the chat history up to the last [CacheBreakpoint::Message].
+    /// Gemini requests don't include [CompletionRequest::documents], so [CacheBreakpoint::Document] only
+    /// caches the system instruction and tools.
+    ///
+    /// Cached content is reused for identical prefixes until it expires, and expired entries are evicted
+    /// whenever new cached content is created. If it cannot be created (for instance because the prefix is
+    /// below the model's minimum cacheable size) the request is sent uncached, and so are requests with the
+    /// same prefix for the next few minutes instead of retrying on every request.
+    pub(crate) async fn apply_cache_breakpoints(
+        &self,
+        breakpoints: &[CacheBreakpoint],
+        request: &mut GenerateContentRequest,
+    ) {
+        if breakpoints.is_empty() {
+            return;
+        }
+
+        // The request itself must keep at least one message
+        let prefix_len = breakpoints
+            .iter()
+            .filter_map(|breakpoint| match breakpoint {
+                CacheBreakpoint::Message(index) => Some(index + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+            .min(request.contents.len().saturating_sub(1));
+
+        if prefix_len == 0 && request.system_instruction.is_none() && request.tools.is_none() {
+            return;
+        }
+
+        let body = serde_json::json!({
+            "model": format!("models/{}", self.model),
+            "contents": &request.contents[..prefix_len],
+            "systemInstruction": request.system_instruction,
+            "tools": request.tools,
+            "toolConfig": request.tool_config,
+        });
+
+        let mut hasher = DefaultHasher::new();
+        body.to_string().hash(&mut hasher);
+        let key = hasher.finish();
+
+        let now = chrono::Utc::now();
+        let cached = self
+            .cached_contents
+            .lock()
+            .expect("lock poisoned")
+            .get(&key)
+            .filter(|entry| entry.expires_at > now)
+            .map(|entry| entry.name.clone());
+
+        let name = match cached {
+            Some(Some(name)) => name,
+            // Creating cached content for this prefix failed recently
+            Some(None) => return,
+            None => {
+                let (name, expires_at) = match self.create_cached_content(body).await {
+                    Ok(CachedContent { name }) => {
+                        let expires_at = chrono::TimeDelta::from_std(self.cache_ttl)
+                            .ok()
+                            .and_then(|ttl| now.checked_add_signed(ttl - CACHE_EXPIRY_MARGIN))
+                            .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC);
+                        (Some(name), expires_at)
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            target: "rig::completions",
+                            error = %err,
+                            "Failed to create Gemini cached content, sending request uncached"
+                        );
+                        (None, now + FAILED_CACHE_RETRY)
+                    }
+                };
+
+                let mut cached_contents = self.cached_contents.lock().expect("lock poisoned");
+                cached_contents.retain(|_, entry| entry.expires_at > now);
+                cached_contents.insert(
+                    key,
+                    CachedContentEntry {
+                        name: name.clone(),
+                        expires_at,
+                    },
+                );
+
+                match name {
+                    Some(name) => name,
+                    None => return,
+                }
+            }
+        };
+
+        request.cached_content = Some(name);
+        request.contents.drain(..prefix_len);
+        request.system_instruction = None;
+        request.tools = None;
+        request.tool_config = None;
+    }
+
+    async fn create_cached_content(
+        &self,
+        mut body: Value,
+    ) -> Result<CachedContent, CompletionError> {
+        body["ttl"] = format!("{}s", self.cache_ttl.as_secs()).into();
+
+        let request = self
+            .client
+            .post("/v1beta/cachedContents")?
+            .body(serde_json::to_vec(&body)?)
+            .map_err(|e| CompletionError::HttpError(e.into()))?;
+
+        let response = self.client.send::<_, Vec<u8>>(request).await?;
+        if response.status().is_success() {
+            let response_body = response.into_body().await.map_err(CompletionError::from)?;
+            Ok(serde_json::from_slice(&response_body)?)
+        } else {
+            Err(http_client::into_status_error(response).await.into())
         }
     }
 }
@@ -81,12 +244,20 @@ where
         Self::new(client.clone(), model)
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    fn supports_output_schema(&self) -> bool {
+        true
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<GenerateContentResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "generate_content",
                 gen_ai.operation.name = "generate_content",
@@ -95,14 +266,15 @@ where
                 gen_ai.system_instructions = &completion_request.preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
         };
 
-        let request = create_request_body(completion_request)?;
+        let cache_breakpoints = completion_request.cache_breakpoints.clone();
+        let mut request = create_request_body(completion_request)?;
+        self.apply_cache_breakpoints(&cache_breakpoints, &mut request)
+            .await;
 
         if enabled!(Level::TRACE) {
             tracing::trace!(
@@ -126,10 +298,7 @@ where
             let response = self.client.send::<_, Vec<u8>>(request).await?;
 
             if response.status().is_success() {
-                let response_body = response
-                    .into_body()
-                    .await
-                    .map_err(CompletionError::HttpError)?;
+                let response_body = response.into_body().await.map_err(CompletionError::from)?;
 
                 let response_text = String::from_utf8_lossy(&response_body).to_string();
 
@@ -157,15 +326,7 @@ where
 
                 response.try_into()
             } else {
-                let text = String::from_utf8_lossy(
-                    &response
-                        .into_body()
-                        .await
-                        .map_err(CompletionError::HttpError)?,
-                )
-                .into();
-
-                Err(CompletionError::ProviderError(text))
+                Err(http_client::into_status_error(response).await.into())
             }
         }
         .instrument(span)
@@ -183,6 +344,45 @@ where
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CountTokensResponse {
+    total_tokens: u64,
+}
+
+impl<T> RemoteTokenCounter for CompletionModel<T>
+where
+    T: HttpClientExt + Clone + 'static,
+{
+    /// Counts the input tokens of `request` using the
+    /// [countTokens API](https://ai.google.dev/api/tokens).
+    async fn count_request_tokens(
+        &self,
+        completion_request: CompletionRequest,
+    ) -> Result<u64, CompletionError> {
+        let mut request = serde_json::to_value(create_request_body(completion_request)?)?;
+        request["model"] = format!("models/{}", self.model).into();
+        let body = serde_json::json!({ "generateContentRequest": request });
+
+        let path = format!("/v1beta/models/{}:countTokens", self.model);
+
+        let request = self
+            .client
+            .post(path.as_str())?
+            .body(serde_json::to_vec(&body)?)
+            .map_err(|e| CompletionError::HttpError(e.into()))?;
+
+        let response = self.client.send::<_, Vec<u8>>(request).await?;
+        if response.status().is_success() {
+            let response_body = response.into_body().await.map_err(CompletionError::from)?;
+            let response: CountTokensResponse = serde_json::from_slice(&response_body)?;
+            Ok(response.total_tokens)
+        } else {
+            Err(http_client::into_status_error(response).await.into())
+        }
+    }
+}
+
 pub(crate) fn create_request_body(
     completion_request: CompletionRequest,
 ) -> Result<GenerateContentRequest, CompletionError> {
@@ -198,6 +398,13 @@ pub(crate) fn create_request_body(
         additional_params,
     } = serde_json::from_value::<AdditionalParameters>(additional_params)?;
 
+    if let Some(schema) = completion_request.output_schema {
+        let cfg = generation_config.get_or_insert_with(GenerationConfig::default);
+        cfg.response_mime_type = Some("application/json".to_string());
+        cfg.response_schema = None;
+        cfg.response_json_schema = Some(schema.to_value());
+    }
+
     generation_config = generation_config.map(|mut cfg| {
         if let Some(temp) = completion_request.temperature {
             cfg.temperature = Some(temp);
@@ -242,6 +449,7 @@ pub(crate) fn create_request_body(
         tools,
         tool_config,
         system_instruction,
+        cached_content: None,
         additional_params,
     };
 
@@ -401,10 +609,24 @@ impl TryFrom<GenerateContentResponse> for completion::CompletionResponse<Generat
         let usage = response
             .usage_metadata
             .as_ref()
-            .map(|usage| completion::Usage {
-                input_tokens: usage.prompt_token_count as u64,
-                output_tokens: usage.candidates_token_count.unwrap_or(0) as u64,
-                total_tokens: usage.total_token_count as u64,
+            .map(|usage| {
+                let mut total = completion::Usage {
+                    input_tokens: usage.prompt_token_count as u64,
+                    // Thinking tokens are billed as output, on top of the candidates.
+                    output_tokens: (usage.candidates_token_count.unwrap_or_default()
+                        + usage.thoughts_token_count.unwrap_or_default())
+                        as u64,
+                    total_tokens: usage.total_token_count as u64,
+                    ..Default::default()
+                };
+                gemini_api_types::record_usage_details(
+                    &mut total,
+                    usage.cached_content_token_count,
+                    usage.thoughts_token_count,
+                    &usage.prompt_tokens_details,
+                    &usage.candidates_tokens_details,
+                );
+                total
             })
             .unwrap_or_default();
 
@@ -572,13 +794,28 @@ pub mod gemini_api_types {
 
         fn try_from(msg: message::Message) -> Result<Self, Self::Error> {
             Ok(match msg {
-                message::Message::User { content } => Content {
-                    parts: content
-                        .into_iter()
-                        .map(|c| c.try_into())
-                        .collect::<Result<Vec<_>, _>>()?,
-                    role: Some(Role::User),
-                },
+                message::Message::User { content } => {
+                    // Function responses only carry text: the images and documents returned by
+                    // tools follow the function responses as parts of the same user turn
+                    let mut parts = Vec::new();
+                    let mut tool_result_media = Vec::new();
+                    for content in content {
+                        match content {
+                            message::UserContent::ToolResult(tool_result) => {
+                                let (part, media) = split_tool_result(tool_result)?;
+                                parts.push(part);
+                                tool_result_media.extend(media);
+                            }
+                            content => parts.push(content.try_into()?),
+                        }
+                    }
+                    parts.extend(tool_result_media);
+
+                    Content {
+                        parts,
+                        role: Some(Role::User),
+                    }
+                }
                 message::Message::Assistant { content, .. } => Content {
                     role: Some(Role::Model),
                     parts: content
@@ -690,6 +927,47 @@ pub mod gemini_api_types {
         }
     }
 
+    /// Split a tool result into a function response holding its text parts, and a part for each
+    /// of its images and documents.
+    fn split_tool_result(
+        message::ToolResult { id, content, .. }: message::ToolResult,
+    ) -> Result<(Part, Vec<Part>), message::MessageError> {
+        let mut texts = Vec::new();
+        let mut media = Vec::new();
+        for content in content {
+            match content {
+                message::ToolResultContent::Text(text) => texts.push(text.text),
+                message::ToolResultContent::Image(image) => {
+                    media.push(message::UserContent::Image(image).try_into()?)
+                }
+                message::ToolResultContent::Document(document) => {
+                    media.push(message::UserContent::Document(document).try_into()?)
+                }
+            }
+        }
+
+        let content = texts.join("\n");
+        // Convert to JSON since this value may be a valid JSON value
+        let result: serde_json::Value = serde_json::from_str(&content).unwrap_or_else(|error| {
+            tracing::trace!(
+                ?error,
+                "Tool result is not a valid JSON, treat it as normal string"
+            );
+            json!(content)
+        });
+        let part = Part {
+            thought: Some(false),
+            thought_signature: None,
+            part: PartKind::FunctionResponse(FunctionResponse {
+                name: id,
+                response: Some(json!({ "result": result })),
+            }),
+            additional_params: None,
+        };
+
+        Ok((part, media))
+    }
+
     impl TryFrom<message::UserContent> for Part {
         type Error = message::MessageError;
 
@@ -701,33 +979,15 @@ pub mod gemini_api_types {
                     part: PartKind::Text(text),
                     additional_params: None,
                 }),
-                message::UserContent::ToolResult(message::ToolResult { id, content, .. }) => {
-                    let content = match content.first() {
-                        message::ToolResultContent::Text(text) => text.text,
-                        message::ToolResultContent::Image(_) => {
-                            return Err(message::MessageError::ConversionError(
-                                "Tool result content must be text".to_string(),
-                            ));
-                        }
-                    };
-                    // Convert to JSON since this value may be a valid JSON value
-                    let result: serde_json::Value =
-                        serde_json::from_str(&content).unwrap_or_else(|error| {
-                            tracing::trace!(
-                                ?error,
-                                "Tool result is not a valid JSON, treat it as normal string"
-                            );
-                            json!(content)
-                        });
-                    Ok(Part {
-                        thought: Some(false),
-                        thought_signature: None,
-                        part: PartKind::FunctionResponse(FunctionResponse {
-                            name: id,
-                            response: Some(json!({ "result": result })),
-                        }),
-                        additional_params: None,
-                    })
+                message::UserContent::ToolResult(tool_result) => {
+                    let (part, media) = split_tool_result(tool_result)?;
+                    if !media.is_empty() {
+                        return Err(message::MessageError::ConversionError(
+                            "Tool results with images or documents must be converted as part of a message"
+                                .to_string(),
+                        ));
+                    }
+                    Ok(part)
                 }
                 message::UserContent::Image(message::Image {
                     data, media_type, ..
@@ -1065,6 +1325,58 @@ pub mod gemini_api_types {
         pub total_token_count: i32,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub thoughts_token_count: Option<i32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub prompt_tokens_details: Vec<ModalityTokenCount>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub candidates_tokens_details: Vec<ModalityTokenCount>,
+    }
+
+    /// Represents token counting info for a single modality.
+    #[derive(Debug, Deserialize, Clone, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ModalityTokenCount {
+        pub modality: Modality,
+        #[serde(default)]
+        pub token_count: i32,
+    }
+
+    /// Content part modality.
+    #[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Serialize)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    pub enum Modality {
+        Text,
+        Image,
+        Video,
+        Audio,
+        Document,
+        #[serde(other)]
+        ModalityUnspecified,
+    }
+
+    fn modality_token_count(details: &[ModalityTokenCount], modality: Modality) -> u64 {
+        details
+            .iter()
+            .filter(|detail| detail.modality == modality)
+            .map(|detail| detail.token_count as u64)
+            .sum()
+    }
+
+    /// Fills in the cache, reasoning and per-modality breakdown of a Gemini usage report.
+    pub(crate) fn record_usage_details(
+        usage: &mut crate::completion::Usage,
+        cached_content_token_count: Option<i32>,
+        thoughts_token_count: Option<i32>,
+        prompt_tokens_details: &[ModalityTokenCount],
+        candidates_tokens_details: &[ModalityTokenCount],
+    ) {
+        usage.cached_input_tokens = cached_content_token_count.unwrap_or_default() as u64;
+        usage.reasoning_tokens = thoughts_token_count.unwrap_or_default() as u64;
+        usage.audio_input_tokens = modality_token_count(prompt_tokens_details, Modality::Audio);
+        usage.audio_output_tokens =
+            modality_token_count(candidates_tokens_details, Modality::Audio);
+        usage.image_input_tokens = modality_token_count(prompt_tokens_details, Modality::Image);
+        usage.image_output_tokens =
+            modality_token_count(candidates_tokens_details, Modality::Image);
     }
 
     impl std::fmt::Display for UsageMetadata {
@@ -1096,6 +1408,13 @@ pub mod gemini_api_types {
                 + self.thoughts_token_count.unwrap_or_default())
                 as u64;
             usage.total_tokens = usage.input_tokens + usage.output_tokens;
+            record_usage_details(
+                &mut usage,
+                self.cached_content_token_count,
+                self.thoughts_token_count,
+                &self.prompt_tokens_details,
+                &self.candidates_tokens_details,
+            );
 
             Some(usage)
         }
@@ -1652,7 +1971,9 @@ pub mod gemini_api_types {
         /// Optional. Developer set system instruction(s). Currently, text only.
         /// From [Gemini API Reference](https://ai.google.dev/gemini-api/docs/system-instructions?lang=rest)
         pub system_instruction: Option<Content>,
-        // cachedContent: Optional<String>
+        /// Optional. The name of the cached content to use as context, in the form `cachedContents/{id}`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub cached_content: Option<String>,
         /// Additional parameters.
         #[serde(flatten, skip_serializing_if = "Option::is_none")]
         pub additional_params: Option<serde_json::Value>,
@@ -1734,6 +2055,7 @@ pub mod gemini_api_types {
 
 #[cfg(test)]
 mod tests {
+    use crate::http_client::mock::MockHttpClient;
     use crate::{message, providers::gemini::completion::gemini_api_types::flatten_schema};
 
     use super::*;
@@ -1946,6 +2268,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_message_conversion_multi_part_tool_result() {
+        let msg = message::Message::User {
+            content: OneOrMany::one(message::UserContent::tool_result(
+                "get_chart",
+                OneOrMany::many(vec![
+                    message::ToolResultContent::text("Revenue by month"),
+                    message::ToolResultContent::image_base64(
+                        "aGVsbG8=",
+                        Some(message::ImageMediaType::PNG),
+                        None,
+                    ),
+                    message::ToolResultContent::text("Q3 was the best quarter"),
+                ])
+                .unwrap(),
+            )),
+        };
+
+        let content: Content = msg.try_into().unwrap();
+        assert_eq!(
+            serde_json::to_value(&content).unwrap(),
+            json!({
+                "parts": [
+                    {
+                        "thought": false,
+                        "functionResponse": {
+                            "name": "get_chart",
+                            "response": {
+                                "result": "Revenue by month\nQ3 was the best quarter"
+                            }
+                        }
+                    },
+                    {
+                        "thought": false,
+                        "inlineData": { "mimeType": "image/png", "data": "aGVsbG8=" }
+                    }
+                ],
+                "role": "user"
+            })
+        );
+    }
+
     #[test]
     fn test_vec_schema_conversion() {
         let schema_with_ref = json!({
@@ -2063,4 +2427,175 @@ mod tests {
             assert!(items.properties.is_some());
         }
     }
+
+    #[test]
+    fn test_output_schema_request_body() {
+        let request = CompletionRequest {
+            preamble: None,
+            chat_history: crate::OneOrMany::one(message::Message::user("Tell me about Ferris")),
+            documents: vec![],
+            tools: vec![],
+            temperature: Some(0.2),
+            max_tokens: None,
+            tool_choice: None,
+            additional_params: None,
+            output_schema: Some(schemars::json_schema!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" }
+                }
+            })),
+            cache_breakpoints: vec![],
+        };
+
+        let body = create_request_body(request).unwrap();
+        let cfg = body
+            .generation_config
+            .expect("the output schema should create a generation config");
+
+        assert_eq!(cfg.response_mime_type.as_deref(), Some("application/json"));
+        assert_eq!(
+            cfg.response_json_schema,
+            Some(json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" }
+                }
+            }))
+        );
+        assert_eq!(cfg.temperature, Some(0.2));
+    }
+
+    #[test]
+    fn test_response_usage_includes_thoughts() {
+        let response: GenerateContentResponse = serde_json::from_value(json!({
+            "responseId": "resp_1",
+            "candidates": [{
+                "content": { "role": "model", "parts": [{ "text": "Hello" }] },
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {
+                "promptTokenCount": 12,
+                "candidatesTokenCount": 5,
+                "thoughtsTokenCount": 20,
+                "totalTokenCount": 37
+            }
+        }))
+        .unwrap();
+
+        let response: completion::CompletionResponse<GenerateContentResponse> =
+            response.try_into().unwrap();
+        assert_eq!(response.usage.input_tokens, 12);
+        assert_eq!(response.usage.output_tokens, 25);
+        assert_eq!(response.usage.reasoning_tokens, 20);
+        assert_eq!(response.usage.total_tokens, 37);
+    }
+
+    fn cached_request() -> GenerateContentRequest {
+        create_request_body(cached_request_params()).unwrap()
+    }
+
+    fn cached_request_params() -> CompletionRequest {
+        CompletionRequest {
+            preamble: Some("You are a helpful assistant.".to_string()),
+            chat_history: crate::OneOrMany::many(vec![
+                message::Message::user("First message"),
+                message::Message::assistant("Response"),
+                message::Message::user("Second message"),
+            ])
+            .unwrap(),
+            documents: vec![],
+            tools: vec![],
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            additional_params: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
+        }
+    }
+
+    fn cached_model(http_client: MockHttpClient) -> CompletionModel<MockHttpClient> {
+        let client = Client::<MockHttpClient>::builder()
+            .api_key("key")
+            .http_client(http_client)
+            .build()
+            .unwrap();
+        CompletionModel::new(client, GEMINI_2_5_FLASH)
+    }
+
+    #[tokio::test]
+    async fn test_cache_breakpoints_reuse_cached_content() {
+        let http_client =
+            MockHttpClient::default().respond(200, r#"{"name": "cachedContents/abc"}"#);
+        let model = cached_model(http_client.clone());
+        let breakpoints = [CacheBreakpoint::Preamble, CacheBreakpoint::Message(1)];
+
+        for _ in 0..2 {
+            let mut request = cached_request();
+            model
+                .apply_cache_breakpoints(&breakpoints, &mut request)
+                .await;
+
+            assert_eq!(
+                request.cached_content.as_deref(),
+                Some("cachedContents/abc")
+            );
+            assert!(request.system_instruction.is_none());
+            assert_eq!(request.contents.len(), 1);
+        }
+
+        // The second request reuses the cached content
+        assert_eq!(http_client.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_breakpoints_remember_failures() {
+        let http_client = MockHttpClient::default().respond(400, "content is too small");
+        let model = cached_model(http_client.clone());
+
+        for _ in 0..2 {
+            let mut request = cached_request();
+            model
+                .apply_cache_breakpoints(&[CacheBreakpoint::Preamble], &mut request)
+                .await;
+
+            assert!(request.cached_content.is_none());
+            assert!(request.system_instruction.is_some());
+            assert_eq!(request.contents.len(), 3);
+        }
+
+        // Creating the cached content is not retried on every request
+        assert_eq!(http_client.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_count_request_tokens() {
+        use crate::tokens::RemoteTokenCounter;
+
+        let http_client = MockHttpClient::default().respond(200, r#"{"totalTokens": 42}"#);
+        let model = cached_model(http_client.clone());
+
+        let mut request = cached_request_params();
+        request.chat_history = crate::OneOrMany::one(message::Message::user("Hello!"));
+        assert_eq!(model.count_request_tokens(request).await.unwrap(), 42);
+
+        let requests = http_client.requests();
+        assert_eq!(requests.len(), 1);
+        let (uri, body) = &requests[0];
+        assert_eq!(
+            uri,
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:countTokens?key=key"
+        );
+
+        let body: Value = serde_json::from_slice(body).unwrap();
+        let request = &body["generateContentRequest"];
+        assert_eq!(request["model"], "models/gemini-2.5-flash");
+        assert_eq!(request["contents"][0]["role"], "user");
+        assert_eq!(request["contents"][0]["parts"][0]["text"], "Hello!");
+        assert_eq!(
+            request["systemInstruction"]["parts"][0]["text"],
+            "You are a helpful assistant."
+        );
+    }
 }
@@ -1,16 +1,18 @@
 use async_stream::stream;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tracing::{Level, enabled, info_span};
+use tracing::{Level, enabled};
 use tracing_futures::Instrument;
 
-use super::completion::gemini_api_types::{ContentCandidate, Part, PartKind};
+use super::completion::gemini_api_types::{
+    ContentCandidate, ModalityTokenCount, Part, PartKind, record_usage_details,
+};
 use super::completion::{CompletionModel, create_request_body};
 use crate::completion::{CompletionError, CompletionRequest, GetTokenUsage};
 use crate::http_client::HttpClientExt;
 use crate::http_client::sse::{Event, GenericEventSource};
 use crate::streaming;
-use crate::telemetry::SpanCombinator;
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 
 #[derive(Debug, Deserialize, Serialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -23,6 +25,10 @@ pub struct PartialUsage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thoughts_token_count: Option<i32>,
     pub prompt_token_count: i32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompt_tokens_details: Vec<ModalityTokenCount>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates_tokens_details: Vec<ModalityTokenCount>,
 }
 
 impl GetTokenUsage for PartialUsage {
@@ -34,6 +40,13 @@ impl GetTokenUsage for PartialUsage {
             + self.candidates_token_count.unwrap_or_default()
             + self.thoughts_token_count.unwrap_or_default()) as u64;
         usage.total_tokens = usage.input_tokens + usage.output_tokens;
+        record_usage_details(
+            &mut usage,
+            self.cached_content_token_count,
+            self.thoughts_token_count,
+            &self.prompt_tokens_details,
+            &self.candidates_tokens_details,
+        );
 
         Some(usage)
     }
@@ -57,12 +70,20 @@ impl GetTokenUsage for StreamingCompletionResponse {
     fn token_usage(&self) -> Option<crate::completion::Usage> {
         let mut usage = crate::completion::Usage::new();
         usage.total_tokens = self.usage_metadata.total_token_count as u64;
-        usage.output_tokens = self
+        usage.output_tokens = (self
             .usage_metadata
             .candidates_token_count
-            .map(|x| x as u64)
-            .unwrap_or(0);
+            .unwrap_or_default()
+            + self.usage_metadata.thoughts_token_count.unwrap_or_default())
+            as u64;
         usage.input_tokens = self.usage_metadata.prompt_token_count as u64;
+        record_usage_details(
+            &mut usage,
+            self.usage_metadata.cached_content_token_count,
+            self.usage_metadata.thoughts_token_count,
+            &self.usage_metadata.prompt_tokens_details,
+            &self.usage_metadata.candidates_tokens_details,
+        );
         Some(usage)
     }
 }
@@ -77,7 +98,7 @@ where
     ) -> Result<streaming::StreamingCompletionResponse<StreamingCompletionResponse>, CompletionError>
     {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -86,13 +107,14 @@ where
                 gen_ai.system_instructions = &completion_request.preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = self.model,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
         };
-        let request = create_request_body(completion_request)?;
+        let cache_breakpoints = completion_request.cache_breakpoints.clone();
+        let mut request = create_request_body(completion_request)?;
+        self.apply_cache_breakpoints(&cache_breakpoints, &mut request)
+            .await;
 
         if enabled!(Level::TRACE) {
             tracing::trace!(
@@ -200,7 +222,7 @@ where
                     }
                     Err(error) => {
                         tracing::error!(?error, "SSE error");
-                        yield Err(CompletionError::ProviderError(error.to_string()));
+                        yield Err(error.into());
                         break;
                     }
                 }
@@ -495,12 +517,38 @@ mod tests {
             candidates_token_count: Some(30),
             thoughts_token_count: Some(10),
             prompt_token_count: 40,
+            ..Default::default()
         };
 
         let token_usage = usage.token_usage().unwrap();
         assert_eq!(token_usage.input_tokens, 40);
         assert_eq!(token_usage.output_tokens, 60); // 20 + 30 + 10
         assert_eq!(token_usage.total_tokens, 100);
+        assert_eq!(token_usage.cached_input_tokens, 20);
+        assert_eq!(token_usage.reasoning_tokens, 10);
+    }
+
+    #[test]
+    fn test_partial_usage_modality_details() {
+        let usage: PartialUsage = serde_json::from_value(json!({
+            "promptTokenCount": 300,
+            "candidatesTokenCount": 40,
+            "totalTokenCount": 340,
+            "promptTokensDetails": [
+                { "modality": "TEXT", "tokenCount": 42 },
+                { "modality": "IMAGE", "tokenCount": 258 }
+            ],
+            "candidatesTokensDetails": [
+                { "modality": "AUDIO", "tokenCount": 40 }
+            ]
+        }))
+        .unwrap();
+
+        let token_usage = usage.token_usage().unwrap();
+        assert_eq!(token_usage.input_tokens, 300);
+        assert_eq!(token_usage.image_input_tokens, 258);
+        assert_eq!(token_usage.audio_input_tokens, 0);
+        assert_eq!(token_usage.audio_output_tokens, 40);
     }
 
     #[test]
@@ -511,6 +559,7 @@ mod tests {
             candidates_token_count: Some(30),
             thoughts_token_count: None,
             prompt_token_count: 20,
+            ..Default::default()
         };
 
         let token_usage = usage.token_usage().unwrap();
@@ -528,6 +577,7 @@ mod tests {
                 candidates_token_count: Some(75),
                 thoughts_token_count: None,
                 prompt_token_count: 75,
+                ..Default::default()
             },
         };
 
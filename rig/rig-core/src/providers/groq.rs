@@ -12,7 +12,6 @@ use bytes::Bytes;
 use http::Request;
 use serde_json::Map;
 use std::collections::HashMap;
-use tracing::info_span;
 use tracing_futures::Instrument;
 
 use super::openai::{
@@ -28,6 +27,7 @@ use crate::http_client::sse::{Event, GenericEventSource};
 use crate::http_client::{self, HttpClientExt, MultipartForm};
 use crate::json_utils::empty_or_none;
 use crate::providers::openai::{AssistantContent, Function, ToolType};
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 use async_stream::stream;
 use futures::StreamExt;
 
@@ -281,12 +281,16 @@ where
         Self::new(client.clone(), model)
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -295,8 +299,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -332,11 +334,7 @@ where
                         span.record("gen_ai.response.id", response.id.clone());
                         span.record("gen_ai.response.model_name", response.model.clone());
                         if let Some(ref usage) = response.usage {
-                            span.record("gen_ai.usage.input_tokens", usage.prompt_tokens);
-                            span.record(
-                                "gen_ai.usage.output_tokens",
-                                usage.total_tokens - usage.prompt_tokens,
-                            );
+                            span.record_token_usage(usage);
                         }
 
                         if tracing::enabled!(tracing::Level::TRACE) {
@@ -348,10 +346,15 @@ where
 
                         response.try_into()
                     }
-                    ApiResponse::Err(err) => Err(CompletionError::ProviderError(err.message)),
+                    ApiResponse::Err(err) => Err(CompletionError::from_error_body(
+                        &response_body,
+                        err.message,
+                    )),
                 }
             } else {
-                Err(CompletionError::ProviderError(
+                Err(CompletionError::from_response(
+                    status,
+                    None,
                     String::from_utf8_lossy(&response_body).to_string(),
                 ))
             }
@@ -368,7 +371,7 @@ where
         CompletionError,
     > {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -377,8 +380,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -542,13 +543,7 @@ pub struct StreamingCompletionResponse {
 
 impl GetTokenUsage for StreamingCompletionResponse {
     fn token_usage(&self) -> Option<crate::completion::Usage> {
-        let mut usage = crate::completion::Usage::new();
-
-        usage.input_tokens = self.usage.prompt_tokens as u64;
-        usage.total_tokens = self.usage.total_tokens as u64;
-        usage.output_tokens = self.usage.total_tokens as u64 - self.usage.prompt_tokens as u64;
-
-        Some(usage)
+        self.usage.token_usage()
     }
 }
 
@@ -568,10 +563,7 @@ where
 
     let stream = stream! {
         let span = tracing::Span::current();
-        let mut final_usage = Usage {
-            prompt_tokens: 0,
-            total_tokens: 0
-        };
+        let mut final_usage = Usage::new();
 
         let mut text_response = String::new();
 
@@ -699,8 +691,7 @@ where
         };
 
         span.record("gen_ai.output.messages", serde_json::to_string(&vec![response_message]).unwrap());
-        span.record("gen_ai.usage.input_tokens", final_usage.prompt_tokens);
-        span.record("gen_ai.usage.output_tokens", final_usage.total_tokens - final_usage.prompt_tokens);
+        span.record_token_usage(&final_usage);
 
         // Final response
         yield Ok(crate::streaming::RawStreamingChoice::FinalResponse(
@@ -2,7 +2,7 @@ use super::client::Client;
 use crate::completion::GetTokenUsage;
 use crate::http_client::HttpClientExt;
 use crate::providers::openai::StreamingCompletionResponse;
-use crate::telemetry::SpanCombinator;
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
@@ -13,7 +13,7 @@ use crate::{
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 use std::{convert::Infallible, str::FromStr};
-use tracing::{Level, enabled, info_span};
+use tracing::{Level, enabled};
 use tracing_futures::Instrument;
 
 #[derive(Debug, Deserialize)]
@@ -602,6 +602,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
             input_tokens: response.usage.prompt_tokens as u64,
             output_tokens: response.usage.completion_tokens as u64,
             total_tokens: response.usage.total_tokens as u64,
+            ..Default::default()
         };
 
         Ok(completion::CompletionResponse {
@@ -702,12 +703,16 @@ where
         Self::new(client.clone(), &model.into())
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -716,8 +721,6 @@ where
                 gen_ai.system_instructions = &completion_request.preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -769,17 +772,16 @@ where
 
                         response.try_into()
                     }
-                    ApiResponse::Err(err) => Err(CompletionError::ProviderError(err.to_string())),
+                    ApiResponse::Err(err) => {
+                        Err(CompletionError::from_error_body(&bytes, err.to_string()))
+                    }
                 }
             } else {
                 let status = response.status();
                 let text: Vec<u8> = response.into_body().await?;
                 let text: String = String::from_utf8_lossy(&text).into();
 
-                Err(CompletionError::ProviderError(format!(
-                    "{}: {}",
-                    status, text
-                )))
+                Err(CompletionError::from_response(status, None, text))
             }
         }
         .instrument(span)
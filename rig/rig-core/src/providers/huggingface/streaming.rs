@@ -5,7 +5,8 @@ use crate::json_utils::{self};
 use crate::providers::huggingface::completion::HuggingfaceCompletionRequest;
 use crate::providers::openai::{StreamingCompletionResponse, send_compatible_streaming_request};
 use crate::streaming;
-use tracing::{Instrument, info_span};
+use crate::telemetry::gen_ai_span;
+use tracing::Instrument;
 
 impl<T> CompletionModel<T>
 where
@@ -48,7 +49,7 @@ where
             .map_err(|e| CompletionError::HttpError(e.into()))?;
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
             target: "rig::completions",
             "chat",
             gen_ai.operation.name = "chat",
@@ -56,8 +57,6 @@ where
             gen_ai.request.model = self.model,
             gen_ai.response.id = tracing::field::Empty,
             gen_ai.response.model = self.model,
-            gen_ai.usage.output_tokens = tracing::field::Empty,
-            gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
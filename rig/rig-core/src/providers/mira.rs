@@ -16,6 +16,7 @@ use crate::message::{Document, DocumentSourceKind};
 use crate::providers::openai;
 use crate::providers::openai::send_compatible_streaming_request;
 use crate::streaming::StreamingCompletionResponse;
+use crate::telemetry::gen_ai_span;
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
@@ -24,7 +25,7 @@ use crate::{
 use serde::{Deserialize, Serialize};
 use std::string::FromUtf8Error;
 use thiserror::Error;
-use tracing::{self, Instrument, info_span};
+use tracing::{self, Instrument};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct MiraExt;
@@ -320,12 +321,16 @@ where
         Self::new(client.clone(), model)
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -334,8 +339,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -386,11 +389,11 @@ where
             let response_body = response.into_body().into_future().await?.to_vec();
 
             if !status.is_success() {
-                let status = status.as_u16();
-                let error_text = String::from_utf8_lossy(&response_body).to_string();
-                return Err(CompletionError::ProviderError(format!(
-                    "API error: {status} - {error_text}"
-                )));
+                return Err(CompletionError::from_response(
+                    status,
+                    None,
+                    String::from_utf8_lossy(&response_body).to_string(),
+                ));
             }
 
             let response: CompletionResponse = serde_json::from_slice(&response_body)?;
@@ -429,7 +432,7 @@ where
         completion_request: CompletionRequest,
     ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -438,8 +441,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -508,6 +509,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                         input_tokens: usage.prompt_tokens as u64,
                         output_tokens: (usage.total_tokens - usage.prompt_tokens) as u64,
                         total_tokens: usage.total_tokens as u64,
+                        ..Default::default()
                     })
                     .unwrap_or_default();
 
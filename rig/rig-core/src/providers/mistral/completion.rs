@@ -1,12 +1,13 @@
 use async_stream::stream;
 use serde::{Deserialize, Serialize};
 use std::{convert::Infallible, str::FromStr};
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 
 use super::client::{Client, Usage};
 use crate::completion::GetTokenUsage;
 use crate::http_client::{self, HttpClientExt};
 use crate::streaming::{RawStreamingChoice, RawStreamingToolCall, StreamingCompletionResponse};
+use crate::telemetry::gen_ai_span;
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
@@ -126,13 +127,23 @@ impl TryFrom<message::Message> for Vec<Message> {
                             content: tool_content,
                         }) => {
                             let call_id_key = call_id.unwrap_or_else(|| id.clone());
+                            // Mistral only accepts text: images and binary documents are
+                            // replaced by a placeholder
                             let content_text = tool_content
                                 .into_iter()
-                                .find_map(|content_item| match content_item {
-                                    message::ToolResultContent::Text(text) => Some(text.text),
-                                    message::ToolResultContent::Image(_) => None,
+                                .map(|content_item| match content_item {
+                                    message::ToolResultContent::Text(text) => text.text,
+                                    message::ToolResultContent::Image(_) => String::from("[Image]"),
+                                    message::ToolResultContent::Document(message::Document {
+                                        data: message::DocumentSourceKind::String(text),
+                                        ..
+                                    }) => text,
+                                    message::ToolResultContent::Document(_) => {
+                                        String::from("[Document]")
+                                    }
                                 })
-                                .unwrap_or_default();
+                                .collect::<Vec<_>>()
+                                .join("\n");
                             tool_result_messages.push(Message::Tool {
                                 name: id,
                                 content: content_text,
@@ -515,6 +526,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                 input_tokens: usage.prompt_tokens as u64,
                 output_tokens: (usage.total_tokens - usage.prompt_tokens) as u64,
                 total_tokens: usage.total_tokens as u64,
+                ..Default::default()
             })
             .unwrap_or_default();
 
@@ -539,6 +551,10 @@ where
         Self::new(client.clone(), model.into())
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
@@ -556,7 +572,7 @@ where
         }
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -565,8 +581,6 @@ where
                 gen_ai.system_instructions = &preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -592,11 +606,12 @@ where
                         span.record_response_metadata(&response);
                         response.try_into()
                     }
-                    ApiResponse::Err(err) => Err(CompletionError::ProviderError(err.message)),
+                    ApiResponse::Err(err) => {
+                        Err(CompletionError::from_error_body(&text, err.message))
+                    }
                 }
             } else {
-                let text = http_client::text(response).await?;
-                Err(CompletionError::ProviderError(text))
+                Err(http_client::into_status_error(response).await.into())
             }
         }
         .instrument(span)
@@ -45,6 +45,7 @@ use crate::completion::{GetTokenUsage, Usage};
 use crate::http_client::{self, HttpClientExt};
 use crate::message::DocumentSourceKind;
 use crate::streaming::RawStreamingChoice;
+use crate::telemetry::gen_ai_span;
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
@@ -59,7 +60,6 @@ use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::{convert::TryFrom, str::FromStr};
-use tracing::info_span;
 use tracing_futures::Instrument;
 // ---------- Main Client ----------
 
@@ -342,6 +342,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                         input_tokens: prompt_tokens,
                         output_tokens: completion_tokens,
                         total_tokens: prompt_tokens + completion_tokens,
+                        ..Default::default()
                     },
                     raw_response,
                 })
@@ -365,6 +366,8 @@ pub(super) struct OllamaCompletionRequest {
     think: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
     options: serde_json::Value,
 }
 
@@ -426,6 +429,7 @@ impl TryFrom<(&str, CompletionRequest)> for OllamaCompletionRequest {
                 .into_iter()
                 .map(ToolDefinition::from)
                 .collect::<Vec<_>>(),
+            format: req.output_schema.map(|schema| schema.to_value()),
             options,
         })
     }
@@ -485,12 +489,20 @@ where
         Self::new(client.clone(), model.into().as_str())
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    fn supports_output_schema(&self) -> bool {
+        true
+    }
+
     async fn completion(
         &self,
         completion_request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -499,8 +511,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -530,7 +540,9 @@ where
             let response_body = response.into_body().into_future().await?.to_vec();
 
             if !status.is_success() {
-                return Err(CompletionError::ProviderError(
+                return Err(CompletionError::from_response(
+                    status,
+                    None,
                     String::from_utf8_lossy(&response_body).to_string(),
                 ));
             }
@@ -569,7 +581,7 @@ where
     ) -> Result<streaming::StreamingCompletionResponse<Self::StreamingResponse>, CompletionError>
     {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -578,8 +590,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = self.model,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -610,9 +620,15 @@ where
         let mut byte_stream = response.into_body();
 
         if !status.is_success() {
-            return Err(CompletionError::ProviderError(format!(
-                "Got error status code trying to send a request to Ollama: {status}"
-            )));
+            let mut body = Vec::new();
+            while let Some(chunk) = byte_stream.next().await {
+                body.extend_from_slice(&chunk.map_err(|e| http_client::Error::Instance(e.into()))?);
+            }
+            return Err(CompletionError::from_response(
+                status,
+                None,
+                String::from_utf8_lossy(&body).into_owned(),
+            ));
         }
 
         let stream = try_stream! {
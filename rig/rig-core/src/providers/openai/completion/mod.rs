@@ -13,13 +13,13 @@ use crate::completion::{
 use crate::http_client::{self, HttpClientExt};
 use crate::message::{AudioMediaType, DocumentSourceKind, ImageDetail, MimeType};
 use crate::one_or_many::string_or_one_or_many;
-use crate::telemetry::{ProviderResponseExt, SpanCombinator};
+use crate::telemetry::{ProviderResponseExt, SpanCombinator, gen_ai_span};
 use crate::wasm_compat::{WasmCompatSend, WasmCompatSync};
 use crate::{OneOrMany, completion, json_utils, message};
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 use std::fmt;
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 
 use std::str::FromStr;
 
@@ -803,11 +803,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         let usage = response
             .usage
             .as_ref()
-            .map(|usage| completion::Usage {
-                input_tokens: usage.prompt_tokens as u64,
-                output_tokens: (usage.total_tokens - usage.prompt_tokens) as u64,
-                total_tokens: usage.total_tokens as u64,
-            })
+            .and_then(GetTokenUsage::token_usage)
             .unwrap_or_default();
 
         Ok(completion::CompletionResponse {
@@ -863,6 +859,10 @@ pub struct Choice {
 pub struct Usage {
     pub prompt_tokens: usize,
     pub total_tokens: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<PromptTokensDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens_details: Option<CompletionTokensDetails>,
 }
 
 impl Usage {
@@ -870,6 +870,8 @@ impl Usage {
         Self {
             prompt_tokens: 0,
             total_tokens: 0,
+            prompt_tokens_details: None,
+            completion_tokens_details: None,
         }
     }
 }
@@ -880,11 +882,48 @@ impl Default for Usage {
     }
 }
 
+/// Breakdown of the tokens used in a prompt.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PromptTokensDetails {
+    /// Tokens read from the prompt cache.
+    #[serde(default)]
+    pub cached_tokens: Option<u64>,
+    /// Audio input tokens present in the prompt.
+    #[serde(default)]
+    pub audio_tokens: Option<u64>,
+}
+
+impl PromptTokensDetails {
+    pub(crate) fn record(&self, usage: &mut crate::completion::Usage) {
+        usage.cached_input_tokens = self.cached_tokens.unwrap_or_default();
+        usage.audio_input_tokens = self.audio_tokens.unwrap_or_default();
+    }
+}
+
+/// Breakdown of the tokens used in a completion.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CompletionTokensDetails {
+    /// Tokens generated by the model for reasoning.
+    #[serde(default)]
+    pub reasoning_tokens: Option<u64>,
+    /// Audio output tokens generated by the model.
+    #[serde(default)]
+    pub audio_tokens: Option<u64>,
+}
+
+impl CompletionTokensDetails {
+    pub(crate) fn record(&self, usage: &mut crate::completion::Usage) {
+        usage.reasoning_tokens = self.reasoning_tokens.unwrap_or_default();
+        usage.audio_output_tokens = self.audio_tokens.unwrap_or_default();
+    }
+}
+
 impl fmt::Display for Usage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let Usage {
             prompt_tokens,
             total_tokens,
+            ..
         } = self;
         write!(
             f,
@@ -900,6 +939,13 @@ impl GetTokenUsage for Usage {
         usage.output_tokens = (self.total_tokens - self.prompt_tokens) as u64;
         usage.total_tokens = self.total_tokens as u64;
 
+        if let Some(details) = &self.prompt_tokens_details {
+            details.record(&mut usage);
+        }
+        if let Some(details) = &self.completion_tokens_details {
+            details.record(&mut usage);
+        }
+
         Some(usage)
     }
 }
@@ -963,10 +1009,21 @@ pub struct CompletionRequest {
     tool_choice: Option<ToolChoice>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
     #[serde(flatten)]
     additional_params: Option<serde_json::Value>,
 }
 
+/// The format the model must output. Used for structured outputs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    JsonSchema {
+        json_schema: super::responses_api::StructuredOutputsInput,
+    },
+}
+
 pub struct OpenAIRequestParams {
     pub model: String,
     pub request: CoreCompletionRequest,
@@ -996,6 +1053,7 @@ impl TryFrom<OpenAIRequestParams> for CompletionRequest {
             temperature,
             additional_params,
             tool_choice,
+            output_schema,
             ..
         } = req;
 
@@ -1038,6 +1096,9 @@ impl TryFrom<OpenAIRequestParams> for CompletionRequest {
             tools,
             tool_choice,
             temperature,
+            response_format: output_schema.map(|schema| ResponseFormat::JsonSchema {
+                json_schema: super::output_schema_input(schema),
+            }),
             additional_params,
         };
 
@@ -1121,12 +1182,20 @@ where
         Self::new(client.clone(), model)
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    fn supports_output_schema(&self) -> bool {
+        true
+    }
+
     async fn completion(
         &self,
         completion_request: CoreCompletionRequest,
     ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -1135,8 +1204,6 @@ where
                 gen_ai.system_instructions = &completion_request.preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -1187,11 +1254,12 @@ where
 
                         response.try_into()
                     }
-                    ApiResponse::Err(err) => Err(CompletionError::ProviderError(err.message)),
+                    ApiResponse::Err(err) => {
+                        Err(CompletionError::from_error_body(&text, err.message))
+                    }
                 }
             } else {
-                let text = http_client::text(response).await?;
-                Err(CompletionError::ProviderError(text))
+                Err(http_client::into_status_error(response).await.into())
             }
         }
         .instrument(span)
@@ -5,7 +5,7 @@ use futures::StreamExt;
 use http::Request;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::{Level, enabled, info_span};
+use tracing::{Level, enabled};
 use tracing_futures::Instrument;
 
 use crate::completion::{CompletionError, CompletionRequest, GetTokenUsage};
@@ -15,6 +15,7 @@ use crate::json_utils::{self, merge};
 use crate::message::{ToolCall, ToolFunction};
 use crate::providers::openai::completion::{self, CompletionModel, OpenAIRequestParams, Usage};
 use crate::streaming::{self, RawStreamingChoice};
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 
 // ================================================================
 // OpenAI Completion Streaming API
@@ -70,11 +71,7 @@ pub struct StreamingCompletionResponse {
 
 impl GetTokenUsage for StreamingCompletionResponse {
     fn token_usage(&self) -> Option<crate::completion::Usage> {
-        let mut usage = crate::completion::Usage::new();
-        usage.input_tokens = self.usage.prompt_tokens as u64;
-        usage.output_tokens = self.usage.total_tokens as u64 - self.usage.prompt_tokens as u64;
-        usage.total_tokens = self.usage.total_tokens as u64;
-        Some(usage)
+        self.usage.token_usage()
     }
 }
 
@@ -119,7 +116,7 @@ where
             .map_err(|e| CompletionError::HttpError(e.into()))?;
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -127,8 +124,6 @@ where
                 gen_ai.request.model = self.model,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = self.model,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
                 gen_ai.input.messages = request_messages,
                 gen_ai.output.messages = tracing::field::Empty,
             )
@@ -286,7 +281,7 @@ where
                 }
                 Err(error) => {
                     tracing::error!(?error, "SSE error");
-                    yield Err(CompletionError::ProviderError(error.to_string()));
+                    yield Err(error.into());
                     break;
                 }
             }
@@ -309,8 +304,7 @@ where
 
         let final_usage = final_usage.unwrap_or_default();
         if !span.is_disabled() {
-            span.record("gen_ai.usage.input_tokens", final_usage.prompt_tokens);
-            span.record("gen_ai.usage.output_tokens", final_usage.total_tokens - final_usage.prompt_tokens);
+            span.record_token_usage(&final_usage);
         }
 
         yield Ok(RawStreamingChoice::FinalResponse(StreamingCompletionResponse {
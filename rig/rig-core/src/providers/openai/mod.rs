@@ -29,6 +29,39 @@ pub use client::*;
 pub use completion::*;
 pub use embedding::*;
 
+/// Returns the structured output schema for an output schema set on a [`crate::completion::CompletionRequest`],
+/// named after its title (OpenAI requires schema names to match `^[a-zA-Z0-9_-]+$`).
+pub(crate) fn output_schema_input(
+    schema: schemars::Schema,
+) -> responses_api::StructuredOutputsInput {
+    let name = schema
+        .get("title")
+        .and_then(|title| title.as_str())
+        .map(|title| {
+            title
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '-' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>()
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "response".to_string());
+
+    let mut schema = schema.to_value();
+    sanitize_schema(&mut schema);
+
+    responses_api::StructuredOutputsInput {
+        name,
+        schema,
+        strict: true,
+    }
+}
+
 /// Recursively ensures all object schemas in a JSON schema respect OpenAI structured output restrictions.
 /// Nested arrays, schema $defs, object properties and enums should be handled through this method
 pub(crate) fn sanitize_schema(schema: &mut serde_json::Value) {
@@ -10,7 +10,7 @@
 use super::completion::ToolChoice;
 use super::{Client, responses_api::streaming::StreamingCompletionResponse};
 use super::{InputAudio, SystemContent};
-use crate::completion::CompletionError;
+use crate::completion::{CompletionError, GetTokenUsage};
 use crate::http_client;
 use crate::http_client::HttpClientExt;
 use crate::json_utils;
@@ -19,12 +19,13 @@ use crate::message::{
     MimeType, Text,
 };
 use crate::one_or_many::string_or_one_or_many;
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 
 use crate::wasm_compat::{WasmCompatSend, WasmCompatSync};
 use crate::{OneOrMany, completion, message};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 
 use std::convert::Infallible;
 use std::ops::Add;
@@ -483,6 +484,23 @@ impl ResponsesUsage {
     }
 }
 
+impl GetTokenUsage for ResponsesUsage {
+    fn token_usage(&self) -> Option<crate::completion::Usage> {
+        let mut usage = crate::completion::Usage::new();
+        usage.input_tokens = self.input_tokens;
+        usage.output_tokens = self.output_tokens;
+        usage.total_tokens = self.total_tokens;
+        usage.cached_input_tokens = self
+            .input_tokens_details
+            .as_ref()
+            .map(|details| details.cached_tokens)
+            .unwrap_or_default();
+        usage.reasoning_tokens = self.output_tokens_details.reasoning_tokens;
+
+        Some(usage)
+    }
+}
+
 impl Add for ResponsesUsage {
     type Output = Self;
 
@@ -628,13 +646,19 @@ impl TryFrom<(String, crate::completion::CompletionRequest)> for CompletionReque
             .unwrap_or(Value::Null)
             .as_bool();
 
-        let additional_parameters = if let Some(map) = req.additional_params {
+        let mut additional_parameters = if let Some(map) = req.additional_params {
             serde_json::from_value::<AdditionalParameters>(map).expect("Converting additional parameters to AdditionalParameters should never fail as every field is an Option")
         } else {
             // If there's no additional parameters, initialise an empty object
             AdditionalParameters::default()
         };
 
+        if let Some(schema) = req.output_schema {
+            additional_parameters.text = Some(TextConfig {
+                format: TextFormat::JsonSchema(super::output_schema_input(schema)),
+            });
+        }
+
         let tool_choice = req.tool_choice.map(ToolChoice::try_from).transpose()?;
 
         Ok(Self {
@@ -1026,12 +1050,20 @@ where
         Self::new(client.clone(), model)
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    fn supports_output_schema(&self) -> bool {
+        true
+    }
+
     async fn completion(
         &self,
         completion_request: crate::completion::CompletionRequest,
     ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -1039,8 +1071,6 @@ where
                 gen_ai.request.model = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
                 gen_ai.input.messages = tracing::field::Empty,
                 gen_ai.output.messages = tracing::field::Empty,
             )
@@ -1077,8 +1107,7 @@ where
                 span.record("gen_ai.response.id", &response.id);
                 span.record("gen_ai.response.model", &response.model);
                 if let Some(ref usage) = response.usage {
-                    span.record("gen_ai.usage.output_tokens", usage.output_tokens);
-                    span.record("gen_ai.usage.input_tokens", usage.input_tokens);
+                    span.record_token_usage(usage);
                 }
                 if enabled!(Level::TRACE) {
                     tracing::trace!(
@@ -1089,8 +1118,7 @@ where
                 }
                 response.try_into()
             } else {
-                let text = http_client::text(response).await?;
-                Err(CompletionError::ProviderError(text))
+                Err(http_client::into_status_error(response).await.into())
             }
         }
         .instrument(span)
@@ -1134,11 +1162,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         let usage = response
             .usage
             .as_ref()
-            .map(|usage| completion::Usage {
-                input_tokens: usage.input_tokens,
-                output_tokens: usage.output_tokens,
-                total_tokens: usage.total_tokens,
-            })
+            .and_then(GetTokenUsage::token_usage)
             .unwrap_or_default();
 
         Ok(completion::CompletionResponse {
@@ -8,11 +8,12 @@ use crate::providers::openai::responses_api::{
 };
 use crate::streaming;
 use crate::streaming::RawStreamingChoice;
+use crate::telemetry::{SpanCombinator, gen_ai_span};
 use crate::wasm_compat::WasmCompatSend;
 use async_stream::stream;
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tracing::{Level, debug, enabled, info_span};
+use tracing::{Level, debug, enabled};
 use tracing_futures::Instrument as _;
 
 use super::{CompletionResponse, Output};
@@ -41,11 +42,7 @@ pub struct StreamingCompletionResponse {
 
 impl GetTokenUsage for StreamingCompletionResponse {
     fn token_usage(&self) -> Option<crate::completion::Usage> {
-        let mut usage = crate::completion::Usage::new();
-        usage.input_tokens = self.usage.input_tokens;
-        usage.output_tokens = self.usage.output_tokens;
-        usage.total_tokens = self.usage.total_tokens;
-        Some(usage)
+        self.usage.token_usage()
     }
 }
 
@@ -231,7 +228,7 @@ where
         // let request_builder = self.client.post_reqwest("/responses").json(&request);
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -239,8 +236,6 @@ where
                 gen_ai.request.model = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -357,7 +352,7 @@ where
                     }
                     Err(error) => {
                         tracing::error!(?error, "SSE error");
-                        yield Err(CompletionError::ProviderError(error.to_string()));
+                        yield Err(error.into());
                         break;
                     }
                 }
@@ -370,8 +365,7 @@ where
                 yield Ok(tool_call.to_owned())
             }
 
-            span.record("gen_ai.usage.input_tokens", final_usage.input_tokens);
-            span.record("gen_ai.usage.output_tokens", final_usage.output_tokens);
+            span.record_token_usage(&final_usage);
             tracing::info!("OpenAI stream finished");
 
             yield Ok(RawStreamingChoice::FinalResponse(StreamingCompletionResponse {
@@ -5,6 +5,7 @@ use crate::{
     },
     completion::GetTokenUsage,
     http_client,
+    providers::openai,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -90,6 +91,10 @@ pub struct Usage {
     pub prompt_tokens: usize,
     pub completion_tokens: usize,
     pub total_tokens: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<openai::PromptTokensDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens_details: Option<openai::CompletionTokensDetails>,
 }
 
 impl std::fmt::Display for Usage {
@@ -109,6 +114,12 @@ impl GetTokenUsage for Usage {
         usage.input_tokens = self.prompt_tokens as u64;
         usage.output_tokens = self.completion_tokens as u64;
         usage.total_tokens = self.total_tokens as u64;
+        if let Some(details) = &self.prompt_tokens_details {
+            details.record(&mut usage);
+        }
+        if let Some(details) = &self.completion_tokens_details {
+            details.record(&mut usage);
+        }
 
         Some(usage)
     }
@@ -5,17 +5,18 @@ use futures::StreamExt;
 use http::Request;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tracing::info_span;
 use tracing_futures::Instrument;
 
 use crate::completion::{CompletionError, CompletionRequest, GetTokenUsage};
 use crate::http_client::HttpClientExt;
 use crate::http_client::sse::{Event, GenericEventSource};
 use crate::json_utils;
+use crate::providers::openai;
 use crate::providers::openrouter::{
     OpenRouterRequestParams, OpenrouterCompletionRequest, ReasoningDetails,
 };
 use crate::streaming;
+use crate::telemetry::gen_ai_span;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct StreamingCompletionResponse {
@@ -29,6 +30,12 @@ impl GetTokenUsage for StreamingCompletionResponse {
         usage.input_tokens = self.usage.prompt_tokens as u64;
         usage.output_tokens = self.usage.completion_tokens as u64;
         usage.total_tokens = self.usage.total_tokens as u64;
+        if let Some(details) = &self.usage.prompt_tokens_details {
+            details.record(&mut usage);
+        }
+        if let Some(details) = &self.usage.completion_tokens_details {
+            details.record(&mut usage);
+        }
 
         Some(usage)
     }
@@ -76,6 +83,10 @@ pub struct Usage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens_details: Option<openai::PromptTokensDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens_details: Option<openai::CompletionTokensDetails>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -139,7 +150,7 @@ where
             .map_err(|x| CompletionError::HttpError(x.into()))?;
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -148,8 +159,6 @@ where
                 gen_ai.system_instructions = preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -298,7 +307,7 @@ where
                 }
                 Err(error) => {
                     tracing::error!(?error, "SSE error");
-                    yield Err(CompletionError::ProviderError(error.to_string()));
+                    yield Err(error.into());
                     break;
                 }
             }
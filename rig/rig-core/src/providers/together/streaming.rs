@@ -6,8 +6,9 @@ use crate::providers::openai;
 use crate::providers::openai::send_compatible_streaming_request;
 use crate::providers::together::completion::TogetherAICompletionRequest;
 use crate::streaming::StreamingCompletionResponse;
+use crate::telemetry::gen_ai_span;
 
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 
 impl<T> CompletionModel<T>
 where
@@ -47,7 +48,7 @@ where
             .map_err(|x| CompletionError::HttpError(x.into()))?;
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -56,8 +57,6 @@ where
                 gen_ai.system_instructions = preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
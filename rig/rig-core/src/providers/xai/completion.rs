@@ -3,6 +3,7 @@
 //! From [xAI Reference](https://docs.x.ai/docs/api-reference#chat-completions)
 // ================================================================
 
+use crate::telemetry::gen_ai_span;
 use crate::{
     completion::{self, CompletionError},
     http_client::HttpClientExt,
@@ -15,7 +16,7 @@ use crate::providers::openai;
 use crate::streaming::StreamingCompletionResponse;
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
-use tracing::{Instrument, Level, enabled, info_span};
+use tracing::{Instrument, Level, enabled};
 use xai_api_types::{CompletionResponse, ToolDefinition};
 
 /// xAI completion models as of 2025-06-04
@@ -117,12 +118,16 @@ where
         Self::new(client.clone(), model)
     }
 
+    fn model_name(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
     async fn completion(
         &self,
         completion_request: completion::CompletionRequest,
     ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat",
                 gen_ai.operation.name = "chat",
@@ -131,8 +136,6 @@ where
                 gen_ai.system_instructions = tracing::field::Empty,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
@@ -174,12 +177,15 @@ where
 
                         response.try_into()
                     }
-                    ApiResponse::Error(error) => {
-                        Err(CompletionError::ProviderError(error.message()))
-                    }
+                    ApiResponse::Error(error) => Err(CompletionError::from_error_body(
+                        &response_body,
+                        error.message(),
+                    )),
                 }
             } else {
-                Err(CompletionError::ProviderError(
+                Err(CompletionError::from_response(
+                    status,
+                    None,
                     String::from_utf8_lossy(&response_body).to_string(),
                 ))
             }
@@ -200,8 +206,8 @@ pub mod xai_api_types {
     use serde::{Deserialize, Serialize};
 
     use crate::OneOrMany;
-    use crate::completion::{self, CompletionError};
-    use crate::providers::openai::{AssistantContent, Message};
+    use crate::completion::{self, CompletionError, GetTokenUsage};
+    use crate::providers::openai::{self, AssistantContent, Message};
 
     impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionResponse> {
         type Error = CompletionError;
@@ -253,11 +259,7 @@ pub mod xai_api_types {
                 )
             })?;
 
-            let usage = completion::Usage {
-                input_tokens: response.usage.prompt_tokens as u64,
-                output_tokens: response.usage.completion_tokens as u64,
-                total_tokens: response.usage.total_tokens as u64,
-            };
+            let usage = response.usage.token_usage().unwrap_or_default();
 
             Ok(completion::CompletionResponse {
                 choice,
@@ -311,5 +313,26 @@ pub mod xai_api_types {
         pub completion_tokens: i32,
         pub prompt_tokens: i32,
         pub total_tokens: i32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub prompt_tokens_details: Option<openai::PromptTokensDetails>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub completion_tokens_details: Option<openai::CompletionTokensDetails>,
+    }
+
+    impl GetTokenUsage for Usage {
+        fn token_usage(&self) -> Option<completion::Usage> {
+            let mut usage = completion::Usage::new();
+            usage.input_tokens = self.prompt_tokens as u64;
+            usage.output_tokens = self.completion_tokens as u64;
+            usage.total_tokens = self.total_tokens as u64;
+            if let Some(details) = &self.prompt_tokens_details {
+                details.record(&mut usage);
+            }
+            if let Some(details) = &self.completion_tokens_details {
+                details.record(&mut usage);
+            }
+
+            Some(usage)
+        }
     }
 }
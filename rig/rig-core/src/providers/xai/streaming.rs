@@ -5,7 +5,8 @@ use crate::providers::openai;
 use crate::providers::openai::send_compatible_streaming_request;
 use crate::providers::xai::completion::{CompletionModel, XAICompletionRequest};
 use crate::streaming::StreamingCompletionResponse;
-use tracing::{Instrument, Level, enabled, info_span};
+use crate::telemetry::gen_ai_span;
+use tracing::{Instrument, Level, enabled};
 
 impl<T> CompletionModel<T>
 where
@@ -42,7 +43,7 @@ where
             .map_err(|e| CompletionError::HttpError(e.into()))?;
 
         let span = if tracing::Span::current().is_disabled() {
-            info_span!(
+            gen_ai_span!(
                 target: "rig::completions",
                 "chat_streaming",
                 gen_ai.operation.name = "chat_streaming",
@@ -51,8 +52,6 @@ where
                 gen_ai.system_instructions = preamble,
                 gen_ai.response.id = tracing::field::Empty,
                 gen_ai.response.model = tracing::field::Empty,
-                gen_ai.usage.output_tokens = tracing::field::Empty,
-                gen_ai.usage.input_tokens = tracing::field::Empty,
             )
         } else {
             tracing::Span::current()
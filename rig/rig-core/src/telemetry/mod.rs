@@ -6,6 +6,36 @@
 use crate::completion::GetTokenUsage;
 use serde::Serialize;
 
+/// Creates an `info` span like [`tracing::info_span!`], also declaring the `gen_ai.usage.*` fields
+/// recorded by [`SpanCombinator::record_token_usage`] and [`crate::completion::Usage::record_in_span`].
+macro_rules! gen_ai_span {
+    (target: $target:expr, parent: $parent:expr, $name:expr, $($fields:tt)*) => {
+        $crate::telemetry::gen_ai_span!(@span [target: $target, parent: $parent, $name,] $($fields)*)
+    };
+    (target: $target:expr, $name:expr, $($fields:tt)*) => {
+        $crate::telemetry::gen_ai_span!(@span [target: $target, $name,] $($fields)*)
+    };
+    (@span [$($head:tt)*] $($fields:tt)*) => {
+        ::tracing::info_span!(
+            $($head)*
+            gen_ai.usage.input_tokens = ::tracing::field::Empty,
+            gen_ai.usage.output_tokens = ::tracing::field::Empty,
+            gen_ai.usage.cache_read.input_tokens = ::tracing::field::Empty,
+            gen_ai.usage.cache_creation.input_tokens = ::tracing::field::Empty,
+            gen_ai.usage.reasoning.output_tokens = ::tracing::field::Empty,
+            gen_ai.usage.audio.input_tokens = ::tracing::field::Empty,
+            gen_ai.usage.audio.output_tokens = ::tracing::field::Empty,
+            gen_ai.usage.image.input_tokens = ::tracing::field::Empty,
+            gen_ai.usage.image.output_tokens = ::tracing::field::Empty,
+            $($fields)*
+        )
+    };
+    ($name:expr, $($fields:tt)*) => {
+        $crate::telemetry::gen_ai_span!(@span [$name,] $($fields)*)
+    };
+}
+pub(crate) use gen_ai_span;
+
 pub trait ProviderRequestExt {
     type InputMessage: Serialize;
 
@@ -60,8 +90,7 @@ impl SpanCombinator for tracing::Span {
         }
 
         if let Some(usage) = usage.token_usage() {
-            self.record("gen_ai.usage.input_tokens", usage.input_tokens);
-            self.record("gen_ai.usage.output_tokens", usage.output_tokens);
+            usage.record_in_span(self);
         }
     }
 
@@ -0,0 +1,207 @@
+//! Test fixtures shared by the unit tests of the crate.
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use serde_json::json;
+
+use crate::{
+    OneOrMany,
+    client::FinalCompletionResponse,
+    completion::{
+        CompletionError, CompletionModel, CompletionRequest, CompletionResponse, ToolDefinition,
+        Usage,
+    },
+    message::AssistantContent,
+    streaming::{RawStreamingChoice, RawStreamingToolCall, StreamingCompletionResponse},
+    tool::Tool,
+};
+
+/// Usage reported by every response of a [ScriptedModel].
+pub(crate) const SCRIPTED_USAGE: Usage = Usage {
+    input_tokens: 10,
+    output_tokens: 5,
+    total_tokens: 15,
+    cached_input_tokens: 0,
+    cache_creation_input_tokens: 0,
+    reasoning_tokens: 0,
+    audio_input_tokens: 0,
+    audio_output_tokens: 0,
+    image_input_tokens: 0,
+    image_output_tokens: 0,
+};
+
+/// A completion model answering with scripted responses, both when prompted and streamed.
+/// Every request it receives is recorded in `requests`.
+#[derive(Clone, Default)]
+pub(crate) struct ScriptedModel {
+    responses: Arc<Mutex<VecDeque<Vec<AssistantContent>>>>,
+    pub(crate) requests: Arc<Mutex<Vec<CompletionRequest>>>,
+    output_schema_support: bool,
+}
+
+impl ScriptedModel {
+    /// Creates a model answering each request with the next single-content response.
+    pub(crate) fn new(responses: impl IntoIterator<Item = AssistantContent>) -> Self {
+        Self::with_turns(responses.into_iter().map(|content| vec![content]))
+    }
+
+    /// Creates a model answering each request with the next response, which may hold several
+    /// contents (e.g.: parallel tool calls).
+    pub(crate) fn with_turns(turns: impl IntoIterator<Item = Vec<AssistantContent>>) -> Self {
+        Self {
+            responses: Arc::new(Mutex::new(turns.into_iter().collect())),
+            ..Default::default()
+        }
+    }
+
+    /// Makes the model report native support of output schemas.
+    pub(crate) fn with_output_schema_support(mut self) -> Self {
+        self.output_schema_support = true;
+        self
+    }
+
+    fn next_response(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<Vec<AssistantContent>, CompletionError> {
+        self.requests.lock().expect("lock poisoned").push(request);
+        self.responses
+            .lock()
+            .expect("lock poisoned")
+            .pop_front()
+            .ok_or_else(|| CompletionError::ProviderError("no more responses".into()))
+    }
+}
+
+impl CompletionModel for ScriptedModel {
+    type Response = ();
+    type StreamingResponse = FinalCompletionResponse;
+    type Client = ();
+
+    fn make(_: &Self::Client, _: impl Into<String>) -> Self {
+        Self::default()
+    }
+
+    fn supports_output_schema(&self) -> bool {
+        self.output_schema_support
+    }
+
+    fn model_name(&self) -> Option<&str> {
+        Some("scripted")
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        let choice = OneOrMany::many(self.next_response(request)?)
+            .map_err(|_| CompletionError::ResponseError("empty scripted response".into()))?;
+
+        Ok(CompletionResponse {
+            choice,
+            usage: SCRIPTED_USAGE,
+            raw_response: (),
+        })
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        let mut chunks = self
+            .next_response(request)?
+            .into_iter()
+            .filter_map(|content| match content {
+                AssistantContent::Text(text) => Some(RawStreamingChoice::Message(text.text)),
+                AssistantContent::ToolCall(tool_call) => {
+                    let raw = RawStreamingToolCall::new(
+                        tool_call.id,
+                        tool_call.function.name,
+                        tool_call.function.arguments,
+                    );
+                    Some(RawStreamingChoice::ToolCall(match tool_call.call_id {
+                        Some(call_id) => raw.with_call_id(call_id),
+                        None => raw,
+                    }))
+                }
+                _ => None,
+            })
+            .map(Ok)
+            .collect::<Vec<_>>();
+
+        chunks.push(Ok(RawStreamingChoice::FinalResponse(
+            FinalCompletionResponse {
+                usage: Some(SCRIPTED_USAGE),
+            },
+        )));
+
+        Ok(StreamingCompletionResponse::stream(Box::pin(
+            futures::stream::iter(chunks),
+        )))
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(crate) struct EchoArgs {
+    text: String,
+    #[serde(default)]
+    delay_ms: u64,
+}
+
+/// A tool returning its `text` argument, after waiting `delay_ms` milliseconds if given.
+/// It counts its calls and the highest number of calls running at the same time.
+#[derive(Clone, Default)]
+pub(crate) struct EchoTool {
+    pub(crate) calls: Arc<AtomicUsize>,
+    in_flight: Arc<AtomicUsize>,
+    pub(crate) max_in_flight: Arc<AtomicUsize>,
+}
+
+impl Tool for EchoTool {
+    const NAME: &'static str = "echo";
+    type Error = std::convert::Infallible;
+    type Args = EchoArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Echo a text back".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "text": {"type": "string"},
+                    "delay_ms": {"type": "integer"}
+                },
+                "required": ["text"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+
+        if args.delay_ms > 0 {
+            tokio::time::sleep(std::time::Duration::from_millis(args.delay_ms)).await;
+        }
+
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        Ok(args.text)
+    }
+}
+
+/// A call of [EchoTool] returning `text`.
+pub(crate) fn echo_call(id: &str, text: &str, delay_ms: u64) -> AssistantContent {
+    AssistantContent::tool_call(
+        id,
+        EchoTool::NAME,
+        json!({"text": text, "delay_ms": delay_ms}),
+    )
+}
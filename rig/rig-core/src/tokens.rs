@@ -0,0 +1,343 @@
+//! Token counting and estimation.
+//!
+//! A [TokenCounter] tells how many tokens a piece of text, a [Message] or a whole
+//! [CompletionRequest] uses, without sending anything to the provider. It can be used to trim the
+//! chat history, check a request against a budget or size chunks before embedding them.
+//!
+//! Every [CompletionModel](crate::completion::CompletionModel) and
+//! [EmbeddingModel](crate::embeddings::EmbeddingModel) exposes a counter through its
+//! `token_counter` method. Rig ships with the following offline counters:
+//! - [BpeEstimator]: splits text the way BPE tokenizers do before merging (words, groups of digits,
+//!   runs of punctuation and whitespace) and estimates the tokens of each piece. It is a heuristic
+//!   without any vocabulary, not an actual BPE tokenizer. This is the default counter of all models.
+//! - [CharEstimator]: counts a fixed number of characters per token.
+//!
+//! Offline counts are estimates, as exact counts require the provider's own tokenizer. Models of
+//! providers with a token counting endpoint also implement [RemoteTokenCounter].
+//!
+//! # Example
+//! ```rust,ignore
+//! use rig::{completion::CompletionModel, tokens::RemoteTokenCounter};
+//!
+//! let request = model.completion_request("Hello!").preamble("Be brief.").build();
+//!
+//! // Offline estimate
+//! let estimate = model.token_counter().count_request(&request);
+//!
+//! // Exact count, using the provider's endpoint
+//! let count = model.count_request_tokens(request).await?;
+//! ```
+use crate::{
+    completion::{CompletionError, CompletionRequest, Message, ToolDefinition},
+    message::{AssistantContent, DocumentSourceKind, ToolResultContent, UserContent},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+/// Fixed overhead (role, separators, etc.) added to every message.
+const TOKENS_PER_MESSAGE: u64 = 4;
+/// Flat estimate used for non-text content (images, audio, video, binary documents).
+const TOKENS_PER_MEDIA: u64 = 1_000;
+
+/// Trait for counting the tokens of text, messages and completion requests.
+///
+/// Only [TokenCounter::count_text] needs to be implemented: messages and requests are counted
+/// through the text they contain.
+pub trait TokenCounter: WasmCompatSend + WasmCompatSync {
+    /// Returns the number of tokens of `text`.
+    fn count_text(&self, text: &str) -> u64;
+
+    /// Returns the number of tokens used by non-text content (images, audio, video, binary
+    /// documents).
+    fn media_tokens(&self) -> u64 {
+        TOKENS_PER_MEDIA
+    }
+
+    /// Returns the fixed number of tokens (role, separators, etc.) added to every message.
+    fn message_overhead(&self) -> u64 {
+        TOKENS_PER_MESSAGE
+    }
+
+    /// Returns the number of tokens of `message`. Tool call arguments are counted through their
+    /// JSON representation.
+    fn count_message(&self, message: &Message) -> u64 {
+        let source_tokens = |data: &DocumentSourceKind| match data {
+            DocumentSourceKind::String(text) => self.count_text(text),
+            _ => self.media_tokens(),
+        };
+
+        let content_tokens: u64 = match message {
+            Message::User { content } => content
+                .iter()
+                .map(|content| match content {
+                    UserContent::Text(text) => self.count_text(&text.text),
+                    UserContent::ToolResult(result) => result
+                        .content
+                        .iter()
+                        .map(|content| match content {
+                            ToolResultContent::Text(text) => self.count_text(&text.text),
+                            ToolResultContent::Image(_) => self.media_tokens(),
+                            ToolResultContent::Document(document) => source_tokens(&document.data),
+                        })
+                        .sum(),
+                    UserContent::Document(document) => source_tokens(&document.data),
+                    UserContent::Image(_) | UserContent::Audio(_) | UserContent::Video(_) => {
+                        self.media_tokens()
+                    }
+                })
+                .sum(),
+            Message::Assistant { content, .. } => content
+                .iter()
+                .map(|content| match content {
+                    AssistantContent::Text(text) => self.count_text(&text.text),
+                    AssistantContent::ToolCall(tool_call) => {
+                        self.count_text(&tool_call.function.name)
+                            + self.count_text(&tool_call.function.arguments.to_string())
+                    }
+                    AssistantContent::Reasoning(reasoning) => {
+                        reasoning.reasoning.iter().map(|r| self.count_text(r)).sum()
+                    }
+                    AssistantContent::Image(_) => self.media_tokens(),
+                })
+                .sum(),
+        };
+
+        content_tokens + self.message_overhead()
+    }
+
+    /// Returns the number of tokens of a list of messages.
+    fn count_messages(&self, messages: &[Message]) -> u64 {
+        messages
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum()
+    }
+
+    /// Returns the number of tokens of a tool definition, counted through its name, description
+    /// and the JSON representation of its parameters.
+    fn count_tool(&self, tool: &ToolDefinition) -> u64 {
+        self.count_text(&tool.name)
+            + self.count_text(&tool.description)
+            + self.count_text(&tool.parameters.to_string())
+    }
+
+    /// Returns the number of input tokens of `request`: its preamble, documents, tool
+    /// definitions and chat history.
+    fn count_request(&self, request: &CompletionRequest) -> u64 {
+        let preamble_tokens = request
+            .preamble
+            .as_ref()
+            .map(|preamble| self.count_text(preamble) + self.message_overhead())
+            .unwrap_or_default();
+
+        let document_tokens = request
+            .normalized_documents()
+            .map(|documents| self.count_message(&documents))
+            .unwrap_or_default();
+
+        let tool_tokens: u64 = request.tools.iter().map(|tool| self.count_tool(tool)).sum();
+
+        let history_tokens: u64 = request
+            .chat_history
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum();
+
+        preamble_tokens + document_tokens + tool_tokens + history_tokens
+    }
+}
+
+/// Trait for models whose provider offers a token counting endpoint.
+///
+/// Unlike [TokenCounter], counts are exact but require a request to the provider.
+pub trait RemoteTokenCounter: WasmCompatSend + WasmCompatSync {
+    /// Returns the number of input tokens of `request`, as counted by the provider.
+    fn count_request_tokens(
+        &self,
+        request: CompletionRequest,
+    ) -> impl std::future::Future<Output = Result<u64, CompletionError>> + WasmCompatSend;
+}
+
+/// Offline estimator mimicking BPE tokenizers (e.g.: `cl100k_base`, `o200k_base`).
+///
+/// Text is first split the way BPE tokenizers do before merging: words (with their leading
+/// space), groups of up to 3 digits, runs of punctuation and runs of whitespace. Each piece is then
+/// estimated on its own: short words are a single token, longer words are split every few
+/// characters and non-ASCII text is counted by bytes.
+///
+/// This is a heuristic: it doesn't load any vocabulary or merge table, so it never applies actual
+/// BPE merges and its counts are approximations, whatever the model's tokenizer.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BpeEstimator;
+
+impl BpeEstimator {
+    /// Words up to this length (in bytes) are assumed to be a single token.
+    const MAX_SINGLE_TOKEN_WORD: usize = 7;
+    /// Average number of bytes per token within longer ASCII words.
+    const BYTES_PER_WORD_TOKEN: usize = 4;
+    /// Average number of bytes per token for non-ASCII text.
+    const BYTES_PER_NON_ASCII_TOKEN: usize = 3;
+    /// Number of digits merged in a single token.
+    const DIGITS_PER_TOKEN: usize = 3;
+    /// Average number of punctuation characters merged in a single token.
+    const PUNCTUATION_PER_TOKEN: usize = 2;
+
+    fn word_tokens(bytes: usize, ascii: bool) -> usize {
+        if !ascii {
+            bytes.div_ceil(Self::BYTES_PER_NON_ASCII_TOKEN)
+        } else if bytes <= Self::MAX_SINGLE_TOKEN_WORD {
+            1
+        } else {
+            bytes.div_ceil(Self::BYTES_PER_WORD_TOKEN)
+        }
+    }
+}
+
+impl TokenCounter for BpeEstimator {
+    fn count_text(&self, text: &str) -> u64 {
+        let mut tokens: usize = 0;
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c.is_alphabetic() {
+                let (mut bytes, mut ascii) = (c.len_utf8(), c.is_ascii());
+                while let Some(&next) = chars.peek() {
+                    if !next.is_alphabetic() {
+                        break;
+                    }
+                    bytes += next.len_utf8();
+                    ascii &= next.is_ascii();
+                    chars.next();
+                }
+                tokens += Self::word_tokens(bytes, ascii);
+            } else if c.is_numeric() {
+                let mut digits: usize = 1;
+                while chars.next_if(|next| next.is_numeric()).is_some() {
+                    digits += 1;
+                }
+                tokens += digits.div_ceil(Self::DIGITS_PER_TOKEN);
+            } else if c.is_whitespace() {
+                let mut len: usize = 1;
+                while chars.next_if(|next| next.is_whitespace()).is_some() {
+                    len += 1;
+                }
+                // A single space is merged with the word or punctuation that follows it
+                if len > 1 || c != ' ' || chars.peek().is_none() {
+                    tokens += 1;
+                }
+            } else {
+                let mut len: usize = 1;
+                while chars
+                    .next_if(|next| !next.is_alphanumeric() && !next.is_whitespace())
+                    .is_some()
+                {
+                    len += 1;
+                }
+                tokens += len.div_ceil(Self::PUNCTUATION_PER_TOKEN);
+            }
+        }
+
+        tokens as u64
+    }
+}
+
+/// Offline estimator counting a fixed number of characters per token (4 by default).
+#[derive(Clone, Copy, Debug)]
+pub struct CharEstimator {
+    chars_per_token: u64,
+}
+
+impl CharEstimator {
+    /// Creates an estimator counting `chars_per_token` characters per token.
+    ///
+    /// # Panics
+    /// If `chars_per_token` is 0.
+    pub fn new(chars_per_token: u64) -> Self {
+        assert!(chars_per_token > 0, "chars_per_token must be positive");
+        Self { chars_per_token }
+    }
+}
+
+impl Default for CharEstimator {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl TokenCounter for CharEstimator {
+    fn count_text(&self, text: &str) -> u64 {
+        (text.chars().count() as u64).div_ceil(self.chars_per_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OneOrMany;
+    use crate::completion::Document;
+
+    #[test]
+    fn test_bpe_estimator_count_text() {
+        let counter = BpeEstimator;
+
+        assert_eq!(counter.count_text(""), 0);
+        assert_eq!(counter.count_text("Hello world"), 2);
+        assert_eq!(counter.count_text("Hello, world!"), 4);
+        // Words longer than 7 bytes are split
+        assert_eq!(counter.count_text("internationalization"), 5);
+        // Digits are grouped by 3
+        assert_eq!(counter.count_text("1234567"), 3);
+        // Runs of whitespace are a single token
+        assert_eq!(counter.count_text("a\n\nb"), 3);
+        // Non-ASCII text is counted by bytes
+        assert_eq!(counter.count_text("日本語"), 3);
+    }
+
+    #[test]
+    fn test_char_estimator_count_text() {
+        let counter = CharEstimator::default();
+
+        assert_eq!(counter.count_text(""), 0);
+        assert_eq!(counter.count_text("abcd"), 1);
+        assert_eq!(counter.count_text("abcde"), 2);
+        assert_eq!(CharEstimator::new(2).count_text("abcde"), 3);
+    }
+
+    #[test]
+    fn test_count_request() {
+        let counter = CharEstimator::default();
+        let request = CompletionRequest {
+            preamble: Some("abcdefgh".to_string()),
+            chat_history: OneOrMany::many(vec![
+                Message::user("abcd"),
+                Message::assistant("abcdabcd"),
+            ])
+            .unwrap(),
+            documents: vec![Document {
+                id: "doc".to_string(),
+                text: "abcd".to_string(),
+                additional_props: Default::default(),
+            }],
+            tools: vec![ToolDefinition {
+                name: "abcd".to_string(),
+                description: "abcd".to_string(),
+                parameters: serde_json::json!({}),
+            }],
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            additional_params: None,
+            output_schema: None,
+            cache_breakpoints: vec![],
+        };
+
+        let preamble_tokens = 2 + TOKENS_PER_MESSAGE;
+        let document_tokens = counter.count_message(&request.normalized_documents().unwrap());
+        let tool_tokens = 1 + 1 + 1;
+        let history_tokens = (1 + TOKENS_PER_MESSAGE) + (2 + TOKENS_PER_MESSAGE);
+
+        assert_eq!(
+            counter.count_request(&request),
+            preamble_tokens + document_tokens + tool_tokens + history_tokens
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! The context of a tool call.
+//!
+//! Every tool call made by an agent receives a [ToolContext], which carries the id of the tool
+//! call, the name of the agent and the session that made it, the metadata attached by the caller
+//! (see [PromptRequest::with_tool_context](crate::agent::PromptRequest::with_tool_context)), typed
+//! extensions and the [CancelSignal] of the agent loop.
+//!
+//! Tools access the context by implementing [Tool::call_with_context](crate::tool::Tool::call_with_context),
+//! or by taking a `&ToolContext` parameter in a `#[rig_tool]` function.
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     completion::ToolDefinition,
+//!     tool::{Tool, ToolContext},
+//! };
+//!
+//! #[derive(Debug, thiserror::Error)]
+//! #[error("Forbidden")]
+//! struct Forbidden;
+//!
+//! struct DeleteInvoice;
+//!
+//! impl Tool for DeleteInvoice {
+//!     const NAME: &'static str = "delete_invoice";
+//!
+//!     type Error = Forbidden;
+//!     type Args = serde_json::Value;
+//!     type Output = String;
+//!
+//!     async fn definition(&self, _prompt: String) -> ToolDefinition {
+//!         todo!()
+//!     }
+//!
+//!     async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+//!         // Calls without a context are never authorized
+//!         Err(Forbidden)
+//!     }
+//!
+//!     async fn call_with_context(
+//!         &self,
+//!         args: Self::Args,
+//!         context: &ToolContext,
+//!     ) -> Result<Self::Output, Self::Error> {
+//!         match context.metadata("role").and_then(|role| role.as_str()) {
+//!             Some("admin") => Ok(format!("Deleted invoice {}", args["id"])),
+//!             _ => Err(Forbidden),
+//!         }
+//!     }
+//! }
+//! ```
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+};
+
+use crate::agent::CancelSignal;
+
+/// The context of a single tool call.
+#[derive(Clone, Default)]
+pub struct ToolContext {
+    tool_call_id: Option<String>,
+    agent_name: Option<String>,
+    session_id: Option<String>,
+    metadata: HashMap<String, serde_json::Value>,
+    extensions: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    cancel_signal: Option<CancelSignal>,
+}
+
+impl ToolContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach caller metadata (eg: the id or the role of the user) to the context.
+    pub fn with_metadata(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Attach a typed extension (eg: a database handle or the credentials of the user) to the
+    /// context. Extensions are keyed by their type, so adding a value of the same type replaces
+    /// the previous one.
+    pub fn with_extension<T: Send + Sync + 'static>(mut self, extension: T) -> Self {
+        self.extensions
+            .insert(TypeId::of::<T>(), Arc::new(extension));
+        self
+    }
+
+    pub fn with_tool_call_id(mut self, tool_call_id: impl Into<String>) -> Self {
+        self.tool_call_id = Some(tool_call_id.into());
+        self
+    }
+
+    pub fn with_agent_name(mut self, agent_name: impl Into<String>) -> Self {
+        self.agent_name = Some(agent_name.into());
+        self
+    }
+
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    pub fn with_cancel_signal(mut self, cancel_signal: CancelSignal) -> Self {
+        self.cancel_signal = Some(cancel_signal);
+        self
+    }
+
+    /// The id of the tool call, as generated by the model.
+    pub fn tool_call_id(&self) -> Option<&str> {
+        self.tool_call_id.as_deref()
+    }
+
+    /// The name of the agent that made the tool call.
+    pub fn agent_name(&self) -> Option<&str> {
+        self.agent_name.as_deref()
+    }
+
+    /// The id of the session the tool call was made in.
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    pub fn metadata(&self, key: &str) -> Option<&serde_json::Value> {
+        self.metadata.get(key)
+    }
+
+    pub fn extension<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.extensions
+            .get(&TypeId::of::<T>())
+            .and_then(|extension| extension.downcast_ref())
+    }
+
+    pub fn cancel_signal(&self) -> Option<&CancelSignal> {
+        self.cancel_signal.as_ref()
+    }
+
+    /// Returns true if the agent loop that made the tool call was cancelled. Long-running tools
+    /// can use this to stop early.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_signal
+            .as_ref()
+            .is_some_and(|cancel_signal| cancel_signal.is_cancelled())
+    }
+}
+
+impl fmt::Debug for ToolContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ToolContext")
+            .field("tool_call_id", &self.tool_call_id)
+            .field("agent_name", &self.agent_name)
+            .field("session_id", &self.session_id)
+            .field("metadata", &self.metadata)
+            .field("extensions", &self.extensions.len())
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::ToolContext;
+
+    struct TenantId(u64);
+
+    #[test]
+    fn test_tool_context() {
+        let context = ToolContext::new()
+            .with_metadata("role", "admin")
+            .with_extension(TenantId(42))
+            .with_tool_call_id("call_1");
+
+        assert_eq!(context.metadata("role"), Some(&json!("admin")));
+        assert_eq!(
+            context.extension::<TenantId>().map(|tenant| tenant.0),
+            Some(42)
+        );
+        assert!(context.extension::<String>().is_none());
+        assert_eq!(context.tool_call_id(), Some("call_1"));
+        assert!(!context.is_cancelled());
+    }
+}
@@ -0,0 +1,421 @@
+//! Use a whole MCP server from an agent, by connection.
+//!
+//! An [McpClient] connects to an MCP server and exposes everything it offers to rig:
+//! - its tools, which are registered on a [ToolServer](crate::tool::server::ToolServer) (see
+//!   [ToolServer::mcp_client](crate::tool::server::ToolServer::mcp_client) and
+//!   [AgentBuilder::mcp_client](crate::agent::AgentBuilder::mcp_client)) and kept up to date when
+//!   the server sends a `tools/list_changed` notification. When several servers offer tools with
+//!   the same name, give each client a namespace (see [McpClient::with_namespace]),
+//! - its resources, which can be used as dynamic context (see [McpClient::resources]),
+//! - its prompts, which can be rendered into a preamble (see [McpClient::preamble]).
+//!
+//! Images and embedded resources returned by MCP tools are sent to the model as multimodal
+//! content (see [ToolOutput](crate::tool::ToolOutput)).
+//!
+//! # Example
+//! ```rust
+//! use rig::{providers::openai, tool::mcp_client::McpClient};
+//! use rmcp::transport::TokioChildProcess;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let transport = TokioChildProcess::new(tokio::process::Command::new("my-mcp-server"))?;
+//! let client = McpClient::connect(transport).await?;
+//!
+//! let agent = openai::Client::from_env()
+//!     .agent("gpt-4o")
+//!     .preamble(&client.preamble("assistant", [("language", "French")]).await?)
+//!     .dynamic_context(2, client.resources())
+//!     .mcp_client(client)
+//!     .build();
+//! # Ok(())
+//! # }
+//! ```
+use std::sync::Arc;
+
+use rmcp::{
+    ClientHandler, RoleClient, ServiceExt,
+    model::{
+        GetPromptRequestParam, PromptMessageContent, RawResource, ReadResourceRequestParam,
+        ResourceContents,
+    },
+    service::{
+        ClientInitializeError, NotificationContext, RunningService, ServerSink, ServiceError,
+    },
+    transport::IntoTransport,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    tool::{
+        NamespacedTool, ToolDyn, ToolSetError,
+        rmcp::McpTool,
+        server::{ToolServerError, ToolServerHandle, WeakToolServerHandle},
+    },
+    vector_store::{
+        TopNResults, VectorSearchRequest, VectorStoreError, VectorStoreIndexDyn, request::Filter,
+    },
+    wasm_compat::WasmBoxedFuture,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum McpClientError {
+    #[error("Failed to initialize the MCP client: {0}")]
+    InitializeError(#[from] Box<ClientInitializeError>),
+    #[error("MCP request failed: {0}")]
+    ServiceError(#[from] ServiceError),
+    #[error("ToolServerError: {0}")]
+    ToolServerError(#[from] Box<ToolServerError>),
+}
+
+impl From<ToolServerError> for McpClientError {
+    fn from(e: ToolServerError) -> Self {
+        McpClientError::ToolServerError(Box::new(e))
+    }
+}
+
+/// A connection to an MCP server. Cloning the client shares the connection, which is closed once
+/// every clone is dropped.
+#[derive(Clone)]
+pub struct McpClient {
+    service: Arc<RunningService<RoleClient, McpClientHandler>>,
+    namespace: Option<String>,
+}
+
+impl McpClient {
+    /// Connect to an MCP server over the given transport (eg: a child process, a streamable HTTP
+    /// client or any async reader/writer).
+    pub async fn connect<T, E, A>(transport: T) -> Result<Self, McpClientError>
+    where
+        T: IntoTransport<RoleClient, E, A>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let service = McpClientHandler::default()
+            .serve(transport)
+            .await
+            .map_err(Box::new)?;
+
+        Ok(Self {
+            service: Arc::new(service),
+            namespace: None,
+        })
+    }
+
+    /// Prefix the names of the tools of the server with the given namespace when they are
+    /// registered, eg: `github__search` for the `search` tool in the `github` namespace.
+    pub fn with_namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// The namespace of the tools of the server, if any.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// The connection to the server, to make raw MCP requests.
+    pub fn peer(&self) -> &ServerSink {
+        self.service.peer()
+    }
+
+    /// List the tools currently offered by the server.
+    pub async fn tools(&self) -> Result<Vec<McpTool>, McpClientError> {
+        let tools = self.peer().list_all_tools().await?;
+
+        Ok(tools
+            .into_iter()
+            .map(|tool| McpTool::from_mcp_server(tool, self.peer().clone()))
+            .collect())
+    }
+
+    /// The tools of the server, prefixed with the namespace of the client (if any).
+    pub(crate) async fn namespaced_tools(&self) -> Result<Vec<Box<dyn ToolDyn>>, McpClientError> {
+        Ok(namespaced_tools(self.namespace(), self.tools().await?)
+            .map_err(ToolServerError::from)?)
+    }
+
+    /// Add the tools of the server to a running tool server. The tools are replaced whenever the
+    /// MCP server reports that its tool list changed.
+    ///
+    /// The client must be kept alive for as long as the tools are used, since dropping it closes
+    /// the connection. Use [ToolServer::mcp_client](crate::tool::server::ToolServer::mcp_client)
+    /// to have the tool server own the client instead.
+    pub async fn register_tools(
+        &self,
+        tool_server: &ToolServerHandle,
+    ) -> Result<(), McpClientError> {
+        let mut tool_names = Vec::new();
+        for tool in self.namespaced_tools().await? {
+            tool_names.push(tool.name());
+            tool_server.add_tool_boxed(tool).await?;
+        }
+
+        self.watch_tools(tool_server.downgrade(), tool_names).await;
+        Ok(())
+    }
+
+    /// Keep the given tools of a tool server in sync with the tool list of the MCP server.
+    pub(crate) async fn watch_tools(
+        &self,
+        tool_server: WeakToolServerHandle,
+        tool_names: Vec<String>,
+    ) {
+        self.service
+            .service()
+            .registrations
+            .lock()
+            .await
+            .push(Registration {
+                tool_server,
+                namespace: self.namespace.clone(),
+                tool_names,
+            });
+    }
+
+    /// The resources of the server, as a [VectorStoreIndexDyn] to be used as the dynamic context
+    /// of an agent (see [AgentBuilder::dynamic_context](crate::agent::AgentBuilder::dynamic_context)).
+    pub fn resources(&self) -> McpResources {
+        McpResources {
+            client: self.clone(),
+        }
+    }
+
+    /// Render a prompt of the server with the given arguments, to be used as a preamble. The text
+    /// of every message of the prompt is joined with blank lines.
+    pub async fn preamble<K, V>(
+        &self,
+        name: &str,
+        arguments: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<String, McpClientError>
+    where
+        K: Into<String>,
+        V: Into<serde_json::Value>,
+    {
+        let arguments = arguments
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect::<serde_json::Map<_, _>>();
+
+        let prompt = self
+            .peer()
+            .get_prompt(GetPromptRequestParam {
+                name: name.to_string(),
+                arguments: (!arguments.is_empty()).then_some(arguments),
+            })
+            .await?;
+
+        Ok(prompt
+            .messages
+            .into_iter()
+            .filter_map(|message| match message.content {
+                PromptMessageContent::Text { text } => Some(text),
+                PromptMessageContent::Resource { resource } => match resource.raw.resource {
+                    ResourceContents::TextResourceContents { text, .. } => Some(text),
+                    ResourceContents::BlobResourceContents { .. } => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
+}
+
+/// The resources of an MCP server, used as dynamic context.
+///
+/// On each prompt, resources are ranked by how many words of the prompt appear in their name,
+/// title, description or URI. Resources that match none of the words are left out, and the text
+/// of the best matches is read from the server.
+#[derive(Clone)]
+pub struct McpResources {
+    client: McpClient,
+}
+
+impl McpResources {
+    async fn search(
+        &self,
+        req: &VectorSearchRequest<Filter<serde_json::Value>>,
+    ) -> Result<Vec<(f64, RawResource)>, VectorStoreError> {
+        let resources = self
+            .client
+            .peer()
+            .list_all_resources()
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        let mut matches = resources
+            .into_iter()
+            .map(|resource| (relevance(&resource.raw, req.query()), resource.raw))
+            .filter(|(score, _)| *score > 0.0)
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        matches.truncate(req.samples() as usize);
+
+        Ok(matches)
+    }
+}
+
+impl VectorStoreIndexDyn for McpResources {
+    fn top_n<'a>(
+        &'a self,
+        req: VectorSearchRequest<Filter<serde_json::Value>>,
+    ) -> WasmBoxedFuture<'a, TopNResults> {
+        Box::pin(async move {
+            let mut documents = Vec::new();
+            for (score, resource) in self.search(&req).await? {
+                let contents = self
+                    .client
+                    .peer()
+                    .read_resource(ReadResourceRequestParam {
+                        uri: resource.uri.clone(),
+                    })
+                    .await
+                    .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?
+                    .contents;
+
+                let text = contents
+                    .into_iter()
+                    .filter_map(|content| match content {
+                        ResourceContents::TextResourceContents { text, .. } => Some(text),
+                        ResourceContents::BlobResourceContents { .. } => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                documents.push((
+                    score,
+                    resource.uri.clone(),
+                    serde_json::json!({
+                        "name": resource.name,
+                        "uri": resource.uri,
+                        "text": text,
+                    }),
+                ));
+            }
+
+            Ok(documents)
+        })
+    }
+
+    fn top_n_ids<'a>(
+        &'a self,
+        req: VectorSearchRequest<Filter<serde_json::Value>>,
+    ) -> WasmBoxedFuture<'a, Result<Vec<(f64, String)>, VectorStoreError>> {
+        Box::pin(async move {
+            Ok(self
+                .search(&req)
+                .await?
+                .into_iter()
+                .map(|(score, resource)| (score, resource.uri))
+                .collect())
+        })
+    }
+}
+
+/// The number of words (of at least 3 characters) of the query found in the resource metadata.
+fn relevance(resource: &RawResource, query: &str) -> f64 {
+    let metadata = [
+        Some(&resource.name),
+        resource.title.as_ref(),
+        resource.description.as_ref(),
+        Some(&resource.uri),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|text| text.to_lowercase())
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() >= 3)
+        .filter(|word| metadata.contains(&word.to_lowercase()))
+        .count() as f64
+}
+
+fn namespaced_tools(
+    namespace: Option<&str>,
+    tools: Vec<McpTool>,
+) -> Result<Vec<Box<dyn ToolDyn>>, ToolSetError> {
+    tools
+        .into_iter()
+        .map(|tool| -> Result<Box<dyn ToolDyn>, ToolSetError> {
+            match namespace {
+                Some(namespace) => Ok(Box::new(NamespacedTool::new(namespace, tool)?)),
+                None => Ok(Box::new(tool)),
+            }
+        })
+        .collect()
+}
+
+/// The tools of a tool server that were added from an MCP server.
+struct Registration {
+    tool_server: WeakToolServerHandle,
+    namespace: Option<String>,
+    tool_names: Vec<String>,
+}
+
+impl Registration {
+    /// Replace the registered tools with the given ones.
+    async fn replace_tools(
+        &mut self,
+        tool_server: &ToolServerHandle,
+        tools: &[McpTool],
+    ) -> Result<(), ToolServerError> {
+        for tool_name in self.tool_names.drain(..) {
+            tool_server.remove_tool(&tool_name).await?;
+        }
+
+        for tool in namespaced_tools(self.namespace.as_deref(), tools.to_vec())? {
+            self.tool_names.push(tool.name());
+            tool_server.add_tool_boxed(tool).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct McpClientHandler {
+    registrations: Mutex<Vec<Registration>>,
+}
+
+impl ClientHandler for McpClientHandler {
+    async fn on_tool_list_changed(&self, context: NotificationContext<RoleClient>) {
+        let tools = match context.peer.list_all_tools().await {
+            Ok(tools) => tools
+                .into_iter()
+                .map(|tool| McpTool::from_mcp_server(tool, context.peer.clone()))
+                .collect::<Vec<_>>(),
+            Err(e) => {
+                tracing::warn!("Failed to refresh the tools of the MCP server: {e}");
+                return;
+            }
+        };
+
+        let mut registrations = self.registrations.lock().await;
+        // Tool servers that were shut down don't need to be updated anymore
+        registrations.retain(|registration| registration.tool_server.upgrade().is_some());
+
+        for registration in registrations.iter_mut() {
+            let Some(tool_server) = registration.tool_server.upgrade() else {
+                continue;
+            };
+            if let Err(e) = registration.replace_tools(&tool_server, &tools).await {
+                tracing::warn!("Failed to update the tools of the MCP server: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::relevance;
+
+    #[test]
+    fn test_resource_relevance() {
+        let resource = rmcp::model::RawResource::new("file:///docs/refunds.md", "Refund policy");
+
+        assert_eq!(relevance(&resource, "How do refunds work?"), 1.0);
+        assert_eq!(relevance(&resource, "What is the refund policy?"), 2.0);
+        assert_eq!(relevance(&resource, "Hello there"), 0.0);
+    }
+}
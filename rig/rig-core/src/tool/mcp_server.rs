@@ -0,0 +1,432 @@
+//! Serve a [ToolSet] (or the tools of a running [ToolServer]) over MCP, so that they can be used
+//! by any MCP client.
+//!
+//! The [ToolDefinition] of every tool is exposed as MCP tool metadata, and tool calls are
+//! dispatched to the [ToolServer]. Since [Agent](crate::agent::Agent)s implement
+//! [Tool](crate::tool::Tool), agents registered in the toolset are served as MCP tools as well.
+//!
+//! # Example
+//! ```rust
+//! use rig::tool::{ToolSet, mcp_server::McpServer};
+//!
+//! # async fn run(toolset: ToolSet) -> Result<(), Box<dyn std::error::Error>> {
+//! // Serve the tools over stdio...
+//! McpServer::from_toolset(toolset)
+//!     .server_info("calculator", "0.1.0")
+//!     .serve_stdio()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ...or over streamable HTTP, using [McpServer::into_streamable_http_service] to get a tower
+//! service that can be mounted in an axum router (eg: `Router::new().nest_service("/mcp", service)`).
+use std::sync::Arc;
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceExt,
+    model::{
+        CallToolRequestParam, CallToolResult, Content, Implementation, ListToolsResult, Meta,
+        PaginatedRequestParam, RequestId, ResourceContents, ServerCapabilities, ServerInfo,
+    },
+    service::{RequestContext, ServerInitializeError},
+    transport::streamable_http_server::{
+        StreamableHttpService, session::local::LocalSessionManager,
+    },
+};
+
+use crate::{
+    completion::{
+        ToolDefinition,
+        message::{DocumentSourceKind, MimeType, ToolResultContent},
+    },
+    tool::{
+        ToolContext, ToolOutput, ToolSet,
+        server::{ToolServer, ToolServerHandle},
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum McpServerError {
+    #[error("Failed to initialize the MCP server: {0}")]
+    InitializeError(#[from] Box<ServerInitializeError>),
+    #[error("MCP server task failed: {0}")]
+    JoinError(#[from] tokio::task::JoinError),
+}
+
+/// An MCP server exposing the tools of a [ToolServer].
+#[derive(Clone)]
+pub struct McpServer {
+    tools: ToolServerHandle,
+    server_info: Implementation,
+    instructions: Option<String>,
+}
+
+impl McpServer {
+    /// Create an MCP server serving the tools of the given (running) tool server.
+    pub fn new(tools: ToolServerHandle) -> Self {
+        Self {
+            tools,
+            server_info: Implementation::from_build_env(),
+            instructions: None,
+        }
+    }
+
+    /// Create an MCP server serving the tools of the given toolset.
+    pub fn from_toolset(toolset: ToolSet) -> Self {
+        let tool_names = toolset.tools.keys().cloned().collect();
+        Self::new(
+            ToolServer::new()
+                .static_tool_names(tool_names)
+                .add_tools(toolset)
+                .run(),
+        )
+    }
+
+    /// Set the name and version the server reports to MCP clients.
+    pub fn server_info(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.server_info = Implementation {
+            name: name.into(),
+            version: version.into(),
+            ..Implementation::from_build_env()
+        };
+        self
+    }
+
+    /// Set the instructions the server sends to MCP clients.
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Serve the tools over stdio until the client disconnects.
+    pub async fn serve_stdio(self) -> Result<(), McpServerError> {
+        let service = self
+            .serve(rmcp::transport::stdio())
+            .await
+            .map_err(Box::new)?;
+        service.waiting().await?;
+        Ok(())
+    }
+
+    /// Returns a tower service serving the tools over streamable HTTP.
+    pub fn into_streamable_http_service(self) -> StreamableHttpService<Self, LocalSessionManager> {
+        StreamableHttpService::new(
+            move || Ok(self.clone()),
+            Arc::new(LocalSessionManager::default()),
+            Default::default(),
+        )
+    }
+}
+
+impl ServerHandler for McpServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            server_info: self.server_info.clone(),
+            instructions: self.instructions.clone(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let definitions = self
+            .tools
+            .get_tool_defs(None)
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+
+        Ok(ListToolsResult::with_all_items(
+            definitions.into_iter().map(Into::into).collect(),
+        ))
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let args = serde_json::Value::Object(request.arguments.unwrap_or_default()).to_string();
+        let context = tool_context(&context.id, &context.meta);
+
+        // Tool errors are sent back as the result of the call, so that the model can see them
+        match self
+            .tools
+            .call_tool_with_context(&request.name, &args, context)
+            .await
+        {
+            Ok(output) => Ok(CallToolResult::success(to_mcp_content(output))),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+}
+
+/// Builds the context of a tool call from the MCP request: the id of the request becomes the id
+/// of the tool call, and the `_meta` fields of the request become the metadata of the context.
+fn tool_context(id: &RequestId, meta: &Meta) -> ToolContext {
+    meta.iter().fold(
+        ToolContext::new().with_tool_call_id(id.to_string()),
+        |context, (key, value)| context.with_metadata(key.clone(), value.clone()),
+    )
+}
+
+impl From<ToolDefinition> for rmcp::model::Tool {
+    fn from(definition: ToolDefinition) -> Self {
+        let input_schema = match definition.parameters {
+            serde_json::Value::Object(schema) => schema,
+            _ => serde_json::Map::from_iter([("type".to_string(), "object".into())]),
+        };
+
+        rmcp::model::Tool::new(definition.name, definition.description, input_schema)
+    }
+}
+
+/// Converts the output of a tool into MCP content. Images and documents that cannot be
+/// represented inline (eg: URLs) are sent as text.
+fn to_mcp_content(output: ToolOutput) -> Vec<Content> {
+    output
+        .into_content()
+        .into_iter()
+        .map(|content| match content {
+            ToolResultContent::Text(text) => Content::text(text.text),
+            ToolResultContent::Image(image) => {
+                let mime_type = image
+                    .media_type
+                    .map(|media_type| media_type.to_mime_type())
+                    .unwrap_or("image/png");
+                match image.data {
+                    DocumentSourceKind::Base64(data) => Content::image(data, mime_type),
+                    DocumentSourceKind::Raw(data) => {
+                        Content::image(BASE64_STANDARD.encode(data), mime_type)
+                    }
+                    DocumentSourceKind::Url(url) | DocumentSourceKind::String(url) => {
+                        Content::text(url)
+                    }
+                    DocumentSourceKind::Unknown => Content::text("[Image]"),
+                }
+            }
+            ToolResultContent::Document(document) => {
+                let mime_type = document
+                    .media_type
+                    .map(|media_type| media_type.to_mime_type().to_string());
+                let blob = match document.data {
+                    DocumentSourceKind::Base64(data) => data,
+                    DocumentSourceKind::Raw(data) => BASE64_STANDARD.encode(data),
+                    DocumentSourceKind::Url(text) | DocumentSourceKind::String(text) => {
+                        return Content::text(text);
+                    }
+                    DocumentSourceKind::Unknown => return Content::text("[Document]"),
+                };
+                // The document has no URI of its own: identify it by its content
+                let uri = format!(
+                    "data:{};base64,{blob}",
+                    mime_type.as_deref().unwrap_or("application/octet-stream")
+                );
+                Content::resource(ResourceContents::BlobResourceContents {
+                    uri,
+                    mime_type,
+                    blob,
+                    meta: None,
+                })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rmcp::{
+        ServiceExt,
+        model::{Meta, NumberOrString, ResourceContents},
+    };
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::{McpServer, to_mcp_content, tool_context};
+    use crate::{
+        completion::{
+            ToolDefinition,
+            message::{DocumentMediaType, ImageMediaType, ToolResultContent},
+        },
+        tool::{Tool, ToolOutput, mcp_client::McpClient, server::ToolServer},
+    };
+
+    #[derive(Deserialize)]
+    struct Operands {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("Math error")]
+    struct MathError;
+
+    #[derive(Deserialize, Serialize)]
+    struct Adder;
+
+    impl Tool for Adder {
+        const NAME: &'static str = "add";
+        type Error = MathError;
+        type Args = Operands;
+        type Output = i32;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: "add".to_string(),
+                description: "Add x and y together".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": { "x": { "type": "number" }, "y": { "type": "number" } },
+                    "required": ["x", "y"]
+                }),
+            }
+        }
+
+        async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+            Ok(args.x + args.y)
+        }
+    }
+
+    #[derive(Deserialize, Serialize)]
+    struct Subtract;
+
+    impl Tool for Subtract {
+        const NAME: &'static str = "subtract";
+        type Error = MathError;
+        type Args = Operands;
+        type Output = i32;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: "subtract".to_string(),
+                description: "Subtract y from x".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": { "x": { "type": "number" }, "y": { "type": "number" } },
+                    "required": ["x", "y"]
+                }),
+            }
+        }
+
+        async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+            Ok(args.x - args.y)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mcp_client_tool_list_changed() {
+        let remote_tools = ToolServer::new().tool(Adder).run();
+        let (server_transport, client_transport) = tokio::io::duplex(4096);
+        let server = tokio::spawn(McpServer::new(remote_tools.clone()).serve(server_transport));
+
+        let client = McpClient::connect(client_transport).await.unwrap();
+        let server = server.await.unwrap().unwrap();
+
+        // The remote `add` tool doesn't conflict with the local one once namespaced
+        let tool_server = ToolServer::new()
+            .tool(Adder)
+            .mcp_client(client.with_namespace("remote"))
+            .run();
+        let result = tool_server
+            .call_tool("remote__add", &json!({ "x": 2, "y": 3 }).to_string())
+            .await
+            .unwrap();
+        assert_eq!(result.to_string(), "5");
+
+        remote_tools.add_tool(Subtract).await.unwrap();
+        server.peer().notify_tool_list_changed().await.unwrap();
+
+        let mut tool_names = Vec::new();
+        for _ in 0..50 {
+            tool_names = tool_server
+                .get_tool_defs(None)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|definition| definition.name)
+                .collect::<Vec<_>>();
+            if tool_names.len() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(tool_names, vec!["add", "remote__add", "remote__subtract"]);
+    }
+
+    #[test]
+    fn test_tool_definition_to_mcp() {
+        let tool: rmcp::model::Tool = ToolDefinition {
+            name: "add".to_string(),
+            description: "Add x and y together".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": { "x": { "type": "number" }, "y": { "type": "number" } }
+            }),
+        }
+        .into();
+
+        assert_eq!(tool.name, "add");
+        assert_eq!(tool.description.as_deref(), Some("Add x and y together"));
+        assert_eq!(
+            tool.input_schema["properties"]["x"],
+            json!({ "type": "number" })
+        );
+    }
+
+    #[test]
+    fn test_tool_output_to_mcp() {
+        let output = ToolOutput::text("Here is the chart:").with_content(
+            ToolResultContent::image_base64("iVBORw0KGgo=", Some(ImageMediaType::PNG), None),
+        );
+
+        let content = to_mcp_content(output);
+        assert_eq!(content.len(), 2);
+        assert_eq!(
+            content[0].as_text().map(|text| text.text.as_str()),
+            Some("Here is the chart:")
+        );
+        let image = content[1].as_image().unwrap();
+        assert_eq!(image.mime_type, "image/png");
+        assert_eq!(image.data, "iVBORw0KGgo=");
+    }
+
+    #[test]
+    fn test_document_output_to_mcp() {
+        let output = ToolOutput::from(ToolResultContent::document_base64(
+            "JVBERi0=",
+            Some(DocumentMediaType::PDF),
+        ));
+
+        let content = to_mcp_content(output);
+        let resource = &content[0].as_resource().unwrap().resource;
+        let ResourceContents::BlobResourceContents {
+            uri,
+            mime_type,
+            blob,
+            ..
+        } = resource
+        else {
+            panic!("Expected a blob resource, got {resource:?}");
+        };
+        assert_eq!(uri, "data:application/pdf;base64,JVBERi0=");
+        assert_eq!(mime_type.as_deref(), Some("application/pdf"));
+        assert_eq!(blob, "JVBERi0=");
+    }
+
+    #[test]
+    fn test_tool_context_from_request() {
+        let mut meta = Meta::new();
+        meta.insert("user".to_string(), json!("alice"));
+
+        let context = tool_context(&NumberOrString::Number(7), &meta);
+        assert_eq!(context.tool_call_id(), Some("7"));
+        assert_eq!(context.metadata("user"), Some(&json!("alice")));
+    }
+}
@@ -7,11 +7,23 @@
 //! stored in a vector store and RAGged.
 //!
 //! The [ToolSet] struct is a collection of tools that can be used by an [Agent](crate::agent::Agent)
-//! and optionally RAGged.
+//! and optionally RAGged. Tool names are unique within a toolset: toolsets coming from different
+//! sources (eg: two MCP servers) can be namespaced to avoid conflicts (see [ToolSet::namespaced]).
 
+pub mod context;
+#[cfg(feature = "rmcp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+pub mod mcp_client;
+#[cfg(feature = "rmcp-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rmcp-server")))]
+pub mod mcp_server;
+pub mod openapi;
+pub mod output;
 pub mod server;
-use std::collections::HashMap;
+pub mod validation;
+use std::collections::{HashMap, hash_map::Entry};
 use std::fmt;
+use std::sync::Arc;
 
 use futures::Future;
 use serde::{Deserialize, Serialize};
@@ -19,9 +31,14 @@ use serde::{Deserialize, Serialize};
 use crate::{
     completion::{self, ToolDefinition},
     embeddings::{embed::EmbedError, tool::ToolSchema},
+    tool::validation::ArgumentValidator,
     wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
 };
 
+pub use context::ToolContext;
+pub use output::{IntoToolOutput, ToolOutput};
+pub use validation::{ArgumentValidationError, ArgumentViolation};
+
 #[derive(Debug, thiserror::Error)]
 pub enum ToolError {
     #[cfg(not(target_family = "wasm"))]
@@ -116,8 +133,9 @@ pub trait Tool: Sized + WasmCompatSend + WasmCompatSync {
     type Error: std::error::Error + WasmCompatSend + WasmCompatSync + 'static;
     /// The arguments type of the tool.
     type Args: for<'a> Deserialize<'a> + WasmCompatSend + WasmCompatSync;
-    /// The output type of the tool.
-    type Output: Serialize;
+    /// The output type of the tool. Serializable outputs are sent to the model as JSON text, use
+    /// [ToolOutput] to return images or documents.
+    type Output: IntoToolOutput;
 
     /// A method returning the name of the tool.
     fn name(&self) -> String {
@@ -138,6 +156,17 @@ pub trait Tool: Sized + WasmCompatSend + WasmCompatSync {
         &self,
         args: Self::Args,
     ) -> impl Future<Output = Result<Self::Output, Self::Error>> + WasmCompatSend;
+
+    /// The tool execution method, given the [ToolContext] of the tool call (eg: the agent and
+    /// session that made the call, the caller metadata and the cancellation signal).
+    /// By default, the context is ignored and [Tool::call] is used.
+    fn call_with_context(
+        &self,
+        args: Self::Args,
+        _context: &ToolContext,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + WasmCompatSend {
+        self.call(args)
+    }
 }
 
 /// Trait that represents an LLM tool that can be stored in a vector store and RAGged
@@ -173,7 +202,16 @@ pub trait ToolDyn: WasmCompatSend + WasmCompatSync {
 
     fn definition<'a>(&'a self, prompt: String) -> WasmBoxedFuture<'a, ToolDefinition>;
 
-    fn call<'a>(&'a self, args: String) -> WasmBoxedFuture<'a, Result<String, ToolError>>;
+    fn call<'a>(&'a self, args: String) -> WasmBoxedFuture<'a, Result<ToolOutput, ToolError>>;
+
+    /// Call the tool with the given [ToolContext]. By default, the context is ignored.
+    fn call_with_context<'a>(
+        &'a self,
+        args: String,
+        _context: ToolContext,
+    ) -> WasmBoxedFuture<'a, Result<ToolOutput, ToolError>> {
+        self.call(args)
+    }
 }
 
 impl<T: Tool> ToolDyn for T {
@@ -185,15 +223,29 @@ impl<T: Tool> ToolDyn for T {
         Box::pin(<Self as Tool>::definition(self, prompt))
     }
 
-    fn call<'a>(&'a self, args: String) -> WasmBoxedFuture<'a, Result<String, ToolError>> {
+    fn call<'a>(&'a self, args: String) -> WasmBoxedFuture<'a, Result<ToolOutput, ToolError>> {
         Box::pin(async move {
             match serde_json::from_str(&args) {
                 Ok(args) => <Self as Tool>::call(self, args)
                     .await
                     .map_err(|e| ToolError::ToolCallError(Box::new(e)))
-                    .and_then(|output| {
-                        serde_json::to_string(&output).map_err(ToolError::JsonError)
-                    }),
+                    .and_then(|output| output.into_tool_output().map_err(ToolError::JsonError)),
+                Err(e) => Err(ToolError::JsonError(e)),
+            }
+        })
+    }
+
+    fn call_with_context<'a>(
+        &'a self,
+        args: String,
+        context: ToolContext,
+    ) -> WasmBoxedFuture<'a, Result<ToolOutput, ToolError>> {
+        Box::pin(async move {
+            match serde_json::from_str(&args) {
+                Ok(args) => <Self as Tool>::call_with_context(self, args, &context)
+                    .await
+                    .map_err(|e| ToolError::ToolCallError(Box::new(e)))
+                    .and_then(|output| output.into_tool_output().map_err(ToolError::JsonError)),
                 Err(e) => Err(ToolError::JsonError(e)),
             }
         })
@@ -203,9 +255,14 @@ impl<T: Tool> ToolDyn for T {
 #[cfg(feature = "rmcp")]
 #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
 pub mod rmcp {
+    use crate::OneOrMany;
     use crate::completion::ToolDefinition;
+    use crate::completion::message::{
+        DocumentMediaType, ImageMediaType, MimeType, ToolResultContent,
+    };
     use crate::tool::ToolDyn;
     use crate::tool::ToolError;
+    use crate::tool::ToolOutput;
     use crate::wasm_compat::WasmBoxedFuture;
     use rmcp::model::RawContent;
     use std::borrow::Cow;
@@ -276,7 +333,7 @@ pub mod rmcp {
             })
         }
 
-        fn call(&self, args: String) -> WasmBoxedFuture<'_, Result<String, ToolError>> {
+        fn call(&self, args: String) -> WasmBoxedFuture<'_, Result<ToolOutput, ToolError>> {
             let name = self.definition.name.clone();
             let arguments = serde_json::from_str(&args).unwrap_or_default();
 
@@ -303,48 +360,59 @@ pub mod rmcp {
                     }
                 };
 
-                Ok(result
+                let content = result
                     .content
                     .into_iter()
                     .map(|c| match c.raw {
-                        rmcp::model::RawContent::Text(raw) => raw.text,
-                        rmcp::model::RawContent::Image(raw) => {
-                            format!("data:{};base64,{}", raw.mime_type, raw.data)
-                        }
+                        rmcp::model::RawContent::Text(raw) => ToolResultContent::text(raw.text),
+                        rmcp::model::RawContent::Image(raw) => ToolResultContent::image_base64(
+                            raw.data,
+                            ImageMediaType::from_mime_type(&raw.mime_type),
+                            None,
+                        ),
                         rmcp::model::RawContent::Resource(raw) => match raw.resource {
                             rmcp::model::ResourceContents::TextResourceContents {
                                 uri,
                                 mime_type,
                                 text,
                                 ..
-                            } => {
-                                format!(
-                                    "{mime_type}{uri}:{text}",
-                                    mime_type = mime_type
-                                        .map(|m| format!("data:{m};"))
-                                        .unwrap_or_default(),
-                                )
-                            }
+                            } => ToolResultContent::text(format!(
+                                "{mime_type}{uri}:{text}",
+                                mime_type =
+                                    mime_type.map(|m| format!("data:{m};")).unwrap_or_default(),
+                            )),
                             rmcp::model::ResourceContents::BlobResourceContents {
                                 uri,
                                 mime_type,
                                 blob,
                                 ..
-                            } => format!(
-                                "{mime_type}{uri}:{blob}",
-                                mime_type = mime_type
-                                    .map(|m| format!("data:{m};"))
-                                    .unwrap_or_default(),
-                            ),
+                            } => match mime_type
+                                .as_deref()
+                                .and_then(DocumentMediaType::from_mime_type)
+                            {
+                                Some(media_type) => {
+                                    ToolResultContent::document_base64(blob, Some(media_type))
+                                }
+                                None => ToolResultContent::text(format!(
+                                    "{mime_type}{uri}:{blob}",
+                                    mime_type =
+                                        mime_type.map(|m| format!("data:{m};")).unwrap_or_default(),
+                                )),
+                            },
                         },
-                        RawContent::Audio(_) => {
-                            panic!("Support for audio results from an MCP tool is currently unimplemented. Come back later!")
+                        // Audio can't be sent back to the model as a tool result
+                        RawContent::Audio(raw) => {
+                            ToolResultContent::text(format!("[Audio: {}]", raw.mime_type))
                         }
-                        thing => {
-                            panic!("Unsupported type found: {thing:?}")
+                        RawContent::ResourceLink(raw) => {
+                            ToolResultContent::text(format!("{}: {}", raw.name, raw.uri))
                         }
                     })
-                    .collect::<String>())
+                    .collect::<Vec<_>>();
+
+                Ok(OneOrMany::many(content)
+                    .map(ToolOutput::new)
+                    .unwrap_or_else(|_| ToolOutput::text("")))
             })
         }
     }
@@ -370,32 +438,188 @@ where
     }
 }
 
-pub(crate) enum ToolType {
-    Simple(Box<dyn ToolDyn>),
-    Embedding(Box<dyn ToolEmbeddingDyn>),
+/// The separator between the namespace and the name of a namespaced tool, eg: `github__search`.
+pub const NAMESPACE_SEPARATOR: &str = "__";
+
+/// The maximum length of the name of a namespaced tool (the limit of most providers).
+pub const MAX_NAMESPACED_NAME_LENGTH: usize = 64;
+
+/// Prefix the name of a tool with a namespace. The namespace may only contain ASCII letters,
+/// digits, `_` and `-`, and the prefixed name may be at most [MAX_NAMESPACED_NAME_LENGTH]
+/// characters long.
+fn namespaced_name(namespace: &str, name: &str) -> Result<String, ToolSetError> {
+    if namespace.is_empty()
+        || !namespace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(ToolSetError::InvalidNamespace(format!(
+            "`{namespace}` may only contain ASCII letters, digits, `_` and `-`"
+        )));
+    }
+
+    let name = format!("{namespace}{NAMESPACE_SEPARATOR}{name}");
+    if name.len() > MAX_NAMESPACED_NAME_LENGTH {
+        return Err(ToolSetError::InvalidNamespace(format!(
+            "`{name}` is longer than {MAX_NAMESPACED_NAME_LENGTH} characters"
+        )));
+    }
+
+    Ok(name)
+}
+
+/// A tool whose name is prefixed with a namespace (eg: `github__search` for the `search` tool in
+/// the `github` namespace). The prefixed name is the one sent to the model.
+pub struct NamespacedTool<T: ?Sized> {
+    name: String,
+    tool: Arc<T>,
+}
+
+impl<T: ToolDyn> NamespacedTool<T> {
+    /// Fails if the namespace is invalid (see [ToolSetError::InvalidNamespace]).
+    pub fn new(namespace: &str, tool: T) -> Result<Self, ToolSetError> {
+        Self::from_arc(namespace, Arc::new(tool))
+    }
+}
+
+impl<T: ToolDyn + ?Sized> NamespacedTool<T> {
+    fn from_arc(namespace: &str, tool: Arc<T>) -> Result<Self, ToolSetError> {
+        Ok(Self {
+            name: namespaced_name(namespace, &tool.name())?,
+            tool,
+        })
+    }
+
+    /// The wrapped tool
+    pub fn inner(&self) -> &T {
+        &self.tool
+    }
+}
+
+impl<T: ToolDyn + ?Sized> ToolDyn for NamespacedTool<T> {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn definition<'a>(&'a self, prompt: String) -> WasmBoxedFuture<'a, ToolDefinition> {
+        Box::pin(async move {
+            ToolDefinition {
+                name: self.name.clone(),
+                ..self.tool.definition(prompt).await
+            }
+        })
+    }
+
+    fn call<'a>(&'a self, args: String) -> WasmBoxedFuture<'a, Result<ToolOutput, ToolError>> {
+        self.tool.call(args)
+    }
+
+    fn call_with_context<'a>(
+        &'a self,
+        args: String,
+        context: ToolContext,
+    ) -> WasmBoxedFuture<'a, Result<ToolOutput, ToolError>> {
+        self.tool.call_with_context(args, context)
+    }
+}
+
+impl ToolEmbeddingDyn for NamespacedTool<dyn ToolEmbeddingDyn> {
+    fn context(&self) -> serde_json::Result<serde_json::Value> {
+        self.tool.context()
+    }
+
+    fn embedding_docs(&self) -> Vec<String> {
+        self.tool.embedding_docs()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum ToolKind {
+    Simple(Arc<dyn ToolDyn>),
+    Embedding(Arc<dyn ToolEmbeddingDyn>),
+}
+
+/// A tool registered in a [ToolSet], along with the compiled schema of its arguments.
+#[derive(Clone)]
+pub(crate) struct ToolType {
+    pub(crate) kind: ToolKind,
+    validator: ArgumentValidator,
 }
 
 impl ToolType {
+    fn simple(tool: Arc<dyn ToolDyn>) -> Self {
+        Self {
+            kind: ToolKind::Simple(tool),
+            validator: ArgumentValidator::default(),
+        }
+    }
+
+    fn embedding(tool: Arc<dyn ToolEmbeddingDyn>) -> Self {
+        Self {
+            kind: ToolKind::Embedding(tool),
+            validator: ArgumentValidator::default(),
+        }
+    }
+
     pub fn name(&self) -> String {
-        match self {
-            ToolType::Simple(tool) => tool.name(),
-            ToolType::Embedding(tool) => tool.name(),
+        match &self.kind {
+            ToolKind::Simple(tool) => tool.name(),
+            ToolKind::Embedding(tool) => tool.name(),
         }
     }
 
+    /// Prefix the name of the tool with the given namespace.
+    fn namespaced(self, namespace: &str) -> Result<Self, ToolSetError> {
+        let kind = match self.kind {
+            ToolKind::Simple(tool) => {
+                ToolKind::Simple(Arc::new(NamespacedTool::from_arc(namespace, tool)?))
+            }
+            ToolKind::Embedding(tool) => {
+                ToolKind::Embedding(Arc::new(NamespacedTool::from_arc(namespace, tool)?))
+            }
+        };
+
+        // The arguments of the tool don't change with its name
+        Ok(Self {
+            kind,
+            validator: self.validator,
+        })
+    }
+
     pub async fn definition(&self, prompt: String) -> ToolDefinition {
-        match self {
-            ToolType::Simple(tool) => tool.definition(prompt).await,
-            ToolType::Embedding(tool) => tool.definition(prompt).await,
+        match &self.kind {
+            ToolKind::Simple(tool) => tool.definition(prompt).await,
+            ToolKind::Embedding(tool) => tool.definition(prompt).await,
         }
     }
 
-    pub async fn call(&self, args: String) -> Result<String, ToolError> {
-        match self {
-            ToolType::Simple(tool) => tool.call(args).await,
-            ToolType::Embedding(tool) => tool.call(args).await,
+    pub async fn call(&self, args: String, context: ToolContext) -> Result<ToolOutput, ToolError> {
+        match &self.kind {
+            ToolKind::Simple(tool) => tool.call_with_context(args, context).await,
+            ToolKind::Embedding(tool) => tool.call_with_context(args, context).await,
         }
     }
+
+    /// Validates the arguments against the schema of the tool, then calls the tool.
+    ///
+    /// The schema is compiled on the first call and reused by the following ones.
+    pub async fn call_checked(
+        &self,
+        args: String,
+        context: ToolContext,
+    ) -> Result<ToolOutput, ToolSetError> {
+        let name = self.name();
+        tracing::debug!(target: "rig",
+            "Calling tool {name} with args:\n{}",
+            serde_json::to_string_pretty(&args).unwrap()
+        );
+        self.validator
+            .validate(&name, &args, || async {
+                self.definition(String::new()).await.parameters
+            })
+            .await?;
+        Ok(self.call(args, context).await?)
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -408,6 +632,11 @@ pub enum ToolSetError {
     #[error("ToolNotFoundError: {0}")]
     ToolNotFoundError(String),
 
+    /// A tool with the same name was already added. Namespacing one of the toolsets (see
+    /// [ToolSet::namespaced]) keeps both tools.
+    #[error("DuplicateToolError: a tool named `{0}` was already added")]
+    DuplicateToolError(String),
+
     // TODO: Revisit this
     #[error("JsonError: {0}")]
     JsonError(#[from] serde_json::Error),
@@ -415,9 +644,21 @@ pub enum ToolSetError {
     /// Tool call was interrupted. Primarily useful for agent multi-step/turn prompting.
     #[error("Tool call interrupted")]
     Interrupted,
+
+    /// The arguments of the tool call do not match the schema of the tool
+    #[error("ArgumentValidationError: {0}")]
+    ArgumentValidationError(#[from] ArgumentValidationError),
+
+    /// A namespace contains characters other than `[a-zA-Z0-9_-]`, or makes the name of a tool
+    /// too long (see [MAX_NAMESPACED_NAME_LENGTH]).
+    #[error("InvalidNamespaceError: {0}")]
+    InvalidNamespace(String),
 }
 
-/// A struct that holds a set of tools
+/// A struct that holds a set of tools.
+///
+/// Tools are identified by their name: adding a tool whose name is already taken fails with a
+/// [ToolSetError::DuplicateToolError] instead of replacing the existing tool.
 #[derive(Default)]
 pub struct ToolSet {
     pub(crate) tools: HashMap<String, ToolType>,
@@ -425,20 +666,20 @@ pub struct ToolSet {
 
 impl ToolSet {
     /// Create a new ToolSet from a list of tools
-    pub fn from_tools(tools: Vec<impl ToolDyn + 'static>) -> Self {
+    pub fn from_tools(tools: Vec<impl ToolDyn + 'static>) -> Result<Self, ToolSetError> {
         let mut toolset = Self::default();
-        tools.into_iter().for_each(|tool| {
-            toolset.add_tool(tool);
-        });
-        toolset
+        for tool in tools {
+            toolset.add_tool(tool)?;
+        }
+        Ok(toolset)
     }
 
-    pub fn from_tools_boxed(tools: Vec<Box<dyn ToolDyn + 'static>>) -> Self {
+    pub fn from_tools_boxed(tools: Vec<Box<dyn ToolDyn + 'static>>) -> Result<Self, ToolSetError> {
         let mut toolset = Self::default();
-        tools.into_iter().for_each(|tool| {
-            toolset.add_tool_boxed(tool);
-        });
-        toolset
+        for tool in tools {
+            toolset.add_tool_boxed(tool)?;
+        }
+        Ok(toolset)
     }
 
     /// Create a toolset builder
@@ -452,23 +693,65 @@ impl ToolSet {
     }
 
     /// Add a tool to the toolset
-    pub fn add_tool(&mut self, tool: impl ToolDyn + 'static) {
-        self.tools
-            .insert(tool.name(), ToolType::Simple(Box::new(tool)));
+    pub fn add_tool(&mut self, tool: impl ToolDyn + 'static) -> Result<(), ToolSetError> {
+        self.insert(ToolType::simple(Arc::new(tool)))
     }
 
     /// Adds a boxed tool to the toolset. Useful for situations when dynamic dispatch is required.
-    pub fn add_tool_boxed(&mut self, tool: Box<dyn ToolDyn>) {
-        self.tools.insert(tool.name(), ToolType::Simple(tool));
+    pub fn add_tool_boxed(&mut self, tool: Box<dyn ToolDyn>) -> Result<(), ToolSetError> {
+        self.insert(ToolType::simple(Arc::from(tool)))
+    }
+
+    fn insert(&mut self, tool: ToolType) -> Result<(), ToolSetError> {
+        match self.tools.entry(tool.name()) {
+            Entry::Occupied(entry) => Err(ToolSetError::DuplicateToolError(entry.key().clone())),
+            Entry::Vacant(entry) => {
+                entry.insert(tool);
+                Ok(())
+            }
+        }
     }
 
     pub fn delete_tool(&mut self, tool_name: &str) {
         let _ = self.tools.remove(tool_name);
     }
 
-    /// Merge another toolset into this one
-    pub fn add_tools(&mut self, toolset: ToolSet) {
+    /// Merge another toolset into this one. If any tool of the other toolset has the same name as
+    /// a tool of this one, no tool is added.
+    pub fn add_tools(&mut self, toolset: ToolSet) -> Result<(), ToolSetError> {
+        if let Some(name) = toolset.tools.keys().find(|name| self.contains(name)) {
+            return Err(ToolSetError::DuplicateToolError(name.clone()));
+        }
         self.tools.extend(toolset.tools);
+        Ok(())
+    }
+
+    /// Merge another toolset into this one, prefixing the names of its tools with the given
+    /// namespace (see [ToolSet::namespaced]).
+    pub fn add_namespaced_tools(
+        &mut self,
+        namespace: &str,
+        toolset: ToolSet,
+    ) -> Result<(), ToolSetError> {
+        self.add_tools(toolset.namespaced(namespace)?)
+    }
+
+    /// Prefix the names of the tools with the given namespace, eg: the `search` tool becomes
+    /// `github__search` in the `github` namespace. The model sees (and calls) the tools by their
+    /// prefixed names.
+    ///
+    /// Fails if the namespace is invalid (see [ToolSetError::InvalidNamespace]).
+    pub fn namespaced(self, namespace: &str) -> Result<Self, ToolSetError> {
+        Ok(Self {
+            tools: self
+                .tools
+                .into_values()
+                .map(|tool| {
+                    let tool = tool.namespaced(namespace)?;
+                    Ok((tool.name(), tool))
+                })
+                .collect::<Result<_, ToolSetError>>()?,
+        })
     }
 
     pub(crate) fn get(&self, toolname: &str) -> Option<&ToolType> {
@@ -484,14 +767,23 @@ impl ToolSet {
         Ok(defs)
     }
 
-    /// Call a tool with the given name and arguments
-    pub async fn call(&self, toolname: &str, args: String) -> Result<String, ToolSetError> {
+    /// Call a tool with the given name and arguments.
+    ///
+    /// The arguments are validated against the schema of the tool before the tool is called.
+    pub async fn call(&self, toolname: &str, args: String) -> Result<ToolOutput, ToolSetError> {
+        self.call_with_context(toolname, args, ToolContext::default())
+            .await
+    }
+
+    /// Call a tool with the given name and arguments, passing the given [ToolContext] to the tool.
+    pub async fn call_with_context(
+        &self,
+        toolname: &str,
+        args: String,
+        context: ToolContext,
+    ) -> Result<ToolOutput, ToolSetError> {
         if let Some(tool) = self.tools.get(toolname) {
-            tracing::debug!(target: "rig",
-                "Calling tool {toolname} with args:\n{}",
-                serde_json::to_string_pretty(&args).unwrap()
-            );
-            Ok(tool.call(args).await?)
+            tool.call_checked(args, context).await
         } else {
             Err(ToolSetError::ToolNotFoundError(toolname.to_string()))
         }
@@ -501,8 +793,8 @@ impl ToolSet {
     pub async fn documents(&self) -> Result<Vec<completion::Document>, ToolSetError> {
         let mut docs = Vec::new();
         for tool in self.tools.values() {
-            match tool {
-                ToolType::Simple(tool) => {
+            match &tool.kind {
+                ToolKind::Simple(tool) => {
                     docs.push(completion::Document {
                         id: tool.name(),
                         text: format!(
@@ -517,7 +809,7 @@ impl ToolSet {
                         additional_props: HashMap::new(),
                     });
                 }
-                ToolType::Embedding(tool) => {
+                ToolKind::Embedding(tool) => {
                     docs.push(completion::Document {
                         id: tool.name(),
                         text: format!(
@@ -544,7 +836,7 @@ impl ToolSet {
         self.tools
             .values()
             .filter_map(|tool_type| {
-                if let ToolType::Embedding(tool) = tool_type {
+                if let ToolKind::Embedding(tool) = &tool_type.kind {
                     Some(ToolSchema::try_from(&**tool))
                 } else {
                     None
@@ -557,27 +849,37 @@ impl ToolSet {
 #[derive(Default)]
 pub struct ToolSetBuilder {
     tools: Vec<ToolType>,
+    namespace: Option<String>,
 }
 
 impl ToolSetBuilder {
     pub fn static_tool(mut self, tool: impl ToolDyn + 'static) -> Self {
-        self.tools.push(ToolType::Simple(Box::new(tool)));
+        self.tools.push(ToolType::simple(Arc::new(tool)));
         self
     }
 
     pub fn dynamic_tool(mut self, tool: impl ToolEmbeddingDyn + 'static) -> Self {
-        self.tools.push(ToolType::Embedding(Box::new(tool)));
+        self.tools.push(ToolType::embedding(Arc::new(tool)));
         self
     }
 
-    pub fn build(self) -> ToolSet {
-        ToolSet {
-            tools: self
-                .tools
-                .into_iter()
-                .map(|tool| (tool.name(), tool))
-                .collect(),
+    /// Prefix the names of the tools with the given namespace (see [ToolSet::namespaced]).
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.namespace = Some(namespace.to_string());
+        self
+    }
+
+    /// Build the toolset. Fails if two tools have the same name, or if the namespace is invalid.
+    pub fn build(self) -> Result<ToolSet, ToolSetError> {
+        let mut toolset = ToolSet::default();
+        for tool in self.tools {
+            let tool = match &self.namespace {
+                Some(namespace) => tool.namespaced(namespace)?,
+                None => tool,
+            };
+            toolset.insert(tool)?;
         }
+        Ok(toolset)
     }
 }
 
@@ -673,8 +975,8 @@ mod tests {
             }
         }
 
-        toolset.add_tool(Adder);
-        toolset.add_tool(Subtract);
+        toolset.add_tool(Adder).unwrap();
+        toolset.add_tool(Subtract).unwrap();
         toolset
     }
 
@@ -693,4 +995,131 @@ mod tests {
         assert!(!toolset.contains("add"));
         assert_eq!(toolset.tools.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_call_with_invalid_arguments() {
+        let toolset = get_test_toolset();
+        assert_eq!(
+            toolset
+                .call("add", r#"{"x": 1, "y": 2}"#.to_string())
+                .await
+                .unwrap()
+                .to_string(),
+            "3"
+        );
+
+        let error = toolset
+            .call("add", r#"{"x": 1, "y": "two"}"#.to_string())
+            .await
+            .unwrap_err();
+        let ToolSetError::ArgumentValidationError(error) = error else {
+            panic!("Expected an ArgumentValidationError, got {error:?}");
+        };
+        assert_eq!(error.violations.len(), 1);
+        assert_eq!(error.violations[0].path, "/y");
+    }
+
+    #[tokio::test]
+    async fn test_schema_compiled_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Clone, Default)]
+        struct Counted {
+            definitions: Arc<AtomicUsize>,
+        }
+
+        impl Tool for Counted {
+            const NAME: &'static str = "counted";
+            type Error = ToolError;
+            type Args = serde_json::Value;
+            type Output = String;
+
+            async fn definition(&self, _prompt: String) -> ToolDefinition {
+                self.definitions.fetch_add(1, Ordering::SeqCst);
+                ToolDefinition {
+                    name: Self::NAME.to_string(),
+                    description: "Counts the fetches of its definition".to_string(),
+                    parameters: json!({
+                        "type": "object",
+                        "properties": { "n": { "type": "number" } },
+                        "required": ["n"]
+                    }),
+                }
+            }
+
+            async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+                Ok(String::new())
+            }
+        }
+
+        let tool = Counted::default();
+        let mut toolset = ToolSet::default();
+        toolset.add_tool(tool.clone()).unwrap();
+        let toolset = toolset.namespaced("ns").unwrap();
+
+        for _ in 0..3 {
+            toolset
+                .call("ns__counted", r#"{"n": 1}"#.to_string())
+                .await
+                .unwrap();
+        }
+        assert!(toolset.call("ns__counted", "{}".to_string()).await.is_err());
+        assert_eq!(tool.definitions.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_and_namespaced_tools() {
+        let mut toolset = get_test_toolset();
+
+        let error = toolset.add_tools(get_test_toolset()).unwrap_err();
+        assert!(
+            matches!(error, ToolSetError::DuplicateToolError(name) if name == "add" || name == "subtract")
+        );
+        assert_eq!(toolset.tools.len(), 2);
+
+        toolset
+            .add_namespaced_tools("remote", get_test_toolset())
+            .unwrap();
+        let mut names = toolset
+            .get_tool_definitions()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|definition| definition.name)
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["add", "remote__add", "remote__subtract", "subtract"]
+        );
+
+        let result = toolset
+            .call("remote__subtract", r#"{"x": 5, "y": 2}"#.to_string())
+            .await
+            .unwrap();
+        assert_eq!(result.to_string(), "3");
+    }
+
+    #[test]
+    fn test_invalid_namespaces() {
+        for namespace in ["", "git hub", "github.com", "ns/tools"] {
+            assert!(
+                matches!(
+                    get_test_toolset().namespaced(namespace),
+                    Err(ToolSetError::InvalidNamespace(_))
+                ),
+                "`{namespace}` should be rejected"
+            );
+        }
+
+        // `subtract` doesn't fit in 64 characters anymore
+        let namespace = "n".repeat(MAX_NAMESPACED_NAME_LENGTH - "__add".len());
+        assert!(matches!(
+            get_test_toolset().namespaced(&namespace),
+            Err(ToolSetError::InvalidNamespace(_))
+        ));
+
+        let toolset = get_test_toolset().namespaced("git-hub_2").unwrap();
+        assert!(toolset.contains("git-hub_2__add"));
+    }
 }
@@ -0,0 +1,962 @@
+//! Generate tools from an [OpenAPI 3](https://spec.openapis.org/oas/v3.1.0) document.
+//!
+//! Every operation of the document becomes an [OpenApiTool], named after its `operationId`, whose
+//! parameters are derived from the path, query and header parameters of the operation, and from
+//! its JSON request body (passed as the `body` argument). Parameters whose name is ambiguous (eg:
+//! an `id` in both the path and the query, or a parameter named `body`) are prefixed with their
+//! location: `path_id`, `query_id`, `header_body`. Local `$ref`s are resolved.
+//!
+//! Requests are sent through any [HttpClientExt] (a [reqwest::Client] by default), with an
+//! optional [RequestAuth] applied to every request (see [ApiAuth] for the common schemes).
+//!
+//! # Example
+//! ```rust
+//! use rig::tool::openapi::{ApiAuth, OpenApiToolsetBuilder};
+//!
+//! # fn run(spec: &str) -> Result<(), rig::tool::openapi::OpenApiError> {
+//! let toolset = OpenApiToolsetBuilder::from_json(spec)?
+//!     .base_url("https://billing.internal/v2")
+//!     .auth(ApiAuth::Bearer(std::env::var("BILLING_TOKEN").unwrap_or_default()))
+//!     // Only keep the operations tagged `invoices`, plus `getCustomer`
+//!     .tags(["invoices"])
+//!     .operation_ids(["getCustomer"])
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! YAML documents can be loaded with [OpenApiToolsetBuilder::from_yaml], which requires the
+//! `openapi-yaml` feature.
+use std::{collections::HashSet, sync::Arc};
+
+use bytes::Bytes;
+use http::{HeaderName, HeaderValue, Method, Request};
+use serde_json::{Map, Value, json};
+use url::Url;
+
+use crate::{
+    completion::ToolDefinition,
+    http_client::{self, HttpClientExt},
+    tool::{ToolDyn, ToolError, ToolOutput, ToolSet, ToolSetError},
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
+
+/// The HTTP methods an operation can be defined for, in the order they are read.
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum OpenApiError {
+    #[error("Failed to parse the OpenAPI document: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[cfg(feature = "openapi-yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "openapi-yaml")))]
+    #[error("Failed to parse the OpenAPI document: {0}")]
+    YamlError(#[from] serde_yaml::Error),
+    #[error("Invalid OpenAPI document: {0}")]
+    InvalidSpec(String),
+    #[error("No base URL: the document has no server, set one with `base_url`")]
+    MissingBaseUrl,
+    #[error("Invalid base URL: {0}")]
+    InvalidBaseUrl(#[from] url::ParseError),
+    #[error("Invalid arguments: {0}")]
+    InvalidArguments(String),
+    #[error("HTTP error: {0}")]
+    HttpError(#[from] http_client::Error),
+    #[error("ToolSetError: {0}")]
+    ToolSetError(#[from] ToolSetError),
+}
+
+/// Authentication applied to every request sent by an [OpenApiTool], eg: adding a header with a
+/// token fetched from a secret store. See [ApiAuth] for the common schemes.
+pub trait RequestAuth: WasmCompatSend + WasmCompatSync {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut Request<Vec<u8>>,
+    ) -> WasmBoxedFuture<'a, Result<(), http_client::Error>>;
+}
+
+/// The common authentication schemes of HTTP APIs.
+#[derive(Debug, Clone)]
+pub enum ApiAuth {
+    /// An `Authorization: Bearer <token>` header
+    Bearer(String),
+    /// A header with the given name and value, eg: `X-API-Key`
+    Header { name: String, value: String },
+    /// A query parameter with the given name and value, eg: `api_key`
+    Query { name: String, value: String },
+}
+
+impl RequestAuth for ApiAuth {
+    fn authenticate<'a>(
+        &'a self,
+        request: &'a mut Request<Vec<u8>>,
+    ) -> WasmBoxedFuture<'a, Result<(), http_client::Error>> {
+        Box::pin(async move {
+            match self {
+                ApiAuth::Bearer(token) => {
+                    http_client::bearer_auth_header(request.headers_mut(), token)?;
+                }
+                ApiAuth::Header { name, value } => {
+                    let name = HeaderName::from_bytes(name.as_bytes())
+                        .map_err(|e| http_client::Error::Protocol(e.into()))?;
+                    request
+                        .headers_mut()
+                        .insert(name, HeaderValue::from_str(value)?);
+                }
+                ApiAuth::Query { name, value } => {
+                    let mut url = Url::parse(&request.uri().to_string())
+                        .map_err(|e| http_client::Error::Instance(e.into()))?;
+                    url.query_pairs_mut().append_pair(name, value);
+                    *request.uri_mut() =
+                        url.as_str().parse().map_err(|e: http::uri::InvalidUri| {
+                            http_client::Error::Protocol(e.into())
+                        })?;
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Where the value of a parameter goes in the request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+}
+
+impl ParameterLocation {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ParameterLocation::Path => "path",
+            ParameterLocation::Query => "query",
+            ParameterLocation::Header => "header",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Parameter {
+    name: String,
+    location: ParameterLocation,
+    /// The name of the argument holding the value of the parameter. It is the name of the
+    /// parameter, prefixed with its location (eg: `query_id`) if the name is ambiguous.
+    property: String,
+}
+
+/// A tool calling an operation of an HTTP API described by an OpenAPI document (see
+/// [OpenApiToolsetBuilder]).
+pub struct OpenApiTool<H = reqwest::Client> {
+    definition: ToolDefinition,
+    method: Method,
+    base_url: Url,
+    path: String,
+    parameters: Vec<Parameter>,
+    has_body: bool,
+    client: H,
+    auth: Option<Arc<dyn RequestAuth>>,
+}
+
+impl<H> OpenApiTool<H>
+where
+    H: HttpClientExt + 'static,
+{
+    /// Build the request of the operation from the arguments given by the model.
+    fn request(&self, args: &Map<String, Value>) -> Result<Request<Vec<u8>>, OpenApiError> {
+        let mut url = self.base_url.clone();
+        {
+            let mut segments = url
+                .path_segments_mut()
+                .map_err(|_| OpenApiError::InvalidSpec("the base URL can't have a path".into()))?;
+            segments.pop_if_empty();
+            for segment in self.path.split('/').filter(|segment| !segment.is_empty()) {
+                let mut segment = segment.to_string();
+                for parameter in &self.parameters {
+                    if parameter.location != ParameterLocation::Path {
+                        continue;
+                    }
+                    let placeholder = format!("{{{}}}", parameter.name);
+                    if segment.contains(&placeholder) {
+                        let value = args.get(&parameter.property).ok_or_else(|| {
+                            OpenApiError::InvalidArguments(format!(
+                                "missing path parameter `{}`",
+                                parameter.name
+                            ))
+                        })?;
+                        segment = segment.replace(&placeholder, &to_string(value));
+                    }
+                }
+                segments.push(&segment);
+            }
+        }
+
+        for parameter in &self.parameters {
+            if parameter.location != ParameterLocation::Query {
+                continue;
+            }
+            match args.get(&parameter.property) {
+                None | Some(Value::Null) => {}
+                // Arrays are sent as repeated parameters (the default `form` style)
+                Some(Value::Array(values)) => {
+                    for value in values {
+                        url.query_pairs_mut()
+                            .append_pair(&parameter.name, &to_string(value));
+                    }
+                }
+                Some(value) => {
+                    url.query_pairs_mut()
+                        .append_pair(&parameter.name, &to_string(value));
+                }
+            }
+        }
+
+        let mut request = Request::builder()
+            .method(self.method.clone())
+            .uri(url.as_str());
+
+        for parameter in &self.parameters {
+            if parameter.location != ParameterLocation::Header {
+                continue;
+            }
+            if let Some(value) = args
+                .get(&parameter.property)
+                .filter(|value| !value.is_null())
+            {
+                request = request.header(parameter.name.as_str(), to_string(value));
+            }
+        }
+
+        let body = match args.get("body").filter(|_| self.has_body) {
+            Some(body) => {
+                request = request.header(http::header::CONTENT_TYPE, "application/json");
+                serde_json::to_vec(body)?
+            }
+            None => Vec::new(),
+        };
+
+        Ok(request.body(body).map_err(http_client::Error::Protocol)?)
+    }
+
+    async fn send(&self, args: String) -> Result<ToolOutput, OpenApiError> {
+        let args = match serde_json::from_str(&args)? {
+            Value::Object(args) => args,
+            Value::Null => Map::new(),
+            _ => {
+                return Err(OpenApiError::InvalidArguments(
+                    "the arguments must be an object".into(),
+                ));
+            }
+        };
+
+        let mut request = self.request(&args)?;
+        if let Some(auth) = &self.auth {
+            auth.authenticate(&mut request).await?;
+        }
+
+        let response = self.client.send::<_, Bytes>(request).await?;
+        let body = response.into_body().await?;
+
+        Ok(ToolOutput::text(String::from_utf8_lossy(&body)))
+    }
+}
+
+impl<H> ToolDyn for OpenApiTool<H>
+where
+    H: HttpClientExt + 'static,
+{
+    fn name(&self) -> String {
+        self.definition.name.clone()
+    }
+
+    fn definition<'a>(&'a self, _prompt: String) -> WasmBoxedFuture<'a, ToolDefinition> {
+        Box::pin(async move { self.definition.clone() })
+    }
+
+    fn call<'a>(&'a self, args: String) -> WasmBoxedFuture<'a, Result<ToolOutput, ToolError>> {
+        Box::pin(async move {
+            self.send(args)
+                .await
+                .map_err(|e| ToolError::ToolCallError(Box::new(e)))
+        })
+    }
+}
+
+/// Loads the operations of an OpenAPI 3 document as tools.
+pub struct OpenApiToolsetBuilder<H = reqwest::Client> {
+    spec: Value,
+    base_url: Option<String>,
+    tags: HashSet<String>,
+    operation_ids: HashSet<String>,
+    client: H,
+    auth: Option<Arc<dyn RequestAuth>>,
+}
+
+impl OpenApiToolsetBuilder<reqwest::Client> {
+    /// Load an OpenAPI document in JSON.
+    pub fn from_json(spec: &str) -> Result<Self, OpenApiError> {
+        Self::from_value(serde_json::from_str(spec)?)
+    }
+
+    /// Load an OpenAPI document in YAML.
+    #[cfg(feature = "openapi-yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "openapi-yaml")))]
+    pub fn from_yaml(spec: &str) -> Result<Self, OpenApiError> {
+        Self::from_value(serde_yaml::from_str(spec)?)
+    }
+
+    /// Load an already parsed OpenAPI document.
+    pub fn from_value(spec: Value) -> Result<Self, OpenApiError> {
+        let is_openapi_3 = spec
+            .get("openapi")
+            .and_then(Value::as_str)
+            .is_some_and(|version| version.starts_with("3."));
+        if !is_openapi_3 {
+            return Err(OpenApiError::InvalidSpec(
+                "only OpenAPI 3 documents are supported".into(),
+            ));
+        }
+
+        Ok(Self {
+            spec,
+            base_url: None,
+            tags: HashSet::new(),
+            operation_ids: HashSet::new(),
+            client: reqwest::Client::new(),
+            auth: None,
+        })
+    }
+}
+
+impl<H> OpenApiToolsetBuilder<H>
+where
+    H: HttpClientExt + Clone + 'static,
+{
+    /// Set the URL the paths of the operations are relative to. Defaults to the URL of the first
+    /// server of the document.
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// Only load the operations with one of the given tags (or one of the operation ids given to
+    /// [OpenApiToolsetBuilder::operation_ids]).
+    pub fn tags<S: Into<String>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only load the operations with one of the given ids (or one of the tags given to
+    /// [OpenApiToolsetBuilder::tags]).
+    pub fn operation_ids<S: Into<String>>(
+        mut self,
+        operation_ids: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.operation_ids
+            .extend(operation_ids.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the authentication applied to every request.
+    pub fn auth(mut self, auth: impl RequestAuth + 'static) -> Self {
+        self.auth = Some(Arc::new(auth));
+        self
+    }
+
+    /// Set the HTTP client used to send the requests.
+    pub fn http_client<H2>(self, client: H2) -> OpenApiToolsetBuilder<H2>
+    where
+        H2: HttpClientExt + Clone + 'static,
+    {
+        OpenApiToolsetBuilder {
+            spec: self.spec,
+            base_url: self.base_url,
+            tags: self.tags,
+            operation_ids: self.operation_ids,
+            client,
+            auth: self.auth,
+        }
+    }
+
+    fn is_selected(&self, operation: &Value) -> bool {
+        if self.tags.is_empty() && self.operation_ids.is_empty() {
+            return true;
+        }
+
+        let has_tag = operation
+            .get("tags")
+            .and_then(Value::as_array)
+            .is_some_and(|tags| {
+                tags.iter()
+                    .filter_map(Value::as_str)
+                    .any(|tag| self.tags.contains(tag))
+            });
+        let has_id = operation
+            .get("operationId")
+            .and_then(Value::as_str)
+            .is_some_and(|id| self.operation_ids.contains(id));
+
+        has_tag || has_id
+    }
+
+    fn resolved_base_url(&self) -> Result<Url, OpenApiError> {
+        if let Some(base_url) = &self.base_url {
+            return Ok(Url::parse(base_url)?);
+        }
+
+        let server = self
+            .spec
+            .pointer("/servers/0")
+            .ok_or(OpenApiError::MissingBaseUrl)?;
+        let mut url = server
+            .get("url")
+            .and_then(Value::as_str)
+            .ok_or(OpenApiError::MissingBaseUrl)?
+            .to_string();
+
+        // Server variables, eg: `https://{region}.example.com`, take their default value
+        if let Some(variables) = server.get("variables").and_then(Value::as_object) {
+            for (name, variable) in variables {
+                if let Some(default) = variable.get("default").and_then(Value::as_str) {
+                    url = url.replace(&format!("{{{name}}}"), default);
+                }
+            }
+        }
+
+        // Relative server URLs can't be resolved without knowing where the document came from
+        Url::parse(&url).map_err(|_| OpenApiError::MissingBaseUrl)
+    }
+
+    /// Create a tool for every selected operation of the document.
+    pub fn tools(&self) -> Result<Vec<OpenApiTool<H>>, OpenApiError> {
+        let base_url = self.resolved_base_url()?;
+        let paths = self
+            .spec
+            .get("paths")
+            .and_then(Value::as_object)
+            .ok_or_else(|| OpenApiError::InvalidSpec("the document has no `paths`".into()))?;
+
+        let mut tools = Vec::new();
+        for (path, path_item) in paths {
+            let path_item = resolve(&self.spec, path_item, &mut Vec::new());
+            let shared_parameters = path_item
+                .get("parameters")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+
+            for method in METHODS {
+                let Some(operation) = path_item.get(method) else {
+                    continue;
+                };
+                if !self.is_selected(operation) {
+                    continue;
+                }
+
+                match self.tool(&base_url, path, method, operation, &shared_parameters) {
+                    Ok(tool) => tools.push(tool),
+                    Err(e) => {
+                        tracing::warn!("Skipping operation {} {path}: {e}", method.to_uppercase())
+                    }
+                }
+            }
+        }
+
+        Ok(tools)
+    }
+
+    /// Create a [ToolSet] with a tool for every selected operation of the document.
+    pub fn build(&self) -> Result<ToolSet, OpenApiError> {
+        let mut toolset = ToolSet::default();
+        for tool in self.tools()? {
+            toolset.add_tool(tool)?;
+        }
+        Ok(toolset)
+    }
+
+    fn tool(
+        &self,
+        base_url: &Url,
+        path: &str,
+        method: &str,
+        operation: &Value,
+        shared_parameters: &[Value],
+    ) -> Result<OpenApiTool<H>, OpenApiError> {
+        let name = operation
+            .get("operationId")
+            .and_then(Value::as_str)
+            .map(tool_name)
+            .unwrap_or_else(|| tool_name(&format!("{method}_{path}")));
+
+        let summary = [operation.get("summary"), operation.get("description")]
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let description = if summary.is_empty() {
+            format!("{} {path}", method.to_uppercase())
+        } else {
+            summary
+        };
+
+        let mut parameters = Vec::<(Parameter, Value, bool)>::new();
+
+        // Operation parameters override the parameters shared by the operations of the path
+        let operation_parameters = operation
+            .get("parameters")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for parameter in operation_parameters.iter().chain(shared_parameters) {
+            let parameter = resolve(&self.spec, parameter, &mut Vec::new());
+            let name = parameter
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| OpenApiError::InvalidSpec("a parameter has no name".into()))?;
+            let location = match parameter.get("in").and_then(Value::as_str) {
+                Some("path") => ParameterLocation::Path,
+                Some("query") => ParameterLocation::Query,
+                Some("header") => ParameterLocation::Header,
+                // Cookies are left to the HTTP client
+                _ => continue,
+            };
+            if parameters
+                .iter()
+                .any(|(p, ..)| p.name == name && p.location == location)
+            {
+                continue;
+            }
+
+            let mut schema = parameter
+                .get("schema")
+                .cloned()
+                .unwrap_or_else(|| json!({ "type": "string" }));
+            if let (Value::Object(schema), Some(description)) =
+                (&mut schema, parameter.get("description"))
+            {
+                schema
+                    .entry("description")
+                    .or_insert_with(|| description.clone());
+            }
+
+            let is_required = location == ParameterLocation::Path
+                || parameter.get("required").and_then(Value::as_bool) == Some(true);
+            parameters.push((
+                Parameter {
+                    name: name.to_string(),
+                    location,
+                    property: name.to_string(),
+                },
+                schema,
+                is_required,
+            ));
+        }
+
+        // Parameters sharing a name (in different locations), or named like the request body, are
+        // prefixed with their location
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let names = parameters
+            .iter()
+            .map(|(parameter, ..)| parameter.name.clone())
+            .collect::<Vec<_>>();
+        for (parameter, schema, is_required) in &mut parameters {
+            let is_ambiguous = parameter.name == "body"
+                || names.iter().filter(|name| **name == parameter.name).count() > 1;
+            if is_ambiguous {
+                parameter.property = format!("{}_{}", parameter.location.as_str(), parameter.name);
+            }
+            if properties.contains_key(&parameter.property) {
+                return Err(OpenApiError::InvalidSpec(format!(
+                    "the parameter `{}` conflicts with another parameter",
+                    parameter.property
+                )));
+            }
+
+            if *is_required {
+                required.push(Value::String(parameter.property.clone()));
+            }
+            properties.insert(parameter.property.clone(), schema.take());
+        }
+        let parameters = parameters
+            .into_iter()
+            .map(|(parameter, ..)| parameter)
+            .collect::<Vec<_>>();
+
+        let mut has_body = false;
+        if let Some(request_body) = operation.get("requestBody") {
+            let request_body = resolve(&self.spec, request_body, &mut Vec::new());
+            let is_required = request_body.get("required").and_then(Value::as_bool) == Some(true);
+            let schema = request_body
+                .get("content")
+                .and_then(Value::as_object)
+                .and_then(|content| {
+                    content
+                        .iter()
+                        .find(|(media_type, _)| {
+                            *media_type == "application/json" || media_type.ends_with("+json")
+                        })
+                        .map(|(_, media_type)| {
+                            media_type.get("schema").cloned().unwrap_or_default()
+                        })
+                });
+
+            match schema {
+                Some(mut schema) => {
+                    if let (Value::Object(schema), Some(description)) =
+                        (&mut schema, request_body.get("description"))
+                    {
+                        schema
+                            .entry("description")
+                            .or_insert_with(|| description.clone());
+                    }
+                    // Parameters named `body` were prefixed with their location above
+                    properties.insert("body".to_string(), schema);
+                    if is_required {
+                        required.push(Value::String("body".to_string()));
+                    }
+                    has_body = true;
+                }
+                None if is_required => {
+                    return Err(OpenApiError::InvalidSpec(
+                        "only JSON request bodies are supported".into(),
+                    ));
+                }
+                None => {}
+            }
+        }
+
+        Ok(OpenApiTool {
+            definition: ToolDefinition {
+                name,
+                description,
+                parameters: json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                }),
+            },
+            method: method
+                .to_uppercase()
+                .parse()
+                .map_err(|e: http::method::InvalidMethod| {
+                    OpenApiError::InvalidSpec(e.to_string())
+                })?,
+            base_url: base_url.clone(),
+            path: path.to_string(),
+            parameters,
+            has_body,
+            client: self.client.clone(),
+            auth: self.auth.clone(),
+        })
+    }
+}
+
+/// Inline the local `$ref`s (eg: `#/components/schemas/Pet`) of a value. `resolving` holds the
+/// references being inlined: a reference to one of them (a recursive schema) is replaced with an
+/// empty schema, as are external references.
+fn resolve(spec: &Value, value: &Value, resolving: &mut Vec<String>) -> Value {
+    match value {
+        Value::Object(object) => {
+            if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+                let target = reference
+                    .strip_prefix('#')
+                    .and_then(|pointer| spec.pointer(pointer));
+                return match target {
+                    Some(target) if !resolving.iter().any(|r| r == reference) => {
+                        resolving.push(reference.to_string());
+                        let resolved = resolve(spec, target, resolving);
+                        resolving.pop();
+                        resolved
+                    }
+                    _ => json!({}),
+                };
+            }
+
+            Value::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), resolve(spec, value, resolving)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| resolve(spec, value, resolving))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Tool names can only contain letters, digits, `_` and `-`, and are at most 64 characters long.
+fn tool_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .take(64)
+        .collect()
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::http_client::{LazyBody, MultipartForm, Response, StreamingResponse};
+
+    type RecordedRequests = Arc<Mutex<Vec<(Request<Bytes>, String)>>>;
+
+    /// Records the requests it is sent, and answers them with their URL.
+    #[derive(Clone, Default)]
+    struct MockHttpClient {
+        requests: RecordedRequests,
+    }
+
+    impl HttpClientExt for MockHttpClient {
+        fn send<T, U>(
+            &self,
+            req: Request<T>,
+        ) -> impl Future<Output = http_client::Result<Response<LazyBody<U>>>> + WasmCompatSend + 'static
+        where
+            T: Into<Bytes>,
+            T: WasmCompatSend,
+            U: From<Bytes>,
+            U: WasmCompatSend + 'static,
+        {
+            let (parts, body) = req.into_parts();
+            let uri = parts.uri.to_string();
+            self.requests
+                .lock()
+                .unwrap()
+                .push((Request::from_parts(parts, body.into()), uri.clone()));
+
+            let body: LazyBody<U> = Box::pin(async move { Ok(U::from(Bytes::from(uri))) });
+            std::future::ready(Response::builder().body(body).map_err(Into::into))
+        }
+
+        fn send_multipart<U>(
+            &self,
+            _req: Request<MultipartForm>,
+        ) -> impl Future<Output = http_client::Result<Response<LazyBody<U>>>> + WasmCompatSend + 'static
+        where
+            U: From<Bytes>,
+            U: WasmCompatSend + 'static,
+        {
+            std::future::ready(Err(http_client::Error::InvalidStatusCode(
+                http::StatusCode::NOT_IMPLEMENTED,
+            )))
+        }
+
+        fn send_streaming<T>(
+            &self,
+            _req: Request<T>,
+        ) -> impl Future<Output = http_client::Result<StreamingResponse>> + WasmCompatSend
+        where
+            T: Into<Bytes>,
+        {
+            std::future::ready(Err(http_client::Error::InvalidStatusCode(
+                http::StatusCode::NOT_IMPLEMENTED,
+            )))
+        }
+    }
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Pets", "version": "1.0" },
+            "servers": [{ "url": "https://{env}.pets.test/v1", "variables": { "env": { "default": "api" } } }],
+            "paths": {
+                "/pets/{petId}": {
+                    "parameters": [{ "name": "petId", "in": "path", "required": true, "schema": { "type": "integer" } }],
+                    "get": {
+                        "operationId": "getPet",
+                        "tags": ["pets"],
+                        "summary": "Get a pet",
+                        "parameters": [
+                            { "name": "fields", "in": "query", "schema": { "type": "array", "items": { "type": "string" } } },
+                            { "$ref": "#/components/parameters/RequestId" }
+                        ]
+                    },
+                    "put": {
+                        "operationId": "update pet",
+                        "tags": ["pets"],
+                        "requestBody": {
+                            "required": true,
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } } }
+                        }
+                    }
+                },
+                "/stores": {
+                    "get": { "operationId": "listStores", "tags": ["stores"] }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "RequestId": { "name": "X-Request-Id", "in": "header", "description": "Tracing id", "schema": { "type": "string" } }
+                },
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": { "name": { "type": "string" }, "parent": { "$ref": "#/components/schemas/Pet" } },
+                        "required": ["name"]
+                    }
+                }
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_openapi_tools() {
+        let client = MockHttpClient::default();
+        let builder = OpenApiToolsetBuilder::from_value(spec())
+            .unwrap()
+            .tags(["pets"])
+            .auth(ApiAuth::Query {
+                name: "key".into(),
+                value: "secret".into(),
+            })
+            .http_client(client.clone());
+
+        let tools = builder.tools().unwrap();
+        let names = tools.iter().map(|tool| tool.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["getPet", "update_pet"]);
+
+        let definition = tools[0].definition(String::new()).await;
+        assert_eq!(definition.description, "Get a pet");
+        assert_eq!(
+            definition.parameters["properties"]["X-Request-Id"],
+            json!({ "type": "string", "description": "Tracing id" })
+        );
+        assert_eq!(definition.parameters["required"], json!(["petId"]));
+
+        // Recursive schemas are cut off instead of being expanded forever
+        let definition = tools[1].definition(String::new()).await;
+        assert_eq!(definition.parameters["required"], json!(["petId", "body"]));
+        assert_eq!(
+            definition.parameters["properties"]["body"]["properties"]["parent"],
+            json!({})
+        );
+
+        let output = tools[0]
+            .call(
+                json!({ "petId": 7, "fields": ["name", "age"], "X-Request-Id": "abc" }).to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            output.to_string(),
+            "https://api.pets.test/v1/pets/7?fields=name&fields=age&key=secret"
+        );
+
+        tools[1]
+            .call(json!({ "petId": 7, "body": { "name": "Rex" } }).to_string())
+            .await
+            .unwrap();
+
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests[0].0.headers()["X-Request-Id"], "abc");
+        assert_eq!(requests[1].0.method(), Method::PUT);
+        assert_eq!(requests[1].0.body().as_ref(), br#"{"name":"Rex"}"#);
+        assert_eq!(requests[1].0.headers()["content-type"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_openapi_ambiguous_parameters() {
+        let client = MockHttpClient::default();
+        let tools = OpenApiToolsetBuilder::from_value(json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Items", "version": "1.0" },
+            "servers": [{ "url": "https://items.test" }],
+            "paths": {
+                "/items/{id}": {
+                    "post": {
+                        "operationId": "updateItem",
+                        "parameters": [
+                            { "name": "id", "in": "path", "schema": { "type": "integer" } },
+                            { "name": "id", "in": "query", "schema": { "type": "string" } },
+                            { "name": "body", "in": "header", "schema": { "type": "string" } }
+                        ],
+                        "requestBody": {
+                            "content": { "application/json": { "schema": { "type": "object" } } }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+        .http_client(client.clone())
+        .tools()
+        .unwrap();
+
+        let definition = tools[0].definition(String::new()).await;
+        let mut properties = definition.parameters["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        properties.sort();
+        assert_eq!(
+            properties,
+            vec!["body", "header_body", "path_id", "query_id"]
+        );
+        assert_eq!(definition.parameters["required"], json!(["path_id"]));
+
+        let output = tools[0]
+            .call(
+                json!({
+                    "path_id": 7,
+                    "query_id": "abc",
+                    "header_body": "raw",
+                    "body": { "name": "Rex" }
+                })
+                .to_string(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.to_string(), "https://items.test/items/7?id=abc");
+
+        let requests = client.requests.lock().unwrap();
+        assert_eq!(requests[0].0.headers()["body"], "raw");
+        assert_eq!(requests[0].0.body().as_ref(), br#"{"name":"Rex"}"#);
+    }
+
+    #[test]
+    fn test_openapi_toolset_filters() {
+        let toolset = OpenApiToolsetBuilder::from_value(spec())
+            .unwrap()
+            .operation_ids(["listStores"])
+            .build()
+            .unwrap();
+        assert!(toolset.contains("listStores"));
+        assert_eq!(toolset.tools.len(), 1);
+
+        let error = OpenApiToolsetBuilder::from_value(json!({ "swagger": "2.0" }))
+            .err()
+            .unwrap();
+        assert!(matches!(error, OpenApiError::InvalidSpec(_)));
+    }
+}
@@ -0,0 +1,176 @@
+//! The output of a tool call.
+//!
+//! Tools return a [ToolOutput], which can carry text, images and documents. The output flows
+//! unchanged through the [ToolSet](crate::tool::ToolSet) and the
+//! [ToolServer](crate::tool::server::ToolServer), and is sent back to the model as the content of
+//! the [ToolResult](crate::completion::message::ToolResult) of the call.
+//!
+//! The output of a [Tool](crate::tool::Tool) is converted using [IntoToolOutput]: any serializable
+//! output is sent as JSON text, while a tool can return images or documents by using [ToolOutput]
+//! as its output type.
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     completion::{ToolDefinition, message::{ImageMediaType, ToolResultContent}},
+//!     tool::{Tool, ToolOutput},
+//! };
+//!
+//! struct Screenshot;
+//!
+//! impl Tool for Screenshot {
+//!     const NAME: &'static str = "screenshot";
+//!
+//!     type Error = std::io::Error;
+//!     type Args = serde_json::Value;
+//!     type Output = ToolOutput;
+//!
+//!     async fn definition(&self, _prompt: String) -> ToolDefinition {
+//!         ToolDefinition {
+//!             name: "screenshot".to_string(),
+//!             description: "Take a screenshot of the screen".to_string(),
+//!             parameters: serde_json::json!({ "type": "object" }),
+//!         }
+//!     }
+//!
+//!     async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+//!         let png = std::fs::read("screenshot.png")?;
+//!         Ok(ToolOutput::text("Here is the screenshot:").with_content(
+//!             ToolResultContent::image_raw(png, Some(ImageMediaType::PNG), None),
+//!         ))
+//!     }
+//! }
+//! ```
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{OneOrMany, completion::message::ToolResultContent};
+
+/// The output of a tool call: one or more parts of text, images and documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolOutput {
+    content: OneOrMany<ToolResultContent>,
+}
+
+impl ToolOutput {
+    pub fn new(content: OneOrMany<ToolResultContent>) -> Self {
+        Self { content }
+    }
+
+    /// Create a text-only output.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(OneOrMany::one(ToolResultContent::text(text)))
+    }
+
+    /// Add a part (text, image or document) to the output.
+    pub fn with_content(mut self, content: ToolResultContent) -> Self {
+        self.content.push(content);
+        self
+    }
+
+    pub fn content(&self) -> &OneOrMany<ToolResultContent> {
+        &self.content
+    }
+
+    pub fn into_content(self) -> OneOrMany<ToolResultContent> {
+        self.content
+    }
+
+    /// Returns true if the output only contains text.
+    pub fn is_text(&self) -> bool {
+        self.content
+            .iter()
+            .all(|content| matches!(content, ToolResultContent::Text(_)))
+    }
+}
+
+/// Renders the output as text, using placeholders for images and documents.
+impl fmt::Display for ToolOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, content) in self.content.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match content {
+                ToolResultContent::Text(text) => write!(f, "{}", text.text)?,
+                ToolResultContent::Image(_) => write!(f, "[Image]")?,
+                ToolResultContent::Document(_) => write!(f, "[Document]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<String> for ToolOutput {
+    fn from(text: String) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<&str> for ToolOutput {
+    fn from(text: &str) -> Self {
+        Self::text(text)
+    }
+}
+
+impl From<ToolResultContent> for ToolOutput {
+    fn from(content: ToolResultContent) -> Self {
+        Self::new(OneOrMany::one(content))
+    }
+}
+
+impl From<OneOrMany<ToolResultContent>> for ToolOutput {
+    fn from(content: OneOrMany<ToolResultContent>) -> Self {
+        Self::new(content)
+    }
+}
+
+impl From<ToolOutput> for OneOrMany<ToolResultContent> {
+    fn from(output: ToolOutput) -> Self {
+        output.content
+    }
+}
+
+/// Conversion of the output of a [Tool](crate::tool::Tool) into a [ToolOutput].
+///
+/// Serializable outputs are converted to JSON text, while a [ToolOutput] is passed through as is.
+pub trait IntoToolOutput {
+    fn into_tool_output(self) -> Result<ToolOutput, serde_json::Error>;
+}
+
+impl<T: Serialize> IntoToolOutput for T {
+    fn into_tool_output(self) -> Result<ToolOutput, serde_json::Error> {
+        serde_json::to_string(&self).map(ToolOutput::text)
+    }
+}
+
+impl IntoToolOutput for ToolOutput {
+    fn into_tool_output(self) -> Result<ToolOutput, serde_json::Error> {
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IntoToolOutput, ToolOutput};
+    use crate::completion::message::{DocumentMediaType, ToolResultContent};
+
+    #[test]
+    fn test_into_tool_output() {
+        let output = 42.into_tool_output().unwrap();
+        assert!(output.is_text());
+        assert_eq!(output.to_string(), "42");
+
+        let output = ToolOutput::text("Here is the report:")
+            .with_content(ToolResultContent::document_base64(
+                "JVBERi0xLjQ=",
+                Some(DocumentMediaType::PDF),
+            ))
+            .into_tool_output()
+            .unwrap();
+        assert!(!output.is_text());
+        assert_eq!(output.content().len(), 2);
+        assert_eq!(output.to_string(), "Here is the report:\n[Document]");
+    }
+}
@@ -1,12 +1,64 @@
-use futures::{StreamExt, TryStreamExt, channel::oneshot::Canceled, stream};
-use tokio::sync::mpsc::{Sender, error::SendError};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
+use futures::{
+    StreamExt, TryStreamExt,
+    channel::oneshot::Canceled,
+    future::{self, Either},
+    stream,
+};
+use futures_timer::Delay;
+use tokio::sync::{
+    Semaphore,
+    mpsc::{Sender, error::SendError},
+};
+
+#[cfg(feature = "rmcp")]
+use tokio::sync::mpsc::WeakSender;
+
+#[cfg(feature = "rmcp")]
+use crate::tool::mcp_client::McpClient;
 use crate::{
     completion::{CompletionError, ToolDefinition},
-    tool::{Tool, ToolDyn, ToolError, ToolSet, ToolSetError},
+    tool::{
+        ArgumentValidationError, Tool, ToolContext, ToolDyn, ToolError, ToolOutput, ToolSet,
+        ToolSetError, ToolType,
+    },
     vector_store::{VectorSearchRequest, VectorStoreError, VectorStoreIndexDyn, request::Filter},
+    wasm_compat::WasmCompatSend,
 };
 
+/// Options applied to every call of a tool registered on a [ToolServer]
+/// (see [ToolServer::tool_with_options]).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolCallOptions {
+    /// How long a single attempt of the tool call can take before it is abandoned.
+    pub timeout: Option<Duration>,
+    /// How many times a failed (or timed out) tool call is retried.
+    pub max_retries: usize,
+    /// How long to wait before retrying a failed tool call.
+    pub retry_delay: Duration,
+}
+
+impl ToolCallOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the timeout of a single attempt of the tool call.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry failed tool calls up to `max_retries` times, waiting `delay` between attempts.
+    /// Calls with invalid arguments are never retried.
+    pub fn retries(mut self, max_retries: usize, delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = delay;
+        self
+    }
+}
+
 pub struct ToolServer {
     /// A list of static tool names.
     /// These tools will always exist on the tool server for as long as they are not deleted.
@@ -15,6 +67,13 @@ pub struct ToolServer {
     dynamic_tools: Vec<(usize, Box<dyn VectorStoreIndexDyn + Send + Sync>)>,
     /// The toolset where tools are called (to be executed).
     toolset: ToolSet,
+    /// Timeouts and retry policies of the tools registered with options.
+    call_options: HashMap<String, ToolCallOptions>,
+    /// Limits how many tool calls are executed at the same time (unlimited if not set).
+    call_limit: Option<Arc<Semaphore>>,
+    /// MCP servers whose tools are registered when the tool server starts.
+    #[cfg(feature = "rmcp")]
+    mcp_clients: Vec<McpClient>,
 }
 
 impl Default for ToolServer {
@@ -29,9 +88,21 @@ impl ToolServer {
             static_tool_names: Vec::new(),
             dynamic_tools: Vec::new(),
             toolset: ToolSet::default(),
+            call_options: HashMap::new(),
+            call_limit: None,
+            #[cfg(feature = "rmcp")]
+            mcp_clients: Vec::new(),
         }
     }
 
+    /// Set the maximum number of tool calls executed at the same time (unlimited by default).
+    /// Calls over the limit wait for a running call to finish. A limit of 0 is raised to 1, as no
+    /// call could ever run otherwise.
+    pub fn max_concurrent_calls(mut self, max_concurrent_calls: usize) -> Self {
+        self.call_limit = Some(Arc::new(Semaphore::new(max_concurrent_calls.max(1))));
+        self
+    }
+
     pub(crate) fn static_tool_names(mut self, names: Vec<String>) -> Self {
         self.static_tool_names = names;
         self
@@ -51,106 +122,246 @@ impl ToolServer {
     }
 
     /// Add a static tool to the agent
-    pub fn tool(mut self, tool: impl Tool + 'static) -> Self {
+    ///
+    /// # Panics
+    /// If a tool with the same name was already added (see [ToolServer::try_tool]).
+    pub fn tool(self, tool: impl Tool + 'static) -> Self {
+        self.try_tool(tool).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add a static tool to the tool server. Fails if a tool with the same name was already added.
+    pub fn try_tool(mut self, tool: impl Tool + 'static) -> Result<Self, ToolSetError> {
         let toolname = tool.name();
-        self.toolset.add_tool(tool);
+        self.toolset.add_tool(tool)?;
         self.static_tool_names.push(toolname);
-        self
+        Ok(self)
+    }
+
+    /// Add a static tool to the tool server, with a timeout and/or retry policy applied to its calls
+    ///
+    /// # Panics
+    /// If a tool with the same name was already added (see [ToolServer::try_tool_with_options]).
+    pub fn tool_with_options(self, tool: impl Tool + 'static, options: ToolCallOptions) -> Self {
+        self.try_tool_with_options(tool, options)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add a static tool to the tool server, with a timeout and/or retry policy applied to its
+    /// calls. Fails if a tool with the same name was already added.
+    pub fn try_tool_with_options(
+        mut self,
+        tool: impl Tool + 'static,
+        options: ToolCallOptions,
+    ) -> Result<Self, ToolSetError> {
+        let toolname = tool.name();
+        self.toolset.add_tool(tool)?;
+        self.call_options.insert(toolname.clone(), options);
+        self.static_tool_names.push(toolname);
+        Ok(self)
+    }
+
+    /// Add an MCP tool (from `rmcp`) to the agent
+    ///
+    /// # Panics
+    /// If a tool with the same name was already added (see [ToolServer::try_rmcp_tool]).
+    #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+    #[cfg(feature = "rmcp")]
+    pub fn rmcp_tool(self, tool: rmcp::model::Tool, client: rmcp::service::ServerSink) -> Self {
+        self.try_rmcp_tool(tool, client)
+            .unwrap_or_else(|e| panic!("{e}"))
     }
 
-    // Add an MCP tool (from `rmcp`) to the agent
+    /// Add an MCP tool (from `rmcp`) to the tool server. Fails if a tool with the same name was
+    /// already added.
     #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
     #[cfg(feature = "rmcp")]
-    pub fn rmcp_tool(mut self, tool: rmcp::model::Tool, client: rmcp::service::ServerSink) -> Self {
+    pub fn try_rmcp_tool(
+        mut self,
+        tool: rmcp::model::Tool,
+        client: rmcp::service::ServerSink,
+    ) -> Result<Self, ToolSetError> {
         use crate::tool::rmcp::McpTool;
         let toolname = tool.name.clone();
         self.toolset
-            .add_tool(McpTool::from_mcp_server(tool, client));
+            .add_tool(McpTool::from_mcp_server(tool, client))?;
         self.static_tool_names.push(toolname.to_string());
+        Ok(self)
+    }
+
+    /// Add every tool of an MCP server to the tool server. The tools are registered when the tool
+    /// server starts, and are kept in sync with the tool list of the MCP server.
+    #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+    #[cfg(feature = "rmcp")]
+    pub fn mcp_client(mut self, client: McpClient) -> Self {
+        self.mcp_clients.push(client);
+        self
+    }
+
+    #[cfg(feature = "rmcp")]
+    pub(crate) fn mcp_clients(mut self, clients: Vec<McpClient>) -> Self {
+        self.mcp_clients.extend(clients);
         self
     }
 
     /// Add some dynamic tools to the agent. On each prompt, `sample` tools from the
     /// dynamic toolset will be inserted in the request.
+    ///
+    /// # Panics
+    /// If a tool of the toolset has the same name as a tool that was already added (see
+    /// [ToolServer::try_dynamic_tools]).
     pub fn dynamic_tools(
-        mut self,
+        self,
         sample: usize,
         dynamic_tools: impl VectorStoreIndexDyn + Send + Sync + 'static,
         toolset: ToolSet,
     ) -> Self {
+        self.try_dynamic_tools(sample, dynamic_tools, toolset)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Add some dynamic tools to the tool server. Fails if a tool of the toolset has the same name
+    /// as a tool that was already added.
+    pub fn try_dynamic_tools(
+        mut self,
+        sample: usize,
+        dynamic_tools: impl VectorStoreIndexDyn + Send + Sync + 'static,
+        toolset: ToolSet,
+    ) -> Result<Self, ToolSetError> {
+        self.toolset.add_tools(toolset)?;
         self.dynamic_tools.push((sample, Box::new(dynamic_tools)));
-        self.toolset.add_tools(toolset);
-        self
+        Ok(self)
     }
 
     pub fn run(mut self) -> ToolServerHandle {
         let (tx, mut rx) = tokio::sync::mpsc::channel(1000);
+        let handle = ToolServerHandle(tx);
+        #[cfg(feature = "rmcp")]
+        let weak_handle = handle.downgrade();
+
+        spawn(async move {
+            // The tools of the MCP servers are registered before any request is handled. The
+            // clients are kept for as long as the server runs, so that the connections stay open.
+            #[cfg(feature = "rmcp")]
+            for client in self.mcp_clients.clone() {
+                self.add_mcp_tools(&client, weak_handle.clone()).await;
+            }
 
-        #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
-        tokio::spawn(async move {
             while let Some(message) = rx.recv().await {
                 self.handle_message(message).await;
             }
         });
 
-        // SAFETY: `rig` currently doesn't compile to WASM without the `worker` feature.
-        // Therefore, we can safely assume that the user won't try to compile to wasm without the worker feature.
-        #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
-        wasm_bindgen_futures::spawn_local(async move {
-            while let Some(message) = rx.recv().await {
-                self.handle_message(message).await;
+        handle
+    }
+
+    #[cfg(feature = "rmcp")]
+    async fn add_mcp_tools(&mut self, client: &McpClient, handle: WeakToolServerHandle) {
+        let tools = match client.namespaced_tools().await {
+            Ok(tools) => tools,
+            Err(e) => {
+                tracing::warn!("Failed to list the tools of the MCP server: {e}");
+                return;
             }
-        });
+        };
 
-        ToolServerHandle(tx)
+        let mut tool_names = Vec::new();
+        for tool in tools {
+            let tool_name = tool.name();
+            match self.toolset.add_tool_boxed(tool) {
+                Ok(()) => tool_names.push(tool_name),
+                Err(e) => tracing::warn!("Failed to add a tool of the MCP server: {e}"),
+            }
+        }
+        self.static_tool_names.extend(tool_names.iter().cloned());
+
+        client.watch_tools(handle, tool_names).await;
     }
 
+    /// Handles a request sent through a [ToolServerHandle].
+    ///
+    /// Tool calls are executed in their own task so that a slow tool doesn't hold up the other
+    /// requests; every other request is handled in the order it was received.
     pub async fn handle_message(&mut self, message: ToolServerRequest) {
         let ToolServerRequest {
             callback_channel,
             data,
         } = message;
 
-        match data {
+        // Sending the response only fails if the caller stopped waiting for it
+        let response = match data {
             ToolServerRequestMessageKind::AddTool(tool) => {
-                self.static_tool_names.push(tool.name());
-                self.toolset.add_tool_boxed(tool);
-                callback_channel
-                    .send(ToolServerResponse::ToolAdded)
-                    .unwrap();
+                let tool_name = tool.name();
+                match self.toolset.add_tool_boxed(tool) {
+                    Ok(()) => {
+                        self.static_tool_names.push(tool_name);
+                        ToolServerResponse::ToolAdded
+                    }
+                    Err(_) => ToolServerResponse::DuplicateTool { name: tool_name },
+                }
+            }
+            ToolServerRequestMessageKind::AddToolWithOptions { tool, options } => {
+                let tool_name = tool.name();
+                match self.toolset.add_tool_boxed(tool) {
+                    Ok(()) => {
+                        self.static_tool_names.push(tool_name.clone());
+                        self.call_options.insert(tool_name, options);
+                        ToolServerResponse::ToolAdded
+                    }
+                    Err(_) => ToolServerResponse::DuplicateTool { name: tool_name },
+                }
             }
             ToolServerRequestMessageKind::AppendToolset(tools) => {
-                self.toolset.add_tools(tools);
-                callback_channel
-                    .send(ToolServerResponse::ToolAdded)
-                    .unwrap();
+                match self.toolset.add_tools(tools) {
+                    Ok(()) => ToolServerResponse::ToolAdded,
+                    Err(ToolSetError::DuplicateToolError(name)) => {
+                        ToolServerResponse::DuplicateTool { name }
+                    }
+                    Err(e) => ToolServerResponse::Error {
+                        error: e.to_string(),
+                    },
+                }
             }
             ToolServerRequestMessageKind::RemoveTool { tool_name } => {
                 self.static_tool_names.retain(|x| *x != tool_name);
+                self.call_options.remove(&tool_name);
                 self.toolset.delete_tool(&tool_name);
-                callback_channel
-                    .send(ToolServerResponse::ToolDeleted)
-                    .unwrap();
+                ToolServerResponse::ToolDeleted
             }
-            ToolServerRequestMessageKind::CallTool { name, args } => {
-                match self.toolset.call(&name, args.clone()).await {
-                    Ok(result) => {
-                        let _ = callback_channel.send(ToolServerResponse::ToolExecuted { result });
-                    }
-                    Err(err) => {
-                        let _ = callback_channel.send(ToolServerResponse::ToolError {
-                            error: err.to_string(),
-                        });
-                    }
-                }
+            ToolServerRequestMessageKind::CallTool {
+                name,
+                args,
+                context,
+            } => {
+                let Some(tool) = self.toolset.get(&name).cloned() else {
+                    let _ = callback_channel.send(ToolServerResponse::ToolError {
+                        error: ToolSetError::ToolNotFoundError(name).to_string(),
+                    });
+                    return;
+                };
+                let options = self.call_options.get(&name).cloned().unwrap_or_default();
+                let call_limit = self.call_limit.clone();
+
+                spawn(async move {
+                    let _permit = match call_limit {
+                        Some(call_limit) => call_limit.acquire_owned().await.ok(),
+                        None => None,
+                    };
+                    let response = call_with_options(&tool, &name, args, *context, &options).await;
+                    let _ = callback_channel.send(response);
+                });
+                return;
             }
             ToolServerRequestMessageKind::GetToolDefs { prompt } => {
-                let res = self.get_tool_definitions(prompt).await.unwrap();
-                callback_channel
-                    .send(ToolServerResponse::ToolDefinitions(res))
-                    .unwrap();
+                match self.get_tool_definitions(prompt).await {
+                    Ok(defs) => ToolServerResponse::ToolDefinitions(defs),
+                    Err(e) => ToolServerResponse::Error {
+                        error: e.to_string(),
+                    },
+                }
             }
-        }
+        };
+
+        let _ = callback_channel.send(response);
     }
 
     pub async fn get_tool_definitions(
@@ -204,13 +415,98 @@ impl ToolServer {
     }
 }
 
+/// Spawns a task on the current runtime.
+fn spawn(future: impl Future<Output = ()> + WasmCompatSend + 'static) {
+    #[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+    tokio::spawn(future);
+
+    // SAFETY: `rig` currently doesn't compile to WASM without the `worker` feature.
+    // Therefore, we can safely assume that the user won't try to compile to wasm without the worker feature.
+    #[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+    wasm_bindgen_futures::spawn_local(future);
+}
+
+/// Calls a tool, applying the timeout and retry policy of the tool.
+async fn call_with_options(
+    tool: &ToolType,
+    name: &str,
+    args: String,
+    context: ToolContext,
+    options: &ToolCallOptions,
+) -> ToolServerResponse {
+    let mut attempt = 0;
+
+    loop {
+        let call = tool.call_checked(args.clone(), context.clone());
+        let result = match options.timeout {
+            Some(timeout) => match future::select(Box::pin(call), Delay::new(timeout)).await {
+                Either::Left((result, _)) => Some(result),
+                Either::Right(_) => None,
+            },
+            None => Some(call.await),
+        };
+
+        let response = match result {
+            Some(Ok(result)) => return ToolServerResponse::ToolExecuted { result },
+            // Retrying won't help if the arguments are wrong
+            Some(Err(ToolSetError::ArgumentValidationError(error))) => {
+                return ToolServerResponse::InvalidArguments(error);
+            }
+            Some(Err(err @ ToolSetError::ToolCallError(ToolError::JsonError(_)))) => {
+                return ToolServerResponse::ToolError {
+                    error: err.to_string(),
+                };
+            }
+            Some(Err(err)) => ToolServerResponse::ToolError {
+                error: err.to_string(),
+            },
+            None => ToolServerResponse::ToolTimedOut {
+                timeout: options.timeout.unwrap_or_default(),
+            },
+        };
+
+        if attempt >= options.max_retries {
+            return response;
+        }
+
+        attempt += 1;
+        tracing::warn!(
+            "Tool call {name} failed ({response:?}), retrying ({attempt}/{})",
+            options.max_retries
+        );
+        Delay::new(options.retry_delay).await;
+    }
+}
+
 #[derive(Clone)]
 pub struct ToolServerHandle(Sender<ToolServerRequest>);
 
+/// A [ToolServerHandle] that doesn't keep the tool server running.
+#[cfg(feature = "rmcp")]
+#[derive(Clone)]
+pub(crate) struct WeakToolServerHandle(WeakSender<ToolServerRequest>);
+
+#[cfg(feature = "rmcp")]
+impl WeakToolServerHandle {
+    pub(crate) fn upgrade(&self) -> Option<ToolServerHandle> {
+        self.0.upgrade().map(ToolServerHandle)
+    }
+}
+
 impl ToolServerHandle {
+    #[cfg(feature = "rmcp")]
+    pub(crate) fn downgrade(&self) -> WeakToolServerHandle {
+        WeakToolServerHandle(self.0.downgrade())
+    }
+
+    /// Add a tool to the server. Fails with a [ToolSetError::DuplicateToolError] if a tool with the
+    /// same name was already added.
     pub async fn add_tool(&self, tool: impl ToolDyn + 'static) -> Result<(), ToolServerError> {
-        let tool = Box::new(tool);
+        self.add_tool_boxed(Box::new(tool)).await
+    }
 
+    /// Add a boxed tool to the server. Useful for situations when dynamic dispatch is required.
+    pub async fn add_tool_boxed(&self, tool: Box<dyn ToolDyn>) -> Result<(), ToolServerError> {
         let (tx, rx) = futures::channel::oneshot::channel();
 
         self.0
@@ -220,15 +516,43 @@ impl ToolServerHandle {
             })
             .await?;
 
-        let res = rx.await?;
+        match rx.await? {
+            ToolServerResponse::ToolAdded => Ok(()),
+            ToolServerResponse::DuplicateTool { name } => {
+                Err(ToolSetError::DuplicateToolError(name).into())
+            }
+            invalid => Err(ToolServerError::InvalidMessage(invalid)),
+        }
+    }
 
-        let ToolServerResponse::ToolAdded = res else {
-            return Err(ToolServerError::InvalidMessage(res));
-        };
+    /// Add a tool to the server, with a timeout and/or retry policy applied to its calls.
+    pub async fn add_tool_with_options(
+        &self,
+        tool: impl ToolDyn + 'static,
+        options: ToolCallOptions,
+    ) -> Result<(), ToolServerError> {
+        let tool = Box::new(tool);
 
-        Ok(())
+        let (tx, rx) = futures::channel::oneshot::channel();
+
+        self.0
+            .send(ToolServerRequest {
+                callback_channel: tx,
+                data: ToolServerRequestMessageKind::AddToolWithOptions { tool, options },
+            })
+            .await?;
+
+        match rx.await? {
+            ToolServerResponse::ToolAdded => Ok(()),
+            ToolServerResponse::DuplicateTool { name } => {
+                Err(ToolSetError::DuplicateToolError(name).into())
+            }
+            invalid => Err(ToolServerError::InvalidMessage(invalid)),
+        }
     }
 
+    /// Add every tool of a toolset to the server. If any tool has the same name as a tool of the
+    /// server, no tool is added (see [ToolSet::namespaced] to keep both).
     pub async fn append_toolset(&self, toolset: ToolSet) -> Result<(), ToolServerError> {
         let (tx, rx) = futures::channel::oneshot::channel();
 
@@ -239,13 +563,13 @@ impl ToolServerHandle {
             })
             .await?;
 
-        let res = rx.await?;
-
-        let ToolServerResponse::ToolAdded = res else {
-            return Err(ToolServerError::InvalidMessage(res));
-        };
-
-        Ok(())
+        match rx.await? {
+            ToolServerResponse::ToolAdded => Ok(()),
+            ToolServerResponse::DuplicateTool { name } => {
+                Err(ToolSetError::DuplicateToolError(name).into())
+            }
+            invalid => Err(ToolServerError::InvalidMessage(invalid)),
+        }
     }
 
     pub async fn remove_tool(&self, tool_name: &str) -> Result<(), ToolServerError> {
@@ -269,7 +593,22 @@ impl ToolServerHandle {
         Ok(())
     }
 
-    pub async fn call_tool(&self, tool_name: &str, args: &str) -> Result<String, ToolServerError> {
+    pub async fn call_tool(
+        &self,
+        tool_name: &str,
+        args: &str,
+    ) -> Result<ToolOutput, ToolServerError> {
+        self.call_tool_with_context(tool_name, args, ToolContext::default())
+            .await
+    }
+
+    /// Call a tool, passing the given [ToolContext] to the tool.
+    pub async fn call_tool_with_context(
+        &self,
+        tool_name: &str,
+        args: &str,
+        context: ToolContext,
+    ) -> Result<ToolOutput, ToolServerError> {
         let (tx, rx) = futures::channel::oneshot::channel();
 
         self.0
@@ -278,6 +617,7 @@ impl ToolServerHandle {
                 data: ToolServerRequestMessageKind::CallTool {
                     name: tool_name.to_string(),
                     args: args.to_string(),
+                    context: Box::new(context),
                 },
             })
             .await?;
@@ -289,6 +629,13 @@ impl ToolServerHandle {
             ToolServerResponse::ToolError { error } => Err(ToolServerError::ToolsetError(
                 ToolSetError::ToolCallError(ToolError::ToolCallError(error.into())),
             )),
+            ToolServerResponse::InvalidArguments(error) => Err(ToolServerError::ToolsetError(
+                ToolSetError::ArgumentValidationError(error),
+            )),
+            ToolServerResponse::ToolTimedOut { timeout } => Err(ToolServerError::Timeout {
+                tool_name: tool_name.to_string(),
+                timeout,
+            }),
             invalid => Err(ToolServerError::InvalidMessage(invalid)),
         }
     }
@@ -308,11 +655,13 @@ impl ToolServerHandle {
 
         let res = rx.await?;
 
-        let ToolServerResponse::ToolDefinitions(tooldefs) = res else {
-            return Err(ToolServerError::InvalidMessage(res));
-        };
-
-        Ok(tooldefs)
+        match res {
+            ToolServerResponse::ToolDefinitions(tooldefs) => Ok(tooldefs),
+            ToolServerResponse::Error { error } => {
+                Err(ToolServerError::ToolDefinitionsError(error))
+            }
+            invalid => Err(ToolServerError::InvalidMessage(invalid)),
+        }
     }
 }
 
@@ -323,19 +672,35 @@ pub struct ToolServerRequest {
 
 pub enum ToolServerRequestMessageKind {
     AddTool(Box<dyn ToolDyn>),
+    AddToolWithOptions {
+        tool: Box<dyn ToolDyn>,
+        options: ToolCallOptions,
+    },
     AppendToolset(ToolSet),
-    RemoveTool { tool_name: String },
-    CallTool { name: String, args: String },
-    GetToolDefs { prompt: Option<String> },
+    RemoveTool {
+        tool_name: String,
+    },
+    CallTool {
+        name: String,
+        args: String,
+        context: Box<ToolContext>,
+    },
+    GetToolDefs {
+        prompt: Option<String>,
+    },
 }
 
 #[derive(PartialEq, Debug)]
 pub enum ToolServerResponse {
     ToolAdded,
     ToolDeleted,
-    ToolExecuted { result: String },
+    ToolExecuted { result: ToolOutput },
     ToolError { error: String },
+    InvalidArguments(ArgumentValidationError),
+    ToolTimedOut { timeout: Duration },
+    DuplicateTool { name: String },
     ToolDefinitions(Vec<ToolDefinition>),
+    Error { error: String },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -348,16 +713,34 @@ pub enum ToolServerError {
     SendError(#[from] SendError<ToolServerRequest>),
     #[error("An invalid message type was returned")]
     InvalidMessage(ToolServerResponse),
+    #[error("Tool call {tool_name} timed out after {timeout:?}")]
+    Timeout {
+        tool_name: String,
+        timeout: Duration,
+    },
+    #[error("Error while getting tool definitions: {0}")]
+    ToolDefinitionsError(String),
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
     use serde::{Deserialize, Serialize};
     use serde_json::json;
 
     use crate::{
         completion::ToolDefinition,
-        tool::{Tool, server::ToolServer},
+        tool::{
+            Tool, ToolSetError,
+            server::{ToolCallOptions, ToolServer, ToolServerError},
+        },
     };
 
     #[derive(Deserialize)]
@@ -420,11 +803,111 @@ mod tests {
         let json_args_as_string =
             serde_json::to_string(&serde_json::json!({"x": 2, "y": 5})).unwrap();
         let res = handle.call_tool("add", &json_args_as_string).await.unwrap();
-        assert_eq!(res, "7");
+        assert_eq!(res.to_string(), "7");
 
         handle.remove_tool("add").await.unwrap();
         let res = handle.get_tool_defs(None).await.unwrap();
 
         assert_eq!(res.len(), 0);
     }
+
+    #[derive(Deserialize)]
+    struct SleepArgs {
+        millis: u64,
+    }
+
+    #[derive(Clone, Default)]
+    struct Sleeper {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Tool for Sleeper {
+        const NAME: &'static str = "sleep";
+        type Error = MathError;
+        type Args = SleepArgs;
+        type Output = u64;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: "sleep".to_string(),
+                description: "Sleep for the given number of milliseconds".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "millis": { "type": "integer" }
+                    },
+                    "required": ["millis"],
+                }),
+            }
+        }
+
+        async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(args.millis)).await;
+            Ok(args.millis)
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_toolserver_timeouts_and_retries() {
+        let sleeper = Sleeper::default();
+        let handle = ToolServer::new()
+            .tool_with_options(
+                sleeper.clone(),
+                ToolCallOptions::new()
+                    .timeout(Duration::from_millis(200))
+                    .retries(1, Duration::from_millis(10)),
+            )
+            .max_concurrent_calls(4)
+            .run();
+
+        // Calls are executed concurrently: a slow call doesn't hold up a fast one
+        let (slow, fast) = tokio::join!(
+            handle.call_tool("sleep", r#"{"millis": 1000}"#),
+            handle.call_tool("sleep", r#"{"millis": 10}"#),
+        );
+        assert_eq!(fast.unwrap().to_string(), "10");
+        assert!(matches!(
+            slow,
+            Err(ToolServerError::Timeout { tool_name, timeout })
+                if tool_name == "sleep" && timeout == Duration::from_millis(200)
+        ));
+        // The timed out call was retried once
+        assert_eq!(sleeper.calls.load(Ordering::SeqCst), 3);
+
+        // Invalid arguments are not retried
+        assert!(
+            handle
+                .call_tool("sleep", r#"{"millis": "soon"}"#)
+                .await
+                .is_err()
+        );
+        assert_eq!(sleeper.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_toolserver_duplicate_tool() {
+        let error = ToolServer::new()
+            .tool(Sleeper::default())
+            .try_tool(Sleeper::default())
+            .err()
+            .unwrap();
+        assert!(matches!(error, ToolSetError::DuplicateToolError(name) if name == "sleep"));
+    }
+
+    #[tokio::test]
+    pub async fn test_toolserver_zero_call_limit() {
+        let handle = ToolServer::new()
+            .tool(Sleeper::default())
+            .max_concurrent_calls(0)
+            .run();
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(1),
+            handle.call_tool("sleep", r#"{"millis": 1}"#),
+        )
+        .await
+        .expect("a call limit of 0 should not block every call");
+        assert_eq!(result.unwrap().to_string(), "1");
+    }
 }
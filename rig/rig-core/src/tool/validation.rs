@@ -0,0 +1,165 @@
+//! Validation of tool call arguments against the JSON schema of the tool
+//! (ie: [ToolDefinition::parameters](crate::completion::ToolDefinition::parameters)).
+//!
+//! [ToolSet::call](crate::tool::ToolSet::call) validates the arguments of every call before
+//! dispatching it to the tool. When validation fails, the call returns a
+//! [ToolSetError::ArgumentValidationError](crate::tool::ToolSetError::ArgumentValidationError)
+//! listing every failing path, which the agent loop sends back to the model so that it can correct
+//! its arguments.
+use std::sync::{Arc, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+/// A single violation of the schema of a tool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArgumentViolation {
+    /// JSON pointer to the failing value (empty for the root of the arguments).
+    pub path: String,
+    pub message: String,
+}
+
+/// The arguments of a tool call do not match the schema of the tool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("Invalid arguments for tool {tool}: {}", self.to_json())]
+pub struct ArgumentValidationError {
+    pub tool: String,
+    pub violations: Vec<ArgumentViolation>,
+}
+
+impl ArgumentValidationError {
+    fn to_json(&self) -> String {
+        serde_json::to_string(&self.violations).unwrap_or_default()
+    }
+}
+
+/// Validate the (raw) arguments of a call to `tool` against its JSON schema.
+///
+/// Schemas that cannot be compiled are logged and skipped, so that a tool with a schema the
+/// validator doesn't understand can still be called.
+pub fn validate_arguments(
+    tool: &str,
+    schema: &serde_json::Value,
+    args: &str,
+) -> Result<(), ArgumentValidationError> {
+    validate_with(tool, compile_schema(tool, schema).as_ref(), args)
+}
+
+/// Compile the JSON schema of `tool`, or log and return `None` if the schema is invalid.
+fn compile_schema(tool: &str, schema: &serde_json::Value) -> Option<jsonschema::Validator> {
+    match jsonschema::validator_for(schema) {
+        Ok(validator) => Some(validator),
+        Err(e) => {
+            tracing::warn!("Skipping argument validation for tool {tool}, invalid schema: {e}");
+            None
+        }
+    }
+}
+
+fn validate_with(
+    tool: &str,
+    validator: Option<&jsonschema::Validator>,
+    args: &str,
+) -> Result<(), ArgumentValidationError> {
+    let args = match serde_json::from_str::<serde_json::Value>(args) {
+        Ok(args) => args,
+        Err(e) => {
+            return Err(ArgumentValidationError {
+                tool: tool.to_string(),
+                violations: vec![ArgumentViolation {
+                    path: String::new(),
+                    message: format!("Arguments are not valid JSON: {e}"),
+                }],
+            });
+        }
+    };
+
+    let Some(validator) = validator else {
+        return Ok(());
+    };
+
+    let violations = validator
+        .iter_errors(&args)
+        .map(|error| ArgumentViolation {
+            path: error.instance_path.to_string(),
+            message: error.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(ArgumentValidationError {
+            tool: tool.to_string(),
+            violations,
+        })
+    }
+}
+
+/// The compiled schema of a registered tool.
+///
+/// The schema is compiled the first time the tool is called and shared by every clone of the
+/// tool, so that later calls neither fetch the definition of the tool nor recompile its schema.
+#[derive(Clone, Default)]
+pub(crate) struct ArgumentValidator(Arc<OnceLock<Option<jsonschema::Validator>>>);
+
+impl ArgumentValidator {
+    /// Validate the arguments of a call to `tool`, compiling the schema returned by `schema` if
+    /// it wasn't compiled yet.
+    pub(crate) async fn validate<F>(
+        &self,
+        tool: &str,
+        args: &str,
+        schema: impl FnOnce() -> F,
+    ) -> Result<(), ArgumentValidationError>
+    where
+        F: Future<Output = serde_json::Value>,
+    {
+        let validator = match self.0.get() {
+            Some(validator) => validator,
+            None => {
+                let validator = compile_schema(tool, &schema().await);
+                self.0.get_or_init(|| validator)
+            }
+        };
+
+        validate_with(tool, validator.as_ref(), args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{ArgumentViolation, validate_arguments};
+
+    #[test]
+    fn test_validate_arguments() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "x": { "type": "number" },
+                "y": { "type": "number" }
+            },
+            "required": ["x", "y"]
+        });
+
+        assert!(validate_arguments("add", &schema, r#"{"x": 1, "y": 2}"#).is_ok());
+
+        let error = validate_arguments("add", &schema, r#"{"x": "one"}"#).unwrap_err();
+        assert_eq!(error.tool, "add");
+        assert_eq!(
+            error
+                .violations
+                .iter()
+                .map(|violation| violation.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/x", ""]
+        );
+
+        let error = validate_arguments("add", &schema, "{x: 1").unwrap_err();
+        assert!(matches!(
+            error.violations.as_slice(),
+            [ArgumentViolation { path, .. }] if path.is_empty()
+        ));
+    }
+}
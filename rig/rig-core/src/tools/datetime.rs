@@ -0,0 +1,265 @@
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::completion::ToolDefinition;
+use crate::tool::Tool;
+
+/// Arguments for the date and time tool
+#[derive(Debug, Deserialize)]
+pub struct DateTimeArgs {
+    /// The operation to perform
+    pub operation: DateTimeOperation,
+    /// A date and time in RFC 3339 format (for `add` and `difference`)
+    pub datetime: Option<String>,
+    /// A second date and time in RFC 3339 format (for `difference`)
+    pub other: Option<String>,
+    /// The amount of `unit`s to add, which can be negative (for `add`)
+    pub amount: Option<i64>,
+    /// The unit of `amount` (for `add`)
+    pub unit: Option<DurationUnit>,
+    /// The UTC offset of the result, eg: `+02:00` (for `now`)
+    pub utc_offset: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateTimeOperation {
+    /// Get the current date and time
+    Now,
+    /// Add an amount of time to a date and time
+    Add,
+    /// Get the time elapsed between two dates and times
+    Difference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationUnit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+}
+
+/// Error type for the date and time tool
+#[derive(Debug, thiserror::Error)]
+pub enum DateTimeError {
+    #[error("Invalid date and time (expected RFC 3339, eg: 2025-01-31T09:30:00Z): {0}")]
+    InvalidDateTime(#[from] chrono::ParseError),
+    #[error("Invalid UTC offset (expected eg: +02:00): {0}")]
+    InvalidUtcOffset(String),
+    #[error("Missing argument: {0}")]
+    MissingArgument(&'static str),
+    #[error("The resulting date is out of range")]
+    OutOfRange,
+}
+
+/// A tool giving the current date and time, and doing date arithmetic, which models are
+/// notoriously bad at.
+///
+/// Dates are exchanged in RFC 3339 format (eg: `2025-01-31T09:30:00+01:00`).
+#[derive(Debug, Clone, Copy)]
+pub struct DateTimeTool {
+    utc_offset: FixedOffset,
+}
+
+impl Default for DateTimeTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DateTimeTool {
+    /// Create a date and time tool giving the current time in UTC.
+    pub fn new() -> Self {
+        Self {
+            utc_offset: FixedOffset::east_opt(0).expect("UTC offset of 0 is valid"),
+        }
+    }
+
+    /// Set the UTC offset used when the model doesn't give one (eg: the time zone of the user).
+    pub fn utc_offset(mut self, utc_offset: FixedOffset) -> Self {
+        self.utc_offset = utc_offset;
+        self
+    }
+}
+
+fn parse_datetime(datetime: Option<&str>) -> Result<DateTime<FixedOffset>, DateTimeError> {
+    let datetime = datetime.ok_or(DateTimeError::MissingArgument("datetime"))?;
+    Ok(DateTime::parse_from_rfc3339(datetime)?)
+}
+
+fn describe(datetime: DateTime<FixedOffset>) -> serde_json::Value {
+    json!({
+        "datetime": datetime.to_rfc3339(),
+        "weekday": datetime.format("%A").to_string(),
+        "unix_timestamp": datetime.timestamp(),
+    })
+}
+
+impl Tool for DateTimeTool {
+    const NAME: &'static str = "datetime";
+
+    type Error = DateTimeError;
+    type Args = DateTimeArgs;
+    type Output = serde_json::Value;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Get the current date and time (`now`), add an amount of time to a date \
+            (`add`), or compute the time elapsed between two dates (`difference`). Dates are in \
+            RFC 3339 format, eg: 2025-01-31T09:30:00Z."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["now", "add", "difference"],
+                        "description": "The operation to perform."
+                    },
+                    "datetime": {
+                        "type": "string",
+                        "description": "The date and time to start from (for `add` and `difference`)."
+                    },
+                    "other": {
+                        "type": "string",
+                        "description": "The date and time to compute the difference with (for `difference`)."
+                    },
+                    "amount": {
+                        "type": "integer",
+                        "description": "The amount of time to add, negative to subtract (for `add`)."
+                    },
+                    "unit": {
+                        "type": "string",
+                        "enum": ["seconds", "minutes", "hours", "days", "weeks"],
+                        "description": "The unit of the amount (for `add`)."
+                    },
+                    "utc_offset": {
+                        "type": "string",
+                        "description": "The UTC offset of the time zone to use, eg: +02:00 (for `now`)."
+                    }
+                },
+                "required": ["operation"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        match args.operation {
+            DateTimeOperation::Now => {
+                let utc_offset = match args.utc_offset {
+                    Some(utc_offset) => utc_offset
+                        .parse::<FixedOffset>()
+                        .map_err(|_| DateTimeError::InvalidUtcOffset(utc_offset))?,
+                    None => self.utc_offset,
+                };
+                Ok(describe(Utc::now().with_timezone(&utc_offset)))
+            }
+            DateTimeOperation::Add => {
+                let datetime = parse_datetime(args.datetime.as_deref())?;
+                let amount = args
+                    .amount
+                    .ok_or(DateTimeError::MissingArgument("amount"))?;
+                let duration = match args.unit.ok_or(DateTimeError::MissingArgument("unit"))? {
+                    DurationUnit::Seconds => Duration::try_seconds(amount),
+                    DurationUnit::Minutes => Duration::try_minutes(amount),
+                    DurationUnit::Hours => Duration::try_hours(amount),
+                    DurationUnit::Days => Duration::try_days(amount),
+                    DurationUnit::Weeks => Duration::try_weeks(amount),
+                }
+                .ok_or(DateTimeError::OutOfRange)?;
+
+                let result = datetime
+                    .checked_add_signed(duration)
+                    .ok_or(DateTimeError::OutOfRange)?;
+                Ok(describe(result))
+            }
+            DateTimeOperation::Difference => {
+                let start = parse_datetime(args.datetime.as_deref())?;
+                let end = parse_datetime(Some(
+                    args.other
+                        .as_deref()
+                        .ok_or(DateTimeError::MissingArgument("other"))?,
+                ))?;
+                let seconds = (end - start).num_seconds();
+
+                Ok(json!({
+                    "seconds": seconds,
+                    "hours": seconds as f64 / 3600.0,
+                    "days": seconds as f64 / 86400.0,
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(operation: DateTimeOperation) -> DateTimeArgs {
+        DateTimeArgs {
+            operation,
+            datetime: None,
+            other: None,
+            amount: None,
+            unit: None,
+            utc_offset: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_datetime_tool() {
+        let tool = DateTimeTool::new();
+
+        let now = tool
+            .call(DateTimeArgs {
+                utc_offset: Some("+02:00".to_string()),
+                ..args(DateTimeOperation::Now)
+            })
+            .await
+            .unwrap();
+        assert!(now["datetime"].as_str().unwrap().ends_with("+02:00"));
+
+        let later = tool
+            .call(DateTimeArgs {
+                datetime: Some("2024-02-28T12:00:00Z".to_string()),
+                amount: Some(2),
+                unit: Some(DurationUnit::Days),
+                ..args(DateTimeOperation::Add)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            later,
+            json!({
+                "datetime": "2024-03-01T12:00:00+00:00",
+                "weekday": "Friday",
+                "unix_timestamp": 1709294400,
+            })
+        );
+
+        let difference = tool
+            .call(DateTimeArgs {
+                datetime: Some("2025-01-01T00:00:00+01:00".to_string()),
+                other: Some("2025-01-02T12:00:00+01:00".to_string()),
+                ..args(DateTimeOperation::Difference)
+            })
+            .await
+            .unwrap();
+        assert_eq!(difference["hours"], 36.0);
+
+        let err = tool
+            .call(DateTimeArgs {
+                datetime: Some("tomorrow".to_string()),
+                ..args(DateTimeOperation::Difference)
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DateTimeError::InvalidDateTime(_)));
+    }
+}
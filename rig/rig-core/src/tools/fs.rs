@@ -0,0 +1,325 @@
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::completion::ToolDefinition;
+use crate::tool::Tool;
+
+/// Arguments for the file system tool
+#[derive(Debug, Deserialize)]
+pub struct FileSystemArgs {
+    /// The operation to perform
+    pub operation: FileSystemOperation,
+    /// The path of the file or directory, relative to the root directory
+    pub path: Option<String>,
+    /// The text to search for (for the `search` operation)
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSystemOperation {
+    /// Read a text file
+    Read,
+    /// List the entries of a directory
+    List,
+    /// Search the text files of a directory (recursively) for lines containing a query
+    Search,
+}
+
+/// Error type for the file system tool
+#[derive(Debug, thiserror::Error)]
+pub enum FileSystemError {
+    #[error("Path is outside of the root directory: {0}")]
+    OutsideRoot(String),
+    #[error("Missing argument: {0}")]
+    MissingArgument(&'static str),
+    #[error("File is too large ({size} bytes, the limit is {limit} bytes)")]
+    FileTooLarge { size: u64, limit: u64 },
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// A read-only file system tool, confined to a root directory.
+///
+/// The model can read text files, list directories and search files for a query. Paths are
+/// resolved relative to the root directory: absolute paths, `..` components and symlinks pointing
+/// outside of the root are rejected.
+///
+/// # Example
+/// ```rust
+/// use rig::tools::FileSystemTool;
+///
+/// let tool = FileSystemTool::new("./docs")?
+///     .max_file_size(64 * 1024)
+///     .max_results(20);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileSystemTool {
+    root: PathBuf,
+    max_file_size: u64,
+    max_results: usize,
+}
+
+impl FileSystemTool {
+    /// Create a file system tool confined to the given (existing) directory.
+    pub fn new(root: impl AsRef<Path>) -> Result<Self, std::io::Error> {
+        Ok(Self {
+            root: root.as_ref().canonicalize()?,
+            max_file_size: 1024 * 1024,
+            max_results: 50,
+        })
+    }
+
+    /// Set the size (in bytes) of the largest file that can be read or searched. Defaults to 1 MiB.
+    pub fn max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Set the maximum number of entries (or search matches) returned. Defaults to 50.
+    pub fn max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results;
+        self
+    }
+
+    /// Resolve a path given by the model, making sure that it stays inside of the root directory.
+    async fn resolve(&self, path: Option<&str>) -> Result<PathBuf, FileSystemError> {
+        let path = path.unwrap_or(".");
+        let relative = Path::new(path);
+
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(FileSystemError::OutsideRoot(path.to_string()));
+        }
+
+        // Canonicalizing resolves symlinks, which could point outside of the root
+        let resolved = tokio::fs::canonicalize(self.root.join(relative)).await?;
+        if !resolved.starts_with(&self.root) {
+            return Err(FileSystemError::OutsideRoot(path.to_string()));
+        }
+
+        Ok(resolved)
+    }
+
+    /// The path of a file, relative to the root directory.
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+
+    async fn read(&self, path: &Path) -> Result<String, FileSystemError> {
+        let size = tokio::fs::metadata(path).await?.len();
+        if size > self.max_file_size {
+            return Err(FileSystemError::FileTooLarge {
+                size,
+                limit: self.max_file_size,
+            });
+        }
+
+        Ok(tokio::fs::read_to_string(path).await?)
+    }
+
+    async fn list(&self, path: &Path) -> Result<String, FileSystemError> {
+        let mut entries = Vec::new();
+        let mut directory = tokio::fs::read_dir(path).await?;
+        while let Some(entry) = directory.next_entry().await? {
+            let name = self.display(&entry.path());
+            entries.push(if entry.file_type().await?.is_dir() {
+                format!("{name}/")
+            } else {
+                name
+            });
+        }
+        entries.sort();
+
+        let total = entries.len();
+        entries.truncate(self.max_results);
+        if total > entries.len() {
+            entries.push(format!("... ({} more entries)", total - entries.len()));
+        }
+
+        Ok(entries.join("\n"))
+    }
+
+    async fn search(&self, path: &Path, query: &str) -> Result<String, FileSystemError> {
+        let query = query.to_lowercase();
+        let mut matches = Vec::new();
+        let mut directories = vec![path.to_path_buf()];
+
+        while let Some(directory) = directories.pop() {
+            let mut entries = Vec::new();
+            let mut read_dir = tokio::fs::read_dir(&directory).await?;
+            while let Some(entry) = read_dir.next_entry().await? {
+                entries.push(entry.path());
+            }
+            entries.sort();
+
+            for entry in entries {
+                // Symlinks are not followed, since they could point outside of the root
+                let metadata = tokio::fs::symlink_metadata(&entry).await?;
+                if metadata.is_dir() {
+                    directories.push(entry);
+                    continue;
+                }
+                if !metadata.is_file() || metadata.len() > self.max_file_size {
+                    continue;
+                }
+                // Binary files can't be read as text, and are skipped
+                let Ok(content) = tokio::fs::read_to_string(&entry).await else {
+                    continue;
+                };
+
+                for (number, line) in content.lines().enumerate() {
+                    if line.to_lowercase().contains(&query) {
+                        matches.push(format!(
+                            "{}:{}: {}",
+                            self.display(&entry),
+                            number + 1,
+                            line.trim()
+                        ));
+                        if matches.len() >= self.max_results {
+                            return Ok(matches.join("\n"));
+                        }
+                    }
+                }
+            }
+        }
+
+        if matches.is_empty() {
+            return Ok(format!("No matches found for \"{query}\""));
+        }
+        Ok(matches.join("\n"))
+    }
+}
+
+impl Tool for FileSystemTool {
+    const NAME: &'static str = "file_system";
+
+    type Error = FileSystemError;
+    type Args = FileSystemArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Access the files of the workspace (read-only). Use `list` to see the \
+            entries of a directory, `read` to read a text file and `search` to find the lines of \
+            the files of a directory containing some text. Paths are relative to the workspace."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["read", "list", "search"],
+                        "description": "The operation to perform."
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "The relative path of the file (for `read`) or directory (for `list` and `search`). Defaults to the root of the workspace."
+                    },
+                    "query": {
+                        "type": "string",
+                        "description": "The text to search for (case-insensitive), for `search`."
+                    }
+                },
+                "required": ["operation"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = self.resolve(args.path.as_deref()).await?;
+
+        match args.operation {
+            FileSystemOperation::Read => self.read(&path).await,
+            FileSystemOperation::List => self.list(&path).await,
+            FileSystemOperation::Search => {
+                let query = args
+                    .query
+                    .ok_or(FileSystemError::MissingArgument("query"))?;
+                self.search(&path, &query).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::{FileWriteStr, PathChild};
+
+    use super::*;
+
+    fn args(operation: FileSystemOperation, path: Option<&str>) -> FileSystemArgs {
+        FileSystemArgs {
+            operation,
+            path: path.map(str::to_string),
+            query: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_system_tool() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("README.md")
+            .write_str("# Project\nRun `cargo test`")
+            .unwrap();
+        temp.child("src/main.rs")
+            .write_str("fn main() {\n    println!(\"Hello\");\n}")
+            .unwrap();
+        let tool = FileSystemTool::new(temp.path()).unwrap();
+
+        let listing = tool
+            .call(args(FileSystemOperation::List, None))
+            .await
+            .unwrap();
+        assert_eq!(listing, "README.md\nsrc/");
+
+        let content = tool
+            .call(args(FileSystemOperation::Read, Some("src/main.rs")))
+            .await
+            .unwrap();
+        assert!(content.contains("println!"));
+
+        let matches = tool
+            .call(FileSystemArgs {
+                query: Some("hello".to_string()),
+                ..args(FileSystemOperation::Search, None)
+            })
+            .await
+            .unwrap();
+        assert_eq!(matches, "src/main.rs:2: println!(\"Hello\");");
+    }
+
+    #[tokio::test]
+    async fn test_file_system_tool_stays_in_root() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("workspace/notes.txt")
+            .write_str("notes")
+            .unwrap();
+        temp.child("secret.txt").write_str("secret").unwrap();
+        let tool = FileSystemTool::new(temp.child("workspace").path()).unwrap();
+
+        for path in ["../secret.txt", "/etc/passwd", "./../secret.txt"] {
+            let err = tool
+                .call(args(FileSystemOperation::Read, Some(path)))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, FileSystemError::OutsideRoot(_)), "{path}");
+        }
+
+        let tool = tool.max_file_size(2);
+        let err = tool
+            .call(args(FileSystemOperation::Read, Some("notes.txt")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FileSystemError::FileTooLarge { size: 5, .. }));
+    }
+}
@@ -0,0 +1,425 @@
+use std::{sync::Arc, time::Duration};
+
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+
+use crate::completion::ToolDefinition;
+use crate::tool::Tool;
+
+/// Arguments for the HTTP fetch tool
+#[derive(Debug, Deserialize)]
+pub struct HttpFetchArgs {
+    /// The URL to fetch
+    pub url: String,
+}
+
+/// Error type for the HTTP fetch tool
+#[derive(Debug, thiserror::Error)]
+pub enum HttpFetchError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+    #[error("Fetching {0} is not allowed: only http(s) URLs of the allowed domains can be fetched")]
+    DomainNotAllowed(String),
+    #[error("Request failed with status code {0}")]
+    InvalidStatusCode(reqwest::StatusCode),
+    #[error("Unsupported content type: {0}")]
+    UnsupportedContentType(String),
+    #[error("HTTP error: {0}")]
+    HttpError(#[from] reqwest::Error),
+}
+
+/// A domain a [HttpFetchTool] is allowed to fetch, optionally restricted to a port
+/// (eg: `localhost:8080`).
+#[derive(Debug, Clone, PartialEq)]
+struct AllowedDomain {
+    host: String,
+    port: Option<u16>,
+}
+
+impl AllowedDomain {
+    fn parse(domain: &str) -> Self {
+        let domain = domain.to_lowercase();
+        let (host, port) = match domain.rsplit_once(':') {
+            Some((host, port)) => match port.parse() {
+                Ok(port) => (host, Some(port)),
+                Err(_) => (domain.as_str(), None),
+            },
+            None => (domain.as_str(), None),
+        };
+
+        Self {
+            host: host.trim_end_matches('.').to_string(),
+            port,
+        }
+    }
+}
+
+impl std::fmt::Display for AllowedDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.port {
+            Some(port) => write!(f, "{}:{port}", self.host),
+            None => write!(f, "{}", self.host),
+        }
+    }
+}
+
+/// The domains a [HttpFetchTool] is allowed to fetch. Subdomains of an allowed domain are allowed
+/// as well. A domain without a port only allows the default port of the scheme (80 for http, 443
+/// for https).
+#[derive(Debug, Clone)]
+struct DomainAllowlist(Arc<Vec<AllowedDomain>>);
+
+impl DomainAllowlist {
+    fn allows(&self, url: &Url) -> bool {
+        let default_port = match url.scheme() {
+            "http" => 80,
+            "https" => 443,
+            _ => return false,
+        };
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.trim_end_matches('.').to_lowercase();
+        let port = url.port().unwrap_or(default_port);
+
+        self.0.iter().any(|domain| {
+            domain.port.unwrap_or(default_port) == port
+                && (host == domain.host
+                    || host
+                        .strip_suffix(domain.host.as_str())
+                        .is_some_and(|subdomain| subdomain.ends_with('.')))
+        })
+    }
+}
+
+/// A tool fetching web pages and text documents from an allowlist of domains.
+///
+/// HTML pages are converted to text (scripts, styles and markup are removed) and the output is
+/// truncated to a maximum length. Redirects are only followed to allowed domains.
+///
+/// # Example
+/// ```rust
+/// use rig::tools::HttpFetchTool;
+///
+/// let tool = HttpFetchTool::new(["docs.rs", "rust-lang.org"])
+///     .max_length(10_000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct HttpFetchTool {
+    client: reqwest::Client,
+    allowlist: DomainAllowlist,
+    max_bytes: usize,
+    max_length: usize,
+}
+
+impl HttpFetchTool {
+    /// Create a tool fetching pages of the given domains (and their subdomains). A domain can be
+    /// restricted to a port (eg: `localhost:8080`), otherwise only the default port of the scheme
+    /// is allowed.
+    pub fn new<S: Into<String>>(allowed_domains: impl IntoIterator<Item = S>) -> Self {
+        let allowlist = DomainAllowlist(Arc::new(
+            allowed_domains
+                .into_iter()
+                .map(|domain| AllowedDomain::parse(&domain.into()))
+                .collect(),
+        ));
+
+        let redirect_allowlist = allowlist.clone();
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+                if attempt.previous().len() >= 5 {
+                    attempt.error("too many redirects")
+                } else if redirect_allowlist.allows(attempt.url()) {
+                    attempt.follow()
+                } else {
+                    attempt.stop()
+                }
+            }))
+            .build()
+            .expect("building the HTTP client should not fail");
+
+        Self {
+            client,
+            allowlist,
+            max_bytes: 2 * 1024 * 1024,
+            max_length: 20_000,
+        }
+    }
+
+    /// Set the maximum number of bytes downloaded from a page. Defaults to 2 MiB.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Set the maximum number of characters returned to the model. Defaults to 20 000.
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+}
+
+impl Tool for HttpFetchTool {
+    const NAME: &'static str = "http_fetch";
+
+    type Error = HttpFetchError;
+    type Args = HttpFetchArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: format!(
+                "Fetch a web page or a text document and return its content as text. Only URLs of \
+                the following domains can be fetched: {}.",
+                self.allowlist
+                    .0
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "The http(s) URL to fetch."
+                    }
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let url = Url::parse(&args.url)?;
+        if !self.allowlist.allows(&url) {
+            return Err(HttpFetchError::DomainNotAllowed(args.url));
+        }
+
+        let mut response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(HttpFetchError::InvalidStatusCode(response.status()));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or("text/plain")
+            .to_lowercase();
+        let is_html = content_type.contains("html");
+        if !(is_html
+            || content_type.starts_with("text/")
+            || content_type.contains("json")
+            || content_type.contains("xml"))
+        {
+            return Err(HttpFetchError::UnsupportedContentType(content_type));
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if body.len() >= self.max_bytes {
+                body.truncate(self.max_bytes);
+                break;
+            }
+        }
+
+        let body = String::from_utf8_lossy(&body);
+        let text = if is_html {
+            html_to_text(&body)
+        } else {
+            body.into_owned()
+        };
+
+        Ok(truncate(text, self.max_length))
+    }
+}
+
+fn truncate(text: String, max_length: usize) -> String {
+    match text.char_indices().nth(max_length) {
+        Some((index, _)) => format!("{}\n[Truncated]", &text[..index]),
+        None => text,
+    }
+}
+
+/// Elements whose content is not text.
+const SKIPPED_ELEMENTS: [&str; 5] = ["script", "style", "noscript", "svg", "template"];
+
+/// Elements starting a new line.
+const BLOCK_ELEMENTS: [&str; 22] = [
+    "p",
+    "div",
+    "br",
+    "li",
+    "ul",
+    "ol",
+    "tr",
+    "table",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "pre",
+    "blockquote",
+    "section",
+    "article",
+    "header",
+    "footer",
+    "nav",
+    "title",
+];
+
+/// Convert an HTML document to text: markup, comments, scripts and styles are removed, entities
+/// are decoded and whitespace is collapsed.
+pub(crate) fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[1..end].trim_start_matches('/').to_lowercase();
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        rest = &rest[end + 1..];
+
+        if SKIPPED_ELEMENTS.contains(&name) && !rest.is_empty() {
+            // Skip everything up to the closing tag of the element
+            let closing = format!("</{name}");
+            rest = match rest.to_ascii_lowercase().find(&closing) {
+                Some(index) => rest[index..]
+                    .find('>')
+                    .map_or("", |end| &rest[index + end + 1..]),
+                None => "",
+            };
+        } else if BLOCK_ELEMENTS.contains(&name) {
+            text.push('\n');
+        } else {
+            text.push(' ');
+        }
+    }
+    text.push_str(rest);
+
+    decode_entities(&text)
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        });
+
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text() {
+        let html = r#"<!DOCTYPE html>
+            <html>
+              <head><title>Rig</title><style>body { color: red; }</style></head>
+              <body>
+                <!-- navigation -->
+                <h1>Build   LLM apps</h1>
+                <script>alert("<p>hi</p>");</script>
+                <p>Fast &amp; <b>modular</b> &lt;3 &#x1F980;</p>
+              </body>
+            </html>"#;
+
+        assert_eq!(
+            html_to_text(html),
+            "Rig\nBuild LLM apps\nFast & modular <3 🦀"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_fetch_allowlist() {
+        let tool = HttpFetchTool::new(["Example.com"]);
+        let allowed = |url: &str| tool.allowlist.allows(&Url::parse(url).unwrap());
+
+        assert!(allowed("https://example.com/docs"));
+        assert!(allowed("http://api.example.com"));
+        assert!(!allowed("https://notexample.com"));
+        assert!(!allowed("https://example.com.evil.io"));
+        assert!(!allowed("file:///etc/passwd"));
+        assert!(allowed("https://example.com:443/docs"));
+        assert!(!allowed("https://example.com:8443/docs"));
+        assert!(!allowed("http://example.com:6379"));
+
+        let tool = HttpFetchTool::new(["localhost:8080"]);
+        let allowed = |url: &str| tool.allowlist.allows(&Url::parse(url).unwrap());
+        assert!(allowed("http://localhost:8080/health"));
+        assert!(!allowed("http://localhost/health"));
+        assert!(!allowed("http://localhost:9090/health"));
+
+        let err = tool
+            .call(HttpFetchArgs {
+                url: "http://127.0.0.1:8080/admin".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HttpFetchError::DomainNotAllowed(_)));
+    }
+}
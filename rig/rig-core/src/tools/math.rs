@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::completion::ToolDefinition;
+use crate::tool::Tool;
+
+/// Arguments for the calculator tool
+#[derive(Debug, Deserialize)]
+pub struct CalculatorArgs {
+    /// The expression to evaluate
+    pub expression: String,
+}
+
+/// Error type for the calculator tool
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum CalculatorError {
+    #[error("Syntax error at position {position}: {message}")]
+    SyntaxError { position: usize, message: String },
+    #[error("Unknown function or constant: {0}")]
+    UnknownIdentifier(String),
+    #[error("The result is not a number (eg: division by zero)")]
+    NotANumber,
+}
+
+/// A tool evaluating math expressions, so that the model doesn't have to do arithmetic itself.
+///
+/// Expressions support numbers, `+`, `-`, `*`, `/`, `%`, `^` (power), parentheses, the constants
+/// `pi` and `e`, and the functions `sqrt`, `abs`, `exp`, `ln`, `log10`, `log2`, `sin`, `cos`,
+/// `tan`, `asin`, `acos`, `atan`, `floor`, `ceil`, `round`, `min` and `max`.
+///
+/// The expression is parsed and evaluated by the tool: no code is ever executed.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+pub struct CalculatorTool;
+
+impl Tool for CalculatorTool {
+    const NAME: &'static str = "calculator";
+
+    type Error = CalculatorError;
+    type Args = CalculatorArgs;
+    type Output = f64;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Evaluate a math expression and return the result. Use it for any \
+            arithmetic instead of computing the result yourself. Supports + - * / % ^, \
+            parentheses, the constants pi and e, and the functions sqrt, abs, exp, ln, log10, \
+            log2, sin, cos, tan, asin, acos, atan, floor, ceil, round, min and max."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "expression": {
+                        "type": "string",
+                        "description": "The expression to evaluate, eg: `(3 + 4) * sqrt(16) / 2^3`."
+                    }
+                },
+                "required": ["expression"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        evaluate(&args.expression)
+    }
+}
+
+/// Evaluate a math expression.
+pub fn evaluate(expression: &str) -> Result<f64, CalculatorError> {
+    let mut parser = Parser {
+        input: expression.as_bytes(),
+        position: 0,
+        depth: 0,
+    };
+
+    let value = parser.expression()?;
+    parser.skip_whitespace();
+    if parser.position < parser.input.len() {
+        return Err(parser.error("unexpected character"));
+    }
+
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(CalculatorError::NotANumber)
+    }
+}
+
+/// A recursive descent parser, evaluating the expression as it is parsed.
+///
+/// ```text
+/// expression = term (("+" | "-") term)*
+/// term       = unary (("*" | "/" | "%") unary)*
+/// unary      = "-" unary | power
+/// power      = atom ("^" unary)?
+/// atom       = number | identifier | identifier "(" arguments ")" | "(" expression ")"
+/// ```
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+    /// How deeply nested the current sub-expression is, to bound the recursion.
+    depth: usize,
+}
+
+const MAX_DEPTH: usize = 256;
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> CalculatorError {
+        CalculatorError::SyntaxError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .input
+            .get(self.position)
+            .is_some_and(|c| c.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+
+    /// Consume the next (non-whitespace) character if it is one of the given ones.
+    fn next_if(&mut self, characters: &[u8]) -> Option<u8> {
+        self.skip_whitespace();
+        let c = *self.input.get(self.position)?;
+        if characters.contains(&c) {
+            self.position += 1;
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    fn expression(&mut self) -> Result<f64, CalculatorError> {
+        let mut value = self.term()?;
+        while let Some(operator) = self.next_if(b"+-") {
+            let rhs = self.term()?;
+            match operator {
+                b'+' => value += rhs,
+                _ => value -= rhs,
+            }
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<f64, CalculatorError> {
+        let mut value = self.unary()?;
+        while let Some(operator) = self.next_if(b"*/%") {
+            let rhs = self.unary()?;
+            match operator {
+                b'*' => value *= rhs,
+                b'/' => value /= rhs,
+                _ => value %= rhs,
+            }
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<f64, CalculatorError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("expression is too deeply nested"));
+        }
+
+        self.depth += 1;
+        let value = if self.next_if(b"-").is_some() {
+            self.unary().map(|value| -value)
+        } else if self.next_if(b"+").is_some() {
+            self.unary()
+        } else {
+            self.power()
+        };
+        self.depth -= 1;
+
+        value
+    }
+
+    fn power(&mut self) -> Result<f64, CalculatorError> {
+        let base = self.atom()?;
+        if self.next_if(b"^").is_some() {
+            // Right associative: 2^3^2 = 2^(3^2)
+            return Ok(base.powf(self.unary()?));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<f64, CalculatorError> {
+        if self.next_if(b"(").is_some() {
+            let value = self.expression()?;
+            if self.next_if(b")").is_none() {
+                return Err(self.error("expected `)`"));
+            }
+            return Ok(value);
+        }
+
+        self.skip_whitespace();
+        let start = self.position;
+        match self.input.get(start) {
+            Some(c) if c.is_ascii_digit() || *c == b'.' => {
+                while self
+                    .input
+                    .get(self.position)
+                    .is_some_and(|c| c.is_ascii_digit() || *c == b'.')
+                {
+                    self.position += 1;
+                }
+                // Scientific notation, eg: 1.5e3
+                if self.input.get(self.position) == Some(&b'e')
+                    && self
+                        .input
+                        .get(self.position + 1)
+                        .is_some_and(|c| c.is_ascii_digit() || *c == b'-' || *c == b'+')
+                {
+                    self.position += 2;
+                    while self
+                        .input
+                        .get(self.position)
+                        .is_some_and(|c| c.is_ascii_digit())
+                    {
+                        self.position += 1;
+                    }
+                }
+
+                let number = std::str::from_utf8(&self.input[start..self.position])
+                    .expect("the number only contains ASCII characters");
+                number.parse().map_err(|_| CalculatorError::SyntaxError {
+                    position: start,
+                    message: format!("invalid number `{number}`"),
+                })
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                while self
+                    .input
+                    .get(self.position)
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+                {
+                    self.position += 1;
+                }
+                let identifier = std::str::from_utf8(&self.input[start..self.position])
+                    .expect("the identifier only contains ASCII characters")
+                    .to_lowercase();
+
+                if self.next_if(b"(").is_some() {
+                    let mut arguments = vec![self.expression()?];
+                    while self.next_if(b",").is_some() {
+                        arguments.push(self.expression()?);
+                    }
+                    if self.next_if(b")").is_none() {
+                        return Err(self.error("expected `)`"));
+                    }
+                    return call_function(&identifier, &arguments);
+                }
+
+                match identifier.as_str() {
+                    "pi" => Ok(std::f64::consts::PI),
+                    "e" => Ok(std::f64::consts::E),
+                    _ => Err(CalculatorError::UnknownIdentifier(identifier)),
+                }
+            }
+            Some(_) => Err(self.error("expected a number, a function or `(`")),
+            None => Err(self.error("unexpected end of expression")),
+        }
+    }
+}
+
+fn call_function(name: &str, arguments: &[f64]) -> Result<f64, CalculatorError> {
+    let unary = |f: fn(f64) -> f64| match arguments {
+        [x] => Ok(f(*x)),
+        _ => Err(CalculatorError::SyntaxError {
+            position: 0,
+            message: format!("`{name}` takes exactly one argument"),
+        }),
+    };
+
+    match name {
+        "sqrt" => unary(f64::sqrt),
+        "abs" => unary(f64::abs),
+        "exp" => unary(f64::exp),
+        "ln" => unary(f64::ln),
+        "log10" => unary(f64::log10),
+        "log2" => unary(f64::log2),
+        "sin" => unary(f64::sin),
+        "cos" => unary(f64::cos),
+        "tan" => unary(f64::tan),
+        "asin" => unary(f64::asin),
+        "acos" => unary(f64::acos),
+        "atan" => unary(f64::atan),
+        "floor" => unary(f64::floor),
+        "ceil" => unary(f64::ceil),
+        "round" => unary(f64::round),
+        "min" => Ok(arguments.iter().copied().fold(f64::INFINITY, f64::min)),
+        "max" => Ok(arguments.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+        _ => Err(CalculatorError::UnknownIdentifier(name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(evaluate("1 + 2 * 3"), Ok(7.0));
+        assert_eq!(evaluate("(1 + 2) * 3"), Ok(9.0));
+        assert_eq!(evaluate("-2 ^ 2"), Ok(-4.0));
+        assert_eq!(evaluate("2 ^ 3 ^ 2"), Ok(512.0));
+        assert_eq!(evaluate("10 % 4 - 1.5e1"), Ok(-13.0));
+        assert_eq!(evaluate("sqrt(16) + max(1, 5, 3) + round(pi)"), Ok(12.0));
+        assert_eq!(evaluate("1 / 0"), Err(CalculatorError::NotANumber));
+        assert_eq!(
+            evaluate("foo(2)"),
+            Err(CalculatorError::UnknownIdentifier("foo".to_string()))
+        );
+        assert!(matches!(
+            evaluate("(1 + 2"),
+            Err(CalculatorError::SyntaxError { position: 6, .. })
+        ));
+        assert!(matches!(
+            evaluate("1 + 2)"),
+            Err(CalculatorError::SyntaxError { position: 5, .. })
+        ));
+        assert!(matches!(
+            evaluate(&"(".repeat(10_000)),
+            Err(CalculatorError::SyntaxError { .. })
+        ));
+    }
+}
@@ -1,2 +1,26 @@
+//! A library of ready-to-use tools.
+//!
+//! The tools are configurable (and confined) so that they can be given to a model safely:
+//! - [ThinkTool]: gives the model a space to reason in complex tool use situations.
+//! - [FileSystemTool]: reads, lists and searches files, confined to a root directory.
+//! - [HttpFetchTool]: fetches web pages of an allowlist of domains, converting HTML to text.
+//! - [CalculatorTool]: evaluates math expressions.
+//! - [DateTimeTool]: gives the current date and time, and does date arithmetic.
+//! - [ScratchpadTool]: a key-value memory for notes and intermediate results.
+pub mod datetime;
+#[cfg(not(target_family = "wasm"))]
+pub mod fs;
+#[cfg(not(target_family = "wasm"))]
+pub mod http;
+pub mod math;
+pub mod scratchpad;
 pub mod think;
+
+pub use datetime::DateTimeTool;
+#[cfg(not(target_family = "wasm"))]
+pub use fs::FileSystemTool;
+#[cfg(not(target_family = "wasm"))]
+pub use http::HttpFetchTool;
+pub use math::CalculatorTool;
+pub use scratchpad::ScratchpadTool;
 pub use think::ThinkTool;
@@ -0,0 +1,238 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::RwLock;
+
+use crate::completion::ToolDefinition;
+use crate::tool::Tool;
+
+/// Arguments for the scratchpad tool
+#[derive(Debug, Deserialize)]
+pub struct ScratchpadArgs {
+    /// The operation to perform
+    pub operation: ScratchpadOperation,
+    /// The key of the entry (for `set`, `get` and `delete`)
+    pub key: Option<String>,
+    /// The value of the entry (for `set`)
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScratchpadOperation {
+    /// Save a value under a key, replacing the previous value
+    Set,
+    /// Get the value of a key
+    Get,
+    /// Delete a key
+    Delete,
+    /// List the keys of the scratchpad
+    List,
+}
+
+/// Error type for the scratchpad tool
+#[derive(Debug, thiserror::Error)]
+pub enum ScratchpadError {
+    #[error("Missing argument: {0}")]
+    MissingArgument(&'static str),
+    #[error("The scratchpad is full ({0} entries), delete an entry first")]
+    Full(usize),
+    #[error("The value is too long ({length} characters, the limit is {limit})")]
+    ValueTooLong { length: usize, limit: usize },
+}
+
+/// A key-value scratchpad the model can use to save notes and intermediate results, and to read
+/// them back later (eg: in another turn of a multi-turn prompt).
+///
+/// Clones of the tool share the same entries, so the application can read what the model saved
+/// (see [ScratchpadTool::entries]).
+#[derive(Debug, Clone)]
+pub struct ScratchpadTool {
+    entries: Arc<RwLock<BTreeMap<String, String>>>,
+    max_entries: usize,
+    max_value_length: usize,
+}
+
+impl Default for ScratchpadTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScratchpadTool {
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(BTreeMap::new())),
+            max_entries: 100,
+            max_value_length: 10_000,
+        }
+    }
+
+    /// Set the maximum number of entries of the scratchpad. Defaults to 100.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Set the maximum length (in characters) of a value. Defaults to 10 000.
+    pub fn max_value_length(mut self, max_value_length: usize) -> Self {
+        self.max_value_length = max_value_length;
+        self
+    }
+
+    /// Returns a copy of the entries of the scratchpad.
+    pub async fn entries(&self) -> BTreeMap<String, String> {
+        self.entries.read().await.clone()
+    }
+}
+
+impl Tool for ScratchpadTool {
+    const NAME: &'static str = "scratchpad";
+
+    type Error = ScratchpadError;
+    type Args = ScratchpadArgs;
+    type Output = String;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "A key-value scratchpad to save notes and intermediate results, and read \
+            them back later. Use `set` to save a value under a key, `get` to read it, `delete` to \
+            remove it and `list` to see the saved keys."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "operation": {
+                        "type": "string",
+                        "enum": ["set", "get", "delete", "list"],
+                        "description": "The operation to perform."
+                    },
+                    "key": {
+                        "type": "string",
+                        "description": "The key of the entry (for `set`, `get` and `delete`)."
+                    },
+                    "value": {
+                        "type": "string",
+                        "description": "The value to save (for `set`)."
+                    }
+                },
+                "required": ["operation"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let key = args.key.ok_or(ScratchpadError::MissingArgument("key"));
+
+        match args.operation {
+            ScratchpadOperation::Set => {
+                let key = key?;
+                let value = args
+                    .value
+                    .ok_or(ScratchpadError::MissingArgument("value"))?;
+                let length = value.chars().count();
+                if length > self.max_value_length {
+                    return Err(ScratchpadError::ValueTooLong {
+                        length,
+                        limit: self.max_value_length,
+                    });
+                }
+
+                let mut entries = self.entries.write().await;
+                if !entries.contains_key(&key) && entries.len() >= self.max_entries {
+                    return Err(ScratchpadError::Full(self.max_entries));
+                }
+                entries.insert(key.clone(), value);
+                Ok(format!("Saved `{key}`"))
+            }
+            ScratchpadOperation::Get => {
+                let key = key?;
+                Ok(self
+                    .entries
+                    .read()
+                    .await
+                    .get(&key)
+                    .cloned()
+                    .unwrap_or_else(|| format!("No entry found for `{key}`")))
+            }
+            ScratchpadOperation::Delete => {
+                let key = key?;
+                Ok(match self.entries.write().await.remove(&key) {
+                    Some(_) => format!("Deleted `{key}`"),
+                    None => format!("No entry found for `{key}`"),
+                })
+            }
+            ScratchpadOperation::List => {
+                let entries = self.entries.read().await;
+                if entries.is_empty() {
+                    return Ok("The scratchpad is empty".to_string());
+                }
+                Ok(entries.keys().cloned().collect::<Vec<_>>().join("\n"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(
+        operation: ScratchpadOperation,
+        key: Option<&str>,
+        value: Option<&str>,
+    ) -> ScratchpadArgs {
+        ScratchpadArgs {
+            operation,
+            key: key.map(str::to_string),
+            value: value.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scratchpad_tool() {
+        let tool = ScratchpadTool::new().max_entries(1);
+
+        tool.call(args(
+            ScratchpadOperation::Set,
+            Some("plan"),
+            Some("1. Research"),
+        ))
+        .await
+        .unwrap();
+        let value = tool
+            .call(args(ScratchpadOperation::Get, Some("plan"), None))
+            .await
+            .unwrap();
+        assert_eq!(value, "1. Research");
+
+        let err = tool
+            .call(args(ScratchpadOperation::Set, Some("other"), Some("value")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ScratchpadError::Full(1)));
+
+        // Clones share the entries
+        let clone = tool.clone();
+        clone
+            .call(args(
+                ScratchpadOperation::Set,
+                Some("plan"),
+                Some("2. Write"),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(tool.entries().await["plan"], "2. Write");
+
+        tool.call(args(ScratchpadOperation::Delete, Some("plan"), None))
+            .await
+            .unwrap();
+        let listing = tool
+            .call(args(ScratchpadOperation::List, None, None))
+            .await
+            .unwrap();
+        assert_eq!(listing, "The scratchpad is empty");
+    }
+}
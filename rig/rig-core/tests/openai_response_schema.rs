@@ -1,5 +1,6 @@
-use rig::completion::ToolDefinition;
-use rig::providers::openai::responses_api::ResponsesToolDefinition;
+use rig::OneOrMany;
+use rig::completion::{CompletionRequest, Message, ToolDefinition};
+use rig::providers::openai::responses_api::{self, ResponsesToolDefinition};
 use schemars::{JsonSchema, schema_for};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -118,3 +119,31 @@ fn test_enum_schemas() {
         "Enum variants (anyOf/oneOf) should have additionalProperties: false"
     );
 }
+
+#[test]
+fn test_output_schema() {
+    let request = CompletionRequest {
+        preamble: None,
+        chat_history: OneOrMany::one(Message::user("Who works at Acme?")),
+        documents: vec![],
+        tools: vec![],
+        temperature: None,
+        max_tokens: None,
+        tool_choice: None,
+        additional_params: None,
+        output_schema: Some(schema_for!(Company)),
+        cache_breakpoints: vec![],
+    };
+    let request = responses_api::CompletionRequest::try_from(("gpt-4o".to_string(), request))
+        .expect("request conversion should succeed");
+    let request = serde_json::to_value(request).unwrap();
+
+    let format = &request["text"]["format"];
+    assert_eq!(format["type"], "json_schema");
+    assert_eq!(format["name"], "Company");
+    assert_eq!(format["strict"], true);
+    assert!(
+        check_add_prps(&format["schema"]),
+        "Output schemas should have additionalProperties: false"
+    );
+}
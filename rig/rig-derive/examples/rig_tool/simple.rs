@@ -3,23 +3,23 @@ use rig::completion::Prompt;
 use rig::providers;
 use rig_derive::rig_tool;
 
-// Simple example with no attributes (`required` is still needed for OpenAI's strict function tool calling)
-#[rig_tool(required(a, b))]
+// Simple example with no attributes (parameters that are not `Option`s are required)
+#[rig_tool]
 fn add(a: i32, b: i32) -> Result<i32, rig::tool::ToolError> {
     Ok(a + b)
 }
 
-#[rig_tool(required(a, b))]
+#[rig_tool]
 fn subtract(a: i32, b: i32) -> Result<i32, rig::tool::ToolError> {
     Ok(a - b)
 }
 
-#[rig_tool(required(a, b))]
+#[rig_tool]
 fn multiply(a: i32, b: i32) -> Result<i32, rig::tool::ToolError> {
     Ok(a * b)
 }
 
-#[rig_tool(required(a, b))]
+#[rig_tool]
 fn divide(a: i32, b: i32) -> Result<i32, rig::tool::ToolError> {
     if b == 0 {
         Err(rig::tool::ToolError::ToolCallError(
@@ -1,20 +1,13 @@
 extern crate proc_macro;
 
-use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
-use std::{collections::HashMap, ops::Deref};
-use syn::{
-    DeriveInput, Expr, ExprLit, Ident, Lit, Meta, PathArguments, ReturnType, Token, Type,
-    parse::{Parse, ParseStream},
-    parse_macro_input,
-    punctuated::Punctuated,
-};
+use syn::{DeriveInput, parse_macro_input};
 
 mod basic;
 mod client;
 mod custom;
 mod embed;
+mod tool;
 
 pub(crate) const EMBED: &str = "embed";
 
@@ -46,135 +39,46 @@ pub fn derive_embedding_trait(item: TokenStream) -> TokenStream {
         .into()
 }
 
-struct MacroArgs {
-    description: Option<String>,
-    param_descriptions: HashMap<String, String>,
-    required: Vec<String>,
-}
-
-impl Parse for MacroArgs {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut description = None;
-        let mut param_descriptions = HashMap::new();
-        let mut required = Vec::new();
-
-        // If the input is empty, return default values
-        if input.is_empty() {
-            return Ok(MacroArgs {
-                description,
-                param_descriptions,
-                required,
-            });
-        }
-
-        let meta_list: Punctuated<Meta, Token![,]> = Punctuated::parse_terminated(input)?;
-
-        for meta in meta_list {
-            match meta {
-                Meta::NameValue(nv) => {
-                    let ident = nv.path.get_ident().unwrap().to_string();
-                    if let Expr::Lit(ExprLit {
-                        lit: Lit::Str(lit_str),
-                        ..
-                    }) = nv.value
-                        && ident.as_str() == "description"
-                    {
-                        description = Some(lit_str.value());
-                    }
-                }
-                Meta::List(list) if list.path.is_ident("params") => {
-                    let nested: Punctuated<Meta, Token![,]> =
-                        list.parse_args_with(Punctuated::parse_terminated)?;
-
-                    for meta in nested {
-                        if let Meta::NameValue(nv) = meta
-                            && let Expr::Lit(ExprLit {
-                                lit: Lit::Str(lit_str),
-                                ..
-                            }) = nv.value
-                        {
-                            let param_name = nv.path.get_ident().unwrap().to_string();
-                            param_descriptions.insert(param_name, lit_str.value());
-                        }
-                    }
-                }
-                Meta::List(list) if list.path.is_ident("required") => {
-                    let required_variables: Punctuated<Ident, Token![,]> =
-                        list.parse_args_with(Punctuated::parse_terminated)?;
-
-                    required_variables.into_iter().for_each(|x| {
-                        required.push(x.to_string());
-                    });
-                }
-                _ => {}
-            }
-        }
-
-        Ok(MacroArgs {
-            description,
-            param_descriptions,
-            required,
-        })
-    }
-}
-
-fn get_json_type(ty: &Type) -> proc_macro2::TokenStream {
-    match ty {
-        Type::Path(type_path) => {
-            let segment = &type_path.path.segments[0];
-            let type_name = segment.ident.to_string();
-
-            // Handle Vec types
-            if type_name == "Vec" {
-                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments
-                    && let syn::GenericArgument::Type(inner_type) = &args.args[0]
-                {
-                    let inner_json_type = get_json_type(inner_type);
-                    return quote! {
-                        "type": "array",
-                        "items": { #inner_json_type }
-                    };
-                }
-                return quote! { "type": "array" };
-            }
-
-            // Handle primitive types
-            match type_name.as_str() {
-                "i8" | "i16" | "i32" | "i64" | "u8" | "u16" | "u32" | "u64" | "f32" | "f64" => {
-                    quote! { "type": "number" }
-                }
-                "String" | "str" => {
-                    quote! { "type": "string" }
-                }
-                "bool" => {
-                    quote! { "type": "boolean" }
-                }
-                // Handle other types as objects
-                _ => {
-                    quote! { "type": "object" }
-                }
-            }
-        }
-        _ => {
-            quote! { "type": "object" }
-        }
-    }
-}
-
 /// A procedural macro that transforms a function into a `rig::tool::Tool` that can be used with a `rig::agent::Agent`.
 ///
+/// The JSON schema of the parameters is derived with `schemars::JsonSchema` (re-exported as
+/// `rig::schemars`), so parameters can be of any type implementing `serde::Deserialize` and
+/// `JsonSchema`, including structs, enums and `Option`s (which are not required). The doc comments
+/// of the function are used as the description of the tool, unless a `description` is given.
+///
 /// # Examples
 ///
 /// Basic usage:
 /// ```rust
 /// use rig_derive::rig_tool;
 ///
+/// /// Add two numbers together
 /// #[rig_tool]
 /// fn add(a: i32, b: i32) -> Result<i32, rig::tool::ToolError> {
 ///     Ok(a + b)
 /// }
 /// ```
 ///
+/// With struct and optional parameters (doc comments of the fields become their descriptions):
+/// ```rust
+/// use rig::schemars::JsonSchema;
+/// use rig_derive::rig_tool;
+///
+/// #[derive(serde::Deserialize, JsonSchema)]
+/// #[schemars(crate = "rig::schemars")]
+/// struct Address {
+///     /// The street and house number
+///     street: String,
+///     city: String,
+/// }
+///
+/// /// Compute the shipping cost of an order
+/// #[rig_tool]
+/// fn shipping_cost(address: Address, express: Option<bool>) -> Result<f64, rig::tool::ToolError> {
+///     Ok(if express.unwrap_or(false) { 15.0 } else { 5.0 })
+/// }
+/// ```
+///
 /// With description:
 /// ```rust
 /// use rig_derive::rig_tool;
@@ -211,152 +115,44 @@ fn get_json_type(ty: &Type) -> proc_macro2::TokenStream {
 ///     }
 /// }
 /// ```
+///
+/// With the context of the tool call (which is not one of the arguments of the tool):
+/// ```rust
+/// use rig::tool::{ToolContext, ToolError};
+/// use rig_derive::rig_tool;
+///
+/// #[rig_tool(description = "Get the balance of the account of the user")]
+/// async fn get_balance(currency: String, context: &ToolContext) -> Result<String, ToolError> {
+///     let Some(user_id) = context.metadata("user_id") else {
+///         return Err(ToolError::ToolCallError("Unknown user".into()));
+///     };
+///     Ok(format!("The balance of {user_id} is 42 {currency}"))
+/// }
+/// ```
+///
+/// With state, by using the macro on an impl block with a single `&self` method (the type of the
+/// impl block becomes the tool):
+/// ```rust
+/// use rig_derive::rig_tool;
+///
+/// struct Inventory {
+///     items: Vec<String>,
+/// }
+///
+/// #[rig_tool]
+/// impl Inventory {
+///     /// Check whether an item is in stock
+///     fn in_stock(&self, item: String) -> Result<bool, rig::tool::ToolError> {
+///         Ok(self.items.contains(&item))
+///     }
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn rig_tool(args: TokenStream, input: TokenStream) -> TokenStream {
-    let args = parse_macro_input!(args as MacroArgs);
-    let input_fn = parse_macro_input!(input as syn::ItemFn);
-
-    // Extract function details
-    let fn_name = &input_fn.sig.ident;
-    let fn_name_str = fn_name.to_string();
-    let is_async = input_fn.sig.asyncness.is_some();
-
-    // Extract return type and get Output and Error types from Result<T, E>
-    let return_type = &input_fn.sig.output;
-    let (output_type, error_type) = match return_type {
-        ReturnType::Type(_, ty) => {
-            if let Type::Path(type_path) = ty.deref() {
-                if let Some(last_segment) = type_path.path.segments.last() {
-                    if last_segment.ident == "Result" {
-                        if let PathArguments::AngleBracketed(args) = &last_segment.arguments {
-                            if args.args.len() == 2 {
-                                let output = args.args.first().unwrap();
-                                let error = args.args.last().unwrap();
-
-                                (quote!(#output), quote!(#error))
-                            } else {
-                                panic!("Expected Result with two type parameters");
-                            }
-                        } else {
-                            panic!("Expected angle bracketed type parameters for Result");
-                        }
-                    } else {
-                        panic!("Return type must be a Result");
-                    }
-                } else {
-                    panic!("Invalid return type");
-                }
-            } else {
-                panic!("Invalid return type");
-            }
-        }
-        _ => panic!("Function must have a return type"),
-    };
-
-    // Generate PascalCase struct name from the function name
-    let struct_name = format_ident!("{}", { fn_name_str.to_case(Case::Pascal) });
-
-    // Use provided description or generate a default one
-    let tool_description = match args.description {
-        Some(desc) => quote! { #desc.to_string() },
-        None => quote! { format!("Function to {}", Self::NAME) },
-    };
-
-    // Extract parameter names, types, and descriptions
-    let mut param_names = Vec::new();
-    let mut param_types = Vec::new();
-    let mut param_descriptions = Vec::new();
-    let mut json_types = Vec::new();
-
-    let required_args = args.required;
-
-    for arg in input_fn.sig.inputs.iter() {
-        if let syn::FnArg::Typed(pat_type) = arg
-            && let syn::Pat::Ident(param_ident) = &*pat_type.pat
-        {
-            let param_name = &param_ident.ident;
-            let param_name_str = param_name.to_string();
-            let ty = &pat_type.ty;
-            let default_parameter_description = format!("Parameter {param_name_str}");
-            let description = args
-                .param_descriptions
-                .get(&param_name_str)
-                .map(|s| s.to_owned())
-                .unwrap_or(default_parameter_description);
+    let args = parse_macro_input!(args as tool::MacroArgs);
+    let item = parse_macro_input!(input as syn::Item);
 
-            param_names.push(param_name);
-            param_types.push(ty);
-            param_descriptions.push(description);
-            json_types.push(get_json_type(ty));
-        }
-    }
-
-    let params_struct_name = format_ident!("{}Parameters", struct_name);
-    let static_name = format_ident!("{}", fn_name_str.to_uppercase());
-
-    // Generate the call implementation based on whether the function is async
-    let call_impl = if is_async {
-        quote! {
-            async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-                #fn_name(#(args.#param_names,)*).await
-            }
-        }
-    } else {
-        quote! {
-            async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-                #fn_name(#(args.#param_names,)*)
-            }
-        }
-    };
-
-    let expanded = quote! {
-        #[derive(serde::Deserialize)]
-        pub(crate) struct #params_struct_name {
-            #(#param_names: #param_types,)*
-        }
-
-        #input_fn
-
-        #[derive(Default)]
-        pub(crate) struct #struct_name;
-
-        impl rig::tool::Tool for #struct_name {
-            const NAME: &'static str = #fn_name_str;
-
-            type Args = #params_struct_name;
-            type Output = #output_type;
-            type Error = #error_type;
-
-            fn name(&self) -> String {
-                #fn_name_str.to_string()
-            }
-
-            async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
-                let parameters = serde_json::json!({
-                    "type": "object",
-                    "properties": {
-                        #(
-                            stringify!(#param_names): {
-                                #json_types,
-                                "description": #param_descriptions
-                            }
-                        ),*
-                    },
-                    "required": [#(#required_args),*]
-                });
-
-                rig::completion::ToolDefinition {
-                    name: #fn_name_str.to_string(),
-                    description: #tool_description.to_string(),
-                    parameters,
-                }
-            }
-
-            #call_impl
-        }
-
-        pub static #static_name: #struct_name = #struct_name;
-    };
-
-    TokenStream::from(expanded)
+    tool::expand_rig_tool(args, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
@@ -0,0 +1,412 @@
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::{collections::HashMap, ops::Deref};
+use syn::{
+    Attribute, Expr, ExprLit, FnArg, Ident, ImplItem, Item, ItemFn, ItemImpl, Lit, LitStr, Meta,
+    PathArguments, ReturnType, Signature, Token, Type,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+};
+
+pub(crate) struct MacroArgs {
+    description: Option<LitStr>,
+    param_descriptions: HashMap<String, (Ident, LitStr)>,
+    required: Vec<Ident>,
+}
+
+impl Parse for MacroArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut description = None;
+        let mut param_descriptions = HashMap::new();
+        let mut required = Vec::new();
+
+        let meta_list: Punctuated<Meta, Token![,]> = Punctuated::parse_terminated(input)?;
+
+        for meta in meta_list {
+            match meta {
+                Meta::NameValue(nv) if nv.path.is_ident("description") => {
+                    description = Some(string_literal(&nv.value)?);
+                }
+                Meta::List(list) if list.path.is_ident("params") => {
+                    let nested: Punctuated<Meta, Token![,]> =
+                        list.parse_args_with(Punctuated::parse_terminated)?;
+
+                    for meta in nested {
+                        let Meta::NameValue(nv) = meta else {
+                            return Err(syn::Error::new_spanned(
+                                meta,
+                                "expected a parameter description, eg: `x = \"The first number\"`",
+                            ));
+                        };
+                        let Some(param_name) = nv.path.get_ident() else {
+                            return Err(syn::Error::new_spanned(
+                                nv.path,
+                                "expected the name of a parameter",
+                            ));
+                        };
+                        param_descriptions.insert(
+                            param_name.to_string(),
+                            (param_name.clone(), string_literal(&nv.value)?),
+                        );
+                    }
+                }
+                Meta::List(list) if list.path.is_ident("required") => {
+                    let required_variables: Punctuated<Ident, Token![,]> =
+                        list.parse_args_with(Punctuated::parse_terminated)?;
+                    required.extend(required_variables);
+                }
+                meta => {
+                    return Err(syn::Error::new_spanned(
+                        meta.path(),
+                        "unknown attribute, expected `description`, `params` or `required`",
+                    ));
+                }
+            }
+        }
+
+        Ok(MacroArgs {
+            description,
+            param_descriptions,
+            required,
+        })
+    }
+}
+
+fn string_literal(expr: &Expr) -> syn::Result<LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) => Ok(lit_str.clone()),
+        expr => Err(syn::Error::new_spanned(expr, "expected a string literal")),
+    }
+}
+
+/// Returns `Some(is_reference)` if the given type is `rig::tool::ToolContext` (or a reference to it).
+fn tool_context_param(ty: &Type) -> Option<bool> {
+    let (ty, is_reference) = match ty {
+        Type::Reference(reference) => (reference.elem.deref(), true),
+        ty => (ty, false),
+    };
+
+    match ty {
+        Type::Path(type_path)
+            if type_path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "ToolContext") =>
+        {
+            Some(is_reference)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the `T` and `E` of a function returning `Result<T, E>`.
+fn result_types(sig: &Signature) -> syn::Result<(&syn::GenericArgument, &syn::GenericArgument)> {
+    let ReturnType::Type(_, ty) = &sig.output else {
+        return Err(syn::Error::new_spanned(
+            &sig.ident,
+            "a tool function must return a `Result<T, E>`",
+        ));
+    };
+
+    if let Type::Path(type_path) = ty.deref()
+        && let Some(last_segment) = type_path.path.segments.last()
+        && last_segment.ident == "Result"
+    {
+        return match &last_segment.arguments {
+            PathArguments::AngleBracketed(args) if args.args.len() == 2 => {
+                Ok((&args.args[0], &args.args[1]))
+            }
+            _ => Err(syn::Error::new_spanned(
+                last_segment,
+                "expected a `Result` with an output and an error type, eg: `Result<T, E>`",
+            )),
+        };
+    }
+
+    Err(syn::Error::new_spanned(
+        ty,
+        "a tool function must return a `Result<T, E>`",
+    ))
+}
+
+/// The text of the doc comments of an item.
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) => string_literal(&nv.value).ok(),
+            _ => None,
+        })
+        .map(|lit| lit.value().trim().to_string())
+        .collect::<Vec<_>>();
+
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// What the macro generates for a tool function: its parameters struct and the body of the
+/// `rig::tool::Tool` implementation.
+struct ToolParts {
+    name: String,
+    params_struct: TokenStream,
+    tool_impl: TokenStream,
+}
+
+fn tool_parts(args: &MacroArgs, attrs: &[Attribute], sig: &Signature) -> syn::Result<ToolParts> {
+    let fn_name = &sig.ident;
+    let fn_name_str = fn_name.to_string();
+    let (output_type, error_type) = result_types(sig)?;
+
+    // Use the provided description, or the doc comments of the function
+    let tool_description = match (&args.description, doc_comment(attrs)) {
+        (Some(description), _) => quote! { #description.to_string() },
+        (None, Some(doc)) => quote! { #doc.to_string() },
+        (None, None) => quote! { format!("Function to {}", Self::NAME) },
+    };
+
+    let mut param_names = Vec::new();
+    let mut param_types = Vec::new();
+    let mut param_attrs = Vec::new();
+    // The arguments the function is called with, in order
+    let mut call_args = Vec::new();
+    let mut uses_context = false;
+    let mut is_method = false;
+
+    for arg in sig.inputs.iter() {
+        let pat_type = match arg {
+            FnArg::Receiver(receiver) => {
+                if receiver.reference.is_none() || receiver.mutability.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        receiver,
+                        "tools are called through a shared reference, use `&self`",
+                    ));
+                }
+                is_method = true;
+                continue;
+            }
+            FnArg::Typed(pat_type) => pat_type,
+        };
+
+        // A `ToolContext` (or `&ToolContext`) parameter receives the context of the tool call
+        // instead of being one of the arguments of the tool
+        match tool_context_param(&pat_type.ty) {
+            Some(true) => {
+                call_args.push(quote!(context));
+                uses_context = true;
+                continue;
+            }
+            Some(false) => {
+                call_args.push(quote!(context.clone()));
+                uses_context = true;
+                continue;
+            }
+            None => {}
+        }
+
+        let syn::Pat::Ident(param_ident) = &*pat_type.pat else {
+            return Err(syn::Error::new_spanned(
+                &pat_type.pat,
+                "the parameters of a tool function must be identifiers",
+            ));
+        };
+        if let Type::Reference(reference) = &*pat_type.ty {
+            return Err(syn::Error::new_spanned(
+                reference,
+                "the parameters of a tool function must be owned types, eg: `String` instead of `&str`",
+            ));
+        }
+
+        let param_name = &param_ident.ident;
+        let description = args
+            .param_descriptions
+            .get(&param_name.to_string())
+            .map(|(_, description)| quote! { #[schemars(description = #description)] });
+
+        call_args.push(quote!(args.#param_name));
+        param_names.push(param_name);
+        param_types.push(&pat_type.ty);
+        param_attrs.push(description);
+    }
+
+    // Every parameter named in the attribute must exist
+    for param_name in args
+        .param_descriptions
+        .values()
+        .map(|(param_name, _)| param_name)
+        .chain(args.required.iter())
+    {
+        if !param_names.contains(&param_name) {
+            return Err(syn::Error::new(
+                param_name.span(),
+                format!("`{param_name}` is not a parameter of `{fn_name_str}`"),
+            ));
+        }
+    }
+
+    let params_struct_name = format_ident!("{}Parameters", fn_name_str.to_case(Case::Pascal));
+    let params_struct = quote! {
+        #[derive(serde::Deserialize, rig::schemars::JsonSchema)]
+        #[schemars(crate = "rig::schemars")]
+        pub(crate) struct #params_struct_name {
+            #(#param_attrs #param_names: #param_types,)*
+        }
+    };
+
+    // Generate the call implementation based on whether the function is async
+    let await_call = sig.asyncness.map(|_| quote!(.await));
+    let function = if is_method {
+        quote!(self.#fn_name)
+    } else {
+        quote!(#fn_name)
+    };
+    let call_impl = if uses_context {
+        quote! {
+            async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+                self.call_with_context(args, &rig::tool::ToolContext::default()).await
+            }
+
+            async fn call_with_context(
+                &self,
+                args: Self::Args,
+                context: &rig::tool::ToolContext,
+            ) -> Result<Self::Output, Self::Error> {
+                #function(#(#call_args,)*)#await_call
+            }
+        }
+    } else {
+        quote! {
+            async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+                #function(#(#call_args,)*)#await_call
+            }
+        }
+    };
+
+    let tool_impl = quote! {
+        const NAME: &'static str = #fn_name_str;
+
+        type Args = #params_struct_name;
+        type Output = #output_type;
+        type Error = #error_type;
+
+        fn name(&self) -> String {
+            #fn_name_str.to_string()
+        }
+
+        async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+            rig::completion::ToolDefinition {
+                name: #fn_name_str.to_string(),
+                description: #tool_description,
+                parameters: serde_json::to_value(rig::schemars::schema_for!(#params_struct_name))
+                    .expect("converting JSON schema to JSON value should never fail"),
+            }
+        }
+
+        #call_impl
+    };
+
+    Ok(ToolParts {
+        name: fn_name_str,
+        params_struct,
+        tool_impl,
+    })
+}
+
+pub(crate) fn expand_rig_tool(args: MacroArgs, item: Item) -> syn::Result<TokenStream> {
+    match item {
+        Item::Fn(item_fn) => expand_tool_fn(args, item_fn),
+        Item::Impl(item_impl) => expand_tool_impl(args, item_impl),
+        item => Err(syn::Error::new(
+            item.span(),
+            "#[rig_tool] can only be used on a function, or on an impl block with a single method",
+        )),
+    }
+}
+
+/// A tool function: a unit struct implementing `rig::tool::Tool` is generated.
+fn expand_tool_fn(args: MacroArgs, item_fn: ItemFn) -> syn::Result<TokenStream> {
+    if let Some(receiver) = item_fn.sig.receiver() {
+        return Err(syn::Error::new_spanned(
+            receiver,
+            "to use `self`, put #[rig_tool] on the impl block of the method",
+        ));
+    }
+
+    let ToolParts {
+        name,
+        params_struct,
+        tool_impl,
+    } = tool_parts(&args, &item_fn.attrs, &item_fn.sig)?;
+
+    // Generate PascalCase struct name from the function name
+    let struct_name = format_ident!("{}", name.to_case(Case::Pascal));
+    let static_name = format_ident!("{}", name.to_uppercase());
+
+    Ok(quote! {
+        #params_struct
+
+        #item_fn
+
+        #[derive(Default)]
+        pub(crate) struct #struct_name;
+
+        impl rig::tool::Tool for #struct_name {
+            #tool_impl
+        }
+
+        pub static #static_name: #struct_name = #struct_name;
+    })
+}
+
+/// A tool method: the type of the impl block implements `rig::tool::Tool`, so that the tool can
+/// use its state.
+fn expand_tool_impl(args: MacroArgs, item_impl: ItemImpl) -> syn::Result<TokenStream> {
+    if let Some((_, trait_path, _)) = &item_impl.trait_ {
+        return Err(syn::Error::new_spanned(
+            trait_path,
+            "#[rig_tool] can only be used on an inherent impl block",
+        ));
+    }
+
+    let mut methods = item_impl.items.iter().filter_map(|item| match item {
+        ImplItem::Fn(method) => Some(method),
+        _ => None,
+    });
+    let (Some(method), None) = (methods.next(), methods.next()) else {
+        return Err(syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "#[rig_tool] on an impl block requires exactly one method",
+        ));
+    };
+    if method.sig.receiver().is_none() {
+        return Err(syn::Error::new_spanned(
+            &method.sig.ident,
+            "the method of a tool must take `&self`",
+        ));
+    }
+
+    let ToolParts {
+        params_struct,
+        tool_impl,
+        ..
+    } = tool_parts(&args, &method.attrs, &method.sig)?;
+
+    let self_ty = &item_impl.self_ty;
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+
+    Ok(quote! {
+        #params_struct
+
+        #item_impl
+
+        impl #impl_generics rig::tool::Tool for #self_ty #where_clause {
+            #tool_impl
+        }
+    })
+}
@@ -0,0 +1,91 @@
+use rig::schemars::JsonSchema;
+use rig::tool::Tool;
+use rig_derive::rig_tool;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(crate = "rig::schemars")]
+#[serde(rename_all = "lowercase")]
+enum Shipping {
+    Standard,
+    Express,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(crate = "rig::schemars")]
+struct Order {
+    /// The ids of the ordered items
+    items: Vec<u32>,
+    shipping: Shipping,
+}
+
+/// Compute the total price of an order
+#[rig_tool(params(discount = "Discount in percent"))]
+fn order_total(order: Order, discount: Option<f64>) -> Result<f64, rig::tool::ToolError> {
+    let subtotal = order.items.len() as f64 * 10.0;
+    let shipping = match order.shipping {
+        Shipping::Standard => 5.0,
+        Shipping::Express => 15.0,
+    };
+    Ok(subtotal * (1.0 - discount.unwrap_or(0.0) / 100.0) + shipping)
+}
+
+struct Inventory {
+    items: Vec<String>,
+}
+
+#[rig_tool]
+impl Inventory {
+    /// Check whether an item is in stock
+    async fn in_stock(&self, item: String) -> Result<bool, rig::tool::ToolError> {
+        Ok(self.items.contains(&item))
+    }
+}
+
+#[tokio::test]
+async fn test_struct_and_optional_parameters() {
+    let definition = OrderTotal.definition(String::default()).await;
+    assert_eq!(
+        definition.description,
+        "Compute the total price of an order"
+    );
+
+    let parameters = &definition.parameters;
+    assert_eq!(parameters["type"], "object");
+    assert_eq!(parameters["required"], serde_json::json!(["order"]));
+    assert_eq!(
+        parameters["properties"]["discount"]["description"],
+        "Discount in percent"
+    );
+    assert_eq!(
+        parameters["$defs"]["Order"]["properties"]["items"]["description"],
+        "The ids of the ordered items"
+    );
+    assert_eq!(
+        parameters["$defs"]["Shipping"]["enum"],
+        serde_json::json!(["standard", "express"])
+    );
+
+    let args =
+        serde_json::from_str(r#"{"order": {"items": [1, 2], "shipping": "express"}}"#).unwrap();
+    assert_eq!(OrderTotal.call(args).await.unwrap(), 35.0);
+}
+
+#[tokio::test]
+async fn test_tool_with_state() {
+    let inventory = Inventory {
+        items: vec!["apple".to_string()],
+    };
+
+    let definition = inventory.definition(String::default()).await;
+    assert_eq!(definition.name, "in_stock");
+    assert_eq!(definition.description, "Check whether an item is in stock");
+
+    let in_stock = inventory
+        .call(InStockParameters {
+            item: "apple".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(in_stock);
+}
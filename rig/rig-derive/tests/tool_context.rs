@@ -0,0 +1,48 @@
+use rig::tool::{Tool, ToolContext};
+use rig_derive::rig_tool;
+
+#[rig_tool(
+    description = "Greet the user who made the request",
+    params(greeting = "The greeting to use"),
+    required(greeting)
+)]
+async fn greet(greeting: String, context: &ToolContext) -> Result<String, rig::tool::ToolError> {
+    match context.metadata("user").and_then(|user| user.as_str()) {
+        Some(user) => Ok(format!("{greeting}, {user}!")),
+        None => Err(rig::tool::ToolError::ToolCallError("Unknown user".into())),
+    }
+}
+
+#[tokio::test]
+async fn test_tool_context() {
+    let definition = Greet.definition(String::default()).await;
+    // The context is not one of the arguments of the tool
+    assert_eq!(
+        definition.parameters["properties"],
+        serde_json::json!({
+            "greeting": {
+                "type": "string",
+                "description": "The greeting to use"
+            }
+        })
+    );
+
+    let context = ToolContext::new().with_metadata("user", "Ada");
+    let output = rig::tool::ToolDyn::call_with_context(
+        &Greet,
+        r#"{"greeting": "Hello"}"#.to_string(),
+        context,
+    )
+    .await
+    .unwrap();
+    assert_eq!(output.to_string(), "\"Hello, Ada!\"");
+
+    // Without a context, the tool receives an empty one
+    let err = Greet
+        .call(GreetParameters {
+            greeting: "Hello".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(err, rig::tool::ToolError::ToolCallError(_)));
+}